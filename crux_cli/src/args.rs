@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{ArgAction, Args, Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(
@@ -23,9 +23,9 @@ pub(crate) struct Cli {
     #[arg(long, short, default_value = "false")]
     pub include_source_code: bool,
 
-    /// temporary
+    /// temporary, required by `doctor`
     #[arg(long, short)]
-    pub template_dir: PathBuf,
+    pub template_dir: Option<PathBuf>,
 
     #[arg(long, short)]
     pub path: Option<PathBuf>,
@@ -35,12 +35,51 @@ pub(crate) struct Cli {
 pub(crate) enum Commands {
     #[command(visible_alias = "doc")]
     Doctor(DoctorArgs),
+
+    /// generate the FFI module which exposes the core to the shells
+    Bindgen(BindgenArgs),
 }
 
 #[derive(Args)]
 pub(crate) struct DoctorArgs {
     #[arg(long, short)]
     pub(crate) fix: Option<PathBuf>,
+
+    /// only list the missing and drifted files, without showing diffs
+    #[arg(long)]
+    pub(crate) paths_only: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct BindgenArgs {
+    /// the name of the app type, relative to the root of the core crate
+    #[arg(long)]
+    pub(crate) app: String,
+
+    /// the name of the effect type, relative to the root of the core crate
+    #[arg(long, default_value = "Effect")]
+    pub(crate) effect: String,
+
+    /// the FFI the shells use to call the core
+    #[arg(long, value_enum, default_value_t = BindgenTarget::Both)]
+    pub(crate) target: BindgenTarget,
+
+    /// the uniffi namespace, also the name of the generated UDL file
+    #[arg(long, default_value = "shared")]
+    pub(crate) namespace: String,
+
+    /// the directory to write `ffi.rs` (and the UDL file for uniffi) to
+    #[arg(long, short, default_value = "shared/src")]
+    pub(crate) out_dir: PathBuf,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum BindgenTarget {
+    /// uniffi, for iOS and Android shells
+    Uniffi,
+    /// wasm_bindgen, for web shells
+    Wasm,
+    Both,
 }
 
 #[cfg(test)]
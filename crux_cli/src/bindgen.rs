@@ -0,0 +1,179 @@
+use std::{
+    fmt::{self, Write as _},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context as _, Result};
+
+use crate::args::{BindgenArgs, BindgenTarget};
+
+const FFI_FILE: &str = "ffi.rs";
+
+struct Bindings<'a> {
+    app: &'a str,
+    effect: &'a str,
+    namespace: &'a str,
+    uniffi: bool,
+    wasm: bool,
+}
+
+impl<'a> Bindings<'a> {
+    fn new(args: &'a BindgenArgs) -> Self {
+        Self {
+            app: &args.app,
+            effect: &args.effect,
+            namespace: &args.namespace,
+            uniffi: matches!(args.target, BindgenTarget::Uniffi | BindgenTarget::Both),
+            wasm: matches!(args.target, BindgenTarget::Wasm | BindgenTarget::Both),
+        }
+    }
+
+    /// The module exposing the core through a `Bridge`, to be declared with
+    /// `mod ffi; pub use ffi::*;` at the root of the core crate
+    fn ffi(&self) -> String {
+        let mut out = String::new();
+        self.write_ffi(&mut out).expect("writing to a String");
+        out
+    }
+
+    fn write_ffi(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "// Generated by `crux bindgen`. Do not edit.")?;
+        writeln!(out, "//")?;
+        writeln!(
+            out,
+            "// Declare this module at the root of the core crate with"
+        )?;
+        writeln!(out, "//")?;
+        writeln!(out, "//     mod ffi;")?;
+        writeln!(out, "//     pub use ffi::*;")?;
+        if self.uniffi {
+            writeln!(out, "//")?;
+            writeln!(
+                out,
+                "// The functions are described for uniffi in {}.udl.",
+                self.namespace
+            )?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "use lazy_static::lazy_static;")?;
+        if self.wasm {
+            writeln!(out, "use wasm_bindgen::prelude::wasm_bindgen;")?;
+        }
+        writeln!(out)?;
+        writeln!(out, "use crux_core::{{bridge::Bridge, Core}};")?;
+        writeln!(out)?;
+
+        if self.uniffi {
+            writeln!(out, "uniffi::include_scaffolding!(\"{}\");", self.namespace)?;
+            writeln!(out)?;
+        }
+
+        writeln!(out, "lazy_static! {{")?;
+        writeln!(
+            out,
+            "    static ref CORE: Bridge<crate::{}, crate::{}> = Bridge::new(Core::new());",
+            self.effect, self.app
+        )?;
+        writeln!(out, "}}")?;
+
+        let functions = [
+            ("process_event(data: &[u8])", "CORE.process_event(data)"),
+            (
+                "handle_response(id: u32, data: &[u8])",
+                "CORE.handle_response(id, data)",
+            ),
+            ("view()", "CORE.view()"),
+        ];
+        for (signature, body) in functions {
+            writeln!(out)?;
+            if self.wasm {
+                writeln!(out, "#[wasm_bindgen]")?;
+            }
+            writeln!(out, "pub fn {signature} -> Vec<u8> {{")?;
+            writeln!(out, "    {body}")?;
+            writeln!(out, "}}")?;
+        }
+        Ok(())
+    }
+
+    /// The uniffi interface definition for the functions in the FFI module
+    fn udl(&self) -> String {
+        format!(
+            "namespace {} {{\n  \
+               bytes process_event([ByRef] bytes msg);\n  \
+               bytes handle_response(u32 id, [ByRef] bytes res);\n  \
+               bytes view();\n\
+             }};\n",
+            self.namespace
+        )
+    }
+}
+
+pub(crate) fn bindgen(args: &BindgenArgs) -> Result<()> {
+    let bindings = Bindings::new(args);
+    fs::create_dir_all(&args.out_dir)?;
+
+    write(&args.out_dir.join(FFI_FILE), &bindings.ffi())?;
+    if bindings.uniffi {
+        let udl = format!("{}.udl", args.namespace);
+        write(&args.out_dir.join(udl), &bindings.udl())?;
+    }
+
+    Ok(())
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("Error writing: {}", path.display()))?;
+    println!("Wrote: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bindings(uniffi: bool, wasm: bool) -> Bindings<'static> {
+        Bindings {
+            app: "Counter",
+            effect: "Effect",
+            namespace: "shared",
+            uniffi,
+            wasm,
+        }
+    }
+
+    #[test]
+    fn test_render_uniffi() {
+        let rendered = bindings(true, false).ffi();
+
+        assert!(rendered.contains("uniffi::include_scaffolding!(\"shared\");"));
+        assert!(rendered.contains("Bridge<crate::Effect, crate::Counter>"));
+        assert!(rendered.contains("\npub fn process_event(data: &[u8]) -> Vec<u8> {"));
+        assert!(!rendered.contains("wasm_bindgen"));
+    }
+
+    #[test]
+    fn test_render_wasm() {
+        let rendered = bindings(false, true).ffi();
+
+        assert!(!rendered.contains("uniffi"));
+        assert_eq!(rendered.matches("#[wasm_bindgen]\npub fn").count(), 3);
+    }
+
+    #[test]
+    fn test_render_both() {
+        let rendered = bindings(true, true).ffi();
+
+        assert!(rendered.contains("uniffi::include_scaffolding!(\"shared\");"));
+        assert_eq!(rendered.matches("#[wasm_bindgen]\npub fn").count(), 3);
+    }
+
+    #[test]
+    fn test_render_udl() {
+        let rendered = bindings(true, false).udl();
+
+        assert!(rendered.starts_with("namespace shared {\n"));
+    }
+}
@@ -24,6 +24,7 @@ pub(crate) fn doctor(
     path: Option<&Path>,
     verbosity: u8,
     include_source_code: bool,
+    paths_only: bool,
 ) -> Result<()> {
     let workspace = workspace::read_config()?;
     let current_dir = &env::current_dir()?;
@@ -42,6 +43,7 @@ pub(crate) fn doctor(
                 &Context::Core(CoreContext::new(&workspace, core)),
                 verbosity,
                 include_source_code,
+                paths_only,
             )?;
         }
 
@@ -55,6 +57,7 @@ pub(crate) fn doctor(
                         &Context::Core(CoreContext::new(&workspace, core)),
                         verbosity,
                         include_source_code,
+                        paths_only,
                     )?;
                 }
             }
@@ -86,6 +89,7 @@ pub(crate) fn doctor(
                         &Context::Shell(ShellContext::new(&workspace, core, shell)),
                         verbosity,
                         include_source_code,
+                        paths_only,
                     )?;
                 }
             }
@@ -101,6 +105,7 @@ fn compare(
     context: &Context,
     verbosity: u8,
     include_source_code: bool,
+    paths_only: bool,
 ) -> Result<(), anyhow::Error> {
     println!(
         "{:-<80}\nActual:  {}\nDesired: {}",
@@ -111,7 +116,11 @@ fn compare(
     let (actual, desired) =
         &read_files(root, template_root, context, verbosity, include_source_code)?;
     missing(actual, desired);
-    common(actual, desired);
+    if paths_only {
+        drifted(actual, desired);
+    } else {
+        common(actual, desired);
+    }
     Ok(())
 }
 
@@ -208,6 +217,19 @@ fn missing(actual: &FileMap, desired: &FileMap) {
     }
 }
 
+fn drifted(actual: &FileMap, desired: &FileMap) {
+    let drifted = different(actual, desired);
+    if drifted.is_empty() {
+        println!("No drifted files");
+    } else {
+        println!("Drifted files:");
+        for file_name in drifted {
+            println!("  {}", file_name.to_string_lossy());
+        }
+        println!();
+    }
+}
+
 fn common(actual: &FileMap, desired: &FileMap) {
     for file_name in &intersection(actual, desired) {
         let desired = desired.get(file_name).expect("file not in map");
@@ -245,6 +267,17 @@ fn intersection(first: &FileMap, second: &FileMap) -> Vec<PathBuf> {
     common
 }
 
+/// files in both first and second, with different contents
+fn different(first: &FileMap, second: &FileMap) -> Vec<PathBuf> {
+    let mut different = Vec::new();
+    for (k, v) in first {
+        if second.get(k).is_some_and(|other| other != v) {
+            different.push(k.clone());
+        }
+    }
+    different
+}
+
 /// test if file is source code
 fn is_source_code(path: &Path) -> bool {
     if let Some(ext) = path.extension() {
@@ -294,6 +327,22 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_find_different_files() {
+        let mut actual_map = FileMap::new();
+        actual_map.insert(PathBuf::from("foo"), "foo".to_string());
+        actual_map.insert(PathBuf::from("bar"), "bar".to_string());
+        actual_map.insert(PathBuf::from("baz"), "baz".to_string());
+
+        let mut desired_map = FileMap::new();
+        desired_map.insert(PathBuf::from("foo"), "foo".to_string());
+        desired_map.insert(PathBuf::from("bar"), "changed".to_string());
+
+        let expected = vec![PathBuf::from("bar")];
+        let actual = different(&actual_map, &desired_map);
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn test_is_source_code() {
         assert!(is_source_code(Path::new("foo.rs")));
@@ -1,10 +1,11 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use args::{Commands, DoctorArgs};
 use clap::Parser;
 
 use args::Cli;
 
 mod args;
+mod bindgen;
 mod config;
 mod diff;
 mod doctor;
@@ -14,12 +15,19 @@ mod workspace;
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match &cli.command {
-        Some(Commands::Doctor(DoctorArgs { .. })) => doctor::doctor(
-            &cli.template_dir,
-            cli.path.as_deref(),
-            cli.verbose,
-            cli.include_source_code,
-        ),
+        Some(Commands::Doctor(DoctorArgs { paths_only, .. })) => {
+            let Some(template_dir) = &cli.template_dir else {
+                bail!("doctor needs a --template-dir to compare against");
+            };
+            doctor::doctor(
+                template_dir,
+                cli.path.as_deref(),
+                cli.verbose,
+                cli.include_source_code,
+                *paths_only,
+            )
+        }
+        Some(Commands::Bindgen(args)) => bindgen::bindgen(args),
         None => Ok(()),
     }
 }
@@ -34,6 +34,9 @@ pub fn read_config() -> Result<Workspace> {
                         path = shell.source.display()
                     );
                 }
+                if shell.cores.is_empty() {
+                    bail!("{CONFIG_FILE}: shell ({name}) does not reference any cores");
+                }
                 if !shell.cores.iter().all(|core| all_cores.contains(core)) {
                     bail!("{CONFIG_FILE}: shell ({name}) references a core that does not exist");
                 }
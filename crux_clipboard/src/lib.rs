@@ -0,0 +1,163 @@
+//! Clipboard access for Crux apps
+//!
+//! Copying text to the system clipboard and reading it back to paste it are side-effects
+//! like any other, so the core asks the shell to do them with this capability. Only
+//! plain text is supported.
+//!
+//! # Privacy
+//!
+//! The clipboard is shared with every other app on the device, and often holds sensitive
+//! text, like passwords or one-time codes, which the user copied from elsewhere. Platforms
+//! guard reading it: iOS asks the user whether to allow pasting, Android shows a message
+//! when an app reads the clipboard, and browsers only allow it with the user's permission
+//! or in response to a user gesture. Only read the clipboard when the user asks to paste,
+//! and expect [`Clipboard::read_text`] to answer `None` when the user declines.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use crux_core::macros::Capability;
+use serde::{Deserialize, Serialize};
+
+/// The operations `Clipboard` implements
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClipboardOperation {
+    /// Replace the contents of the clipboard with `text`. The shell doesn't respond.
+    SetText { text: String },
+    /// Read the text on the clipboard, answered with a [`ClipboardResponse`]
+    GetText,
+}
+
+/// The shell's answer to [`ClipboardOperation::GetText`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClipboardResponse {
+    /// The text on the clipboard, or `None` if the clipboard is empty, holds something
+    /// other than text, or the user didn't allow the app to read it
+    pub text: Option<String>,
+}
+
+impl Operation for ClipboardOperation {
+    type Output = ClipboardResponse;
+}
+
+/// The Clipboard capability API
+///
+/// Use an instance of `Clipboard` to copy text to the system clipboard, and to read the
+/// text on it. See the [crate documentation](crate) for the privacy implications of
+/// reading the clipboard.
+#[derive(Capability)]
+pub struct Clipboard<Ev> {
+    context: CapabilityContext<ClipboardOperation, Ev>,
+}
+
+impl<Ev> Clipboard<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<ClipboardOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the shell to replace the contents of the clipboard with `text`.
+    ///
+    /// ```rust,ignore
+    /// Event::CopyLink => {
+    ///     caps.clipboard.write_text(model.note.share_url());
+    ///     caps.notify.show("Link copied", NotifyLevel::Info);
+    /// }
+    /// ```
+    pub fn write_text(&self, text: impl Into<String>) {
+        let operation = ClipboardOperation::SetText { text: text.into() };
+
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            context.notify_shell(operation).await;
+        });
+    }
+
+    /// Request the text on the clipboard, which will be passed to the app wrapped in the
+    /// event produced by the `callback`, or `None` if there is no text or the user didn't
+    /// allow the app to read it.
+    ///
+    /// Reading the clipboard may prompt the user, so only do it when they ask to paste:
+    ///
+    /// ```rust,ignore
+    /// Event::Paste => caps.clipboard.read_text(Event::Pasted),
+    /// Event::Pasted(Some(text)) => {
+    ///     model.note.insert(&text);
+    ///     caps.render.render();
+    /// }
+    /// Event::Pasted(None) => {}
+    /// ```
+    pub fn read_text<F>(&self, callback: F)
+    where
+        F: FnOnce(Option<String>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.update_app(callback(read_text(&context).await));
+            }
+        });
+    }
+
+    /// Request the text on the clipboard.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn read_text_async(&self) -> Option<String> {
+        read_text(&self.context).await
+    }
+}
+
+async fn read_text<Ev: 'static>(
+    context: &CapabilityContext<ClipboardOperation, Ev>,
+) -> Option<String> {
+    context
+        .request_from_shell(ClipboardOperation::GetText)
+        .await
+        .text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serializing_the_operations_as_json() {
+        let operation = ClipboardOperation::SetText {
+            text: "Hello".to_string(),
+        };
+
+        let serialized = serde_json::to_string(&operation).unwrap();
+        assert_eq!(&serialized, r#"{"SetText":{"text":"Hello"}}"#);
+
+        let deserialized: ClipboardOperation = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(operation, deserialized);
+
+        let operation = ClipboardOperation::GetText;
+
+        let serialized = serde_json::to_string(&operation).unwrap();
+        assert_eq!(&serialized, r#""GetText""#);
+
+        let deserialized: ClipboardOperation = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(operation, deserialized);
+    }
+
+    #[test]
+    fn test_serializing_the_response_as_json() {
+        let response = ClipboardResponse {
+            text: Some("Hello".to_string()),
+        };
+
+        let serialized = serde_json::to_string(&response).unwrap();
+        assert_eq!(&serialized, r#"{"text":"Hello"}"#);
+
+        let deserialized: ClipboardResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(response, deserialized);
+
+        let response = ClipboardResponse::default();
+
+        let serialized = serde_json::to_string(&response).unwrap();
+        assert_eq!(&serialized, r#"{"text":null}"#);
+
+        let deserialized: ClipboardResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(response, deserialized);
+    }
+}
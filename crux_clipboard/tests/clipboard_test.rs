@@ -0,0 +1,123 @@
+mod shared {
+    use crux_clipboard::Clipboard;
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Copy,
+        Paste,
+        Pasted(Option<String>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub note: String,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Copy => caps.clipboard.write_text(model.note.clone()),
+                Event::Paste => caps.clipboard.read_text(Event::Pasted),
+                Event::Pasted(Some(text)) => {
+                    model.note.push_str(&text);
+                    caps.render.render();
+                }
+                Event::Pasted(None) => {}
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.note.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub clipboard: Clipboard<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_clipboard::{ClipboardOperation, ClipboardResponse};
+    use crux_core::{testing::AppTester, ResolveError};
+
+    #[test]
+    fn copies_text() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model {
+            note: "Buy milk".to_string(),
+        };
+
+        let mut request = app
+            .update(Event::Copy, &mut model)
+            .expect_one_effect()
+            .expect_clipboard();
+
+        assert_eq!(
+            request.operation,
+            ClipboardOperation::SetText {
+                text: "Buy milk".to_string()
+            }
+        );
+
+        let Err(error) = app.resolve(&mut request, ClipboardResponse::default()) else {
+            panic!("expected writing to the clipboard not to be resolvable");
+        };
+        assert_eq!(
+            error.downcast_ref::<ResolveError>(),
+            Some(&ResolveError::Never)
+        );
+    }
+
+    #[test]
+    fn pastes_text() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model {
+            note: "Buy ".to_string(),
+        };
+
+        let request = &mut app
+            .update(Event::Paste, &mut model)
+            .expect_one_effect()
+            .expect_clipboard();
+        assert_eq!(request.operation, ClipboardOperation::GetText);
+
+        let response = ClipboardResponse {
+            text: Some("milk".to_string()),
+        };
+        let update = app.resolve_to_event_then_update(request, response, &mut model);
+
+        assert!(matches!(update.effects[..], [Effect::Render(_)]));
+        assert_eq!(app.view(&model), "Buy milk");
+    }
+
+    #[test]
+    fn pastes_nothing_when_the_clipboard_has_no_text() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Paste, &mut model)
+            .expect_one_effect()
+            .expect_clipboard();
+
+        let update =
+            app.resolve_to_event_then_update(request, ClipboardResponse::default(), &mut model);
+
+        assert!(update.effects.is_empty());
+        assert_eq!(app.view(&model), "");
+    }
+}
@@ -0,0 +1,20 @@
+#[cfg(feature = "typegen")]
+mod test {
+    use crux_clipboard::Clipboard;
+    use crux_core::{typegen::TypeGen, Capability};
+
+    #[test]
+    fn registers_the_operation_and_response() {
+        let mut gen = TypeGen::new();
+
+        Clipboard::<()>::register_types(&mut gen).expect("should register types");
+
+        let crux_core::typegen::State::Registering(tracer, _) = gen.state else {
+            panic!("expected to still be in registering stage");
+        };
+        let registry = tracer.registry().expect("should get registry");
+
+        assert!(registry.contains_key("ClipboardOperation"));
+        assert!(registry.contains_key("ClipboardResponse"));
+    }
+}
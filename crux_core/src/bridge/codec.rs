@@ -0,0 +1,63 @@
+use bincode::{DefaultOptions, Options};
+
+/// The serialization format used by a [`Bridge`](crate::bridge::Bridge) to exchange
+/// events, effect requests, responses and the view model with the shell.
+///
+/// The bridge works with type-erased values, so implementations deal in
+/// [`erased_serde`](https://docs.rs/erased-serde/) serializers and deserializers.
+/// [`BincodeCodec`] is the default, and is the format supported by type generation.
+/// [`JsonCodec`] is convenient for JavaScript shells and for debugging.
+pub trait BridgeCodec {
+    /// Create a deserializer reading from `bytes` and pass it to `f`.
+    fn deserialize(&self, bytes: &[u8], f: &mut dyn FnMut(&mut dyn erased_serde::Deserializer));
+
+    /// Create a serializer and pass it to `f`, returning the bytes written.
+    fn serialize(&self, f: &mut dyn FnMut(&mut dyn erased_serde::Serializer)) -> Vec<u8>;
+}
+
+/// Serializes with bincode, using fixed size integers and allowing trailing bytes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BincodeCodec;
+
+impl BincodeCodec {
+    fn options() -> impl bincode::Options + Copy {
+        DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+    }
+}
+
+impl BridgeCodec for BincodeCodec {
+    fn deserialize(&self, bytes: &[u8], f: &mut dyn FnMut(&mut dyn erased_serde::Deserializer)) {
+        let mut deser = bincode::Deserializer::from_slice(bytes, Self::options());
+        f(&mut <dyn erased_serde::Deserializer>::erase(&mut deser));
+    }
+
+    fn serialize(&self, f: &mut dyn FnMut(&mut dyn erased_serde::Serializer)) -> Vec<u8> {
+        let mut return_buffer = vec![];
+        let mut ser = bincode::Serializer::new(&mut return_buffer, Self::options());
+        f(&mut <dyn erased_serde::Serializer>::erase(&mut ser));
+        return_buffer
+    }
+}
+
+/// Serializes with JSON.
+///
+/// Note that there is no type generation support for JSON, so the shell needs to
+/// match the serde representation of the types itself.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonCodec;
+
+impl BridgeCodec for JsonCodec {
+    fn deserialize(&self, bytes: &[u8], f: &mut dyn FnMut(&mut dyn erased_serde::Deserializer)) {
+        let mut deser = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(&mut deser));
+    }
+
+    fn serialize(&self, f: &mut dyn FnMut(&mut dyn erased_serde::Serializer)) -> Vec<u8> {
+        let mut return_buffer = vec![];
+        let mut ser = serde_json::Serializer::new(&mut return_buffer);
+        f(&mut <dyn erased_serde::Serializer>::erase(&mut ser));
+        return_buffer
+    }
+}
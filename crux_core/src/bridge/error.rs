@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The reason the bridge could not process a message from the shell, returned by
+/// [`Bridge::try_process_event`](crate::bridge::Bridge::try_process_event) and
+/// [`Bridge::try_handle_response`](crate::bridge::Bridge::try_handle_response).
+///
+/// Each of these is a bug in the shell, e.g. a shell built with out of date generated
+/// types, but one the app can survive. The core is left as it was.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeError {
+    /// The event could not be deserialized as one of the app's events
+    #[error("could not deserialize the event: {message}")]
+    DeserializeEvent { message: String },
+    /// There is no effect waiting for a response with this id
+    #[error("no effect with id {id} is waiting for a response")]
+    UnknownEffect { id: u32 },
+    /// The response could not be deserialized as the output of the effect's operation.
+    /// The effect can still be resolved with a valid response.
+    #[error("could not deserialize the response to effect {id}: {message}")]
+    DeserializeOutput { id: u32, message: String },
+    /// The effect doesn't expect a response, or not this one, see
+    /// [`ResolveError`](crate::ResolveError)
+    #[error("could not resolve effect {id}: {message}")]
+    Resolve { id: u32, message: String },
+}
@@ -1,13 +1,23 @@
+mod codec;
+mod error;
+mod observer;
 mod registry;
 mod request_serde;
+mod view_patch;
+
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use bincode::{DefaultOptions, Options};
 use erased_serde::Serialize as _;
 use serde::{Deserialize, Serialize};
 
 use crate::Effect;
 use crate::{App, Core};
+pub use codec::{BincodeCodec, BridgeCodec, JsonCodec};
+pub use error::BridgeError;
+pub use observer::BridgeEvent;
+use observer::Observer;
 use registry::{EffectId, ResolveRegistry};
+use view_patch::ViewPatches;
 // ResolveByte is public to be accessible from crux_macros
 #[doc(hidden)]
 pub use request_serde::ResolveSerialized;
@@ -28,13 +38,19 @@ where
 // ANCHOR_END: request
 
 /// Bridge is a core wrapper presenting the same interface as the [`Core`] but in a
-/// serialized form, using bincode as the serialization format.
-pub struct Bridge<Eff, A>
+/// serialized form, using bincode as the serialization format by default.
+///
+/// A different format can be chosen with [`Bridge::new_with_codec`].
+pub struct Bridge<Eff, A, C = BincodeCodec>
 where
     Eff: Effect,
     A: App,
 {
     inner: BridgeWithSerializer<Eff, A>,
+    codec: C,
+    observer: Option<Box<Observer>>,
+    seq: AtomicU64,
+    view_patches: Option<ViewPatches>,
 }
 
 impl<Eff, A> Bridge<Eff, A>
@@ -44,11 +60,37 @@ where
 {
     /// Create a new Bridge using the provided `core`.
     pub fn new(core: Core<Eff, A>) -> Self {
+        Self::new_with_codec(core, BincodeCodec)
+    }
+}
+
+impl<Eff, A, C> Bridge<Eff, A, C>
+where
+    Eff: Effect + Send + 'static,
+    A: App,
+    C: BridgeCodec,
+{
+    /// Create a new Bridge using the provided `core`, serializing with `codec`,
+    /// for example [`JsonCodec`].
+    pub fn new_with_codec(core: Core<Eff, A>, codec: C) -> Self {
         Self {
             inner: BridgeWithSerializer::new(core),
+            codec,
+            observer: None,
+            seq: AtomicU64::new(0),
+            view_patches: None,
         }
     }
 
+    /// Set a callback to be called with every effect the bridge emits, every response
+    /// it receives and every view model it renders, for debugging and telemetry.
+    ///
+    /// Replaces any previously set observer. Without an observer, nothing extra is
+    /// serialized or recorded.
+    pub fn set_observer(&mut self, observer: impl Fn(&BridgeEvent) + Send + Sync + 'static) {
+        self.observer = Some(Box::new(observer));
+    }
+
     /// Receive an event from the shell.
     ///
     /// The `event` is serialized and will be deserialized by the core before it's passed
@@ -57,16 +99,44 @@ where
     where
         A::Event: for<'a> Deserialize<'a>,
     {
-        let options = Self::bincode_options();
-
-        let mut deser = bincode::Deserializer::from_slice(event, options);
+        self.process(None, event)
+    }
 
-        let mut return_buffer = vec![];
-        let mut ser = bincode::Serializer::new(&mut return_buffer, options);
+    /// Receive an event from the shell, like [`Bridge::process_event`], but return a
+    /// [`BridgeError`] to the shell instead of panicking if the event can't be processed.
+    ///
+    /// The returned bytes are a serialized `Result<Vec<Request<Effect>>, BridgeError>`,
+    /// using serde's representation of `Result`, i.e. an enum with the variants `Ok`
+    /// and `Err`. With the [`BincodeCodec`], that is a 4 byte little endian variant
+    /// index, `0` for the requests or `1` for the error, followed by the value. With the
+    /// [`JsonCodec`] it is `{"Ok":[...]}` or e.g.
+    /// `{"Err":{"DeserializeEvent":{"message":"..."}}}`.
+    ///
+    /// A panic in the app's `update` function still unwinds, see
+    /// [`Core::catch_panics`] to turn those into values too.
+    pub fn try_process_event(&self, event: &[u8]) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        self.try_process(None, event)
+    }
 
-        self.inner.process_event(&mut deser, &mut ser);
+    /// Receive a response to a capability request from the shell, like
+    /// [`Bridge::handle_response`], but return a [`BridgeError`] to the shell instead of
+    /// panicking if the response can't be handled, e.g. because no effect with the `id`
+    /// is waiting for one. The returned bytes are in the same format as those from
+    /// [`Bridge::try_process_event`].
+    pub fn try_handle_response(&self, id: u32, output: &[u8]) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        self.observe(|seq| BridgeEvent::ResponseReceived {
+            seq,
+            id,
+            bytes: output,
+        });
 
-        return_buffer
+        self.try_process(Some(EffectId(id)), output)
     }
 
     /// Receive a response to a capability request from the shell.
@@ -80,34 +150,197 @@ where
     where
         A::Event: for<'a> Deserialize<'a>,
     {
-        let options = Self::bincode_options();
+        self.observe(|seq| BridgeEvent::ResponseReceived {
+            seq,
+            id,
+            bytes: output,
+        });
 
-        let mut deser = bincode::Deserializer::from_slice(output, options);
+        self.process(Some(EffectId(id)), output)
+    }
 
-        let mut return_buffer = vec![];
-        let mut ser = bincode::Serializer::new(&mut return_buffer, options);
+    /// Receive responses to several capability requests from the shell at once, and
+    /// return the effects they produce together, saving a trip across the FFI boundary
+    /// for each response.
+    ///
+    /// Each item is the `id` of an effect and its serialized output, as for
+    /// [`Bridge::handle_response`]. The responses are handled in the order given, exactly
+    /// as if `handle_response` had been called for each one in turn, and the requests
+    /// returned are in the same order as those calls would have returned them.
+    pub fn handle_responses(&self, items: &[(u32, &[u8])]) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        let mut requests = vec![];
+        for &(id, output) in items {
+            self.observe(|seq| BridgeEvent::ResponseReceived {
+                seq,
+                id,
+                bytes: output,
+            });
 
-        self.inner.handle_response(id, &mut deser, &mut ser);
+            requests.extend(self.process_requests(Some(EffectId(id)), output));
+        }
 
-        return_buffer
+        self.serialize_requests(&requests)
     }
 
     /// Get the current state of the app's view model (serialized).
+    ///
+    /// With [`Bridge::with_view_patches`], this is a JSON Patch from the view model
+    /// returned last time instead.
     pub fn view(&self) -> Vec<u8> {
-        let options = Self::bincode_options();
+        let view = match &self.view_patches {
+            Some(patches) => patches.patch(self.inner.view_json()),
+            None => self.codec.serialize(&mut |ser| self.inner.view_erased(ser)),
+        };
+
+        self.observe(|seq| BridgeEvent::ViewRendered { seq, bytes: &view });
+
+        view
+    }
+
+    /// Get the whole of the app's view model (serialized), even with
+    /// [`Bridge::with_view_patches`], e.g. when the shell renders for the first time or has
+    /// lost track of the view model. Later patches from [`Bridge::view`] start from this one.
+    pub fn view_full(&self) -> Vec<u8> {
+        let view = match &self.view_patches {
+            Some(patches) => patches.full(self.inner.view_json()),
+            None => self.codec.serialize(&mut |ser| self.inner.view_erased(ser)),
+        };
+
+        self.observe(|seq| BridgeEvent::ViewRendered { seq, bytes: &view });
+
+        view
+    }
+
+    /// Get the app's view model called `name` (serialized), or `None` if the app has no
+    /// view by that name. See [`App::view_named`](crate::App::view_named).
+    ///
+    /// Named views are always sent whole, even with [`Bridge::with_view_patches`].
+    pub fn view_named(&self, name: &str) -> Option<Vec<u8>> {
+        let view = self.inner.core.view_named(name)?;
+        let view = self.codec.serialize(&mut |ser| {
+            view.0.erased_serialize(ser).expect("View should serialize");
+        });
+
+        self.observe(|seq| BridgeEvent::ViewRendered { seq, bytes: &view });
+
+        Some(view)
+    }
+
+    /// Take the messages of the panics caught in the app's `update` function since this
+    /// was last called, for the shell to report them. Always empty unless the core was
+    /// created with [`Core::catch_panics`].
+    pub fn take_panics(&self) -> Vec<String> {
+        self.inner.take_panics()
+    }
 
-        let mut return_buffer = vec![];
+    fn process(&self, id: Option<EffectId>, data: &[u8]) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        let requests = self.process_requests(id, data);
 
-        self.inner
-            .view(&mut bincode::Serializer::new(&mut return_buffer, options));
+        self.serialize_requests(&requests)
+    }
 
-        return_buffer
+    fn try_process(&self, id: Option<EffectId>, data: &[u8]) -> Vec<u8>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        let result = self.try_process_requests(id, data);
+
+        self.codec.serialize(&mut |ser| {
+            result
+                .erased_serialize(ser)
+                .expect("Request serialization failed.");
+        })
     }
 
-    fn bincode_options() -> impl bincode::Options + Copy {
-        DefaultOptions::new()
-            .with_fixint_encoding()
-            .allow_trailing_bytes()
+    fn process_requests(&self, id: Option<EffectId>, data: &[u8]) -> Vec<Request<Eff::Ffi>>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        self.try_process_requests(id, data)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    fn try_process_requests(
+        &self,
+        id: Option<EffectId>,
+        data: &[u8],
+    ) -> Result<Vec<Request<Eff::Ffi>>, BridgeError>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        let mut result = Ok(vec![]);
+        self.codec.deserialize(data, &mut |deser| {
+            result = self.inner.try_process_requests(id, deser);
+        });
+        let requests = result?;
+
+        if self.observer.is_some() {
+            for request in &requests {
+                let bytes = self.codec.serialize(&mut |ser| {
+                    request
+                        .erased_serialize(ser)
+                        .expect("Request serialization failed.");
+                });
+
+                self.observe(|seq| BridgeEvent::EffectEmitted {
+                    seq,
+                    id: request.id.0,
+                    bytes: &bytes,
+                });
+            }
+        }
+
+        Ok(requests)
+    }
+
+    fn serialize_requests(&self, requests: &[Request<Eff::Ffi>]) -> Vec<u8> {
+        self.codec.serialize(&mut |ser| {
+            requests
+                .erased_serialize(ser)
+                .expect("Request serialization failed.");
+        })
+    }
+
+    fn observe<'a>(&self, make_event: impl FnOnce(u64) -> BridgeEvent<'a>) {
+        if let Some(observer) = &self.observer {
+            let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+            observer(&make_event(seq));
+        }
+    }
+}
+
+impl<Eff, A> Bridge<Eff, A, JsonCodec>
+where
+    Eff: Effect + Send + 'static,
+    A: App,
+{
+    /// Make [`Bridge::view`] return a JSON Patch ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902))
+    /// against the view model it returned last time, rather than the whole view model, to
+    /// save serializing and copying all of a large view model on every render, e.g.
+    ///
+    /// ```rust,ignore
+    /// let bridge = Bridge::new_with_codec(Core::new(), JsonCodec).with_view_patches();
+    /// ```
+    ///
+    /// The shell applies the patch to its copy of the view model. The first patch replaces
+    /// the whole document, and [`Bridge::view_full`] returns the whole view model whenever
+    /// the shell needs it, after which patches continue from there.
+    ///
+    /// Objects are diffed field by field, so only the fields which changed are in the
+    /// patch. Any other value which changed, including an array, is replaced as a whole.
+    /// The same pair of view models always gives the same patch.
+    ///
+    /// Patches are only available with the [`JsonCodec`], because they are made of JSON.
+    #[must_use]
+    pub fn with_view_patches(mut self) -> Self {
+        self.view_patches = Some(ViewPatches::default());
+        self
     }
 }
 
@@ -187,31 +420,63 @@ where
         requests_out: &mut dyn erased_serde::Serializer,
     ) where
         A::Event: for<'a> Deserialize<'a>,
+    {
+        self.process_requests(id, data)
+            .erased_serialize(requests_out)
+            .expect("Request serialization failed.")
+    }
+
+    fn process_requests(
+        &self,
+        id: Option<EffectId>,
+        data: &mut dyn erased_serde::Deserializer,
+    ) -> Vec<Request<Eff::Ffi>>
+    where
+        A::Event: for<'a> Deserialize<'a>,
+    {
+        self.try_process_requests(id, data)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    fn try_process_requests(
+        &self,
+        id: Option<EffectId>,
+        data: &mut dyn erased_serde::Deserializer,
+    ) -> Result<Vec<Request<Eff::Ffi>>, BridgeError>
+    where
+        A::Event: for<'a> Deserialize<'a>,
     {
         let effects = match id {
             None => {
-                let shell_event =
-                    erased_serde::deserialize(data).expect("Message deserialization failed.");
+                let shell_event = erased_serde::deserialize(data).map_err(|error| {
+                    BridgeError::DeserializeEvent {
+                        message: error.to_string(),
+                    }
+                })?;
 
                 self.core.process_event(shell_event)
             }
             Some(id) => {
-                self.registry.resume(id, data).expect(
-                    "Response could not be handled. The request did not expect a response.",
-                );
+                self.registry.resume(id, data)?;
 
                 self.core.process()
             }
         };
 
-        let requests: Vec<_> = effects
+        Ok(effects
             .into_iter()
             .map(|eff| self.registry.register(eff))
-            .collect();
+            .collect())
+    }
 
-        requests
-            .erased_serialize(requests_out)
-            .expect("Request serialization failed.")
+    /// Take the messages of the panics caught in the app's `update` function since this
+    /// was last called, see [`Bridge::take_panics`].
+    pub fn take_panics(&self) -> Vec<String> {
+        self.core
+            .take_panics()
+            .into_iter()
+            .map(|panic| panic.message)
+            .collect()
     }
 
     /// Get the current state of the app's view model (serialized).
@@ -219,9 +484,32 @@ where
     where
         S: ::serde::ser::Serializer,
     {
+        self.view_erased(&mut <dyn erased_serde::Serializer>::erase(ser));
+    }
+
+    /// Get the current state of the app's view model called `name` (serialized), returning
+    /// whether the app has a view by that name. Nothing is serialized if it doesn't.
+    pub fn view_named<S>(&self, name: &str, ser: S) -> bool
+    where
+        S: ::serde::ser::Serializer,
+    {
+        match self.core.view_named(name) {
+            Some(view) => {
+                view.serialize(ser).expect("View should serialize");
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn view_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.core.view()).expect("View should serialize")
+    }
+
+    fn view_erased(&self, ser: &mut dyn erased_serde::Serializer) {
         self.core
             .view()
-            .erased_serialize(&mut <dyn erased_serde::Serializer>::erase(ser))
-            .expect("View should serialize")
+            .erased_serialize(ser)
+            .expect("View should serialize");
     }
 }
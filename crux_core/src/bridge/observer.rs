@@ -0,0 +1,29 @@
+/// Something which happened at the [`Bridge`](crate::bridge::Bridge) boundary, passed
+/// to the observer set with [`Bridge::set_observer`](crate::bridge::Bridge::set_observer).
+///
+/// The bytes are serialized with the bridge's codec, exactly as they cross the boundary.
+/// The `seq` number increases by one with every event reported by the same bridge, so
+/// events can be put in order on a timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeEvent<'a> {
+    /// The core requested an effect from the shell.
+    EffectEmitted { seq: u64, id: u32, bytes: &'a [u8] },
+    /// The shell responded to the effect request with the given `id`.
+    ResponseReceived { seq: u64, id: u32, bytes: &'a [u8] },
+    /// The shell asked for the view model.
+    ViewRendered { seq: u64, bytes: &'a [u8] },
+}
+
+impl BridgeEvent<'_> {
+    /// The sequence number of the event.
+    #[must_use]
+    pub fn seq(&self) -> u64 {
+        match self {
+            BridgeEvent::EffectEmitted { seq, .. }
+            | BridgeEvent::ResponseReceived { seq, .. }
+            | BridgeEvent::ViewRendered { seq, .. } => *seq,
+        }
+    }
+}
+
+pub(crate) type Observer = dyn Fn(&BridgeEvent) + Send + Sync;
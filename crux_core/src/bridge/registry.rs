@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use slab::Slab;
 
 use super::Request;
-use crate::bridge::request_serde::ResolveSerialized;
-use crate::core::ResolveError;
+use crate::bridge::request_serde::{ResolveSerialized, ResolveSerializedError};
+use crate::bridge::BridgeError;
 use crate::Effect;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -47,23 +47,32 @@ impl ResolveRegistry {
     }
     // ANCHOR_END: register
 
-    /// Resume a previously registered effect. This may fail, either because EffectId wasn't
-    /// found or because this effect was not expected to be resumed again.
+    /// Resume a previously registered effect. This may fail, because EffectId wasn't
+    /// found, because this effect was not expected to be resumed again, or because the
+    /// `body` isn't a valid output for it.
     pub fn resume(
         &self,
         id: EffectId,
         body: &mut dyn erased_serde::Deserializer,
-    ) -> Result<(), ResolveError> {
+    ) -> Result<(), BridgeError> {
         let mut registry_lock = self.0.lock().expect("Registry Mutex poisoned");
 
         let entry = registry_lock.get_mut(id.0 as usize);
 
         let Some(entry) = entry else {
-            // FIXME return an Err instead of panicking here.
-            panic!("Request with {id:?} not found.");
+            return Err(BridgeError::UnknownEffect { id: id.0 });
         };
 
-        let resolved = entry.resolve(body);
+        let resolved = entry.resolve(body).map_err(|error| match error {
+            ResolveSerializedError::Resolve(error) => BridgeError::Resolve {
+                id: id.0,
+                message: error.to_string(),
+            },
+            ResolveSerializedError::Deserialize(error) => BridgeError::DeserializeOutput {
+                id: id.0,
+                message: error.to_string(),
+            },
+        });
 
         if let ResolveSerialized::Never = entry {
             registry_lock.remove(id.0 as usize);
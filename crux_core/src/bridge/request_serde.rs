@@ -6,9 +6,12 @@ use crate::{
 
 // used in docs/internals/bridge.md
 // ANCHOR: resolve_serialized
-type ResolveOnceSerialized = Box<dyn FnOnce(&mut dyn erased_serde::Deserializer) + Send>;
-type ResolveManySerialized =
-    Box<dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), ()> + Send>;
+type ResolveOnceSerialized = Box<
+    dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), ResolveSerializedError> + Send,
+>;
+type ResolveManySerialized = Box<
+    dyn FnMut(&mut dyn erased_serde::Deserializer) -> Result<(), ResolveSerializedError> + Send,
+>;
 
 /// A deserializing version of Resolve
 ///
@@ -23,21 +26,28 @@ pub enum ResolveSerialized {
 }
 // ANCHOR_END: resolve_serialized
 
+/// The reason a serialized response could not be resolved
+#[derive(Debug)]
+pub enum ResolveSerializedError {
+    Resolve(ResolveError),
+    Deserialize(erased_serde::Error),
+}
+
 impl ResolveSerialized {
+    /// Resolve with the output read from `bytes`. If it can't be deserialized, the
+    /// request is left as it was, so it can still be resolved.
     pub(crate) fn resolve(
         &mut self,
         bytes: &mut dyn erased_serde::Deserializer,
-    ) -> Result<(), ResolveError> {
+    ) -> Result<(), ResolveSerializedError> {
         match self {
-            ResolveSerialized::Never => Err(ResolveError::Never),
-            ResolveSerialized::Many(f) => f(bytes).map_err(|_| ResolveError::FinishedMany),
-            ResolveSerialized::Once(_) => {
+            ResolveSerialized::Never => Err(ResolveSerializedError::Resolve(ResolveError::Never)),
+            ResolveSerialized::Many(f) => f(bytes),
+            ResolveSerialized::Once(f) => {
+                f(bytes)?;
+
                 // The resolve has been used, turn it into a Never
-                if let ResolveSerialized::Once(f) =
-                    std::mem::replace(self, ResolveSerialized::Never)
-                {
-                    f(bytes);
-                }
+                *self = ResolveSerialized::Never;
 
                 Ok(())
             }
@@ -62,8 +72,21 @@ where
         // FIXME should Eff be bound as `Serializable`?
         let (operation, resolve) = (self.operation, self.resolve);
 
+        // checked against each response, as `Request::resolve` does for the native core
+        let expected = operation.clone();
         let resolve = resolve.deserializing(move |deserializer| {
-            erased_serde::deserialize(deserializer).expect("Deserialization failed")
+            let output: Op::Output = erased_serde::deserialize(deserializer)
+                .map_err(ResolveSerializedError::Deserialize)?;
+
+            if !expected.is_valid_output(&output) {
+                return Err(ResolveSerializedError::Resolve(
+                    ResolveError::UnexpectedResponse {
+                        operation: std::any::type_name::<Op>(),
+                    },
+                ));
+            }
+
+            Ok(output)
         });
 
         (effect(operation), resolve)
@@ -72,21 +95,33 @@ where
 
 impl<Out> Resolve<Out> {
     /// Convert this Resolve into a version which deserializes from bytes, consuming it.
-    /// The `func` argument is a 'deserializer' converting from bytes into the `Out` type.
+    /// The `func` argument is a 'deserializer' converting from bytes into the `Out` type,
+    /// and rejecting outputs the request doesn't expect.
     fn deserializing<F>(self, mut func: F) -> ResolveSerialized
     where
-        F: (FnMut(&mut dyn erased_serde::Deserializer) -> Out) + Send + Sync + 'static,
+        F: (FnMut(&mut dyn erased_serde::Deserializer) -> Result<Out, ResolveSerializedError>)
+            + Send
+            + 'static,
         Out: 'static,
     {
         match self {
             Resolve::Never => ResolveSerialized::Never,
-            Resolve::Once(resolve) => ResolveSerialized::Once(Box::new(move |deser| {
-                let out = func(deser);
-                resolve(out)
-            })),
+            Resolve::Once(resolve) => {
+                // kept until a valid output deserializes, so a bad response can be
+                // followed by a good one
+                let mut resolve = Some(resolve);
+                ResolveSerialized::Once(Box::new(move |deser| {
+                    let out = func(deser)?;
+                    let resolve = resolve.take().expect("Resolve called after it succeeded");
+                    resolve(out);
+
+                    Ok(())
+                }))
+            }
             Resolve::Many(resolve) => ResolveSerialized::Many(Box::new(move |deser| {
-                let out = func(deser);
+                let out = func(deser)?;
                 resolve(out)
+                    .map_err(|()| ResolveSerializedError::Resolve(ResolveError::FinishedMany))
             })),
         }
     }
@@ -0,0 +1,155 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// A JSON Patch (RFC 6902) operation
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// The view model last sent to the shell, to diff the next one against
+#[derive(Default)]
+pub(crate) struct ViewPatches {
+    previous: Mutex<Option<Value>>,
+}
+
+impl ViewPatches {
+    /// A JSON Patch turning the view model last sent into `view`, which becomes the
+    /// one last sent.
+    pub(crate) fn patch(&self, view: Value) -> Vec<u8> {
+        let mut previous = self.previous.lock().expect("View patches Mutex poisoned.");
+
+        let mut operations = vec![];
+        match previous.as_ref() {
+            Some(previous) => diff(&mut String::new(), previous, &view, &mut operations),
+            None => operations.push(Operation::Replace {
+                path: String::new(),
+                value: view.clone(),
+            }),
+        }
+        *previous = Some(view);
+
+        serde_json::to_vec(&operations).expect("View patch serialization failed.")
+    }
+
+    /// The whole of `view`, which becomes the view model last sent.
+    pub(crate) fn full(&self, view: Value) -> Vec<u8> {
+        let bytes = serde_json::to_vec(&view).expect("View serialization failed.");
+        *self.previous.lock().expect("View patches Mutex poisoned.") = Some(view);
+
+        bytes
+    }
+}
+
+// Objects are compared key by key, in the order serde_json iterates them, and anything
+// else which changed, including arrays, is replaced as a whole.
+fn diff(path: &mut String, from: &Value, to: &Value, operations: &mut Vec<Operation>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for key in from.keys().filter(|key| !to.contains_key(*key)) {
+                operations.push(Operation::Remove {
+                    path: pointer(path, key),
+                });
+            }
+            for (key, value) in to {
+                match from.get(key) {
+                    Some(previous) => {
+                        let len = path.len();
+                        *path = pointer(path, key);
+                        diff(path, previous, value, operations);
+                        path.truncate(len);
+                    }
+                    None => operations.push(Operation::Add {
+                        path: pointer(path, key),
+                        value: value.clone(),
+                    }),
+                }
+            }
+        }
+        (from, to) if from == to => {}
+        (_, to) => operations.push(Operation::Replace {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+    }
+}
+
+// A JSON Pointer (RFC 6901) to `key` in the object at `path`
+fn pointer(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{diff, Operation};
+
+    fn operations(from: &serde_json::Value, to: &serde_json::Value) -> Vec<Operation> {
+        let mut operations = vec![];
+        diff(&mut String::new(), from, to, &mut operations);
+        operations
+    }
+
+    #[test]
+    fn nested_changes() {
+        let from = json!({ "count": 1, "user": { "name": "Ann", "email": "ann@example.com" } });
+        let to = json!({ "count": 1, "user": { "name": "Bob", "age": null } });
+
+        assert_eq!(
+            operations(&from, &to),
+            vec![
+                Operation::Remove {
+                    path: "/user/email".to_string()
+                },
+                Operation::Add {
+                    path: "/user/age".to_string(),
+                    value: json!(null)
+                },
+                Operation::Replace {
+                    path: "/user/name".to_string(),
+                    value: json!("Bob")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_are_replaced() {
+        let from = json!({ "items": [1, 2, 3] });
+        let to = json!({ "items": [1, 2] });
+
+        assert_eq!(
+            operations(&from, &to),
+            vec![Operation::Replace {
+                path: "/items".to_string(),
+                value: json!([1, 2])
+            }]
+        );
+    }
+
+    #[test]
+    fn keys_are_escaped() {
+        let from = json!({ "a/b": 1, "c~d": 1 });
+        let to = json!({ "a/b": 2, "c~d": 2 });
+
+        assert_eq!(
+            operations(&from, &to),
+            vec![
+                Operation::Replace {
+                    path: "/a~1b".to_string(),
+                    value: json!(2)
+                },
+                Operation::Replace {
+                    path: "/c~0d".to_string(),
+                    value: json!(2)
+                },
+            ]
+        );
+    }
+}
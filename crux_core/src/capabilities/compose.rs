@@ -3,7 +3,7 @@
 
 use crate::capability::{CapabilityContext, Never};
 use crate::Capability;
-use futures::Future;
+use futures::{future, stream, Future, FutureExt, StreamExt};
 
 /// Compose capability can be used to orchestrate effects into a single transaction.
 ///
@@ -31,6 +31,74 @@ use futures::Future;
 /// }
 /// ```
 ///
+/// Each capability fails with its own error type, like `HttpError` or `KeyValueError`. To
+/// report the failure of a task which uses several of them as one error type of the app,
+/// map each error as it comes with [`TryFutureExt::map_err`](futures::TryFutureExt::map_err),
+/// the same way as [`Result::map_err`], and use `?`:
+///
+/// ```rust
+/// # use crux_core::{compose::Compose, macros::Effect};
+/// # use crux_http::{Http, HttpError};
+/// # use crux_kv::{error::KeyValueError, KeyValue};
+/// use futures::TryFutureExt;
+/// # const PROFILE_URL: &str = "https://example.com/profile";
+/// # #[derive(Default)]
+/// # pub struct App;
+///
+/// pub enum AppError {
+///     Network(HttpError),
+///     Storage(KeyValueError),
+/// }
+///
+/// pub enum Event {
+///     Refresh,
+///     Refreshed(Result<(), AppError>),
+/// }
+/// # #[derive(Effect)]
+/// # pub struct Capabilities {
+/// #     pub http: Http<Event>,
+/// #     pub key_value: KeyValue<Event>,
+/// #     #[effect(skip)]
+/// #     pub compose: Compose<Event>,
+/// # }
+/// # impl crux_core::App for App {
+/// #     type Event = Event;
+/// #     type Model = ();
+/// #     type ViewModel = ();
+/// #     type Capabilities = Capabilities;
+///
+/// fn update(&self, event: Event, _model: &mut (), caps: &Capabilities) {
+///     match event {
+///         Event::Refresh => {
+///             let http = caps.http.clone();
+///             let key_value = caps.key_value.clone();
+///
+///             caps.compose.spawn(|context| async move {
+///                 let result = async {
+///                     let mut response = http
+///                         .get(PROFILE_URL)
+///                         .send_async()
+///                         .map_err(AppError::Network)
+///                         .await?;
+///                     let profile = response.body_bytes().map_err(AppError::Network).await?;
+///
+///                     key_value
+///                         .set_async("profile".to_string(), profile)
+///                         .map_err(AppError::Storage)
+///                         .await?;
+///                     Ok(())
+///                 };
+///
+///                 context.update_app(Event::Refreshed(result.await));
+///             });
+///         }
+///         Event::Refreshed(_) => {}
+///     }
+/// }
+/// #     fn view(&self, _model: &()) {}
+/// # }
+/// ```
+///
 /// Note that testing composed effects is more difficult, because it is not possible to enter the effect
 /// transaction "in the middle" - only from the beginning - or to ignore some of the effects with out
 /// stalling the entire downstream dependency chain.
@@ -135,6 +203,356 @@ impl<Ev> Compose<Ev> {
         let context = self.context.clone();
         self.context.spawn(effects_task(ComposeContext { context }));
     }
+
+    /// Run `future` to completion for its effects alone, discarding its output and sending
+    /// the app no event.
+    ///
+    /// This is for effects the app doesn't need to hear back from, such as logging,
+    /// without inventing an event to ignore. The effects are still driven to completion,
+    /// so requests which expect a response are waited on until the shell resolves them.
+    ///
+    /// ```rust,ignore
+    /// caps.compose.discard(caps.key_value.set_async("last_seen".to_string(), now));
+    /// ```
+    pub fn discard<Fut>(&self, future: Fut)
+    where
+        Fut: Future + Send + 'static,
+        Ev: 'static,
+    {
+        self.context.spawn(future.map(|_| ()));
+    }
+
+    /// Run two futures concurrently and send the app a single event, made by `make_event`
+    /// from both of their outputs once both have completed.
+    ///
+    /// The futures are polled straight away, so effects they request are sent to the shell
+    /// together rather than one after the other, those of `a` first. The outputs are passed
+    /// on in argument order, `(a, b)`, regardless of which completes first.
+    ///
+    /// For example, to render once both a profile and its settings have loaded:
+    ///
+    /// ```rust,ignore
+    /// caps.compose.join(
+    ///     caps.http.get(PROFILE).send_async(),
+    ///     caps.http.get(SETTINGS).send_async(),
+    ///     |(profile, settings)| Event::Loaded(profile, settings),
+    /// );
+    /// ```
+    pub fn join<A, B, FutA, FutB, F>(&self, a: FutA, b: FutB, make_event: F)
+    where
+        FutA: Future<Output = A> + Send + 'static,
+        FutB: Future<Output = B> + Send + 'static,
+        A: Send,
+        B: Send,
+        F: FnOnce((A, B)) -> Ev + Send + 'static,
+        Ev: 'static,
+    {
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            let outputs = future::join(a, b).await;
+            context.update_app(make_event(outputs));
+        });
+    }
+
+    /// Run a number of futures concurrently and send the app a single event, made by
+    /// `make_event` from all of their outputs once every one has completed.
+    ///
+    /// As with [`Compose::join`], effects are sent to the shell together, in the same order
+    /// as `futures`. The outputs are passed on in that order too, regardless of the order
+    /// they complete in.
+    /// An empty set of futures sends the event with an empty `Vec` straight away.
+    pub fn join_all<T, Fut, F>(&self, futures: impl IntoIterator<Item = Fut>, make_event: F)
+    where
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send,
+        F: FnOnce(Vec<T>) -> Ev + Send + 'static,
+        Ev: 'static,
+    {
+        let futures: Vec<_> = futures.into_iter().collect();
+
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            let outputs = future::join_all(futures).await;
+            context.update_app(make_event(outputs));
+        });
+    }
+
+    /// Run `first`, a future of a `Result`, and if it succeeds, pass its value to `then` to
+    /// start a second future, sending the app an event made by `make_event` from that
+    /// future's output. If `first` fails, `then` is not called, and the app is sent an
+    /// event made by `make_err_event` from the error instead.
+    ///
+    /// This saves matching on the `Result` by hand when the second effect only makes sense
+    /// once the first has succeeded. For example, to fetch a profile and then store it,
+    /// but only if the fetch worked:
+    ///
+    /// ```rust
+    /// # use crux_core::{compose::Compose, macros::Effect};
+    /// # use crux_http::{Http, HttpError};
+    /// # use crux_kv::{error::KeyValueError, KeyValue};
+    /// # const PROFILE_URL: &str = "https://example.com/profile";
+    /// # #[derive(Default)]
+    /// # pub struct App;
+    /// pub enum Event {
+    ///     Refresh,
+    ///     Stored(Result<Option<Vec<u8>>, KeyValueError>),
+    ///     FetchFailed(HttpError),
+    /// }
+    /// # #[derive(Effect)]
+    /// # pub struct Capabilities {
+    /// #     pub http: Http<Event>,
+    /// #     pub key_value: KeyValue<Event>,
+    /// #     #[effect(skip)]
+    /// #     pub compose: Compose<Event>,
+    /// # }
+    /// # impl crux_core::App for App {
+    /// #     type Event = Event;
+    /// #     type Model = ();
+    /// #     type ViewModel = ();
+    /// #     type Capabilities = Capabilities;
+    ///
+    /// fn update(&self, event: Event, _model: &mut (), caps: &Capabilities) {
+    ///     match event {
+    ///         Event::Refresh => {
+    ///             let http = caps.http.clone();
+    ///             let key_value = caps.key_value.clone();
+    ///
+    ///             caps.compose.and_then_ok(
+    ///                 async move { http.get(PROFILE_URL).send_async().await?.body_bytes().await },
+    ///                 move |profile| async move {
+    ///                     key_value.set_async("profile".to_string(), profile).await
+    ///                 },
+    ///                 Event::Stored,
+    ///                 Event::FetchFailed,
+    ///             );
+    ///         }
+    ///         Event::Stored(_) | Event::FetchFailed(_) => {}
+    ///     }
+    /// }
+    /// #     fn view(&self, _model: &()) {}
+    /// # }
+    /// ```
+    pub fn and_then_ok<T, E, U, Fut, F, NextFut, G, H>(
+        &self,
+        first: Fut,
+        then: F,
+        make_event: G,
+        make_err_event: H,
+    ) where
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send,
+        E: Send,
+        F: FnOnce(T) -> NextFut + Send + 'static,
+        NextFut: Future<Output = U> + Send + 'static,
+        G: FnOnce(U) -> Ev + Send + 'static,
+        H: FnOnce(E) -> Ev + Send + 'static,
+        Ev: 'static,
+    {
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            let event = match first.await {
+                Ok(value) => make_event(then(value).await),
+                Err(error) => make_err_event(error),
+            };
+            context.update_app(event);
+        });
+    }
+
+    /// Run a future made by `run` for each of the `items` in turn, starting each one only
+    /// once the previous one has completed, and send the app a single event, made by
+    /// `make_event` from their outputs in the same order as `items`. If one of the futures
+    /// fails, the rest of the items are skipped and the event is made from the error
+    /// instead.
+    ///
+    /// Unlike [`Compose::join_all`], which sends all the effects to the shell at once, this
+    /// suits effects which must happen one after the other, like working through a queue
+    /// of uploads. For example, uploading three items where the second fails:
+    ///
+    /// ```rust
+    /// # use crux_core::{compose::Compose, macros::Effect, Core};
+    /// # use crux_http::{protocol::{HttpResponse, HttpResult}, Http, HttpError, ResponseAsync};
+    /// # #[derive(Default)]
+    /// # pub struct App;
+    /// pub enum Event {
+    ///     Upload(Vec<String>),
+    ///     Uploaded(Result<Vec<ResponseAsync>, HttpError>),
+    /// }
+    /// # #[derive(Effect)]
+    /// # pub struct Capabilities {
+    /// #     pub http: Http<Event>,
+    /// #     #[effect(skip)]
+    /// #     pub compose: Compose<Event>,
+    /// # }
+    /// # impl crux_core::App for App {
+    /// #     type Event = Event;
+    /// #     type Model = Option<Result<usize, HttpError>>;
+    /// #     type ViewModel = Option<Result<usize, HttpError>>;
+    /// #     type Capabilities = Capabilities;
+    ///
+    /// fn update(&self, event: Event, model: &mut Self::Model, caps: &Capabilities) {
+    ///     match event {
+    ///         Event::Upload(items) => {
+    ///             let http = caps.http.clone();
+    ///             caps.compose.sequence(
+    ///                 items,
+    ///                 move |item| http.post("https://example.com/items").body(item).send_async(),
+    ///                 Event::Uploaded,
+    ///             );
+    ///         }
+    ///         Event::Uploaded(result) => *model = Some(result.map(|uploaded| uploaded.len())),
+    ///     }
+    /// }
+    /// #     fn view(&self, model: &Self::Model) -> Self::ViewModel {
+    /// #         model.clone()
+    /// #     }
+    /// # }
+    ///
+    /// let core: Core<Effect, App> = Core::new();
+    /// let items = ["one", "two", "three"].map(String::from).to_vec();
+    ///
+    /// // only the first upload is requested to begin with
+    /// let mut effects = core.process_event(Event::Upload(items));
+    /// let Effect::Http(mut first) = effects.remove(0);
+    /// assert!(effects.is_empty());
+    ///
+    /// let mut effects = core
+    ///     .resolve(&mut first, HttpResult::Ok(HttpResponse::ok().build()))
+    ///     .unwrap();
+    /// let Effect::Http(mut second) = effects.remove(0);
+    ///
+    /// // the second upload fails, so the third is never requested
+    /// let error = HttpError::Io("connection reset".to_string());
+    /// let effects = core
+    ///     .resolve(&mut second, HttpResult::Err(error.clone()))
+    ///     .unwrap();
+    /// assert!(effects.is_empty());
+    /// assert_eq!(core.view(), Some(Err(error)));
+    /// ```
+    ///
+    /// An empty set of items sends the event with an empty `Vec` straight away.
+    pub fn sequence<I, F, Fut, T, E, G>(&self, items: I, mut run: F, make_event: G)
+    where
+        I: IntoIterator,
+        I::Item: Send + 'static,
+        F: FnMut(I::Item) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+        G: FnOnce(Result<Vec<T>, E>) -> Ev + Send + 'static,
+        Ev: 'static,
+    {
+        let items: Vec<_> = items.into_iter().collect();
+
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            let mut outputs = Vec::with_capacity(items.len());
+            let mut result = Ok(());
+            for item in items {
+                match run(item).await {
+                    Ok(output) => outputs.push(output),
+                    Err(error) => {
+                        result = Err(error);
+                        break;
+                    }
+                }
+            }
+            context.update_app(make_event(result.map(|()| outputs)));
+        });
+    }
+
+    /// Run a future made by `run` for each of the `items`, with at most `concurrency` of
+    /// them running at once, and send the app a single event, made by `make_event` from
+    /// their outputs in the same order as `items`.
+    ///
+    /// This sits between [`Compose::join_all`], which runs them all at once, and
+    /// [`Compose::sequence`], which runs one at a time. The first `concurrency` futures
+    /// start straight away, and each time one completes, the next item's future starts,
+    /// so fanning out a large number of effects, like prefetching images, doesn't send
+    /// them all to the shell together:
+    ///
+    /// ```rust,ignore
+    /// let http = caps.http.clone();
+    /// caps.compose.buffered(
+    ///     urls,
+    ///     4,
+    ///     move |url| http.get(url).send_async(),
+    ///     Event::Prefetched,
+    /// );
+    /// ```
+    ///
+    /// An empty set of items sends the event with an empty `Vec` straight away.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is zero.
+    pub fn buffered<I, F, Fut, T, G>(&self, items: I, concurrency: usize, run: F, make_event: G)
+    where
+        I: IntoIterator,
+        I::Item: Send + 'static,
+        F: FnMut(I::Item) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+        G: FnOnce(Vec<T>) -> Ev + Send + 'static,
+        Ev: 'static,
+    {
+        assert!(concurrency > 0, "concurrency must be at least 1");
+
+        let items: Vec<_> = items.into_iter().collect();
+
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            let outputs = stream::iter(items)
+                .map(run)
+                .buffered(concurrency)
+                .collect()
+                .await;
+            context.update_app(make_event(outputs));
+        });
+    }
+
+    /// Run a number of futures concurrently and send the app a single event, made by
+    /// `make_event` from the output of whichever future completes first.
+    ///
+    /// The remaining futures are cancelled by dropping them. Any effects they have
+    /// already sent to the shell are not recalled: the shell should still resolve them as
+    /// usual, but their outputs are discarded and never reach the app. To clean up after
+    /// effects which hold resources in the shell, such as timers, race futures which do so
+    /// themselves, like `Time::timeout_async` from `crux_time`.
+    ///
+    /// Futures of different types can be raced by boxing them first, with
+    /// [`FutureExt::boxed`](futures::FutureExt::boxed).
+    ///
+    /// For example, to use whichever of two mirrors responds first:
+    ///
+    /// ```rust,ignore
+    /// caps.compose.race(
+    ///     [
+    ///         caps.http.get(PRIMARY).send_async(),
+    ///         caps.http.get(MIRROR).send_async(),
+    ///     ],
+    ///     Event::Fetched,
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `futures` is empty, as there would be nothing to produce the event.
+    pub fn race<T, Fut, F>(&self, futures: impl IntoIterator<Item = Fut>, make_event: F)
+    where
+        T: 'static,
+        Fut: Future<Output = T> + Send + 'static,
+        F: FnOnce(T) -> Ev + Send + 'static,
+        Ev: 'static,
+    {
+        let futures: Vec<_> = futures.into_iter().map(FutureExt::boxed).collect();
+        assert!(!futures.is_empty(), "cannot race an empty set of futures");
+
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            let (output, _index, _rest) = future::select_all(futures).await;
+            context.update_app(make_event(output));
+        });
+    }
 }
 
 impl<E> Clone for Compose<E> {
@@ -168,3 +586,78 @@ impl<Ev> Capability<Ev> for Compose<Ev> {
         )
     }
 }
+
+/// Events which can carry an error, which [`map_err`] can route to a parent app's error
+/// handling rather than back to the child app.
+///
+/// This is usually implemented on a child app's event type, taking the errors out of
+/// the variants which carry a `Result`:
+///
+/// ```rust
+/// # use crux_core::compose::ErrorEvent;
+/// # pub struct Profile;
+/// # pub struct HttpError;
+/// pub enum Event {
+///     Load,
+///     Loaded(Result<Profile, HttpError>),
+/// }
+///
+/// impl ErrorEvent for Event {
+///     type Error = HttpError;
+///
+///     fn split_error(self) -> Result<Self, Self::Error> {
+///         match self {
+///             Event::Loaded(Err(error)) => Err(error),
+///             event => Ok(event),
+///         }
+///     }
+/// }
+/// ```
+pub trait ErrorEvent: Sized {
+    /// The error the event can carry
+    type Error;
+
+    /// Take the error out of the event if it carries one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error carried by the event, otherwise the event itself in `Ok`.
+    fn split_error(self) -> Result<Self, Self::Error>;
+}
+
+/// Make a function to pass to [`Capability::map_event`] in place of `map_event`, which
+/// sends the errors carried by a child app's events to the parent app, using `map_err`
+/// to make the parent's event. All other events are translated by `map_event` as usual,
+/// typically wrapping them in the parent's event for the child.
+///
+/// This lets a parent app handle the errors of all its children in one place, and the
+/// child never sees the events carrying them. Each capability given to the child is
+/// mapped separately, so use `map_err` for every one of them which can produce an
+/// error, and plain `map_event` for the rest.
+///
+/// ```rust,ignore
+/// impl From<&Capabilities> for child::Capabilities {
+///     fn from(incoming: &Capabilities) -> Self {
+///         child::Capabilities {
+///             http: incoming
+///                 .http
+///                 .map_event(compose::map_err(Event::Child, Event::Failed)),
+///             render: incoming.render.map_event(Event::Child),
+///         }
+///     }
+/// }
+/// ```
+pub fn map_err<ChildEv, Ev, F, G>(
+    map_event: F,
+    map_err: G,
+) -> impl Fn(ChildEv) -> Ev + Send + Sync + 'static
+where
+    ChildEv: ErrorEvent,
+    F: Fn(ChildEv) -> Ev + Send + Sync + 'static,
+    G: Fn(ChildEv::Error) -> Ev + Send + Sync + 'static,
+{
+    move |event| match event.split_error() {
+        Ok(event) => map_event(event),
+        Err(error) => map_err(error),
+    }
+}
@@ -45,6 +45,8 @@ where
 
     /// Call `render` from [`App::update`](crate::App::update) to signal to the Shell that
     /// UI should be re-drawn.
+    ///
+    /// To tell the Shell why, or which part of the UI to re-draw, use [`RenderHints`].
     pub fn render(&self) {
         let ctx = self.context.clone();
         self.context.spawn(async move {
@@ -66,3 +68,91 @@ impl<Ev> Capability<Ev> for Render<Ev> {
         Render::new(self.context.map_event(f))
     }
 }
+
+/// Use an instance of `RenderHints` alongside [`Render`] to notify the Shell that it
+/// should update the user interface, and tell it why, or which part of it to re-draw.
+///
+/// Its requests are a separate effect from those of [`Render`], so a Shell which doesn't
+/// know about hints keeps working as long as the app only uses [`Render`].
+pub struct RenderHints<Ev> {
+    context: CapabilityContext<RenderHint, Ev>,
+}
+
+impl<Ev> Clone for RenderHints<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+/// The single operation `RenderHints` implements, a request to render with a hint.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct RenderHint {
+    /// Why the render was requested, if the app gave a reason with
+    /// [`RenderHints::render_with_reason`]. Shells can use it to decide e.g. whether to
+    /// animate the change, or ignore it.
+    pub reason: Option<String>,
+    /// The part of the UI to re-draw, if the app asked for one with
+    /// [`RenderHints::render_region`]. `None` means the whole UI. Which regions exist is
+    /// up to the app and the Shell, e.g. each could be backed by a named view, see
+    /// [`App::view_named`](crate::App::view_named).
+    pub region: Option<String>,
+}
+
+impl Operation for RenderHint {
+    type Output = ();
+}
+
+impl<Ev> RenderHints<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<RenderHint, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Like [`Render::render`], also telling the Shell why the UI should be re-drawn,
+    /// e.g. `"item_added"`. The meaning of the reason is up to the app and the Shell.
+    pub fn render_with_reason(&self, reason: impl Into<String>) {
+        self.request(RenderHint {
+            reason: Some(reason.into()),
+            ..Default::default()
+        });
+    }
+
+    /// Like [`Render::render`], telling the Shell that only the part of the UI
+    /// identified by `region_id`, e.g. `"sidebar"`, needs to be re-drawn, so it can
+    /// re-read just that part of the view.
+    ///
+    /// With [`Core::with_render_coalescing`](crate::Core::with_render_coalescing),
+    /// several region renders requested back to back are reduced to one per region, and
+    /// to a single render of the whole UI if a render of the whole UI is among them.
+    pub fn render_region(&self, region_id: impl Into<String>) {
+        self.request(RenderHint {
+            region: Some(region_id.into()),
+            ..Default::default()
+        });
+    }
+
+    fn request(&self, hint: RenderHint) {
+        let ctx = self.context.clone();
+        self.context.spawn(async move {
+            ctx.notify_shell(hint).await;
+        });
+    }
+}
+
+impl<Ev> Capability<Ev> for RenderHints<Ev> {
+    type Operation = RenderHint;
+    type MappedSelf<MappedEv> = RenderHints<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static,
+    {
+        RenderHints::new(self.context.map_event(f))
+    }
+}
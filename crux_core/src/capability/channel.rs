@@ -50,7 +50,12 @@ impl<T> Receiver<T> {
         }
     }
 
-    pub fn drain(&self) -> Drain<T> {
+    /// The number of messages waiting to be received.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn drain(&self) -> Drain<'_, T> {
         Drain { receiver: self }
     }
 }
@@ -199,6 +199,7 @@ use std::sync::Arc;
 pub(crate) use channel::channel;
 pub(crate) use executor::{executor_and_spawner, QueuingExecutor};
 
+use crate::core::{Pending, PendingGuard};
 use crate::Request;
 use channel::Sender;
 
@@ -216,6 +217,33 @@ use channel::Sender;
 pub trait Operation: serde::Serialize + Clone + PartialEq + Send + 'static {
     /// `Output` assigns the type this request results in.
     type Output: serde::de::DeserializeOwned + Send + 'static;
+
+    /// Whether `output` is a valid response to this operation. [`Core::resolve`](crate::Core::resolve)
+    /// checks this before handing the response to the capability, and rejects the response
+    /// with [`ResolveError::UnexpectedResponse`](crate::ResolveError::UnexpectedResponse)
+    /// if it isn't valid.
+    ///
+    /// Implement this when `Output` is an enum and only some of its variants answer a given
+    /// operation, so a mismatched response is reported to the shell, instead of the
+    /// capability panicking when it receives it. The default accepts any output.
+    ///
+    /// ```rust,ignore
+    /// impl Operation for PlatformRequest {
+    ///     type Output = PlatformOutput;
+    ///
+    ///     fn is_valid_output(&self, output: &Self::Output) -> bool {
+    ///         matches!(
+    ///             (self, output),
+    ///             (PlatformRequest::Name, PlatformOutput::Name(_))
+    ///                 | (PlatformRequest::Info, PlatformOutput::Info(_))
+    ///         )
+    ///     }
+    /// }
+    /// ```
+    fn is_valid_output(&self, output: &Self::Output) -> bool {
+        let _ = output;
+        true
+    }
 }
 
 /// A type that can be used as a capability operation, but which will never be sent to the shell.
@@ -364,6 +392,13 @@ pub trait WithContext<Ev, Ef> {
     fn new_with_context(context: ProtoContext<Ef, Ev>) -> Self;
 }
 
+/// Names the effect type of an app's `Capabilities`. Implemented by the `Effect` derive
+/// macro alongside [`WithContext`], so that the app can be embedded in another app with
+/// `#[effect(child = ...)]`. You should not need to implement it directly.
+pub trait CapabilitiesEffect {
+    type Effect: crate::Effect;
+}
+
 /// An interface for capabilities to interact with the app and the shell.
 ///
 /// To use [`update_app`](CapabilityContext::update_app), [`notify_shell`](CapabilityContext::notify_shell)
@@ -415,6 +450,7 @@ where
     shell_channel: Sender<Request<Op>>,
     app_channel: Sender<Event>,
     spawner: executor::Spawner,
+    pending: Pending,
 }
 // ANCHOR_END: capability_context
 
@@ -423,6 +459,7 @@ pub struct ProtoContext<Eff, Event> {
     shell_channel: Sender<Eff>,
     app_channel: Sender<Event>,
     spawner: executor::Spawner,
+    pending: Pending,
 }
 
 impl<Op, Ev> Clone for CapabilityContext<Op, Ev>
@@ -445,11 +482,13 @@ where
         shell_channel: Sender<Eff>,
         app_channel: Sender<Ev>,
         spawner: executor::Spawner,
+        pending: Pending,
     ) -> Self {
         Self {
             shell_channel,
             app_channel,
             spawner,
+            pending,
         }
     }
 
@@ -469,6 +508,32 @@ where
             self.shell_channel.map_input(func),
             self.app_channel.clone(),
             self.spawner.clone(),
+            self.pending.clone(),
+        )
+    }
+
+    /// Map the context into one for the capabilities of a child app embedded in this one.
+    /// The child's effects are wrapped into this app's effects with `effect`, typically an
+    /// Effect variant constructor, and its events into this app's events with `event`.
+    ///
+    /// This is called from the implementation of [`WithContext`] generated for fields
+    /// marked `#[effect(child = ...)]`. You should not need to call this function directly.
+    pub fn map_child<ChildEff, ChildEv, F, G>(
+        &self,
+        effect: F,
+        event: G,
+    ) -> ProtoContext<ChildEff, ChildEv>
+    where
+        F: Fn(ChildEff) -> Eff + Sync + Send + 'static,
+        G: Fn(ChildEv) -> Ev + Sync + Send + 'static,
+        ChildEff: 'static,
+        ChildEv: 'static,
+    {
+        ProtoContext::new(
+            self.shell_channel.map_input(effect),
+            self.app_channel.map_input(event),
+            self.spawner.clone(),
+            self.pending.clone(),
         )
     }
 }
@@ -482,11 +547,13 @@ where
         shell_channel: Sender<Request<Op>>,
         app_channel: Sender<Ev>,
         spawner: executor::Spawner,
+        pending: Pending,
     ) -> Self {
         let inner = Arc::new(ContextInner {
             shell_channel,
             app_channel,
             spawner,
+            pending,
         });
 
         CapabilityContext { inner }
@@ -505,9 +572,10 @@ where
         // it's important that it is.  It forces all capabilities to
         // spawn onto the executor which keeps the ordering of effects
         // consistent with their function calls.
-        self.inner
-            .shell_channel
-            .send(Request::resolves_never(operation));
+        let mut request = Request::resolves_never(operation);
+        request.id = self.inner.pending.next_id();
+
+        self.inner.shell_channel.send(request);
     }
 
     /// Send an event to the app. The event will be processed on the next
@@ -604,11 +672,17 @@ where
             self.inner.shell_channel.clone(),
             self.inner.app_channel.map_input(func),
             self.inner.spawner.clone(),
+            self.inner.pending.clone(),
         )
     }
 
-    pub(crate) fn send_request(&self, request: Request<Op>) {
+    /// Send a request expecting a response to the shell. It's listed as pending
+    /// until the returned guard is dropped.
+    pub(crate) fn send_request(&self, mut request: Request<Op>) -> PendingGuard {
+        let guard = self.inner.pending.track::<Op>();
+        request.id = guard.id();
         self.inner.shell_channel.send(request);
+        guard
     }
 }
 
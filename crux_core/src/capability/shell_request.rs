@@ -7,7 +7,7 @@ use std::{
 
 use futures::Future;
 
-use crate::Request;
+use crate::{core::PendingGuard, Request};
 
 pub struct ShellRequest<T> {
     shared_state: Arc<Mutex<SharedState<T>>>,
@@ -21,6 +21,7 @@ impl ShellRequest<()> {
                 result: None,
                 waker: None,
                 send_request: None,
+                pending: None,
             })),
         }
     }
@@ -29,7 +30,9 @@ impl ShellRequest<()> {
 struct SharedState<T> {
     result: Option<T>,
     waker: Option<Waker>,
-    send_request: Option<Box<dyn FnOnce() + Send + 'static>>,
+    send_request: Option<Box<dyn FnOnce() -> PendingGuard + Send + 'static>>,
+    // lists the request as pending from when it's sent until it's resolved
+    pending: Option<PendingGuard>,
 }
 
 impl<T> Future for ShellRequest<T> {
@@ -43,7 +46,7 @@ impl<T> Future for ShellRequest<T> {
 
         // If there's still a request to send, take it and send it
         if let Some(send_request) = shared_state.send_request.take() {
-            send_request();
+            shared_state.pending = Some(send_request());
         }
 
         // If a result has been delivered, we're ready to continue
@@ -77,6 +80,7 @@ where
             result: None,
             waker: None,
             send_request: None,
+            pending: None,
         }));
 
         // Our callback holds a weak pointer to avoid circular references
@@ -96,6 +100,7 @@ where
 
             // Attach the result to the shared state of the future
             shared_state.result = Some(result);
+            shared_state.pending = None;
             // Signal the executor to wake the task holding this future
             if let Some(waker) = shared_state.waker.take() {
                 waker.wake()
@@ -118,6 +123,7 @@ mod tests {
     use assert_matches::assert_matches;
 
     use crate::capability::{channel, executor_and_spawner, CapabilityContext, Operation};
+    use crate::core::Pending;
 
     #[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
     struct TestOperation;
@@ -131,8 +137,13 @@ mod tests {
         let (request_sender, requests) = channel();
         let (event_sender, events) = channel::<()>();
         let (executor, spawner) = executor_and_spawner();
-        let capability_context =
-            CapabilityContext::new(request_sender, event_sender.clone(), spawner.clone());
+        let pending = Pending::default();
+        let capability_context = CapabilityContext::new(
+            request_sender,
+            event_sender.clone(),
+            spawner.clone(),
+            pending.clone(),
+        );
 
         let future = capability_context.request_from_shell(TestOperation);
 
@@ -156,10 +167,12 @@ mod tests {
 
         executor.run_all();
         let mut request = requests.receive().expect("we should have a request here");
+        assert_eq!(pending.effects().len(), 1);
         assert_matches!(requests.receive(), None);
         assert_matches!(events.receive(), None);
 
         request.resolve(()).expect("request should resolve");
+        assert!(pending.effects().is_empty());
 
         assert_matches!(requests.receive(), None);
         assert_matches!(events.receive(), None);
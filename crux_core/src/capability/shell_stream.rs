@@ -6,7 +6,7 @@ use std::{
 use futures::Stream;
 
 use super::{channel, channel::Receiver};
-use crate::core::Request;
+use crate::core::{PendingGuard, Request};
 
 pub struct ShellStream<T> {
     shared_state: Arc<Mutex<SharedState<T>>>,
@@ -15,7 +15,9 @@ pub struct ShellStream<T> {
 struct SharedState<T> {
     receiver: Receiver<T>,
     waker: Option<Waker>,
-    send_request: Option<Box<dyn FnOnce() + Send + 'static>>,
+    send_request: Option<Box<dyn FnOnce() -> PendingGuard + Send + 'static>>,
+    // lists the request as pending from when it's sent until the stream ends
+    pending: Option<PendingGuard>,
 }
 
 impl<T> Stream for ShellStream<T> {
@@ -28,7 +30,7 @@ impl<T> Stream for ShellStream<T> {
         let mut shared_state = self.shared_state.lock().unwrap();
 
         if let Some(send_request) = shared_state.send_request.take() {
-            send_request();
+            shared_state.pending = Some(send_request());
         }
 
         match shared_state.receiver.try_receive() {
@@ -37,7 +39,10 @@ impl<T> Stream for ShellStream<T> {
                 shared_state.waker = Some(cx.waker().clone());
                 Poll::Pending
             }
-            Err(_) => Poll::Ready(None),
+            Err(_) => {
+                shared_state.pending = None;
+                Poll::Ready(None)
+            }
         }
     }
 }
@@ -54,6 +59,7 @@ where
             receiver,
             waker: None,
             send_request: None,
+            pending: None,
         }));
 
         // Our callback holds a weak pointer so the channel can be freed
@@ -91,6 +97,7 @@ mod tests {
     use assert_matches::assert_matches;
 
     use crate::capability::{channel, executor_and_spawner, CapabilityContext, Operation};
+    use crate::core::Pending;
 
     #[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
     struct TestOperation;
@@ -107,8 +114,13 @@ mod tests {
         let (request_sender, requests) = channel();
         let (event_sender, events) = channel::<()>();
         let (executor, spawner) = executor_and_spawner();
-        let capability_context =
-            CapabilityContext::new(request_sender, event_sender.clone(), spawner.clone());
+        let pending = Pending::default();
+        let capability_context = CapabilityContext::new(
+            request_sender,
+            event_sender.clone(),
+            spawner.clone(),
+            pending.clone(),
+        );
 
         let mut stream = capability_context.stream_from_shell(TestOperation);
 
@@ -137,6 +149,7 @@ mod tests {
 
         executor.run_all();
         let mut request = requests.receive().expect("we should have a request here");
+        assert_eq!(pending.effects().len(), 1);
 
         assert_matches!(requests.receive(), None);
         assert_matches!(events.receive(), None);
@@ -155,6 +168,7 @@ mod tests {
         request.resolve(None).unwrap();
         request.resolve(Some(Done)).unwrap();
         executor.run_all();
+        assert!(pending.effects().is_empty());
 
         // We should have three events
         assert_matches!(requests.receive(), None);
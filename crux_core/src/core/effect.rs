@@ -1,6 +1,11 @@
 use serde::Serialize;
 
-use crate::bridge::ResolveSerialized;
+use crate::{
+    bridge::ResolveSerialized,
+    capability::Operation,
+    render::{RenderHint, RenderOperation},
+    Request,
+};
 
 /// Implemented automatically with the Effect macro from `crux_macros`.
 /// This is used by the [`Bridge`](crate::bridge::Bridge) to serialize effects going across the
@@ -19,5 +24,41 @@ pub trait Effect: Send + 'static {
     /// You should not need to call this method directly. It is called by
     /// the [`Bridge`](crate::bridge::Bridge)
     fn serialize(self) -> (Self::Ffi, ResolveSerialized);
+
+    /// Whether the `Effect` is a request to render from the [`Render`](crate::render::Render)
+    /// or [`RenderHints`](crate::render::RenderHints) capability. Used by the
+    /// [`Core`](crate::Core) to coalesce render requests, see
+    /// [`Core::with_render_coalescing`](crate::Core::with_render_coalescing).
+    fn is_render_operation(&self) -> bool {
+        self.render_operation().is_some() || self.render_hint().is_some()
+    }
+
+    /// The [`RenderOperation`] the `Effect` carries, if it is a request to render the
+    /// whole UI.
+    fn render_operation(&self) -> Option<&RenderOperation> {
+        None
+    }
+
+    /// The [`RenderHint`] the `Effect` carries, if it is a request to render with a hint.
+    /// Used by the [`Core`](crate::Core) to coalesce render requests for the same region.
+    fn render_hint(&self) -> Option<&RenderHint> {
+        None
+    }
+
+    /// Take the request out of the `Effect`, if it is a request for operation `Op`,
+    /// or give back the `Effect` unchanged otherwise.
+    ///
+    /// Used by [`Update::expect_effect`](crate::testing::Update::expect_effect).
+    ///
+    /// # Errors
+    ///
+    /// Returns the original `Effect` if it does not carry a request for `Op`.
+    fn downcast<Op>(self) -> Result<Request<Op>, Self>
+    where
+        Op: Operation,
+        Self: Sized,
+    {
+        Err(self)
+    }
 }
 // ANCHOR_END: effect
@@ -1,16 +1,33 @@
 mod effect;
+mod named_view;
+mod panic;
+mod pending;
 mod request;
 mod resolve;
 
-use std::sync::RwLock;
+use std::{
+    collections::HashSet,
+    panic::UnwindSafe,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+};
 
 pub use effect::Effect;
+pub use named_view::NamedView;
+pub use panic::UpdatePanic;
+pub use pending::PendingEffect;
 pub use request::Request;
 pub use resolve::ResolveError;
 
+pub(crate) use pending::{Pending, PendingGuard};
 pub(crate) use resolve::Resolve;
 
+use panic::CatchPanics;
+
 use crate::capability::{self, channel::Receiver, Operation, ProtoContext, QueuingExecutor};
+use crate::middleware::{EffectResponse, MiddlewareStack, Resolver};
 use crate::{App, WithContext};
 
 /// The Crux core. Create an instance of this type with your effect type, and your app type as type parameters
@@ -21,6 +38,28 @@ use crate::{App, WithContext};
 ///
 /// The result of the capability's work can then be sent back to the core using [`Core::resolve`], passing
 /// in the request and the corresponding capability output type.
+///
+/// Shells written in Rust use these methods, together with [`Core::view`], directly. They work
+/// with the app's own types, and nothing is serialized. Shells in other languages go through the
+/// [`Bridge`](crate::bridge::Bridge) instead, which wraps the core and exchanges serialized
+/// events, effect requests and responses.
+///
+/// A Rust shell typically keeps a queue of effects and handles them until it's empty:
+///
+/// ```rust,ignore
+/// let core: Core<Effect, App> = Core::new();
+///
+/// let mut queue: VecDeque<Effect> = core.process_event(Event::Get).into();
+/// while let Some(effect) = queue.pop_front() {
+///     match effect {
+///         Effect::Http(mut request) => {
+///             let response = http::send(&request.operation);
+///             queue.extend(core.resolve(&mut request, response)?);
+///         }
+///         Effect::Render(_) => ui.draw(core.view()),
+///     }
+/// }
+/// ```
 // used in docs/internals/runtime.md
 // ANCHOR: core
 pub struct Core<Ef, A>
@@ -41,7 +80,15 @@ where
     requests: Receiver<Ef>,
     capability_events: Receiver<A::Event>,
     executor: QueuingExecutor,
+    pending: Pending,
+    render_coalescing: bool,
+    event_coalescing: Option<EventCoalescing<A::Event>>,
+    queued_events: AtomicUsize,
+    catch_panics: Option<CatchPanics<A::Model>>,
+    middleware: MiddlewareStack<Ef>,
 }
+
+type EventCoalescing<Event> = Box<dyn Fn(&Event, &Event) -> bool + Send + Sync>;
 // ANCHOR_END: core
 
 impl<Ef, A> Core<Ef, A>
@@ -62,77 +109,351 @@ where
         let (request_sender, request_receiver) = capability::channel();
         let (event_sender, event_receiver) = capability::channel();
         let (executor, spawner) = capability::executor_and_spawner();
-        let capability_context = ProtoContext::new(request_sender, event_sender, spawner);
+        let pending = Pending::default();
+        let capability_context =
+            ProtoContext::new(request_sender, event_sender, spawner, pending.clone());
 
         Self {
             model: Default::default(),
             executor,
+            pending,
             app: Default::default(),
             capabilities: <<A as App>::Capabilities>::new_with_context(capability_context),
             requests: request_receiver,
             capability_events: event_receiver,
+            render_coalescing: false,
+            event_coalescing: None,
+            queued_events: AtomicUsize::new(0),
+            catch_panics: None,
+            middleware: MiddlewareStack::new(),
         }
     }
 
+    /// Coalesce consecutive render requests returned from a single call to
+    /// [`Core::process_event`] or [`Core::resolve`] into one, e.g.
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> = Core::new().with_render_coalescing();
+    /// ```
+    ///
+    /// The view is recomputed as a whole, so a run of render requests with nothing in
+    /// between has the same outcome as a single one. Other effects are left as they are,
+    /// and the order of all the effects is preserved, so a render request on either side
+    /// of another effect is kept.
+    ///
+    /// Render requests for a region, from [`RenderHints::render_region`](crate::render::RenderHints::render_region),
+    /// are coalesced in the same runs. If the run includes a render of the whole UI, only
+    /// the first such render is kept, as it covers every region. Otherwise the first
+    /// render for each region is kept, in the order the regions were first requested.
+    #[must_use]
+    pub fn with_render_coalescing(mut self) -> Self {
+        self.render_coalescing = true;
+        self
+    }
+
+    /// Coalesce consecutive events passed to [`Core::process_events`] together, when
+    /// `coalesce(previous, next)` returns `true`, e.g.
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> = Core::new().with_event_coalescing(|previous, next| {
+    ///     matches!((previous, next), (Event::Resize(..), Event::Resize(..)))
+    /// });
+    /// ```
+    ///
+    /// Of two events which coalesce, the earlier one is dropped and only the later one
+    /// reaches the app's `update` function, so a run of events which coalesce collapses to
+    /// the last of them. This suits events where only the latest matters, like the size of
+    /// a window being resized or the text of a search field. Events are never reordered,
+    /// an event which doesn't coalesce with its predecessor ends the run.
+    #[must_use]
+    pub fn with_event_coalescing<F>(mut self, coalesce: F) -> Self
+    where
+        F: Fn(&A::Event, &A::Event) -> bool + Send + Sync + 'static,
+    {
+        self.event_coalescing = Some(Box::new(coalesce));
+        self
+    }
+
+    /// Pass the effects leaving the core, and the responses coming back, through a
+    /// [`MiddlewareStack`], e.g.
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> =
+    ///     Core::new().with_middleware(MiddlewareStack::new().with(LoggingMiddleware::default()));
+    /// ```
+    ///
+    /// Effects go through the middleware before render requests are coalesced, so
+    /// middleware sees every render request. See the [`middleware`](crate::middleware)
+    /// module for details.
+    #[must_use]
+    pub fn with_middleware(mut self, stack: MiddlewareStack<Ef>) -> Self {
+        self.middleware = stack;
+        self
+    }
+
+    /// Catch panics in the app's `update` function instead of letting them unwind out of
+    /// the core, e.g.
+    ///
+    /// ```rust,ignore
+    /// let core: Core<Effect, App> = Core::new().catch_panics();
+    /// ```
+    ///
+    /// The model is cloned before each update, and if the update panics, the model is
+    /// put back the way it was, as if the event never happened. The panic is kept for
+    /// the shell to collect with [`Core::take_panics`] (or
+    /// [`Bridge::take_panics`](crate::bridge::Bridge::take_panics)) and the core carries
+    /// on processing. Without this, a panic unwinds through [`Core::process_event`] or
+    /// [`Core::resolve`], and across the FFI boundary for shells using the bridge.
+    ///
+    /// The model must be `Clone` to take the snapshot, and `UnwindSafe`, so it can't
+    /// share state with its snapshot through types like `Rc<RefCell<_>>`, which the
+    /// rollback wouldn't restore. Anything else `update` touches is not rolled back:
+    /// capability calls made before the panic still go ahead, and the panic hook still
+    /// runs, so the panic is printed as usual.
+    ///
+    /// Catching panics relies on unwinding, so it has no effect when the app is built
+    /// with `panic = "abort"`.
+    #[must_use]
+    pub fn catch_panics(mut self) -> Self
+    where
+        A::Model: Clone + UnwindSafe,
+    {
+        self.catch_panics = Some(CatchPanics::new(<A::Model as Clone>::clone));
+        self
+    }
+
+    /// Take the panics caught in the app's `update` function since this was last called,
+    /// in the order they happened. Always empty unless the core was created with
+    /// [`Core::catch_panics`].
+    pub fn take_panics(&self) -> Vec<UpdatePanic> {
+        self.catch_panics
+            .as_ref()
+            .map(CatchPanics::take)
+            .unwrap_or_default()
+    }
+
     /// Run the app's `update` function with a given `event`, returning a vector of
     /// effect requests.
+    ///
+    /// The effects are in the order they were requested, which is stable from run to
+    /// run. Each capability call in `update` spawns a task, and the tasks first run in
+    /// the order they were spawned, so effects requested straight away follow the order
+    /// of the calls. Within a task, effects follow the order their futures are first
+    /// polled. For combinators like `futures::join!` or
+    /// [`Compose::join_all`](crate::compose::Compose::join_all), that is argument order.
+    /// Effects requested later, once an earlier request resolves, come after those.
+    ///
+    /// The same holds for [`Core::resolve`], with the tasks woken by the response running
+    /// in the order they were woken. The order is only guaranteed when the core is not
+    /// used from several threads at once.
     // used in docs/internals/runtime.md
     // ANCHOR: process_event
     pub fn process_event(&self, event: A::Event) -> Vec<Ef> {
-        let mut model = self.model.write().expect("Model RwLock was poisoned.");
+        self.update(event);
 
-        self.app.update(event, &mut model, &self.capabilities);
+        self.process()
+    }
+    // ANCHOR_END: process_event
 
-        // drop the model here, we don't want to hold the lock for the process() call
-        drop(model);
+    /// Run the app's `update` function with each of a burst of `events` in turn,
+    /// returning a vector of effect requests from all of them, in order.
+    ///
+    /// This has the same outcome as calling [`Core::process_event`] with each event,
+    /// except that events are coalesced first if the core was created
+    /// [`with_event_coalescing`](Core::with_event_coalescing). Shells receiving events
+    /// faster than they handle effects, e.g. during scrolling or fast typing, can collect
+    /// them and pass them in together.
+    pub fn process_events(&self, events: impl IntoIterator<Item = A::Event>) -> Vec<Ef> {
+        let mut queue: Vec<A::Event> = Vec::new();
+        for event in events {
+            if let (Some(coalesce), Some(previous)) = (&self.event_coalescing, queue.last()) {
+                if coalesce(previous, &event) {
+                    queue.pop();
+                }
+            }
+            queue.push(event);
+        }
+
+        self.queued_events.fetch_add(queue.len(), Ordering::SeqCst);
+        for event in queue {
+            self.queued_events.fetch_sub(1, Ordering::SeqCst);
+            self.update(event);
+            self.run();
+        }
 
         self.process()
     }
-    // ANCHOR_END: process_event
 
-    /// Resolve an effect `request` for operation `Op` with the corresponding result.
+    /// The number of events waiting to be passed to the app's `update` function, made up
+    /// of the rest of a burst being handled by [`Core::process_events`] and events sent
+    /// by capabilities which haven't been processed yet.
+    ///
+    /// This is meant for diagnostics, e.g. to tell whether the core is keeping up with a
+    /// shell which is flooding it with events. The number is a snapshot, and only ever
+    /// non-zero while another thread is processing.
+    pub fn queue_depth(&self) -> usize {
+        self.queued_events.load(Ordering::SeqCst) + self.capability_events.len()
+    }
+
+    /// Resolve an effect `request` for operation `Op` with the corresponding result,
+    /// returning a vector of any further effect requests.
     ///
     /// Note that the `request` is borrowed mutably. When a request that is expected to
     /// only be resolved once is passed in, it will be consumed and changed to a request
     /// which can no longer be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ResolveError`] if the request can't be resolved, because it doesn't
+    /// expect a response, has already been resolved, or belongs to a stream which has
+    /// finished, or if `result` is not a valid response to the operation (see
+    /// [`Operation::is_valid_output`]). The core is left unchanged.
+    ///
+    /// A response of the wrong kind is a bug in the shell, but returning
+    /// [`ResolveError::UnexpectedResponse`] rather than letting the capability panic
+    /// means the shell can log it and carry on.
     // used in docs/internals/runtime.md and docs/internals/bridge.md
     // ANCHOR: resolve
     // ANCHOR: resolve_sig
-    pub fn resolve<Op>(&self, request: &mut Request<Op>, result: Op::Output) -> Vec<Ef>
+    pub fn resolve<Op>(
+        &self,
+        request: &mut Request<Op>,
+        result: Op::Output,
+    ) -> Result<Vec<Ef>, ResolveError>
     where
         Op: Operation,
         // ANCHOR_END: resolve_sig
     {
-        let resolve_result = request.resolve(result);
-        debug_assert!(resolve_result.is_ok());
+        let mut result = result;
+        self.middleware
+            .on_response(&mut EffectResponse::new(request, &mut result));
 
-        self.process()
+        request.resolve(result)?;
+
+        Ok(self.process())
     }
     // ANCHOR_END: resolve
 
     // used in docs/internals/runtime.md
     // ANCHOR: process
     pub(crate) fn process(&self) -> Vec<Ef> {
+        let effects = self.run_middleware();
+        if !self.render_coalescing {
+            return effects;
+        }
+
+        let mut coalesced = Vec::new();
+        let mut renders = Vec::new();
+        for effect in effects {
+            if effect.is_render_operation() {
+                renders.push(effect);
+            } else {
+                coalesce_renders(&mut renders, &mut coalesced);
+                coalesced.push(effect);
+            }
+        }
+        coalesce_renders(&mut renders, &mut coalesced);
+
+        coalesced
+    }
+
+    // runs the tasks which can make progress, and the app's `update` function with any
+    // events they send, until there is nothing left to do
+    fn run(&self) {
         self.executor.run_all();
 
         while let Some(capability_event) = self.capability_events.receive() {
-            let mut model = self.model.write().expect("Model RwLock was poisoned.");
-            self.app
-                .update(capability_event, &mut model, &self.capabilities);
-            drop(model);
+            self.update(capability_event);
             self.executor.run_all();
         }
+    }
+
+    // runs until there is nothing left to do, passing the effects requested through the
+    // middleware, and running again while the middleware resolves some of them
+    fn run_middleware(&self) -> Vec<Ef> {
+        self.run();
+        if self.middleware.is_empty() {
+            return self.requests.drain().collect();
+        }
 
-        self.requests.drain().collect()
+        let mut effects = Vec::new();
+        loop {
+            let resolver = Resolver::default();
+            effects.extend(
+                self.requests
+                    .drain()
+                    .filter_map(|effect| self.middleware.on_effect(effect, &resolver)),
+            );
+
+            if !resolver.resolved() {
+                return effects;
+            }
+            self.run();
+        }
     }
     // ANCHOR_END: process
 
+    fn update(&self, event: A::Event) {
+        let mut model = self.model.write().expect("Model RwLock was poisoned.");
+
+        match &self.catch_panics {
+            None => self.app.update(event, &mut model, &self.capabilities),
+            Some(catch_panics) => catch_panics.update(&mut model, |model| {
+                self.app.update(event, model, &self.capabilities);
+            }),
+        }
+
+        // the lock is released here, we don't want to hold it while the executor runs
+    }
+
     /// Get the current state of the app's view model.
     pub fn view(&self) -> A::ViewModel {
         let model = self.model.read().expect("Model RwLock was poisoned.");
 
         self.app.view(&model)
     }
+
+    /// Get the current state of the app's view model called `name`, or `None` if the app
+    /// has no view by that name. See [`App::view_named`].
+    pub fn view_named(&self, name: &str) -> Option<NamedView> {
+        let model = self.model.read().expect("Model RwLock was poisoned.");
+
+        self.app.view_named(name, &model)
+    }
+
+    /// List the effect requests which have been sent to the shell and are still
+    /// waiting on a response, in the order they were sent.
+    ///
+    /// This is meant for diagnostics, e.g. finding a request the shell never resolved
+    /// when the UI seems stuck. Requests which don't expect a response, like render,
+    /// are never listed. A stream request stays listed for as long as the capability
+    /// is listening to it.
+    ///
+    /// The list is a snapshot, no locks are held once it is returned.
+    pub fn pending_effects(&self) -> Vec<PendingEffect> {
+        self.pending.effects()
+    }
+}
+
+// Moves a run of consecutive render requests into `effects`, keeping the first render of
+// the whole UI if there is one, or else the first render of each region
+fn coalesce_renders<Ef: Effect>(renders: &mut Vec<Ef>, effects: &mut Vec<Ef>) {
+    fn region<Ef: Effect>(effect: &Ef) -> Option<&str> {
+        effect.render_hint().and_then(|hint| hint.region.as_deref())
+    }
+
+    if let Some(whole) = renders.iter().position(|render| region(render).is_none()) {
+        effects.push(renders.swap_remove(whole));
+        renders.clear();
+        return;
+    }
+
+    let mut regions = HashSet::new();
+    for render in renders.drain(..) {
+        if regions.insert(region(&render).map(ToOwned::to_owned)) {
+            effects.push(render);
+        }
+    }
 }
 
 impl<Ef, A> Default for Core<Ef, A>
@@ -0,0 +1,29 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// A view model of any serializable type, built by
+/// [`App::view_named`](crate::App::view_named) for views other than the app's main one.
+pub struct NamedView(pub(crate) Box<dyn erased_serde::Serialize>);
+
+impl NamedView {
+    /// Wrap `view` to return it from [`App::view_named`](crate::App::view_named).
+    pub fn new(view: impl Serialize + 'static) -> Self {
+        Self(Box::new(view))
+    }
+}
+
+impl Serialize for NamedView {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        erased_serde::serialize(self.0.as_ref(), serializer)
+    }
+}
+
+impl fmt::Debug for NamedView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NamedView(..)")
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::Mutex,
+};
+
+/// A panic in the app's `update` function, caught by a core created with
+/// [`Core::catch_panics`](crate::Core::catch_panics) and returned by
+/// [`Core::take_panics`](crate::Core::take_panics).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdatePanic {
+    /// The message the app panicked with, or a placeholder if the panic payload
+    /// wasn't a string
+    pub message: String,
+}
+
+impl UpdatePanic {
+    fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            (*message).to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "update panicked with a non-string payload".to_string()
+        };
+
+        Self { message }
+    }
+}
+
+/// Runs updates so that a panic leaves the model as it was before the update
+pub(crate) struct CatchPanics<Model> {
+    snapshot: fn(&Model) -> Model,
+    caught: Mutex<Vec<UpdatePanic>>,
+}
+
+impl<Model> CatchPanics<Model> {
+    pub(crate) fn new(snapshot: fn(&Model) -> Model) -> Self {
+        Self {
+            snapshot,
+            caught: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn update(&self, model: &mut Model, update: impl FnOnce(&mut Model)) {
+        let snapshot = (self.snapshot)(model);
+
+        // The model is restored from the snapshot if `update` panics, so it's never
+        // observed in a broken state. `Core::catch_panics` requires `Model: UnwindSafe`
+        // to rule out state shared between the model and its snapshot.
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| update(model))) {
+            *model = snapshot;
+            self.caught
+                .lock()
+                .expect("Caught panics Mutex poisoned.")
+                .push(UpdatePanic::from_payload(payload.as_ref()));
+        }
+    }
+
+    pub(crate) fn take(&self) -> Vec<UpdatePanic> {
+        std::mem::take(&mut *self.caught.lock().expect("Caught panics Mutex poisoned."))
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    any::type_name,
+    collections::BTreeMap,
+    sync::{Arc, Mutex, Weak},
+};
+
+/// An effect request which has been sent to the shell and which the core is still
+/// waiting on a response for, as returned by
+/// [`Core::pending_effects`](crate::Core::pending_effects).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingEffect {
+    /// A number identifying the request, increasing in the order the requests were
+    /// sent. It is the same as the request's [`Request::id`](crate::Request::id), and
+    /// unrelated to the ids used by the [`Bridge`](crate::bridge::Bridge).
+    pub id: u64,
+    /// The name of the request's operation type, as given by [`std::any::type_name`].
+    /// It is meant for display and its exact format may change.
+    pub operation: &'static str,
+}
+
+/// The effect requests the capabilities are waiting on, shared by all the capability
+/// contexts of a core.
+#[derive(Clone, Default)]
+pub(crate) struct Pending(Arc<Mutex<PendingInner>>);
+
+#[derive(Default)]
+struct PendingInner {
+    next_id: u64,
+    operations: BTreeMap<u64, &'static str>,
+}
+
+impl Pending {
+    /// Start tracking a request for operation `Op`, until the returned guard is dropped.
+    pub(crate) fn track<Op>(&self) -> PendingGuard {
+        let mut inner = self.0.lock().expect("Pending effects Mutex poisoned.");
+        let id = inner.next_id();
+        inner.operations.insert(id, type_name::<Op>());
+
+        PendingGuard {
+            id,
+            pending: Arc::downgrade(&self.0),
+        }
+    }
+
+    /// An id for a request which isn't tracked, because it doesn't expect a response
+    pub(crate) fn next_id(&self) -> u64 {
+        self.0
+            .lock()
+            .expect("Pending effects Mutex poisoned.")
+            .next_id()
+    }
+
+    pub(crate) fn effects(&self) -> Vec<PendingEffect> {
+        let inner = self.0.lock().expect("Pending effects Mutex poisoned.");
+
+        inner
+            .operations
+            .iter()
+            .map(|(&id, &operation)| PendingEffect { id, operation })
+            .collect()
+    }
+}
+
+impl PendingInner {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+/// Keeps a request listed as pending while it's alive
+pub(crate) struct PendingGuard {
+    id: u64,
+    pending: Weak<Mutex<PendingInner>>,
+}
+
+impl PendingGuard {
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        if let Some(pending) = self.pending.upgrade() {
+            if let Ok(mut inner) = pending.lock() {
+                inner.operations.remove(&self.id);
+            }
+        }
+    }
+}
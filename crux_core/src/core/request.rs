@@ -1,4 +1,7 @@
-use std::fmt::{self, Debug};
+use std::{
+    any::Any,
+    fmt::{self, Debug},
+};
 
 use crate::{
     capability::Operation,
@@ -18,6 +21,7 @@ where
 {
     pub operation: Op,
     pub(crate) resolve: Resolve<Op::Output>,
+    pub(crate) id: u64,
 }
 
 impl<Op> Request<Op>
@@ -28,6 +32,7 @@ where
         Self {
             operation,
             resolve: Resolve::Never,
+            id: 0,
         }
     }
 
@@ -38,6 +43,7 @@ where
         Self {
             operation,
             resolve: Resolve::Once(Box::new(resolve)),
+            id: 0,
         }
     }
 
@@ -48,12 +54,53 @@ where
         Self {
             operation,
             resolve: Resolve::Many(Box::new(resolve)),
+            id: 0,
         }
     }
 
+    /// A number identifying the request, increasing in the order the requests were sent
+    /// by the core. It's the same number as the [`PendingEffect::id`](crate::PendingEffect)
+    /// of the request while the core waits on a response, and is unrelated to the ids
+    /// used by the [`Bridge`](crate::bridge::Bridge).
+    ///
+    /// This is mostly useful in tests, to tell apart several requests for the same
+    /// operation, e.g. which of the requests raced against each other won.
+    #[must_use]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     pub(crate) fn resolve(&mut self, output: Op::Output) -> Result<(), ResolveError> {
+        if !matches!(self.resolve, Resolve::Never) && !self.operation.is_valid_output(&output) {
+            return Err(ResolveError::UnexpectedResponse {
+                operation: std::any::type_name::<Op>(),
+            });
+        }
+
         self.resolve.resolve(output)
     }
+
+    /// Convert the request into a request for operation `Other`, if that is the
+    /// operation it carries, or give it back unchanged otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the original request if `Other` is not `Op`.
+    pub fn downcast<Other>(self) -> Result<Request<Other>, Self>
+    where
+        Other: Operation,
+    {
+        let request: Box<dyn Any> = Box::new(self);
+
+        request
+            .downcast::<Request<Other>>()
+            .map(|request| *request)
+            .map_err(|request| {
+                *request
+                    .downcast::<Self>()
+                    .expect("request of the same type")
+            })
+    }
 }
 
 impl<Op> fmt::Debug for Request<Op>
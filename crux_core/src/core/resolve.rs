@@ -31,10 +31,26 @@ impl<Out> Resolve<Out> {
     }
 }
 
-#[derive(Error, Debug)]
+/// The reason a request could not be resolved, returned by
+/// [`Core::resolve`](crate::Core::resolve).
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResolveError {
+    /// The request doesn't expect a response, either because the operation has none
+    /// (e.g. render), or because it expects one and has already been resolved.
     #[error("Attempted to resolve a request that is not expected to be resolved.")]
     Never,
+    /// The request expects a stream of responses, but the stream has ended.
     #[error("Attempted to resolve a request that has concluded.")]
     FinishedMany,
+    /// The response is not one the operation expects, according to
+    /// [`Operation::is_valid_output`](crate::capability::Operation::is_valid_output),
+    /// e.g. a response variant belonging to a different operation.
+    ///
+    /// This is a bug in the shell, but one the app can survive: the response is dropped
+    /// and the request is left as it was, so it can still be resolved with a valid response.
+    #[error("Attempted to resolve a request for {operation} with a response it does not expect.")]
+    UnexpectedResponse {
+        /// The type name of the operation
+        operation: &'static str,
+    },
 }
@@ -151,6 +151,7 @@
 
 pub mod bridge;
 pub mod capability;
+pub mod middleware;
 pub mod testing;
 #[cfg(feature = "typegen")]
 pub mod typegen;
@@ -163,7 +164,7 @@ use serde::Serialize;
 pub use self::{
     capabilities::*,
     capability::{Capability, WithContext},
-    core::{Core, Effect, Request},
+    core::{Core, Effect, NamedView, PendingEffect, Request, ResolveError, UpdatePanic},
 };
 pub use crux_macros as macros;
 
@@ -194,4 +195,26 @@ pub trait App: Default {
 
     /// View method is used by the Shell to request the current state of the user interface
     fn view(&self, model: &Self::Model) -> Self::ViewModel;
+
+    /// Build the view model called `name`, for apps which offer the shell more than one
+    /// view of their state, e.g. a "badge" view, cheap enough to recompute on every change,
+    /// alongside the full view returned by [`App::view`]. The shell asks for a named view
+    /// with [`Bridge::view_named`](bridge::Bridge::view_named).
+    ///
+    /// Returns `None` if the app has no view called `name`, which is what the default
+    /// implementation does for every name. Each named view can have its own type, which
+    /// shells using generated types need to register with the type generator themselves.
+    ///
+    /// ```rust,ignore
+    /// fn view_named(&self, name: &str, model: &Model) -> Option<NamedView> {
+    ///     match name {
+    ///         "badge" => Some(NamedView::new(Badge { unread: model.unread.len() })),
+    ///         _ => None,
+    ///     }
+    /// }
+    /// ```
+    fn view_named(&self, name: &str, model: &Self::Model) -> Option<NamedView> {
+        let _ = (name, model);
+        None
+    }
 }
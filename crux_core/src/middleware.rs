@@ -0,0 +1,241 @@
+//! Middleware sits between the [`Core`](crate::Core) and the shell. It sees the effects
+//! on their way out of the core, and the responses on their way back in.
+//!
+//! Implement [`EffectMiddleware`] to observe or change that traffic, e.g. to log it, to
+//! count requests for metrics, or to answer some effects in place of the shell in
+//! integration tests. Stack up middleware with a [`MiddlewareStack`] and give it to
+//! the core with [`Core::with_middleware`](crate::Core::with_middleware):
+//!
+//! ```rust,ignore
+//! let core: Core<Effect, App> = Core::new().with_middleware(
+//!     MiddlewareStack::new()
+//!         .with(LoggingMiddleware::default())
+//!         .with(Metrics::default()),
+//! );
+//! ```
+//!
+//! Middleware works with the app's own effect and operation types, so it sees what a
+//! Rust shell sees. A shell using the [`Bridge`](crate::bridge::Bridge) gets the effects
+//! which made it through the middleware, but its responses are resolved from their
+//! serialized form and don't pass through [`EffectMiddleware::on_response`].
+
+use std::{
+    any::{type_name, Any},
+    cell::Cell,
+    fmt::Debug,
+};
+
+use crate::{capability::Operation, Request, ResolveError};
+
+/// A layer between the [`Core`](crate::Core) and the shell, see the
+/// [module documentation](self).
+///
+/// Both methods have a default implementation which lets the traffic through
+/// unchanged, so implement only the ones you need.
+pub trait EffectMiddleware<Ef>: Send + Sync {
+    /// Called with each effect requested by the app, before it is returned to the shell.
+    ///
+    /// Return the effect, changed or not, to pass it on, or `None` to hold it back from
+    /// the shell. An effect which is held back can be resolved with the `resolver`, in
+    /// which case the core carries on with the response as if it came from the shell,
+    /// and returns any further effects from the same call. A request which is dropped
+    /// without being resolved is never answered.
+    fn on_effect(&self, effect: Ef, resolver: &Resolver) -> Option<Ef> {
+        let _ = resolver;
+        Some(effect)
+    }
+
+    /// Called with each response passed to [`Core::resolve`](crate::Core::resolve),
+    /// before it is handed to the capability waiting on it. The response can be
+    /// changed through [`EffectResponse::output_mut`].
+    fn on_response(&self, response: &mut EffectResponse<'_>) {
+        let _ = response;
+    }
+}
+
+/// A stack of [`EffectMiddleware`], in the order it was added.
+///
+/// The first middleware added is closest to the core. Effects pass through the stack
+/// from the first to the last middleware on their way to the shell, and responses from
+/// the last to the first on their way back. An effect held back by one middleware is
+/// not seen by the ones after it.
+pub struct MiddlewareStack<Ef> {
+    layers: Vec<Box<dyn EffectMiddleware<Ef>>>,
+}
+
+impl<Ef> MiddlewareStack<Ef> {
+    /// An empty stack, which lets all effects and responses through unchanged
+    #[must_use]
+    pub fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Add `middleware` to the stack, further from the core than the middleware already
+    /// in it.
+    #[must_use]
+    pub fn with<M>(mut self, middleware: M) -> Self
+    where
+        M: EffectMiddleware<Ef> + 'static,
+    {
+        self.layers.push(Box::new(middleware));
+        self
+    }
+
+    /// Whether the stack has no middleware in it
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub(crate) fn on_effect(&self, effect: Ef, resolver: &Resolver) -> Option<Ef> {
+        self.layers
+            .iter()
+            .try_fold(effect, |effect, layer| layer.on_effect(effect, resolver))
+    }
+
+    pub(crate) fn on_response(&self, response: &mut EffectResponse<'_>) {
+        for layer in self.layers.iter().rev() {
+            layer.on_response(response);
+        }
+    }
+}
+
+impl<Ef> Default for MiddlewareStack<Ef> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolves requests held back by an [`EffectMiddleware`], in place of the shell.
+/// Passed to [`EffectMiddleware::on_effect`].
+#[derive(Default)]
+pub struct Resolver {
+    resolved: Cell<bool>,
+}
+
+impl Resolver {
+    /// Resolve `request` with `output`, as [`Core::resolve`](crate::Core::resolve) would.
+    /// The core runs the app with the response once the middleware returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ResolveError`] if the request can't be resolved, see
+    /// [`Core::resolve`](crate::Core::resolve).
+    pub fn resolve<Op>(
+        &self,
+        request: &mut Request<Op>,
+        output: Op::Output,
+    ) -> Result<(), ResolveError>
+    where
+        Op: Operation,
+    {
+        request.resolve(output)?;
+        self.resolved.set(true);
+
+        Ok(())
+    }
+
+    pub(crate) fn resolved(&self) -> bool {
+        self.resolved.get()
+    }
+}
+
+/// A response on its way from the shell back to the core, passed to
+/// [`EffectMiddleware::on_response`].
+pub struct EffectResponse<'a> {
+    id: u64,
+    operation_name: &'static str,
+    operation: &'a dyn Any,
+    output: &'a mut dyn Any,
+}
+
+impl<'a> EffectResponse<'a> {
+    pub(crate) fn new<Op>(request: &'a Request<Op>, output: &'a mut Op::Output) -> Self
+    where
+        Op: Operation,
+    {
+        Self {
+            id: request.id(),
+            operation_name: type_name::<Op>(),
+            operation: &request.operation,
+            output,
+        }
+    }
+
+    /// The [id](Request::id) of the request being resolved
+    #[must_use]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The type name of the operation the request is for, e.g. for logging
+    #[must_use]
+    pub fn operation_name(&self) -> &'static str {
+        self.operation_name
+    }
+
+    /// The operation the request is for, if it is an `Op`
+    #[must_use]
+    pub fn operation<Op: Operation>(&self) -> Option<&Op> {
+        self.operation.downcast_ref()
+    }
+
+    /// The response, if it is an `Out`
+    #[must_use]
+    pub fn output<Out: 'static>(&self) -> Option<&Out> {
+        self.output.downcast_ref()
+    }
+
+    /// The response, if it is an `Out`, to change it before the core sees it
+    #[must_use]
+    pub fn output_mut<Out: 'static>(&mut self) -> Option<&mut Out> {
+        self.output.downcast_mut()
+    }
+}
+
+/// Middleware which logs every effect and response passing through it.
+///
+/// Effects are logged with their `Debug` representation. Responses are logged with
+/// the id and the operation of the request they resolve, as their type is only known
+/// to the capability.
+///
+/// By default the lines are printed to stderr, use [`LoggingMiddleware::new`] to send
+/// them somewhere else.
+pub struct LoggingMiddleware {
+    log: Box<dyn Fn(&str) + Send + Sync>,
+}
+
+impl LoggingMiddleware {
+    /// Log with the given function, e.g.
+    ///
+    /// ```rust,ignore
+    /// LoggingMiddleware::new(|line| log::debug!("{line}"))
+    /// ```
+    pub fn new<F>(log: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        Self { log: Box::new(log) }
+    }
+}
+
+impl Default for LoggingMiddleware {
+    fn default() -> Self {
+        Self::new(|line| eprintln!("{line}"))
+    }
+}
+
+impl<Ef: Debug> EffectMiddleware<Ef> for LoggingMiddleware {
+    fn on_effect(&self, effect: Ef, _resolver: &Resolver) -> Option<Ef> {
+        (self.log)(&format!("effect: {effect:?}"));
+        Some(effect)
+    }
+
+    fn on_response(&self, response: &mut EffectResponse<'_>) {
+        (self.log)(&format!(
+            "response: #{} {}",
+            response.id(),
+            response.operation_name()
+        ));
+    }
+}
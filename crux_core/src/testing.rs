@@ -1,12 +1,22 @@
 //! Testing support for unit testing Crux apps.
 use anyhow::Result;
-use std::{collections::VecDeque, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::TypeId,
+    collections::{HashMap, VecDeque},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use crate::{
+    bridge::{Bridge, BridgeCodec},
     capability::{
         channel::Receiver, executor_and_spawner, Operation, ProtoContext, QueuingExecutor,
     },
-    Request, WithContext,
+    core::Pending,
+    render::{RenderHint, RenderOperation},
+    Core, Effect, PendingEffect, Request, WithContext,
 };
 
 /// AppTester is a simplified execution environment for Crux apps for use in
@@ -33,6 +43,7 @@ struct AppContext<Ef, Ev> {
     commands: Receiver<Ef>,
     events: Receiver<Ev>,
     executor: QueuingExecutor,
+    pending: Pending,
 }
 
 impl<App, Ef> AppTester<App, Ef>
@@ -95,6 +106,16 @@ where
     pub fn view(&self, model: &App::Model) -> App::ViewModel {
         self.app.view(model)
     }
+
+    /// List the effect requests which are still waiting on a response, in the order they
+    /// were sent, as [`Core::pending_effects`](crate::Core::pending_effects) does.
+    ///
+    /// Each is listed with the [`Request::id`] of its request, so a test can check which
+    /// of several requests are left unresolved, e.g. that the losers of a race are no
+    /// longer pending once the winner is resolved.
+    pub fn pending_effects(&self) -> Vec<PendingEffect> {
+        self.context.pending.effects()
+    }
 }
 
 impl<App, Ef> Default for AppTester<App, Ef>
@@ -107,7 +128,9 @@ where
         let (command_sender, commands) = crate::capability::channel();
         let (event_sender, events) = crate::capability::channel();
         let (executor, spawner) = executor_and_spawner();
-        let capability_context = ProtoContext::new(command_sender, event_sender, spawner);
+        let pending = Pending::default();
+        let capability_context =
+            ProtoContext::new(command_sender, event_sender, spawner, pending.clone());
 
         Self {
             app: App::default(),
@@ -116,6 +139,7 @@ where
                 commands,
                 events,
                 executor,
+                pending,
             }),
         }
     }
@@ -229,6 +253,450 @@ impl<Ef, Ev> Update<Ef, Ev> {
     }
 }
 
+impl<Ef, Ev> Update<Ef, Ev>
+where
+    Ef: Effect + fmt::Debug,
+{
+    /// Take the next effect out of the [`Update`] and return its request, which must be
+    /// for operation `Op`, e.g.
+    ///
+    /// ```rust,ignore
+    /// let request = update.expect_effect::<HttpRequest>();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no effects left, or if the next effect is not a request for
+    /// operation `Op`, describing the effect found instead.
+    pub fn expect_effect<Op>(&mut self) -> Request<Op>
+    where
+        Op: Operation,
+    {
+        assert!(
+            !self.effects.is_empty(),
+            "Expected a {} effect but found no effects",
+            std::any::type_name::<Op>()
+        );
+
+        match self.effects.remove(0).downcast::<Op>() {
+            Ok(request) => request,
+            Err(effect) => panic!(
+                "Expected a {} effect but found {:?}",
+                std::any::type_name::<Op>(),
+                effect
+            ),
+        }
+    }
+
+    /// Take the next effect out of the [`Update`], which must be a request to render
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no effects left, or if the next effect is not a render request.
+    pub fn expect_render(&mut self) -> Request<RenderOperation> {
+        self.expect_effect()
+    }
+
+    /// Take the next effect out of the [`Update`], which must be a request to render with
+    /// a hint, from [`RenderHints`](crate::render::RenderHints)
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no effects left, or if the next effect is not a request to
+    /// render with a hint.
+    pub fn expect_render_hint(&mut self) -> Request<RenderHint> {
+        self.expect_effect()
+    }
+
+    /// Assert that the update contains no effects, regardless of any events
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any effects, listing them.
+    pub fn expect_no_effects(&self) {
+        assert!(
+            self.effects.is_empty(),
+            "Expected no effects but found {:?}",
+            self.effects
+        );
+    }
+}
+
+/// Runs a [`Core`] end to end, answering its effects with fakes in place of the shell.
+///
+/// Register a fake for each kind of operation with [`Simulator::with_fake`]. Each event
+/// given to [`Simulator::run`] is processed by the core, and every effect it requests is
+/// resolved with the fake registered for its operation, which may lead to more effects,
+/// until the core settles. Then the test can check the view, e.g.
+///
+/// ```rust,ignore
+/// let sim = Simulator::new(Core::<Effect, App>::new())
+///     .with_fake(|_request: &HttpRequest| {
+///         HttpResult::Ok(HttpResponse::ok().body("42").build())
+///     });
+///
+/// sim.run(Event::Fetch);
+/// assert_eq!(sim.view(), "42");
+/// ```
+///
+/// A fake answers each request once, so a stream of responses ends after its first one.
+pub struct Simulator<Ef, A>
+where
+    A: crate::App,
+{
+    core: Core<Ef, A>,
+    fakes: HashMap<TypeId, Box<FakeResolver<Ef, A>>>,
+}
+
+type FakeResolver<Ef, A> = dyn Fn(&Core<Ef, A>, Ef) -> std::result::Result<Vec<Ef>, Ef>;
+
+/// How many effects the [`Simulator`] resolves for one event before deciding the core
+/// will never settle, e.g. because a fake keeps answering a polling loop.
+const MAX_SIMULATED_EFFECTS: usize = 10_000;
+
+impl<Ef, A> Simulator<Ef, A>
+where
+    Ef: Effect,
+    A: crate::App,
+{
+    /// Simulate the shell for `core`, which has no fakes registered yet
+    pub fn new(core: Core<Ef, A>) -> Self {
+        Self {
+            core,
+            fakes: HashMap::new(),
+        }
+    }
+
+    /// Register `fake` to answer every request for operation `Op`, replacing any fake
+    /// already registered for it.
+    #[must_use]
+    pub fn with_fake<Op, F>(mut self, fake: F) -> Self
+    where
+        Op: Operation,
+        F: Fn(&Op) -> Op::Output + 'static,
+    {
+        let resolver = move |core: &Core<Ef, A>, effect: Ef| {
+            let mut request = effect.downcast::<Op>()?;
+            let output = fake(&request.operation);
+
+            Ok(core.resolve(&mut request, output).unwrap_or_else(|e| {
+                panic!(
+                    "failed to resolve {} with a fake: {e}",
+                    std::any::type_name::<Op>()
+                )
+            }))
+        };
+        self.fakes.insert(TypeId::of::<Op>(), Box::new(resolver));
+
+        self
+    }
+
+    /// Process `event`, and resolve the effects it leads to with the fakes until the
+    /// core settles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a fake gives a response the core can't resolve its request with, or if
+    /// the core doesn't settle.
+    pub fn run(&self, event: A::Event) -> Settled<Ef> {
+        let mut settled = Settled {
+            renders: 0,
+            unhandled: Vec::new(),
+        };
+        let mut effects: VecDeque<Ef> = self.core.process_event(event).into();
+        let mut resolved = 0;
+
+        while let Some(effect) = effects.pop_front() {
+            if effect.is_render_operation() {
+                settled.renders += 1;
+                continue;
+            }
+
+            let mut effect = Some(effect);
+            for fake in self.fakes.values() {
+                match fake(&self.core, effect.take().unwrap()) {
+                    Ok(more) => {
+                        effects.extend(more);
+                        break;
+                    }
+                    Err(unhandled) => effect = Some(unhandled),
+                }
+            }
+
+            if let Some(effect) = effect {
+                settled.unhandled.push(effect);
+            } else {
+                resolved += 1;
+                assert!(
+                    resolved <= MAX_SIMULATED_EFFECTS,
+                    "the core did not settle after resolving {MAX_SIMULATED_EFFECTS} effects"
+                );
+            }
+        }
+
+        settled
+    }
+
+    /// Run the app's `view` function with the core's current model
+    pub fn view(&self) -> A::ViewModel {
+        self.core.view()
+    }
+
+    /// The core being simulated, e.g. to resolve an unhandled effect by hand
+    pub fn core(&self) -> &Core<Ef, A> {
+        &self.core
+    }
+}
+
+/// What happened while a [`Simulator`] ran an event, returned by [`Simulator::run`]
+#[derive(Debug)]
+#[must_use]
+pub struct Settled<Ef> {
+    /// How many times the app asked to render
+    pub renders: usize,
+    /// The effects which no fake was registered for, in the order they were requested.
+    /// They are left unresolved.
+    pub unhandled: Vec<Ef>,
+}
+
+/// Records a session at the [`Bridge`], so it can be replayed against a new core with
+/// [`replay`], e.g. to reproduce a bug report.
+///
+/// The shell talks to the `Recorder` in place of the bridge it wraps, which logs each
+/// event and each response passed in, serialized as they cross the bridge. When the
+/// session is over, [`Recorder::finish`] returns the [`SessionLog`], which is itself
+/// serializable so it can be saved and attached to an issue:
+///
+/// ```rust,ignore
+/// let recorder = Recorder::new(Bridge::new_with_codec(Core::new(), JsonCodec));
+/// // ... hand the recorder to the shell, then at the end of the session
+/// let log = serde_json::to_string(&recorder.finish())?;
+/// ```
+///
+/// Replaying relies on the app being deterministic: given the same events and responses,
+/// it must request the same effects in the same order.
+pub struct Recorder<Ef, A, C>
+where
+    Ef: Effect,
+    A: crate::App,
+{
+    bridge: Bridge<Ef, A, C>,
+    entries: Mutex<Vec<LogEntry>>,
+}
+
+/// A session recorded by a [`Recorder`], in the order it happened, and the view it ended
+/// with, all serialized with the codec of the bridge it was recorded at.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionLog {
+    pub entries: Vec<LogEntry>,
+    pub view: Vec<u8>,
+}
+
+/// One step of a [`SessionLog`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogEntry {
+    /// An event from the shell
+    Event(Vec<u8>),
+    /// The shell's response to the effect with `id`
+    Response { id: u32, output: Vec<u8> },
+}
+
+impl<Ef, A, C> Recorder<Ef, A, C>
+where
+    Ef: Effect,
+    A: crate::App,
+    A::Event: for<'a> Deserialize<'a>,
+    C: BridgeCodec,
+{
+    /// Record the session at `bridge`
+    pub fn new(bridge: Bridge<Ef, A, C>) -> Self {
+        Self {
+            bridge,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record the event, and pass it to [`Bridge::process_event`]
+    pub fn process_event(&self, event: &[u8]) -> Vec<u8> {
+        self.record(LogEntry::Event(event.to_vec()));
+        self.bridge.process_event(event)
+    }
+
+    /// Record the response, and pass it to [`Bridge::handle_response`]
+    pub fn handle_response(&self, id: u32, output: &[u8]) -> Vec<u8> {
+        self.record(LogEntry::Response {
+            id,
+            output: output.to_vec(),
+        });
+        self.bridge.handle_response(id, output)
+    }
+
+    /// Get the current view model from [`Bridge::view`]
+    pub fn view(&self) -> Vec<u8> {
+        self.bridge.view()
+    }
+
+    /// End the session, and return its log along with the current view
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log's lock was poisoned by a panic while recording.
+    pub fn finish(self) -> SessionLog {
+        let view = self.bridge.view();
+        let entries = self.entries.into_inner().expect("log lock poisoned");
+
+        SessionLog { entries, view }
+    }
+
+    fn record(&self, entry: LogEntry) {
+        self.entries.lock().expect("log lock poisoned").push(entry);
+    }
+}
+
+/// Replay a session recorded by a [`Recorder`] against `bridge`, which should wrap a new
+/// core and use the same codec, and assert that it ends with the same view.
+///
+/// # Panics
+///
+/// Panics if the view at the end of the replay doesn't match the recorded one, or if
+/// a recorded response doesn't match an effect the core is waiting on, which means the
+/// app didn't request the same effects as in the recorded session.
+#[track_caller]
+pub fn replay<Ef, A, C>(bridge: &Bridge<Ef, A, C>, log: &SessionLog)
+where
+    Ef: Effect,
+    A: crate::App,
+    A::Event: for<'a> Deserialize<'a>,
+    C: BridgeCodec,
+{
+    for entry in &log.entries {
+        match entry {
+            LogEntry::Event(event) => {
+                bridge.process_event(event);
+            }
+            LogEntry::Response { id, output } => {
+                bridge.handle_response(*id, output);
+            }
+        }
+    }
+
+    assert!(
+        bridge.view() == log.view,
+        "replayed view does not match the recorded one\nrecorded: {}\nreplayed: {}",
+        String::from_utf8_lossy(&log.view),
+        String::from_utf8_lossy(&bridge.view())
+    );
+}
+
+/// Set this environment variable to `1` to create the stored snapshots which are missing
+/// and update those which don't match, instead of failing the assertion.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "CRUX_UPDATE_SNAPSHOTS";
+
+/// Assert that a view model matches the snapshot stored as JSON in
+/// `tests/snapshots/<name>.json`, relative to the crate being tested.
+///
+/// ```rust,ignore
+/// let view = app.view(&model);
+/// assert_view_snapshot("counter_initial", &view);
+/// ```
+///
+/// When the view doesn't match, the assertion fails with a line by line diff of the
+/// JSON, and when the snapshot doesn't exist, it fails too, so a missing or misnamed
+/// snapshot can't pass unnoticed, e.g. in CI. With the [`UPDATE_SNAPSHOTS_ENV`]
+/// environment variable set to `1`, the snapshot is written with the new view instead.
+/// Commit it along with the test.
+///
+/// # Panics
+///
+/// Panics if the view doesn't match the snapshot, if the snapshot doesn't exist, or if
+/// the view can't be serialized or the snapshot can't be read or written.
+#[track_caller]
+pub fn assert_view_snapshot<T>(name: &str, view: &T)
+where
+    T: Serialize + ?Sized,
+{
+    let dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map_or_else(PathBuf::new, PathBuf::from)
+        .join("tests")
+        .join("snapshots");
+    let update = std::env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|value| value == "1");
+
+    if let Err(message) = check_snapshot(&dir.join(format!("{name}.json")), view, update) {
+        panic!("{message}");
+    }
+}
+
+fn check_snapshot<T>(path: &Path, view: &T, update: bool) -> std::result::Result<(), String>
+where
+    T: Serialize + ?Sized,
+{
+    let mut actual = serde_json::to_string_pretty(view)
+        .map_err(|e| format!("failed to serialize view for snapshot: {e}"))?;
+    actual.push('\n');
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => Some(expected),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("failed to read snapshot {}: {e}", path.display())),
+    };
+
+    match expected {
+        Some(expected) if expected == actual => Ok(()),
+        Some(expected) if !update => Err(format!(
+            "view does not match snapshot {} (set {UPDATE_SNAPSHOTS_ENV}=1 to update it)\n{}",
+            path.display(),
+            diff_lines(&expected, &actual)
+        )),
+        None if !update => Err(format!(
+            "snapshot {} does not exist (set {UPDATE_SNAPSHOTS_ENV}=1 to create it)\n{actual}",
+            path.display()
+        )),
+        _ => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+            }
+            fs::write(path, actual)
+                .map_err(|e| format!("failed to write snapshot {}: {e}", path.display()))
+        }
+    }
+}
+
+/// A line by line diff, with removed lines marked `-` and added lines marked `+`.
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    // lengths of the longest common subsequences of the remaining lines
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            diff.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
 /// Panics if the pattern doesn't match an `Effect` from the specified `Update`
 ///
 /// Like in a `match` expression, the pattern can be optionally followed by `if`
@@ -251,3 +719,68 @@ macro_rules! assert_effect {
         assert!($expression.effects().any(|e| matches!(e, $( $pattern )|+ $( if $guard )?)));
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use serde::Serialize;
+
+    use super::{check_snapshot, diff_lines};
+
+    #[derive(Serialize)]
+    struct View {
+        count: u32,
+        label: &'static str,
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let diff = diff_lines(
+            "{\n  \"a\": 1,\n  \"b\": 2\n}\n",
+            "{\n  \"a\": 1,\n  \"b\": 3\n}\n",
+        );
+
+        assert_eq!(
+            diff,
+            "  {\n    \"a\": 1,\n-   \"b\": 2\n+   \"b\": 3\n  }\n"
+        );
+    }
+
+    #[test]
+    fn snapshot_is_created_then_compared() {
+        let temp = TempDir::new().unwrap();
+        let snapshot = temp.child("snapshots/view.json");
+        let view = View {
+            count: 1,
+            label: "one",
+        };
+
+        check_snapshot(snapshot.path(), &view, true).unwrap();
+        snapshot.assert("{\n  \"count\": 1,\n  \"label\": \"one\"\n}\n");
+        check_snapshot(snapshot.path(), &view, false).unwrap();
+
+        let changed = View {
+            count: 2,
+            label: "one",
+        };
+        let message = check_snapshot(snapshot.path(), &changed, false).unwrap_err();
+        assert!(message.contains("-   \"count\": 1,\n+   \"count\": 2,\n"));
+
+        check_snapshot(snapshot.path(), &changed, true).unwrap();
+        check_snapshot(snapshot.path(), &changed, false).unwrap();
+    }
+
+    #[test]
+    fn missing_snapshot_fails_unless_updating() {
+        let temp = TempDir::new().unwrap();
+        let snapshot = temp.child("snapshots/missing.json");
+        let view = View {
+            count: 1,
+            label: "one",
+        };
+
+        let message = check_snapshot(snapshot.path(), &view, false).unwrap_err();
+        assert!(message.contains("does not exist"));
+        assert!(!snapshot.path().exists());
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The name of the manifest file written to the output directory of each language.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// The list of files produced by type generation, written as [`MANIFEST_FILE`] into the
+/// output directory given to each of the [`TypeGen`](super::TypeGen) methods.
+///
+/// A build script can compare the manifest with a previous one to find out whether the
+/// generated code changed. Generating for several languages into the same directory
+/// updates the same manifest, replacing only the entries for the language generated.
+///
+/// The entries are sorted by path, so the manifest only changes when the output does.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub files: Vec<ManifestEntry>,
+}
+
+/// A file produced by type generation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// The path of the file relative to the manifest, with `/` separators
+    pub path: String,
+    /// The language the file was generated for
+    pub language: Language,
+    /// A 64 bit FNV-1a hash of the contents of the file, in hex
+    pub hash: String,
+}
+
+/// The languages [`TypeGen`](super::TypeGen) generates code for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Language {
+    #[serde(rename = "swift")]
+    Swift,
+    #[serde(rename = "java")]
+    Java,
+    #[serde(rename = "typescript")]
+    TypeScript,
+    #[serde(rename = "typescript-definitions")]
+    TypeScriptDefinitions,
+}
+
+impl Manifest {
+    /// Read the manifest in the directory `dir`, or an empty one if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest exists but can't be read or parsed.
+    pub fn read(dir: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read(dir.as_ref().join(MANIFEST_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Replace the entries for `language` in the manifest in `dir` with `files`, which
+    /// must be inside `dir`.
+    pub(crate) fn record(
+        dir: &Path,
+        language: Language,
+        files: impl IntoIterator<Item = PathBuf>,
+    ) -> io::Result<()> {
+        let mut manifest = Self::read(dir)?;
+        manifest.files.retain(|entry| entry.language != language);
+
+        for file in files {
+            let path = file
+                .strip_prefix(dir)
+                .expect("generated files are in the output directory")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            manifest.files.push(ManifestEntry {
+                path,
+                language,
+                hash: format!("{:016x}", fnv1a(&fs::read(&file)?)),
+            });
+        }
+        manifest
+            .files
+            .sort_by(|a, b| (&a.path, a.language).cmp(&(&b.path, b.language)));
+
+        let mut json = serde_json::to_string_pretty(&manifest)?;
+        json.push('\n');
+        fs::write(dir.join(MANIFEST_FILE), json)
+    }
+}
+
+/// All the files under `dir`, skipping the manifest and any directories named in `skip`
+pub(crate) fn files_under(dir: &Path, skip: &[&str]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_dir() {
+            if !skip.iter().any(|skip| name == *skip) {
+                files.extend(files_under(&entry.path(), skip)?);
+            }
+        } else if name != MANIFEST_FILE {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
+
+// FNV-1a is stable across platforms and Rust versions, unlike the std hashers
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fnv1a;
+
+    #[test]
+    fn fnv1a_test_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
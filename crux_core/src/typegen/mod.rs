@@ -113,10 +113,18 @@
 //! )
 //! ```
 
+mod manifest;
+mod rename;
+mod typescript_definitions;
+
+pub use manifest::{Language, Manifest, ManifestEntry, MANIFEST_FILE};
+pub use rename::FieldCase;
+
 use serde::Deserialize;
 use serde_generate::{java, swift, typescript, Encoding, SourceInstaller};
-use serde_reflection::{Registry, Tracer, TracerConfig};
+use serde_reflection::{ContainerFormat, Format, Registry, Tracer, TracerConfig};
 use std::{
+    collections::BTreeSet,
     fs::{self, File},
     io::Write,
     mem,
@@ -128,6 +136,7 @@ use thiserror::Error;
 use serde_reflection::Samples;
 
 use crate::App;
+use rename::Renames;
 
 pub type Result = std::result::Result<(), TypeGenError>;
 
@@ -151,6 +160,8 @@ pub enum TypeGenError {
     Generation(String),
     #[error("error writing generated types")]
     Io(#[from] std::io::Error),
+    #[error("type renaming failed: {0}")]
+    Rename(String),
     #[error("`pnpm` is needed for TypeScript type generation, but it could not be found in PATH.\nPlease install it from https://pnpm.io/installation")]
     PnpmNotFound(#[source] std::io::Error),
 }
@@ -163,18 +174,34 @@ pub enum State {
 
 pub trait Export {
     fn register_types(generator: &mut TypeGen) -> Result;
+
+    /// Register the types of the capabilities only, leaving out the effect types. Used
+    /// to register the types of an app embedded in another with `#[effect(child = ...)]`,
+    /// whose effects are registered as part of the parent's.
+    fn register_capability_types(generator: &mut TypeGen) -> Result {
+        let _ = generator;
+        Ok(())
+    }
 }
 
 /// The `TypeGen` struct stores the registered types so that they can be generated for foreign languages
 /// use `TypeGen::new()` to create an instance
 pub struct TypeGen {
     pub state: State,
+    // containers traced from concrete instantiations of generic types, see `register_generic_type`
+    generics: Registry,
+    // enums nested in those instantiations which must also be registered in full
+    incomplete_generics: BTreeSet<String>,
+    renames: Renames,
 }
 
 impl Default for TypeGen {
     fn default() -> Self {
         TypeGen {
             state: State::Registering(Tracer::new(TracerConfig::default()), Samples::new()),
+            generics: Registry::new(),
+            incomplete_generics: BTreeSet::new(),
+            renames: Renames::default(),
         }
     }
 }
@@ -267,9 +294,10 @@ impl TypeGen {
                     r#"{e}:
 {exp}
 HINT: This may be because you are trying to trace a generic type,
-which is currently not supported.
+which is only supported through `TypeGen::register_generic_type`.
 The 2 common cases are:
-    * Capability output types. It's generally recommended to wrap them in your own type.
+    * Capability output types. Register their concrete instantiation with
+      `register_generic_type`, or wrap them in your own type.
     * Event variants which could have a `#[serde(skip)]` because they don't leave the core
 "#,
                     exp = e.explanation()
@@ -279,6 +307,95 @@ The 2 common cases are:
         }
     }
 
+    /// Register a concrete instantiation of a generic enum, such as
+    /// `Result<KeyValueResponse, KeyValueError>`, as a type of its own.
+    ///
+    /// Generic types can't be registered with `register_type()`, because serde only knows
+    /// them by their generic name (e.g. `Result`), so every instantiation would clash.
+    /// This method traces the instantiation separately and registers it under a name made
+    /// by joining the names of the type and its parameters, without their module paths,
+    /// e.g. `ResultKeyValueResponseKeyValueError`. The foreign types are generated as
+    /// ordinary enums with that name, and since the bincode encoding doesn't include type
+    /// names, they are compatible with the generic Rust type.
+    ///
+    /// This is useful for capability output types, which are serialized on their own:
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # use serde::{Serialize, Deserialize};
+    /// # use anyhow::Error;
+    /// #[derive(Serialize, Deserialize)]
+    /// enum Response { Value(Vec<u8>) }
+    /// #[derive(Serialize, Deserialize)]
+    /// enum KvError { Io(String) }
+    /// # fn register() -> Result<(), Error> {
+    /// # let mut gen = TypeGen::new();
+    /// gen.register_type::<Response>()?;
+    /// gen.register_type::<KvError>()?;
+    /// gen.register_generic_type::<Result<Response, KvError>>()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Limitations:
+    /// * Only the instantiation itself is renamed. Other registered types which contain
+    ///   the generic type still refer to it by its generic name, so wrap it in a type of
+    ///   its own to use it inside another type.
+    /// * The instantiation can't contain another instantiation of the same generic type,
+    ///   e.g. `Result<Result<A, B>, C>`.
+    /// * Types with custom serialization inside the instantiation can't be traced from
+    ///   samples.
+    /// * As with `register_type()`, enums nested in the instantiation need to be registered
+    ///   themselves, or type generation fails.
+    pub fn register_generic_type<'de, T>(&mut self) -> Result
+    where
+        T: serde::Deserialize<'de>,
+    {
+        if let State::Generating(_) = self.state {
+            return Err(TypeGenError::LateRegistration);
+        }
+
+        // Enums nested in the instantiation are only traced as far as their first variant,
+        // like with `register_type()`, so note which ones need registering in full. The
+        // tracer is consumed by finding out, so trace the type a second time to keep it.
+        let mut incomplete = Vec::new();
+        for attempt in 0..2 {
+            let mut tracer = Tracer::new(TracerConfig::default());
+            let (format, _) = tracer.trace_simple_type::<T>().map_err(|e| match e {
+                serde_reflection::Error::DeserializationError(_) => {
+                    TypeGenError::Deserialization(format!("{e}: {exp}", exp = e.explanation()))
+                }
+                _ => TypeGenError::TypeTracing(format!("{e}: {exp}", exp = e.explanation())),
+            })?;
+
+            if attempt == 0 {
+                match tracer.registry() {
+                    Ok(_) => {}
+                    Err(serde_reflection::Error::MissingVariants(names)) => incomplete = names,
+                    Err(e) => return Err(TypeGenError::TypeTracing(e.explanation())),
+                }
+                continue;
+            }
+
+            let Format::TypeName(generic_name) = format else {
+                return Err(TypeGenError::TypeTracing(format!(
+                    "{} is not a named container, so it can't be registered as a generic type",
+                    std::any::type_name::<T>()
+                )));
+            };
+
+            let mut registry = tracer.registry_unchecked();
+            let container = registry
+                .remove(&generic_name)
+                .expect("traced container should be registered");
+            registry.insert(monomorphized_name(std::any::type_name::<T>()), container);
+
+            merge_registry(&mut self.generics, registry)?;
+        }
+        self.incomplete_generics.extend(incomplete);
+
+        Ok(())
+    }
+
     /// Usually, the simple `register_type()` method can generate the types you need.
     /// Sometimes, though, you need to provide samples of your type. The `Uuid` type,
     /// for example, requires a sample struct to help the typegen system understand
@@ -345,6 +462,53 @@ The 2 common cases are:
         }
     }
 
+    /// Generate the type registered as `rust_name` with the name `foreign_name` instead.
+    ///
+    /// `rust_name` is the name serde knows the type by, which is the Rust name unless the
+    /// type has a `#[serde(rename)]` attribute, in which case it's the serde name. So the
+    /// explicit rename takes precedence over the serde one. References to the type from
+    /// other types are renamed too.
+    ///
+    /// The rename applies to all the generated languages, including the
+    /// [TypeScript definitions](TypeGen::typescript_definitions), since type names are
+    /// not part of the serialized form.
+    ///
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # let mut gen = TypeGen::new();
+    /// gen.rename_type("Event", "CounterEvent")?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TypeGenError::Rename`] if `rust_name` has already been renamed to a
+    /// different name, or another type has already been renamed to `foreign_name`.
+    /// Generating the types fails if `rust_name` isn't registered, or `foreign_name`
+    /// clashes with the name of another type.
+    pub fn rename_type(&mut self, rust_name: &str, foreign_name: &str) -> Result {
+        self.renames.rename_type(rust_name, foreign_name)
+    }
+
+    /// Generate field names following the `case` policy, e.g. [`FieldCase::Camel`] for
+    /// Swift and Kotlin conventions.
+    ///
+    /// The policy applies to the names as serde serializes them, so it comes after any
+    /// `#[serde(rename)]` or `#[serde(rename_all)]` attributes. Converting a name which
+    /// is already in the target case leaves it unchanged, so serde renames to the same
+    /// case are kept as they are.
+    ///
+    /// Field names are not part of the bincode representation, so the policy applies to
+    /// the Swift, Java and TypeScript types. It doesn't apply to the
+    /// [TypeScript definitions](TypeGen::typescript_definitions), which describe the JSON
+    /// representation, where renaming fields needs a serde attribute instead.
+    ///
+    /// Generating the types fails if two fields of the same struct or variant end up
+    /// with the same name.
+    pub fn rename_fields(&mut self, case: FieldCase) {
+        self.renames.rename_fields(case);
+    }
+
     /// Generates types for Swift
     /// e.g.
     /// ```rust
@@ -358,7 +522,8 @@ The 2 common cases are:
     pub fn swift(&mut self, module_name: &str, path: impl AsRef<Path>) -> Result {
         self.ensure_registry()?;
 
-        let path = path.as_ref().join(module_name);
+        let root = path.as_ref();
+        let path = root.join(module_name);
 
         fs::create_dir_all(&path)?;
 
@@ -370,10 +535,7 @@ The 2 common cases are:
             .install_bincode_runtime()
             .map_err(|e| TypeGenError::Generation(e.to_string()))?;
 
-        let registry = match &self.state {
-            State::Generating(registry) => registry,
-            _ => panic!("registry creation failed"),
-        };
+        let registry = &self.renamed_registry(true)?;
 
         let config = serde_generate::CodeGeneratorConfig::new(module_name.to_string())
             .with_encodings(vec![Encoding::Bincode]);
@@ -408,6 +570,8 @@ The 2 common cases are:
             package_data.replace("SharedTypes", module_name)
         )?;
 
+        Manifest::record(root, Language::Swift, manifest::files_under(&path, &[])?)?;
+
         Ok(())
     }
 
@@ -445,10 +609,7 @@ The 2 common cases are:
             .install_bincode_runtime()
             .map_err(|e| TypeGenError::Generation(e.to_string()))?;
 
-        let registry = match &self.state {
-            State::Generating(registry) => registry,
-            _ => panic!("registry creation failed"),
-        };
+        let registry = &self.renamed_registry(true)?;
 
         installer
             .install_module(&config, registry)
@@ -463,11 +624,17 @@ The 2 common cases are:
         fs::write(
             path.as_ref()
                 .to_path_buf()
-                .join(package_path)
+                .join(&package_path)
                 .join("Requests.java"),
             requests,
         )?;
 
+        let mut files = Vec::new();
+        for dir in [package_path.as_str(), "com/novi/serde", "com/novi/bincode"] {
+            files.extend(manifest::files_under(&path.as_ref().join(dir), &[])?);
+        }
+        Manifest::record(path.as_ref(), Language::Java, files)?;
+
         Ok(())
     }
 
@@ -498,10 +665,7 @@ The 2 common cases are:
         let extensions_dir = self.extensions_path("typescript");
         copy(extensions_dir, path)?;
 
-        let registry = match &self.state {
-            State::Generating(registry) => registry,
-            _ => panic!("registry creation failed"),
-        };
+        let registry = &self.renamed_registry(true)?;
 
         let config = serde_generate::CodeGeneratorConfig::new(module_name.to_string())
             .with_encodings(vec![Encoding::Bincode]);
@@ -536,16 +700,68 @@ The 2 common cases are:
 
         // Build TS code and emit declarations
         std::process::Command::new("pnpm")
-            .current_dir(output_dir)
+            .current_dir(&output_dir)
             .arg("exec")
             .arg("tsc")
             .arg("--build")
             .status()
             .map_err(TypeGenError::Io)?;
 
+        Manifest::record(
+            &output_dir,
+            Language::TypeScript,
+            manifest::files_under(&output_dir, &["node_modules"])?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Generates plain TypeScript type definitions, without any serialization code,
+    /// into `<module_name>.ts` under `path`.
+    ///
+    /// The definitions describe the JSON representation of the registered types, so
+    /// they are meant for shells which talk to a [`Bridge`](crate::bridge::Bridge) using
+    /// [`JsonCodec`](crate::bridge::JsonCodec). Structs become interfaces and enums
+    /// become unions, with unit variants as string literals and other variants as
+    /// objects keyed by the variant name, following serde's externally tagged
+    /// representation. `Vec`s become arrays, `Option`s allow `null`, and maps become
+    /// `Record<string, _>`.
+    ///
+    /// All numbers become `number`, so 64 and 128 bit integers beyond
+    /// `Number.MAX_SAFE_INTEGER` lose precision when parsed with `JSON.parse`.
+    ///
+    /// e.g.
+    /// ```rust
+    /// # use crux_core::typegen::TypeGen;
+    /// # use std::env::temp_dir;
+    /// # let mut gen = TypeGen::new();
+    /// # let output_root = temp_dir().join("crux_core_typegen_doctest");
+    /// gen.typescript_definitions("shared_types", output_root.join("typescript_definitions"))?;
+    /// # Ok::<(), crux_core::typegen::TypeGenError>(())
+    /// ```
+    pub fn typescript_definitions(&mut self, module_name: &str, path: impl AsRef<Path>) -> Result {
+        self.ensure_registry()?;
+
+        // field names are part of the JSON representation, so only rename types
+        let registry = &self.renamed_registry(false)?;
+
+        fs::create_dir_all(&path)?;
+        let file = path.as_ref().join(format!("{module_name}.ts"));
+        fs::write(&file, typescript_definitions::generate(registry))?;
+
+        Manifest::record(path.as_ref(), Language::TypeScriptDefinitions, [file])?;
+
         Ok(())
     }
 
+    fn renamed_registry(&self, fields: bool) -> std::result::Result<Registry, TypeGenError> {
+        let State::Generating(registry) = &self.state else {
+            panic!("registry creation failed");
+        };
+
+        self.renames.apply(registry, fields)
+    }
+
     fn ensure_registry(&mut self) -> Result {
         if let State::Registering(_, _) = self.state {
             // replace the current state with a dummy tracer
@@ -556,12 +772,31 @@ The 2 common cases are:
 
             // convert tracer to registry
             if let State::Registering(tracer, _) = old_state {
+                let mut registry = tracer.registry().map_err(|e| match e {
+                    serde_reflection::Error::MissingVariants(names) => {
+                        TypeGenError::Generation(format!(
+                            "not all the variants of {} have been traced. Enums inside other \
+                             types, including in collections like `Vec` or maps, need to be \
+                             registered themselves with `register_type()`.",
+                            names.join(", ")
+                        ))
+                    }
+                    e => TypeGenError::Generation(e.explanation()),
+                })?;
+                if let Some(name) = self
+                    .incomplete_generics
+                    .iter()
+                    .find(|name| !registry.contains_key(*name))
+                {
+                    return Err(TypeGenError::Generation(format!(
+                        "enum {name} is used in a generic type, but not all of its variants \
+                         have been traced. Register it with `register_type()`."
+                    )));
+                }
+                merge_registry(&mut registry, mem::take(&mut self.generics))?;
+
                 // replace dummy with registry
-                self.state = State::Generating(
-                    tracer
-                        .registry()
-                        .map_err(|e| TypeGenError::Generation(e.explanation()))?,
-                );
+                self.state = State::Generating(registry);
             }
         }
         Ok(())
@@ -584,6 +819,57 @@ The 2 common cases are:
     }
 }
 
+// Join the names in a Rust type name without their module paths, e.g.
+// `core::result::Result<my_app::Response, alloc::string::String>` becomes
+// `ResultResponseString`.
+fn monomorphized_name(type_name: &str) -> String {
+    type_name
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .filter_map(|path| path.rsplit("::").next())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let mut chars = name.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect()
+}
+
+fn merge_registry(into: &mut Registry, from: Registry) -> Result {
+    for (name, container) in from {
+        match (into.get_mut(&name), container) {
+            // enums may have been traced partially, so combine the variants found
+            (Some(ContainerFormat::Enum(existing)), ContainerFormat::Enum(variants)) => {
+                for (index, variant) in variants {
+                    match existing.get(&index) {
+                        Some(known) if *known != variant => {
+                            return Err(TypeGenError::TypeTracing(format!(
+                                "conflicting definitions of type {name}"
+                            )));
+                        }
+                        Some(_) => {}
+                        None => {
+                            existing.insert(index, variant);
+                        }
+                    }
+                }
+            }
+            (Some(existing), container) => {
+                if *existing != container {
+                    return Err(TypeGenError::TypeTracing(format!(
+                        "conflicting definitions of type {name}"
+                    )));
+                }
+            }
+            (None, container) => {
+                into.insert(name, container);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result {
     fs::create_dir_all(to.as_ref())?;
 
@@ -605,7 +891,7 @@ fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result {
 #[cfg(feature = "typegen")]
 #[cfg(test)]
 mod tests {
-    use crate::typegen::TypeGen;
+    use crate::typegen::{monomorphized_name, TypeGen};
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
@@ -635,4 +921,16 @@ mod tests {
         let result = gen.register_type_with_samples(sample_data);
         assert!(result.is_ok(), "typegen failed with second sample data set");
     }
+
+    #[test]
+    fn test_monomorphized_name() {
+        assert_eq!(
+            monomorphized_name(std::any::type_name::<Result<MyUuid, String>>()),
+            "ResultMyUuidString"
+        );
+        assert_eq!(
+            monomorphized_name(std::any::type_name::<Option<Vec<u8>>>()),
+            "OptionVecU8"
+        );
+    }
 }
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use serde_reflection::{ContainerFormat, Format, FormatHolder, Named, Registry, VariantFormat};
+
+use super::TypeGenError;
+
+/// How [`TypeGen`](super::TypeGen) spells field names in the generated foreign types.
+///
+/// The policy is applied to the field names as serde serializes them, i.e. after any
+/// `#[serde(rename)]` or `#[serde(rename_all)]` attributes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldCase {
+    /// Keep the names serde uses.
+    #[default]
+    Unchanged,
+    /// `camelCase`: underscores are removed and the letter after each is upper cased.
+    /// Names without underscores are unchanged.
+    Camel,
+    /// `snake_case`: an underscore is inserted at each word boundary and all letters
+    /// are lower cased.
+    Snake,
+}
+
+impl FieldCase {
+    fn apply(self, name: &str) -> String {
+        match self {
+            FieldCase::Unchanged => name.to_string(),
+            FieldCase::Camel => {
+                // keep leading underscores, which usually mean something
+                let trimmed = name.trim_start_matches('_');
+                let mut out = name[..name.len() - trimmed.len()].to_string();
+                let mut upper = false;
+                for c in trimmed.chars() {
+                    if c == '_' {
+                        upper = true;
+                    } else if upper {
+                        out.extend(c.to_uppercase());
+                        upper = false;
+                    } else {
+                        out.push(c);
+                    }
+                }
+                out
+            }
+            FieldCase::Snake => {
+                let chars: Vec<char> = name.chars().collect();
+                let mut out = String::with_capacity(name.len());
+                for (i, &c) in chars.iter().enumerate() {
+                    if c.is_uppercase() && i > 0 {
+                        let previous = chars[i - 1];
+                        let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+                        if previous != '_'
+                            && (previous.is_lowercase()
+                                || previous.is_ascii_digit()
+                                || (previous.is_uppercase() && next_is_lower))
+                        {
+                            out.push('_');
+                        }
+                    }
+                    out.extend(c.to_lowercase());
+                }
+                out
+            }
+        }
+    }
+}
+
+/// The renames requested with [`TypeGen::rename_type`](super::TypeGen::rename_type) and
+/// [`TypeGen::rename_fields`](super::TypeGen::rename_fields).
+#[derive(Debug, Default)]
+pub(crate) struct Renames {
+    types: BTreeMap<String, String>,
+    fields: FieldCase,
+}
+
+impl Renames {
+    pub(crate) fn rename_type(
+        &mut self,
+        rust_name: &str,
+        foreign_name: &str,
+    ) -> Result<(), TypeGenError> {
+        if let Some(existing) = self.types.get(rust_name) {
+            if existing != foreign_name {
+                return Err(TypeGenError::Rename(format!(
+                    "{rust_name} is already renamed to {existing}, can't rename it to {foreign_name}"
+                )));
+            }
+        }
+        if let Some((other, _)) = self
+            .types
+            .iter()
+            .find(|(other, foreign)| *other != rust_name && *foreign == foreign_name)
+        {
+            return Err(TypeGenError::Rename(format!(
+                "{other} is already renamed to {foreign_name}, can't rename {rust_name} to it too"
+            )));
+        }
+
+        self.types
+            .insert(rust_name.to_string(), foreign_name.to_string());
+        Ok(())
+    }
+
+    pub(crate) fn rename_fields(&mut self, case: FieldCase) {
+        self.fields = case;
+    }
+
+    /// A copy of `registry` with the renames applied. Field names are only renamed if
+    /// `fields` is true, because they're part of the serialized form in some formats.
+    pub(crate) fn apply(
+        &self,
+        registry: &Registry,
+        fields: bool,
+    ) -> Result<Registry, TypeGenError> {
+        if let Some(unknown) = self.types.keys().find(|name| !registry.contains_key(*name)) {
+            return Err(TypeGenError::Rename(format!(
+                "can't rename {unknown}, because no type with that name has been registered"
+            )));
+        }
+
+        let type_name = |name: &str| {
+            self.types
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.to_string())
+        };
+
+        let mut renamed = Registry::new();
+        for (name, container) in registry {
+            let mut container = container.clone();
+            container
+                .visit_mut(&mut |format| {
+                    if let Format::TypeName(name) = format {
+                        *name = type_name(name);
+                    }
+                    Ok(())
+                })
+                .expect("registry formats are resolved");
+
+            if fields {
+                self.rename_container_fields(name, &mut container)?;
+            }
+
+            let foreign_name = type_name(name);
+            if renamed.insert(foreign_name.clone(), container).is_some() {
+                return Err(TypeGenError::Rename(format!(
+                    "more than one type would be generated as {foreign_name}"
+                )));
+            }
+        }
+
+        Ok(renamed)
+    }
+
+    fn rename_container_fields(
+        &self,
+        name: &str,
+        container: &mut ContainerFormat,
+    ) -> Result<(), TypeGenError> {
+        match container {
+            ContainerFormat::Struct(fields) => self.rename_named(name, fields),
+            ContainerFormat::Enum(variants) => {
+                for variant in variants.values_mut() {
+                    if let VariantFormat::Struct(fields) = &mut variant.value {
+                        self.rename_named(&format!("{name}::{}", variant.name), fields)?;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn rename_named(&self, owner: &str, fields: &mut [Named<Format>]) -> Result<(), TypeGenError> {
+        for field in fields.iter_mut() {
+            field.name = self.fields.apply(&field.name);
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|other| other.name == field.name) {
+                return Err(TypeGenError::Rename(format!(
+                    "more than one field of {owner} would be generated as {}",
+                    field.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FieldCase;
+
+    #[test]
+    fn camel_case() {
+        assert_eq!(FieldCase::Camel.apply("updated_at"), "updatedAt");
+        assert_eq!(FieldCase::Camel.apply("text"), "text");
+        assert_eq!(FieldCase::Camel.apply("alreadyCamel"), "alreadyCamel");
+        assert_eq!(FieldCase::Camel.apply("_private_field"), "_privateField");
+    }
+
+    #[test]
+    fn snake_case() {
+        assert_eq!(FieldCase::Snake.apply("updatedAt"), "updated_at");
+        assert_eq!(FieldCase::Snake.apply("already_snake"), "already_snake");
+        assert_eq!(FieldCase::Snake.apply("HTTPStatus"), "http_status");
+        assert_eq!(FieldCase::Snake.apply("field2Name"), "field2_name");
+    }
+}
@@ -0,0 +1,120 @@
+//! Plain TypeScript type definitions matching the JSON representation of the
+//! registered types, for shells using [`JsonCodec`](crate::bridge::JsonCodec).
+
+use std::fmt::{self, Write};
+
+use serde_reflection::{ContainerFormat, Format, Named, Registry, VariantFormat};
+
+pub(crate) fn generate(registry: &Registry) -> String {
+    let mut out = String::from(
+        "// Generated by crux_core::typegen. Do not edit.\n\
+         // These types describe the JSON representation used by the Bridge with JsonCodec.\n",
+    );
+
+    for (name, container) in registry {
+        out.push('\n');
+        container_definition(&mut out, name, container).expect("writing to a String");
+    }
+
+    // the effect requests the core returns to the shell are a list of the registered
+    // `Request` type, which wraps the effect
+    if registry.contains_key("Request") {
+        out.push_str("\nexport type Requests = Request[];\n");
+    }
+
+    out
+}
+
+fn container_definition(out: &mut String, name: &str, container: &ContainerFormat) -> fmt::Result {
+    match container {
+        ContainerFormat::UnitStruct => writeln!(out, "export type {name} = null;"),
+        ContainerFormat::NewTypeStruct(format) => {
+            writeln!(out, "export type {name} = {};", type_of(format))
+        }
+        ContainerFormat::TupleStruct(formats) => {
+            writeln!(out, "export type {name} = {};", tuple(formats))
+        }
+        ContainerFormat::Struct(fields) => {
+            writeln!(out, "export interface {name} {{")?;
+            for field in fields {
+                writeln!(out, "  {}: {};", field.name, type_of(&field.value))?;
+            }
+            writeln!(out, "}}")
+        }
+        ContainerFormat::Enum(variants) => {
+            // serde represents enums as externally tagged: unit variants as their name,
+            // and other variants as an object with the name as the only key
+            writeln!(out, "export type {name} =")?;
+            for Named { name, value } in variants.values() {
+                match value {
+                    VariantFormat::Unit => writeln!(out, "  | \"{name}\""),
+                    VariantFormat::NewType(format) => {
+                        writeln!(out, "  | {{ {name}: {} }}", type_of(format))
+                    }
+                    VariantFormat::Tuple(formats) => {
+                        writeln!(out, "  | {{ {name}: {} }}", tuple(formats))
+                    }
+                    VariantFormat::Struct(fields) => {
+                        writeln!(out, "  | {{ {name}: {} }}", object(fields))
+                    }
+                    VariantFormat::Variable(_) => unreachable!("registry formats are resolved"),
+                }?;
+            }
+            writeln!(out, ";")
+        }
+    }
+}
+
+fn type_of(format: &Format) -> String {
+    match format {
+        Format::TypeName(name) => name.clone(),
+        Format::Unit => "null".to_string(),
+        Format::Bool => "boolean".to_string(),
+        Format::I8
+        | Format::I16
+        | Format::I32
+        | Format::I64
+        | Format::I128
+        | Format::U8
+        | Format::U16
+        | Format::U32
+        | Format::U64
+        | Format::U128
+        | Format::F32
+        | Format::F64 => "number".to_string(),
+        Format::Char | Format::Str => "string".to_string(),
+        Format::Bytes => "number[]".to_string(),
+        Format::Option(format) => format!("{} | null", type_of(format)),
+        Format::Seq(format)
+        | Format::TupleArray {
+            content: format, ..
+        } => {
+            format!("{}[]", element(format))
+        }
+        // JSON object keys are always strings
+        Format::Map { value, .. } => format!("Record<string, {}>", type_of(value)),
+        Format::Tuple(formats) => tuple(formats),
+        Format::Variable(_) => unreachable!("registry formats are resolved"),
+    }
+}
+
+// a type which can be followed by `[]`
+fn element(format: &Format) -> String {
+    match format {
+        Format::Option(_) => format!("({})", type_of(format)),
+        _ => type_of(format),
+    }
+}
+
+fn tuple(formats: &[Format]) -> String {
+    let types: Vec<_> = formats.iter().map(type_of).collect();
+    format!("[{}]", types.join(", "))
+}
+
+fn object(fields: &[Named<Format>]) -> String {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, type_of(&field.value)))
+        .collect();
+    format!("{{ {} }}", fields.join("; "))
+}
@@ -226,7 +226,8 @@ mod tests {
 
                     counter += 3;
 
-                    let effs: Vec<Effect> = core.resolve(&mut request, output);
+                    let effs: Vec<Effect> =
+                        core.resolve(&mut request, output).expect("should resolve");
 
                     for e in effs {
                         effects.push_back(e)
@@ -0,0 +1,131 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_kv::{error::KeyValueError, KeyValue};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Increment,
+        IncrementThenPanic,
+        Load,
+        Loaded(Result<Option<Vec<u8>>, KeyValueError>),
+    }
+
+    #[derive(Default, Clone)]
+    pub struct Model {
+        count: u32,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub key_value: KeyValue<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = u32;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Increment => {
+                    model.count += 1;
+                    caps.render.render();
+                }
+                Event::IncrementThenPanic => {
+                    model.count += 1;
+                    panic!("count went too high");
+                }
+                Event::Load => caps.key_value.get("count".to_string(), Event::Loaded),
+                Event::Loaded(_) => {
+                    model.count += 1;
+                    panic!("{} is not a valid count", "nothing");
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.count
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{bridge::Bridge, Core, UpdatePanic};
+    use crux_kv::{value::Value, KeyValueResponse, KeyValueResult};
+
+    use super::app::{App, Effect, Event};
+
+    #[test]
+    fn panic_leaves_model_unchanged() {
+        let core: Core<Effect, App> = Core::new().catch_panics();
+
+        core.process_event(Event::Increment);
+        let effects = core.process_event(Event::IncrementThenPanic);
+
+        assert!(effects.is_empty());
+        assert_eq!(core.view(), 1);
+        assert_eq!(
+            core.take_panics(),
+            vec![UpdatePanic {
+                message: "count went too high".to_string()
+            }]
+        );
+        assert_eq!(core.take_panics(), vec![]);
+
+        // the core carries on as normal
+        core.process_event(Event::Increment);
+        assert_eq!(core.view(), 2);
+    }
+
+    #[test]
+    fn panic_in_update_from_capability_is_caught() {
+        let core: Core<Effect, App> = Core::new().catch_panics();
+
+        let Effect::KeyValue(mut request) = core.process_event(Event::Load).remove(0) else {
+            panic!("expected a key value request");
+        };
+        let effects = core
+            .resolve(
+                &mut request,
+                KeyValueResult::Ok {
+                    response: KeyValueResponse::Get { value: Value::None },
+                },
+            )
+            .expect("should resolve");
+
+        assert!(effects.is_empty());
+        assert_eq!(core.view(), 0);
+        assert_eq!(
+            core.take_panics(),
+            vec![UpdatePanic {
+                message: "nothing is not a valid count".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn bridge_reports_panic_messages() {
+        let bridge = Bridge::new(Core::<Effect, App>::new().catch_panics());
+
+        bridge.process_event(&bincode::serialize(&Event::IncrementThenPanic).unwrap());
+
+        assert_eq!(
+            bridge.take_panics(),
+            vec!["count went too high".to_string()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "count went too high")]
+    fn panics_are_not_caught_by_default() {
+        let core: Core<Effect, App> = Core::new();
+
+        core.process_event(Event::IncrementThenPanic);
+    }
+}
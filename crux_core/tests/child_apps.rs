@@ -0,0 +1,205 @@
+mod counter {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_http::Http;
+    use serde::Deserialize;
+
+    #[derive(Default)]
+    pub struct Counter;
+
+    #[derive(Deserialize)]
+    pub enum Event {
+        Increment,
+        Fetch,
+        Fetched(crux_http::Result<crux_http::Response<isize>>),
+    }
+
+    #[derive(Effect)]
+    #[cfg_attr(feature = "typegen", derive(crux_core::macros::Export))]
+    #[effect(name = "CounterEffect")]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for Counter {
+        type Event = Event;
+        type Model = isize;
+        type ViewModel = isize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Increment => {
+                    *model += 1;
+                    caps.render.render();
+                }
+                Event::Fetch => {
+                    caps.http
+                        .get("https://example.com/count")
+                        .expect_json()
+                        .send(Event::Fetched);
+                }
+                Event::Fetched(Ok(mut response)) => {
+                    *model = response.take_body().unwrap();
+                    caps.render.render();
+                }
+                Event::Fetched(Err(_)) => {}
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            *model
+        }
+    }
+}
+
+mod parent {
+    use crux_core::{macros::Effect, render::Render};
+    use serde::Deserialize;
+
+    use crate::counter::{self, Counter};
+
+    #[derive(Default)]
+    pub struct App {
+        counter: Counter,
+    }
+
+    #[derive(Deserialize)]
+    pub enum Event {
+        Reset,
+        Counter(counter::Event),
+    }
+
+    impl From<counter::Event> for Event {
+        fn from(event: counter::Event) -> Self {
+            Event::Counter(event)
+        }
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub counter: isize,
+    }
+
+    #[derive(Effect)]
+    #[cfg_attr(feature = "typegen", derive(crux_core::macros::Export))]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        #[effect(child = Counter)]
+        pub counter: counter::Capabilities,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = isize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Reset => {
+                    model.counter = 0;
+                    caps.render.render();
+                }
+                Event::Counter(event) => {
+                    self.counter
+                        .update(event, &mut model.counter, &caps.counter);
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            self.counter.view(&model.counter)
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{
+        bridge::{Bridge, JsonCodec},
+        Core,
+    };
+    use crux_http::protocol::{HttpResponse, HttpResult};
+
+    use crate::counter::{self, CounterEffect};
+    use crate::parent::{App, Effect, Event};
+
+    #[test]
+    fn child_effects_are_wrapped_in_the_parent_effect() {
+        let core: Core<Effect, App> = Core::new();
+
+        let mut effects = core.process_event(Event::Counter(counter::Event::Increment));
+
+        assert_eq!(core.view(), 1);
+        let Some(Effect::Counter(CounterEffect::Render(_))) = effects.pop() else {
+            panic!("expected the counter to render");
+        };
+        assert!(effects.is_empty());
+
+        let effects = core.process_event(Event::Reset);
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+    }
+
+    #[test]
+    fn child_events_are_routed_back_through_the_parent() {
+        let core: Core<Effect, App> = Core::new();
+
+        let mut effects = core.process_event(Event::Counter(counter::Event::Fetch));
+        let mut request = effects.pop().unwrap().expect_counter().expect_http();
+        assert_eq!(request.operation.url, "https://example.com/count");
+
+        let response = HttpResponse::ok().body("42").build();
+        let mut effects = core
+            .resolve(&mut request, HttpResult::Ok(response))
+            .unwrap();
+
+        assert_eq!(core.view(), 42);
+        assert!(effects.pop().unwrap().expect_counter().is_render());
+    }
+
+    #[test]
+    fn child_effects_can_be_converted_into_the_parent_effect() {
+        let core: Core<Effect, App> = Core::new();
+
+        let mut effects = core.process_event(Event::Counter(counter::Event::Increment));
+        let effect: Effect = effects.pop().unwrap().expect_counter().into();
+
+        assert!(effect.is_counter());
+    }
+
+    #[test]
+    fn child_effects_are_serialized_nested_in_the_parent_effect() {
+        let bridge = Bridge::<Effect, App, JsonCodec>::new_with_codec(Core::new(), JsonCodec);
+
+        let requests = bridge.process_event(br#"{"Counter":"Fetch"}"#);
+        let requests: serde_json::Value = serde_json::from_slice(&requests).unwrap();
+
+        assert_eq!(
+            requests[0]["effect"]["Counter"]["Http"]["url"],
+            "https://example.com/count"
+        );
+    }
+
+    #[cfg(feature = "typegen")]
+    #[test]
+    fn child_types_are_registered_with_the_parent() {
+        use crux_core::typegen::{Export, State, TypeGen};
+
+        use crate::parent::Capabilities;
+
+        let mut gen = TypeGen::new();
+        // crux_http registers its error type itself only with its own typegen feature
+        gen.register_type::<crux_http::HttpError>().unwrap();
+        Capabilities::register_types(&mut gen).expect("should register types");
+
+        let State::Registering(tracer, _) = gen.state else {
+            panic!("expected to still be in registering stage");
+        };
+        let registry = tracer.registry().expect("should get registry");
+
+        assert!(registry.contains_key("Effect"));
+        assert!(registry.contains_key("CounterEffect"));
+        assert!(registry.contains_key("HttpRequest"));
+        assert!(registry.contains_key("HttpResult"));
+    }
+}
@@ -0,0 +1,420 @@
+mod app {
+    use crux_core::{compose::Compose, macros::Effect};
+    use crux_time::{Duration, Time, TimeResponse};
+    use futures::FutureExt;
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Race,
+        Raced(&'static str),
+        Join,
+        Joined((&'static str, &'static str)),
+        JoinAll,
+        JoinedAll(Vec<&'static str>),
+        Chain,
+        Chained((&'static str, &'static str)),
+        ChainFailed(&'static str),
+        Discard,
+        Sequence(Vec<&'static str>),
+        Sequenced(Result<Vec<&'static str>, &'static str>),
+        Buffer(Vec<&'static str>, usize),
+        Buffered(Vec<&'static str>),
+    }
+
+    #[derive(Default, Debug)]
+    pub struct Model {
+        pub winner: Option<&'static str>,
+        pub finished: Vec<&'static str>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub time: Time<Event>,
+        #[effect(skip)]
+        pub compose: Compose<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Race => {
+                    let (short, _) = caps
+                        .time
+                        .notify_after_async(Duration::from_millis(100).unwrap());
+                    let (long, _) = caps
+                        .time
+                        .notify_after_async(Duration::from_millis(500).unwrap());
+
+                    caps.compose.race(
+                        [short.map(|_| "short").boxed(), long.map(|_| "long").boxed()],
+                        Event::Raced,
+                    );
+                }
+                Event::Raced(winner) => {
+                    model.winner = Some(winner);
+                }
+                Event::Join => {
+                    let (a, _) = caps.time.notify_after_async(Duration::new(1));
+                    let (b, _) = caps.time.notify_after_async(Duration::new(2));
+
+                    caps.compose
+                        .join(a.map(|_| "a"), b.map(|_| "b"), Event::Joined);
+                }
+                Event::Joined((a, b)) => {
+                    model.finished = vec![a, b];
+                }
+                Event::JoinAll => {
+                    let timers = ["a", "b", "c"].map(|label| {
+                        let (timer, _) = caps.time.notify_after_async(Duration::new(1));
+                        timer.map(move |_| label)
+                    });
+
+                    caps.compose.join_all(timers, Event::JoinedAll);
+                }
+                Event::JoinedAll(finished) => {
+                    model.finished = finished;
+                }
+                Event::Chain => {
+                    let (first, _) = caps.time.notify_after_async(Duration::new(1));
+                    let time = caps.time.clone();
+
+                    caps.compose.and_then_ok(
+                        first.map(|response| match response {
+                            TimeResponse::DurationElapsed { .. } => Ok("first"),
+                            _ => Err("first was cleared"),
+                        }),
+                        move |first| {
+                            let (second, _) = time.notify_after_async(Duration::new(1));
+                            second.map(move |_| (first, "second"))
+                        },
+                        Event::Chained,
+                        Event::ChainFailed,
+                    );
+                }
+                Event::Chained((first, second)) => {
+                    model.finished = vec![first, second];
+                }
+                Event::ChainFailed(_) => {}
+                Event::Discard => {
+                    let (timer, _) = caps.time.notify_after_async(Duration::new(1));
+                    caps.compose.discard(timer);
+                }
+                Event::Sequence(labels) => {
+                    let time = caps.time.clone();
+
+                    caps.compose.sequence(
+                        labels,
+                        move |label| {
+                            let (timer, _) = time.notify_after_async(Duration::new(1));
+                            timer.map(move |response| match response {
+                                TimeResponse::DurationElapsed { .. } => Ok(label),
+                                _ => Err(label),
+                            })
+                        },
+                        Event::Sequenced,
+                    );
+                }
+                Event::Sequenced(_) => {}
+                Event::Buffer(labels, concurrency) => {
+                    let time = caps.time.clone();
+
+                    caps.compose.buffered(
+                        labels,
+                        concurrency,
+                        move |label| {
+                            let (timer, _) = time.notify_after_async(Duration::new(1));
+                            timer.map(move |_| label)
+                        },
+                        Event::Buffered,
+                    );
+                }
+                Event::Buffered(_) => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::testing::AppTester;
+    use crux_core::{Core, Request};
+    use crux_time::{TimeRequest, TimeResponse};
+
+    use crate::app::{App, Effect, Event, Model};
+
+    fn elapse(app: &AppTester<App, Effect>, request: &mut Request<TimeRequest>) -> Vec<Event> {
+        let TimeRequest::NotifyAfter { id, .. } = request.operation else {
+            panic!("expected a timer request");
+        };
+        app.resolve(request, TimeResponse::DurationElapsed { id })
+            .expect("should resolve")
+            .events
+    }
+
+    #[test]
+    fn race_delivers_only_the_first_event() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Race, &mut model);
+        assert_eq!(update.effects.len(), 2);
+
+        let mut long = update.effects.pop().unwrap().expect_time();
+        let mut short = update.effects.pop().unwrap().expect_time();
+
+        let TimeRequest::NotifyAfter { id: short_id, .. } = short.operation else {
+            panic!("expected a timer request");
+        };
+        let TimeRequest::NotifyAfter { id: long_id, .. } = long.operation else {
+            panic!("expected a timer request");
+        };
+
+        let update = app
+            .resolve(&mut short, TimeResponse::DurationElapsed { id: short_id })
+            .expect("should resolve");
+        assert_eq!(update.events, vec![Event::Raced("short")]);
+
+        for event in update.events {
+            app.update(event, &mut model).assert_empty();
+        }
+        assert_eq!(model.winner, Some("short"));
+
+        // the losing timer still resolves, but its event is dropped
+        app.resolve(&mut long, TimeResponse::DurationElapsed { id: long_id })
+            .expect("should resolve")
+            .assert_empty();
+        assert_eq!(model.winner, Some("short"));
+    }
+
+    #[test]
+    fn race_stops_waiting_on_the_losers() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Race, &mut model);
+        let long = update.effects.pop().unwrap().expect_time();
+        let mut short = update.effects.pop().unwrap().expect_time();
+        assert!(short.id() < long.id());
+
+        let pending: Vec<_> = app.pending_effects().iter().map(|e| e.id).collect();
+        assert_eq!(pending, vec![short.id(), long.id()]);
+
+        let TimeRequest::NotifyAfter { id, .. } = short.operation else {
+            panic!("expected a timer request");
+        };
+        let update = app
+            .resolve(&mut short, TimeResponse::DurationElapsed { id })
+            .expect("should resolve");
+        assert_eq!(update.events, vec![Event::Raced("short")]);
+
+        assert!(app.pending_effects().is_empty());
+    }
+
+    #[test]
+    fn join_sends_effects_together_and_keeps_argument_order() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Join, &mut model);
+        let mut timers: Vec<_> = update.into_effects().map(Effect::expect_time).collect();
+        assert_eq!(timers.len(), 2);
+
+        // resolve the second timer first
+        assert!(elapse(&app, &mut timers[1]).is_empty());
+        let pending: Vec<_> = app.pending_effects().iter().map(|e| e.id).collect();
+        assert_eq!(pending, vec![timers[0].id()]);
+
+        let events = elapse(&app, &mut timers[0]);
+        assert_eq!(events, vec![Event::Joined(("a", "b"))]);
+
+        for event in events {
+            app.update(event, &mut model).assert_empty();
+        }
+        assert_eq!(model.finished, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn join_all_keeps_input_order() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let update = app.update(Event::JoinAll, &mut model);
+        let mut timers: Vec<_> = update.into_effects().map(Effect::expect_time).collect();
+        assert_eq!(timers.len(), 3);
+
+        assert!(elapse(&app, &mut timers[2]).is_empty());
+        assert!(elapse(&app, &mut timers[0]).is_empty());
+        let events = elapse(&app, &mut timers[1]);
+        assert_eq!(events, vec![Event::JoinedAll(vec!["a", "b", "c"])]);
+    }
+
+    #[test]
+    fn and_then_ok_continues_on_success() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let mut first = app
+            .update(Event::Chain, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        let TimeRequest::NotifyAfter { id, .. } = first.operation else {
+            panic!("expected a timer request");
+        };
+        let mut second = app
+            .resolve(&mut first, TimeResponse::DurationElapsed { id })
+            .expect("should resolve")
+            .expect_one_effect()
+            .expect_time();
+
+        let events = elapse(&app, &mut second);
+        assert_eq!(events, vec![Event::Chained(("first", "second"))]);
+    }
+
+    #[test]
+    fn and_then_ok_stops_on_error() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let mut first = app
+            .update(Event::Chain, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        let TimeRequest::NotifyAfter { id, .. } = first.operation else {
+            panic!("expected a timer request");
+        };
+        let update = app
+            .resolve(&mut first, TimeResponse::Cleared { id })
+            .expect("should resolve");
+
+        assert!(update.effects.is_empty());
+        assert_eq!(update.events, vec![Event::ChainFailed("first was cleared")]);
+    }
+
+    #[test]
+    fn discard_completes_without_an_event() {
+        let core: Core<Effect, App> = Core::new();
+
+        let mut effects = core.process_event(Event::Discard);
+        assert_eq!(effects.len(), 1);
+        assert_eq!(core.pending_effects().len(), 1);
+
+        let mut timer = effects.pop().unwrap().expect_time();
+        let TimeRequest::NotifyAfter { id, .. } = timer.operation else {
+            panic!("expected a timer request");
+        };
+        let effects = core
+            .resolve(&mut timer, TimeResponse::DurationElapsed { id })
+            .expect("should resolve");
+
+        assert!(effects.is_empty());
+        assert!(core.pending_effects().is_empty());
+    }
+
+    #[test]
+    fn discard_sends_no_event() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let mut timer = app
+            .update(Event::Discard, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        assert!(elapse(&app, &mut timer).is_empty());
+    }
+
+    #[test]
+    fn sequence_runs_one_at_a_time() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Sequence(vec!["a", "b", "c"]), &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        for _ in 0..2 {
+            let TimeRequest::NotifyAfter { id, .. } = request.operation else {
+                panic!("expected a timer request");
+            };
+            request = app
+                .resolve(&mut request, TimeResponse::DurationElapsed { id })
+                .expect("should resolve")
+                .expect_one_effect()
+                .expect_time();
+        }
+
+        let events = elapse(&app, &mut request);
+        assert_eq!(events, vec![Event::Sequenced(Ok(vec!["a", "b", "c"]))]);
+    }
+
+    #[test]
+    fn sequence_of_nothing_completes_straight_away() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Sequence(vec![]), &mut model);
+
+        assert!(update.effects.is_empty());
+        assert_eq!(update.events, vec![Event::Sequenced(Ok(vec![]))]);
+    }
+
+    #[test]
+    fn buffered_limits_effects_in_flight() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+        let labels: Vec<&'static str> = vec!["a", "b", "c", "d", "e", "f", "g"];
+
+        let mut in_flight: Vec<_> = app
+            .update(Event::Buffer(labels.clone(), 3), &mut model)
+            .into_effects()
+            .map(|effect| effect.expect_time())
+            .collect();
+        let mut max_in_flight = in_flight.len();
+        let mut events = vec![];
+
+        // complete the most recent request each time, so they finish out of order
+        while let Some(mut request) = in_flight.pop() {
+            let TimeRequest::NotifyAfter { id, .. } = request.operation else {
+                panic!("expected a timer request");
+            };
+            let update = app
+                .resolve(&mut request, TimeResponse::DurationElapsed { id })
+                .expect("should resolve");
+
+            events.extend(update.events);
+            in_flight.extend(
+                update
+                    .effects
+                    .into_iter()
+                    .map(|effect| effect.expect_time()),
+            );
+            max_in_flight = max_in_flight.max(in_flight.len());
+        }
+
+        assert_eq!(max_in_flight, 3);
+        assert_eq!(events, vec![Event::Buffered(labels)]);
+    }
+
+    #[test]
+    fn buffered_of_nothing_completes_straight_away() {
+        let app: AppTester<App, Effect> = AppTester::default();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Buffer(vec![], 2), &mut model);
+
+        assert!(update.effects.is_empty());
+        assert_eq!(update.events, vec![Event::Buffered(vec![])]);
+    }
+}
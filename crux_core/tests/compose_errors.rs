@@ -0,0 +1,164 @@
+mod child {
+    use crux_core::{compose::Compose, compose::ErrorEvent, macros::Effect, render::Render};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Load(Result<u32, String>),
+        Loaded(Result<u32, String>),
+    }
+
+    impl ErrorEvent for Event {
+        type Error = String;
+
+        fn split_error(self) -> Result<Self, Self::Error> {
+            match self {
+                Event::Loaded(Err(error)) => Err(error),
+                event => Ok(event),
+            }
+        }
+    }
+
+    #[derive(Default, Debug)]
+    pub struct Model {
+        pub value: Option<u32>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        #[effect(skip)]
+        pub compose: Compose<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Load(result) => caps.compose.spawn(|context| async move {
+                    context.update_app(Event::Loaded(result));
+                }),
+                Event::Loaded(Ok(value)) => {
+                    model.value = Some(value);
+                    caps.render.render();
+                }
+                Event::Loaded(Err(_)) => unreachable!("errors are handled by the parent"),
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod parent {
+    use crux_core::{compose, compose::Compose, macros::Effect, render::Render, Capability};
+
+    use super::child;
+
+    #[derive(Default)]
+    pub struct App {
+        child: child::App,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Child(child::Event),
+        Failed(String),
+    }
+
+    #[derive(Default, Debug)]
+    pub struct Model {
+        pub child: child::Model,
+        pub errors: Vec<String>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        #[effect(skip)]
+        pub compose: Compose<Event>,
+    }
+
+    impl From<&Capabilities> for child::Capabilities {
+        fn from(incoming: &Capabilities) -> Self {
+            child::Capabilities {
+                render: incoming.render.map_event(Event::Child),
+                compose: incoming
+                    .compose
+                    .map_event(compose::map_err(Event::Child, Event::Failed)),
+            }
+        }
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Child(event) => {
+                    crux_core::App::update(&self.child, event, &mut model.child, &caps.into());
+                }
+                Event::Failed(error) => {
+                    model.errors.push(error);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::testing::AppTester;
+
+    use super::{
+        child,
+        parent::{App, Event, Model},
+    };
+
+    #[test]
+    fn child_errors_go_to_the_parent() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let update = app.update(
+            Event::Child(child::Event::Load(Err("not found".to_string()))),
+            &mut model,
+        );
+        assert_eq!(update.events, vec![Event::Failed("not found".to_string())]);
+
+        for event in update.events {
+            app.update(event, &mut model).expect_render();
+        }
+        assert_eq!(model.errors, vec!["not found"]);
+        assert_eq!(model.child.value, None);
+    }
+
+    #[test]
+    fn other_child_events_go_to_the_child() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Child(child::Event::Load(Ok(3))), &mut model);
+        assert_eq!(
+            update.events,
+            vec![Event::Child(child::Event::Loaded(Ok(3)))]
+        );
+
+        for event in update.events {
+            app.update(event, &mut model).expect_render();
+        }
+        assert_eq!(model.child.value, Some(3));
+        assert!(model.errors.is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+//! Tests for the effect assertions on `testing::Update`
+
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_time::{Time, TimeResponse};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub enum Event {
+        Render,
+        GetTime,
+        Nothing,
+        Time(TimeResponse),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        pub time: Time<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Render => caps.render.render(),
+                Event::GetTime => caps.time.now(Event::Time),
+                Event::Nothing | Event::Time(_) => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::testing::AppTester;
+    use crux_time::TimeRequest;
+
+    use crate::app::{App, Event};
+
+    #[test]
+    fn expect_effect_returns_the_request() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::GetTime, &mut ());
+        let request = update.expect_effect::<TimeRequest>();
+
+        assert_eq!(request.operation, TimeRequest::Now);
+        update.expect_no_effects();
+    }
+
+    #[test]
+    fn expect_render_returns_the_request() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::Render, &mut ());
+        update.expect_render();
+
+        update.expect_no_effects();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a crux_time::TimeRequest effect but found Render(")]
+    fn expect_effect_reports_the_effect_found() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::Render, &mut ());
+        update.expect_effect::<TimeRequest>();
+    }
+
+    #[test]
+    #[should_panic(expected = "RenderOperation effect but found no effects")]
+    fn expect_render_reports_no_effects() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::Nothing, &mut ());
+        update.expect_render();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected no effects but found [Time(")]
+    fn expect_no_effects_lists_the_effects() {
+        let app = AppTester::<App, _>::default();
+
+        let update = app.update(Event::GetTime, &mut ());
+        update.expect_no_effects();
+    }
+}
@@ -0,0 +1,130 @@
+mod app {
+    use crux_core::{compose::Compose, macros::Effect, render::Render};
+    use crux_http::Http;
+    use crux_kv::KeyValue;
+
+    const URL: &str = "https://example.com/";
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        InCallOrder,
+        InReverseCallOrder,
+        Combined,
+        CombinedMany(usize),
+        Done,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub key_value: KeyValue<Event>,
+        pub render: Render<Event>,
+        #[effect(skip)]
+        pub compose: Compose<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::InCallOrder => {
+                    caps.http.get(URL).send(|_| Event::Done);
+                    caps.key_value.get("key".to_string(), |_| Event::Done);
+                    caps.render.render();
+                }
+                Event::InReverseCallOrder => {
+                    caps.render.render();
+                    caps.key_value.get("key".to_string(), |_| Event::Done);
+                    caps.http.get(URL).send(|_| Event::Done);
+                }
+                Event::Combined => {
+                    caps.render.render();
+
+                    let http = caps.http.clone();
+                    let key_value = caps.key_value.clone();
+                    caps.compose.spawn(|context| async move {
+                        let _ = futures::join!(
+                            key_value.get_async("key".to_string()),
+                            http.get(URL).send_async(),
+                        );
+                        context.update_app(Event::Done);
+                    });
+                }
+                Event::CombinedMany(count) => {
+                    let reads = (0..count).map(|n| {
+                        let key_value = caps.key_value.clone();
+                        async move { key_value.get_async(n.to_string()).await }
+                    });
+                    caps.compose.join_all(reads, |_| Event::Done);
+                }
+                Event::Done => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::Core;
+    use crux_kv::KeyValueOperation;
+
+    use super::app::{App, Effect, Event};
+
+    fn kinds(effects: &[Effect]) -> Vec<&'static str> {
+        effects
+            .iter()
+            .map(|effect| match effect {
+                Effect::Http(_) => "http",
+                Effect::KeyValue(_) => "key_value",
+                Effect::Render(_) => "render",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn effects_follow_call_order() {
+        let core: Core<Effect, App> = Core::new();
+
+        let effects = core.process_event(Event::InCallOrder);
+        assert_eq!(kinds(&effects), ["http", "key_value", "render"]);
+
+        let effects = core.process_event(Event::InReverseCallOrder);
+        assert_eq!(kinds(&effects), ["render", "key_value", "http"]);
+    }
+
+    #[test]
+    fn combined_effects_follow_poll_order() {
+        let core: Core<Effect, App> = Core::new();
+
+        let effects = core.process_event(Event::Combined);
+        assert_eq!(kinds(&effects), ["render", "key_value", "http"]);
+    }
+
+    #[test]
+    fn join_all_effects_follow_input_order() {
+        let core: Core<Effect, App> = Core::new();
+
+        // more than futures::future::join_all handles without a FuturesOrdered
+        let effects = core.process_event(Event::CombinedMany(40));
+
+        let keys: Vec<_> = effects
+            .into_iter()
+            .map(|effect| match effect {
+                Effect::KeyValue(request) => match request.operation {
+                    KeyValueOperation::Get { key } => key,
+                    operation => panic!("expected a read, got {operation:?}"),
+                },
+                _ => panic!("expected a key value effect"),
+            })
+            .collect();
+        let expected: Vec<_> = (0..40).map(|n| n.to_string()).collect();
+        assert_eq!(keys, expected);
+    }
+}
@@ -0,0 +1,97 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Resize(u32, u32),
+        Scroll(u32),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        updates: Vec<String>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = Vec<String>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            model.updates.push(match event {
+                Event::Resize(width, height) => format!("resize {width}x{height}"),
+                Event::Scroll(offset) => format!("scroll {offset}"),
+            });
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.updates.clone()
+        }
+    }
+}
+
+mod tests {
+    use crux_core::Core;
+
+    use crate::app::{App, Effect, Event};
+
+    fn burst() -> Vec<Event> {
+        vec![
+            Event::Resize(100, 100),
+            Event::Resize(200, 150),
+            Event::Resize(300, 200),
+            Event::Scroll(10),
+            Event::Scroll(20),
+            Event::Resize(400, 250),
+        ]
+    }
+
+    fn core() -> Core<Effect, App> {
+        Core::new().with_event_coalescing(|previous, next| {
+            matches!((previous, next), (Event::Resize(..), Event::Resize(..)))
+        })
+    }
+
+    #[test]
+    fn events_are_not_coalesced_by_default() {
+        let core: Core<Effect, App> = Core::new();
+
+        let effects = core.process_events(burst());
+
+        assert_eq!(effects.len(), 6);
+        assert_eq!(core.view().len(), 6);
+    }
+
+    #[test]
+    fn consecutive_events_are_coalesced_to_the_last() {
+        let core = core();
+
+        let effects = core.process_events(burst());
+
+        assert_eq!(effects.len(), 4);
+        assert_eq!(
+            core.view(),
+            vec!["resize 300x200", "scroll 10", "scroll 20", "resize 400x250"]
+        );
+        assert_eq!(core.queue_depth(), 0);
+    }
+
+    #[test]
+    fn events_processed_one_at_a_time_are_not_coalesced() {
+        let core = core();
+
+        core.process_event(Event::Resize(100, 100));
+        core.process_event(Event::Resize(200, 150));
+
+        assert_eq!(core.view(), vec!["resize 100x100", "resize 200x150"]);
+    }
+}
@@ -1,6 +1,7 @@
 mod app {
     use crux_core::macros::Effect;
     use crux_core::render::Render;
+    use crux_time::{Time, TimeResponse};
     use serde::{Deserialize, Serialize};
 
     #[derive(Default)]
@@ -9,6 +10,9 @@ mod app {
     #[derive(Serialize, Deserialize, Debug)]
     pub enum Event {
         Trigger,
+        GetTime,
+        GetTimeTwice,
+        Time(TimeResponse),
     }
 
     #[derive(Serialize, Deserialize)]
@@ -19,33 +23,49 @@ mod app {
         type ViewModel = ViewModel;
         type Capabilities = Capabilities;
 
-        fn update(&self, _event: Event, _model: &mut Self::Model, caps: &Capabilities) {
-            caps.render.render();
+        fn update(&self, event: Event, _model: &mut Self::Model, caps: &Capabilities) {
+            match event {
+                Event::GetTime => caps.time.now(Event::Time),
+                Event::GetTimeTwice => {
+                    caps.time.now(Event::Time);
+                    caps.time.now(Event::Time);
+                }
+                Event::Trigger | Event::Time(_) => caps.render.render(),
+            }
         }
 
         fn view(&self, _model: &Self::Model) -> Self::ViewModel {
-            unimplemented!();
+            ViewModel
         }
     }
 
     #[derive(Effect)]
     pub struct Capabilities {
         pub render: Render<Event>,
+        pub time: Time<Event>,
     }
 }
 
 mod core {
-    use crux_core::bridge::BridgeWithSerializer;
+    use crux_core::bridge::{self, BridgeWithSerializer, JsonCodec};
 
-    use crate::app::{App, Effect};
+    use crate::app::{App, Effect, EffectFfi};
 
     pub type Bridge = BridgeWithSerializer<Effect, App>;
+    pub type JsonBridge = bridge::Bridge<Effect, App, JsonCodec>;
+    pub type BincodeBridge = bridge::Bridge<Effect, App>;
+    pub type BridgeResult = Result<Vec<bridge::Request<EffectFfi>>, bridge::BridgeError>;
 }
 
 mod tests {
 
-    use crate::core::Bridge;
-    use crux_core::Core;
+    use crate::core::{BincodeBridge, Bridge, BridgeResult, JsonBridge};
+    use std::sync::{Arc, Mutex};
+
+    use crux_core::{
+        bridge::{BridgeError, BridgeEvent, JsonCodec},
+        Core,
+    };
     use serde_json::{json, Value};
 
     #[test]
@@ -69,22 +89,222 @@ mod tests {
         };
 
         let Value::Number(id) = &request["id"] else {
-            panic!("Expected id to be a number, got: {:?}", &request["id"])
+            panic!("Expected id to be a number, got: {:?}", request["id"])
         };
         assert_eq!(id.as_u64().unwrap(), 0);
 
         let Value::Object(effect) = &request["effect"] else {
             panic!(
                 "Expected effect to be an object, got: {:?}",
-                &request["effect"]
+                request["effect"]
             )
         };
 
-        let Value::Null = &effect["Render"] else {
+        let Value::Null = &effect["Render"]["reason"] else {
             panic!(
                 "Expected effect to be a 'Render' variant, got: {:?}",
-                &effect
+                effect
             )
         };
     }
+
+    #[test]
+    fn event_effect_loop_with_json_codec() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+
+        let effects_bytes = bridge.process_event(br#""Trigger""#);
+
+        let actual_value: Value = serde_json::from_slice(&effects_bytes).unwrap();
+        assert_eq!(
+            actual_value,
+            json!([{ "id": 0, "effect": { "Render": null } }])
+        );
+    }
+
+    #[test]
+    fn observer_sees_effects_responses_and_views_in_order() {
+        let mut bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        bridge.set_observer({
+            let seen = seen.clone();
+            move |event: &BridgeEvent| {
+                let (kind, id, bytes) = match *event {
+                    BridgeEvent::EffectEmitted { id, bytes, .. } => ("effect", Some(id), bytes),
+                    BridgeEvent::ResponseReceived { id, bytes, .. } => {
+                        ("response", Some(id), bytes)
+                    }
+                    BridgeEvent::ViewRendered { bytes, .. } => ("view", None, bytes),
+                };
+                let value: Value = serde_json::from_slice(bytes).unwrap();
+                seen.lock().unwrap().push((event.seq(), kind, id, value));
+            }
+        });
+
+        let _effects = bridge.process_event(br#""GetTime""#);
+        let response = br#"{"now":{"instant":{"seconds":1,"nanos":2}}}"#;
+        let _effects = bridge.handle_response(0, response);
+        let _view = bridge.view();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (
+                    0,
+                    "effect",
+                    Some(0),
+                    json!({ "id": 0, "effect": { "Time": "now" } })
+                ),
+                (
+                    1,
+                    "response",
+                    Some(0),
+                    json!({ "now": { "instant": { "seconds": 1, "nanos": 2 } } })
+                ),
+                // the resolved request's id is free to be reused
+                (
+                    2,
+                    "effect",
+                    Some(0),
+                    json!({ "id": 0, "effect": { "Render": null } })
+                ),
+                (3, "view", None, json!(null)),
+            ]
+        );
+    }
+
+    #[test]
+    fn handle_responses_in_one_call() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+
+        let effects = bridge.process_event(br#""GetTimeTwice""#);
+        let effects: Value = serde_json::from_slice(&effects).unwrap();
+        assert_eq!(
+            effects,
+            json!([
+                { "id": 0, "effect": { "Time": "now" } },
+                { "id": 1, "effect": { "Time": "now" } },
+            ])
+        );
+
+        let first = br#"{"now":{"instant":{"seconds":1,"nanos":0}}}"#;
+        let second = br#"{"now":{"instant":{"seconds":2,"nanos":0}}}"#;
+        let effects = bridge.handle_responses(&[(1, second), (0, first)]);
+
+        // each response renders in turn, reusing the id it freed
+        let effects: Value = serde_json::from_slice(&effects).unwrap();
+        assert_eq!(
+            effects,
+            json!([
+                { "id": 1, "effect": { "Render": null } },
+                { "id": 0, "effect": { "Render": null } },
+            ])
+        );
+    }
+
+    fn bridge_result(bytes: &[u8]) -> Result<Value, BridgeError> {
+        serde_json::from_slice(bytes).unwrap()
+    }
+
+    #[test]
+    fn try_process_event_wraps_requests_in_ok() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+
+        let result = bridge.try_process_event(br#""GetTime""#);
+
+        let value: Value = serde_json::from_slice(&result).unwrap();
+        assert_eq!(
+            value,
+            json!({ "Ok": [{ "id": 0, "effect": { "Time": "now" } }] })
+        );
+    }
+
+    #[test]
+    fn try_process_event_returns_deserialization_error() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+
+        let result = bridge.try_process_event(br#""Explode""#);
+
+        let value: Value = serde_json::from_slice(&result).unwrap();
+        assert!(value["Err"]["DeserializeEvent"]["message"].is_string());
+        assert!(matches!(
+            bridge_result(&result),
+            Err(BridgeError::DeserializeEvent { .. })
+        ));
+    }
+
+    #[test]
+    fn try_handle_response_returns_unknown_effect() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+
+        let result =
+            bridge.try_handle_response(42, br#"{"now":{"instant":{"seconds":1,"nanos":0}}}"#);
+
+        assert_eq!(
+            bridge_result(&result),
+            Err(BridgeError::UnknownEffect { id: 42 })
+        );
+    }
+
+    #[test]
+    fn effect_can_be_resolved_after_undeserializable_response() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+        bridge.process_event(br#""GetTime""#);
+
+        let result = bridge.try_handle_response(0, br#"{"later":{}}"#);
+        assert!(matches!(
+            bridge_result(&result),
+            Err(BridgeError::DeserializeOutput { id: 0, .. })
+        ));
+
+        let result =
+            bridge.try_handle_response(0, br#"{"now":{"instant":{"seconds":1,"nanos":0}}}"#);
+        assert_eq!(
+            bridge_result(&result),
+            Ok(json!([{ "id": 0, "effect": { "Render": null } }]))
+        );
+
+        let result =
+            bridge.try_handle_response(0, br#"{"now":{"instant":{"seconds":1,"nanos":0}}}"#);
+        assert!(matches!(
+            bridge_result(&result),
+            Err(BridgeError::Resolve { id: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn try_handle_response_rejects_a_response_for_another_operation() {
+        let bridge = JsonBridge::new_with_codec(Core::default(), JsonCodec);
+        bridge.process_event(br#""GetTime""#);
+
+        let result = bridge.try_handle_response(0, br#"{"cleared":{"id":1}}"#);
+        let Err(BridgeError::Resolve { id: 0, message }) = bridge_result(&result) else {
+            panic!("expected a resolve error");
+        };
+        assert!(message.contains("does not expect"));
+
+        let result =
+            bridge.try_handle_response(0, br#"{"now":{"instant":{"seconds":1,"nanos":0}}}"#);
+        assert_eq!(
+            bridge_result(&result),
+            Ok(json!([{ "id": 0, "effect": { "Render": null } }]))
+        );
+    }
+
+    #[test]
+    fn bridge_error_round_trips_with_bincode() {
+        use bincode::Options;
+
+        let bridge = BincodeBridge::new(Core::default());
+
+        let result = bridge.try_process_event(&[9, 0, 0, 0]);
+
+        // a 4 byte variant index, 1 for Err, followed by the error
+        assert_eq!(result[..4], [1, 0, 0, 0]);
+        let result: BridgeResult = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .deserialize(&result)
+            .unwrap();
+        assert!(matches!(result, Err(BridgeError::DeserializeEvent { .. })));
+    }
 }
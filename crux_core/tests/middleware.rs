@@ -0,0 +1,194 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_http::Http;
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Fetch,
+        Fetched(crux_http::Result<crux_http::Response<String>>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        greeting: String,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Fetch => {
+                    caps.http
+                        .get("https://example.com/greeting")
+                        .expect_string()
+                        .send(Event::Fetched);
+                }
+                Event::Fetched(Ok(mut response)) => {
+                    model.greeting = response.take_body().unwrap();
+                    caps.render.render();
+                }
+                Event::Fetched(Err(error)) => {
+                    model.greeting = error.to_string();
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.greeting.clone()
+        }
+    }
+}
+
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crux_core::{
+        middleware::{
+            EffectMiddleware, EffectResponse, LoggingMiddleware, MiddlewareStack, Resolver,
+        },
+        Core,
+    };
+    use crux_http::protocol::{HttpResponse, HttpResult};
+
+    use crate::app::{App, Effect, Event};
+
+    // answers HTTP requests in place of the shell
+    struct MockHttp;
+
+    impl EffectMiddleware<Effect> for MockHttp {
+        fn on_effect(&self, effect: Effect, resolver: &Resolver) -> Option<Effect> {
+            let Effect::Http(mut request) = effect else {
+                return Some(effect);
+            };
+
+            let response = HttpResponse::ok().body("Hello from the mock").build();
+            resolver
+                .resolve(&mut request, HttpResult::Ok(response))
+                .unwrap();
+
+            None
+        }
+    }
+
+    // shouts the body of every HTTP response
+    struct Shout;
+
+    impl EffectMiddleware<Effect> for Shout {
+        fn on_response(&self, response: &mut EffectResponse<'_>) {
+            if let Some(HttpResult::Ok(response)) = response.output_mut::<HttpResult>() {
+                response.body = response.body.to_ascii_uppercase();
+            }
+        }
+    }
+
+    fn logger(label: &'static str, log: &Arc<Mutex<Vec<String>>>) -> LoggingMiddleware {
+        let log = log.clone();
+        LoggingMiddleware::new(move |line| {
+            let kind = line.split(':').next().unwrap();
+            log.lock().unwrap().push(format!("{label} {kind}"));
+        })
+    }
+
+    #[test]
+    fn middleware_resolves_effects_in_place_of_the_shell() {
+        let core: Core<Effect, App> =
+            Core::new().with_middleware(MiddlewareStack::new().with(MockHttp));
+
+        let effects = core.process_event(Event::Fetch);
+
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+        assert_eq!(core.view(), "Hello from the mock");
+        assert!(core.pending_effects().is_empty());
+    }
+
+    #[test]
+    fn middleware_changes_responses() {
+        let core: Core<Effect, App> =
+            Core::new().with_middleware(MiddlewareStack::new().with(Shout));
+
+        let mut effects = core.process_event(Event::Fetch);
+        let Some(Effect::Http(mut request)) = effects.pop() else {
+            panic!("expected an HTTP request");
+        };
+
+        let response = HttpResponse::ok().body("hello").build();
+        core.resolve(&mut request, HttpResult::Ok(response))
+            .unwrap();
+
+        assert_eq!(core.view(), "HELLO");
+    }
+
+    #[test]
+    fn logging_middleware_logs_effects_and_responses() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let lines = log.clone();
+        let core: Core<Effect, App> = Core::new().with_middleware(MiddlewareStack::new().with(
+            LoggingMiddleware::new(move |line| {
+                lines.lock().unwrap().push(line.to_string());
+            }),
+        ));
+
+        let mut effects = core.process_event(Event::Fetch);
+        let Some(Effect::Http(mut request)) = effects.pop() else {
+            panic!("expected an HTTP request");
+        };
+        let response = HttpResponse::ok().body("Hello").build();
+        core.resolve(&mut request, HttpResult::Ok(response))
+            .unwrap();
+
+        let log = log.lock().unwrap();
+        assert_eq!(log.len(), 3);
+        assert!(log[0].starts_with("effect: Http("));
+        assert_eq!(
+            log[1],
+            format!(
+                "response: #{} crux_http::protocol::HttpRequest",
+                request.id()
+            )
+        );
+        assert!(log[2].starts_with("effect: Render("));
+    }
+
+    #[test]
+    fn effects_pass_out_through_the_stack_and_responses_back_in_reverse() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let core: Core<Effect, App> = Core::new().with_middleware(
+            MiddlewareStack::new()
+                .with(logger("inner", &log))
+                .with(logger("outer", &log)),
+        );
+
+        let mut effects = core.process_event(Event::Fetch);
+        let Some(Effect::Http(mut request)) = effects.pop() else {
+            panic!("expected an HTTP request");
+        };
+        let response = HttpResponse::ok().body("Hello").build();
+        core.resolve(&mut request, HttpResult::Ok(response))
+            .unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "inner effect",
+                "outer effect",
+                "outer response",
+                "inner response",
+                "inner effect",
+                "outer effect",
+            ]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render, NamedView};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Receive(String),
+        Read,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub messages: Vec<String>,
+        pub read: usize,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct ViewModel {
+        pub messages: Vec<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Badge {
+        pub unread: usize,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Receive(message) => model.messages.push(message),
+                Event::Read => model.read = model.messages.len(),
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                messages: model.messages.clone(),
+            }
+        }
+
+        fn view_named(&self, name: &str, model: &Self::Model) -> Option<NamedView> {
+            match name {
+                "badge" => Some(NamedView::new(Badge {
+                    unread: model.messages.len() - model.read,
+                })),
+                _ => None,
+            }
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{
+        bridge::{Bridge, JsonCodec},
+        Core,
+    };
+
+    use super::app::{App, Badge, Effect, Event, ViewModel};
+
+    #[test]
+    fn core_returns_named_view() {
+        let core: Core<Effect, App> = Core::new();
+        core.process_event(Event::Receive("hello".to_string()));
+        core.process_event(Event::Receive("again".to_string()));
+
+        let badge = core.view_named("badge").expect("badge view");
+        assert_eq!(
+            serde_json::to_value(badge).unwrap(),
+            serde_json::json!({ "unread": 2 })
+        );
+
+        core.process_event(Event::Read);
+        let badge = core.view_named("badge").expect("badge view");
+        assert_eq!(
+            serde_json::to_value(badge).unwrap(),
+            serde_json::json!({ "unread": 0 })
+        );
+    }
+
+    #[test]
+    fn unknown_name_has_no_view() {
+        let core: Core<Effect, App> = Core::new();
+
+        assert!(core.view_named("inbox").is_none());
+    }
+
+    #[test]
+    fn bridge_serializes_named_view() {
+        let bridge = Bridge::new(Core::<Effect, App>::new());
+        bridge.process_event(&bincode::serialize(&Event::Receive("hello".to_string())).unwrap());
+
+        let badge: Badge = bincode::deserialize(&bridge.view_named("badge").unwrap()).unwrap();
+        assert_eq!(badge, Badge { unread: 1 });
+
+        // the main view is unaffected
+        let view: ViewModel = bincode::deserialize(&bridge.view()).unwrap();
+        assert_eq!(
+            view,
+            ViewModel {
+                messages: vec!["hello".to_string()]
+            }
+        );
+
+        assert!(bridge.view_named("inbox").is_none());
+    }
+
+    #[test]
+    fn named_views_are_not_patched() {
+        let bridge: Bridge<Effect, App, JsonCodec> =
+            Bridge::new_with_codec(Core::new(), JsonCodec).with_view_patches();
+        bridge.view();
+
+        let badge: Badge = serde_json::from_slice(&bridge.view_named("badge").unwrap()).unwrap();
+        assert_eq!(badge, Badge { unread: 0 });
+    }
+}
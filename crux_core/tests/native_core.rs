@@ -0,0 +1,179 @@
+mod app {
+    use crux_core::{
+        capability::{CapabilityContext, Operation},
+        macros::{Capability, Effect},
+        render::Render,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct Lookup(pub String);
+
+    impl Operation for Lookup {
+        type Output = Option<u32>;
+    }
+
+    #[derive(Capability)]
+    pub struct Directory<Ev> {
+        context: CapabilityContext<Lookup, Ev>,
+    }
+
+    impl<Ev> Directory<Ev>
+    where
+        Ev: 'static,
+    {
+        pub fn new(context: CapabilityContext<Lookup, Ev>) -> Self {
+            Self { context }
+        }
+
+        pub fn lookup<F>(&self, name: &str, callback: F)
+        where
+            F: FnOnce(Option<u32>) -> Ev + Send + 'static,
+        {
+            let context = self.context.clone();
+            let name = name.to_string();
+            self.context.spawn(async move {
+                let number = context.request_from_shell(Lookup(name)).await;
+                context.update_app(callback(number));
+            });
+        }
+    }
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Debug, PartialEq)]
+    pub enum Event {
+        Find(String),
+        Found(Option<u32>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        number: Option<u32>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub directory: Directory<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Find(name) => caps.directory.lookup(&name, Event::Found),
+                Event::Found(number) => {
+                    model.number = number;
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            match model.number {
+                Some(number) => format!("found {number}"),
+                None => "not found".to_string(),
+            }
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{Core, ResolveError};
+
+    use super::app::{App, Effect, Event, Lookup};
+
+    #[test]
+    fn event_effect_resolve_cycle() {
+        let core: Core<Effect, App> = Core::new();
+
+        let mut effects = core.process_event(Event::Find("alice".to_string()));
+        assert_eq!(effects.len(), 1);
+
+        let Effect::Directory(mut request) = effects.remove(0) else {
+            panic!("expected a directory lookup");
+        };
+        assert_eq!(request.operation, Lookup("alice".to_string()));
+
+        let effects = core.resolve(&mut request, Some(42)).unwrap();
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+
+        assert_eq!(core.view(), "found 42");
+    }
+
+    #[test]
+    fn resolving_twice_is_an_error() {
+        let core: Core<Effect, App> = Core::new();
+
+        let Effect::Directory(mut request) =
+            core.process_event(Event::Find("bob".to_string())).remove(0)
+        else {
+            panic!("expected a directory lookup");
+        };
+
+        core.resolve(&mut request, None).unwrap();
+        assert_eq!(core.view(), "not found");
+
+        assert_eq!(
+            core.resolve(&mut request, Some(1)).unwrap_err(),
+            ResolveError::Never
+        );
+        assert_eq!(core.view(), "not found");
+    }
+
+    #[test]
+    fn render_requests_cant_be_resolved() {
+        let core: Core<Effect, App> = Core::new();
+
+        let Effect::Directory(mut request) = core
+            .process_event(Event::Find("carol".to_string()))
+            .remove(0)
+        else {
+            panic!("expected a directory lookup");
+        };
+        let Effect::Render(mut render) = core.resolve(&mut request, Some(7)).unwrap().remove(0)
+        else {
+            panic!("expected a render");
+        };
+
+        assert_eq!(
+            core.resolve(&mut render, ()).unwrap_err(),
+            ResolveError::Never
+        );
+    }
+
+    #[test]
+    fn pending_effects_lists_unresolved_requests() {
+        let core: Core<Effect, App> = Core::new();
+        assert_eq!(core.pending_effects(), vec![]);
+
+        let mut first = core.process_event(Event::Find("dave".to_string()));
+        let second = core.process_event(Event::Find("erin".to_string()));
+
+        let pending = core.pending_effects();
+        assert_eq!(pending.len(), 2);
+        assert!(pending[0].id < pending[1].id);
+        assert!(pending
+            .iter()
+            .all(|effect| effect.operation.ends_with("::Lookup")));
+
+        let Effect::Directory(mut request) = first.remove(0) else {
+            panic!("expected a directory lookup");
+        };
+        let effects = core.resolve(&mut request, Some(1)).unwrap();
+
+        // render requests don't expect a response, so are never pending
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+        assert_eq!(core.pending_effects(), vec![pending[1]]);
+
+        // a request the shell drops without resolving is still waited on
+        drop(second);
+        assert_eq!(core.pending_effects(), vec![pending[1]]);
+    }
+}
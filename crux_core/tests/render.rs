@@ -0,0 +1,111 @@
+mod app {
+    use crux_core::{
+        macros::Effect,
+        render::{Render, RenderHints},
+    };
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Changed,
+        ItemAdded,
+        SidebarChanged,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        pub render_hints: RenderHints<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Changed => caps.render.render(),
+                Event::ItemAdded => caps.render_hints.render_with_reason("item_added"),
+                Event::SidebarChanged => caps.render_hints.render_region("sidebar"),
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::{
+        render::{RenderHint, RenderOperation},
+        testing::AppTester,
+        Core,
+    };
+
+    use super::app::{App, Effect, Event};
+
+    #[test]
+    fn render_has_no_hint() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::Changed, &mut ());
+
+        assert_eq!(update.expect_render().operation, RenderOperation);
+    }
+
+    #[test]
+    fn render_with_reason() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::ItemAdded, &mut ());
+
+        assert_eq!(
+            update.expect_render_hint().operation.reason.as_deref(),
+            Some("item_added")
+        );
+    }
+
+    #[test]
+    fn render_region() {
+        let app = AppTester::<App, _>::default();
+
+        let mut update = app.update(Event::SidebarChanged, &mut ());
+
+        let operation = update.expect_render_hint().operation;
+        assert_eq!(operation.region.as_deref(), Some("sidebar"));
+        assert_eq!(operation.reason, None);
+    }
+
+    #[test]
+    fn render_keeps_its_wire_format() {
+        use bincode::Options;
+
+        let core: Core<Effect, App> = Core::new();
+        let effect = core.process_event(Event::Changed).remove(0);
+        let (ffi, _) = crux_core::Effect::serialize(effect);
+
+        // the variant index of the render effect, and nothing for the operation
+        let bytes = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .serialize(&ffi)
+            .unwrap();
+        assert_eq!(bytes, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn render_hint_is_a_separate_effect() {
+        let core: Core<Effect, App> = Core::new();
+
+        let effects = core.process_event(Event::ItemAdded);
+
+        assert!(matches!(
+            &effects[..],
+            [Effect::RenderHints(request)] if request.operation == RenderHint {
+                reason: Some("item_added".to_string()),
+                region: None,
+            }
+        ));
+    }
+}
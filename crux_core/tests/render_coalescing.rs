@@ -0,0 +1,151 @@
+mod app {
+    use crux_core::{
+        macros::Effect,
+        render::{Render, RenderHints},
+    };
+    use crux_time::Time;
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Update,
+        Regions,
+        RegionsAndWhole,
+        Time,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+        pub render_hints: RenderHints<Event>,
+        pub time: Time<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ();
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, _model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Update => {
+                    caps.render.render();
+                    caps.render.render();
+                    caps.time.now(|_| Event::Time);
+                    caps.render.render();
+                    caps.render.render();
+                    caps.render.render();
+                }
+                Event::Regions => {
+                    caps.render_hints.render_region("header");
+                    caps.render_hints.render_region("list");
+                    caps.render_hints.render_region("header");
+                    caps.time.now(|_| Event::Time);
+                    caps.render_hints.render_region("list");
+                }
+                Event::RegionsAndWhole => {
+                    caps.render_hints.render_region("header");
+                    caps.render_hints.render_with_reason("first");
+                    caps.render_hints.render_region("list");
+                    caps.render_hints.render_with_reason("second");
+                }
+                Event::Time => {}
+            }
+        }
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {}
+    }
+}
+
+mod tests {
+    use crux_core::{render::RenderHint, Core};
+
+    use crate::app::{App, Effect, Event};
+
+    fn kinds(effects: &[Effect]) -> Vec<&'static str> {
+        effects
+            .iter()
+            .map(|effect| match effect {
+                Effect::Render(_) | Effect::RenderHints(_) => "render",
+                Effect::Time(_) => "time",
+            })
+            .collect()
+    }
+
+    fn hints(effects: Vec<Effect>) -> Vec<RenderHint> {
+        effects
+            .into_iter()
+            .filter_map(|effect| match effect {
+                Effect::RenderHints(request) => Some(request.operation),
+                Effect::Render(_) | Effect::Time(_) => None,
+            })
+            .collect()
+    }
+
+    fn region(region: &str) -> RenderHint {
+        RenderHint {
+            region: Some(region.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_are_not_coalesced_by_default() {
+        let core: Core<Effect, App> = Core::new();
+
+        let effects = core.process_event(Event::Update);
+
+        assert_eq!(
+            kinds(&effects),
+            vec!["render", "render", "time", "render", "render", "render"]
+        );
+    }
+
+    #[test]
+    fn consecutive_renders_are_coalesced() {
+        let core: Core<Effect, App> = Core::new().with_render_coalescing();
+
+        let effects = core.process_event(Event::Update);
+
+        assert_eq!(kinds(&effects), vec!["render", "time", "render"]);
+    }
+
+    #[test]
+    fn region_renders_are_coalesced_per_region() {
+        let core: Core<Effect, App> = Core::new().with_render_coalescing();
+
+        let effects = core.process_event(Event::Regions);
+
+        assert_eq!(kinds(&effects), vec!["render", "render", "time", "render"]);
+        assert_eq!(
+            hints(effects),
+            vec![region("header"), region("list"), region("list")]
+        );
+    }
+
+    #[test]
+    fn whole_render_covers_region_renders() {
+        let core: Core<Effect, App> = Core::new().with_render_coalescing();
+
+        let effects = core.process_event(Event::RegionsAndWhole);
+
+        assert_eq!(
+            hints(effects),
+            vec![RenderHint {
+                reason: Some("first".to_string()),
+                region: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn region_renders_are_not_coalesced_by_default() {
+        let core: Core<Effect, App> = Core::new();
+
+        let effects = core.process_event(Event::RegionsAndWhole);
+
+        assert_eq!(hints(effects).len(), 4);
+    }
+}
@@ -0,0 +1,122 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_http::Http;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Increment,
+        Fetch,
+        #[serde(skip)]
+        Fetched(crux_http::Result<crux_http::Response<i32>>),
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = i32;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Increment => *model += 1,
+                Event::Fetch => {
+                    caps.http
+                        .get("https://example.com/count")
+                        .expect_json()
+                        .send(Event::Fetched);
+                }
+                Event::Fetched(Ok(mut response)) => {
+                    *model += response.take_body().unwrap();
+                }
+                Event::Fetched(Err(_)) => {}
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            format!("Count is {model}")
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{
+        bridge::{Bridge, JsonCodec},
+        testing::{replay, LogEntry, Recorder, SessionLog},
+        Core,
+    };
+    use crux_http::protocol::{HttpResponse, HttpResult};
+    use serde_json::Value;
+
+    use crate::app::{App, Effect};
+
+    fn bridge() -> Bridge<Effect, App, JsonCodec> {
+        Bridge::new_with_codec(Core::new(), JsonCodec)
+    }
+
+    fn record_session() -> SessionLog {
+        let recorder = Recorder::new(bridge());
+
+        recorder.process_event(br#""Increment""#);
+        let requests = recorder.process_event(br#""Fetch""#);
+
+        let requests: Value = serde_json::from_slice(&requests).unwrap();
+        let http = requests
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|request| request["effect"].get("Http").is_some())
+            .unwrap();
+        let id = u32::try_from(http["id"].as_u64().unwrap()).unwrap();
+
+        let response = HttpResult::Ok(HttpResponse::ok().body("41").build());
+        recorder.handle_response(id, &serde_json::to_vec(&response).unwrap());
+
+        recorder.finish()
+    }
+
+    #[test]
+    fn records_events_and_responses_in_order() {
+        let log = record_session();
+
+        assert!(matches!(
+            &log.entries[..],
+            [
+                LogEntry::Event(_),
+                LogEntry::Event(_),
+                LogEntry::Response { .. }
+            ]
+        ));
+        assert_eq!(log.view, br#""Count is 42""#);
+    }
+
+    #[test]
+    fn replays_a_serialized_session() {
+        let log = serde_json::to_string(&record_session()).unwrap();
+        let log: SessionLog = serde_json::from_str(&log).unwrap();
+
+        let bridge = bridge();
+        replay(&bridge, &log);
+
+        assert_eq!(bridge.view(), br#""Count is 42""#);
+    }
+
+    #[test]
+    #[should_panic(expected = "replayed view does not match the recorded one")]
+    fn replay_fails_when_the_view_differs() {
+        let mut log = record_session();
+        log.view = br#""Count is 7""#.to_vec();
+
+        replay(&bridge(), &log);
+    }
+}
@@ -0,0 +1,134 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_http::Http;
+    use crux_kv::KeyValue;
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Load,
+        LoadedCount(crux_http::Result<crux_http::Response<u32>>),
+        LoadedUnit(crux_http::Result<crux_http::Response<String>>),
+        Save,
+        Saved,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        count: u32,
+        unit: String,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+        pub key_value: KeyValue<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Load => {
+                    caps.http
+                        .get("https://example.com/count")
+                        .expect_json()
+                        .send(Event::LoadedCount);
+                }
+                Event::LoadedCount(Ok(mut response)) => {
+                    model.count = response.take_body().unwrap();
+                    caps.render.render();
+
+                    caps.http
+                        .get("https://example.com/unit")
+                        .expect_string()
+                        .send(Event::LoadedUnit);
+                }
+                Event::LoadedUnit(Ok(mut response)) => {
+                    model.unit = response.take_body().unwrap();
+                    caps.render.render();
+                }
+                Event::LoadedCount(Err(_)) | Event::LoadedUnit(Err(_)) => {}
+                Event::Save => {
+                    caps.key_value.set(
+                        "count".to_string(),
+                        model.count.to_string().into_bytes(),
+                        |_| Event::Saved,
+                    );
+                }
+                Event::Saved => {}
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            format!("{} {}", model.count, model.unit)
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{testing::Simulator, Core};
+    use crux_http::protocol::{HttpRequest, HttpResponse, HttpResult};
+    use crux_kv::KeyValueOperation;
+
+    use crate::app::{App, Effect, Event};
+
+    fn fake_http(request: &HttpRequest) -> HttpResult {
+        let body = match request.url.as_str() {
+            "https://example.com/count" => "42",
+            "https://example.com/unit" => "apples",
+            _ => return HttpResult::Ok(HttpResponse::status(404).build()),
+        };
+
+        HttpResult::Ok(HttpResponse::ok().body(body).build())
+    }
+
+    #[test]
+    fn resolves_effects_with_fakes_until_the_core_settles() {
+        let sim = Simulator::new(Core::<Effect, App>::new()).with_fake(fake_http);
+
+        let settled = sim.run(Event::Load);
+
+        assert_eq!(sim.view(), "42 apples");
+        assert_eq!(settled.renders, 2);
+        assert!(settled.unhandled.is_empty());
+        assert!(sim.core().pending_effects().is_empty());
+    }
+
+    #[test]
+    fn returns_effects_without_a_fake() {
+        let sim = Simulator::new(Core::<Effect, App>::new()).with_fake(fake_http);
+
+        let _ = sim.run(Event::Load);
+        let mut settled = sim.run(Event::Save);
+
+        assert_eq!(settled.renders, 0);
+        let Some(Effect::KeyValue(request)) = settled.unhandled.pop() else {
+            panic!("expected the key value request to be left unhandled");
+        };
+        assert_eq!(
+            request.operation,
+            KeyValueOperation::Set {
+                key: "count".to_string(),
+                value: b"42".to_vec()
+            }
+        );
+    }
+
+    #[test]
+    fn later_fakes_replace_earlier_ones() {
+        let sim = Simulator::new(Core::<Effect, App>::new())
+            .with_fake(fake_http)
+            .with_fake(|_: &HttpRequest| HttpResult::Ok(HttpResponse::ok().body("7").build()));
+
+        let _ = sim.run(Event::Load);
+
+        assert_eq!(sim.view(), "7 7");
+    }
+}
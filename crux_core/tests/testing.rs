@@ -49,3 +49,12 @@ fn app_tester_new() {
 
     assert_eq!(effects.count(), 1);
 }
+
+#[test]
+fn view_matches_snapshot() {
+    let tester = AppTester::<app::MyApp, _>::default();
+
+    let model = "Hello".to_string();
+
+    crux_core::testing::assert_view_snapshot("app_tester_view", &tester.view(&model));
+}
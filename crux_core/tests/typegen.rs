@@ -91,4 +91,296 @@ mod test {
         assert!(registry.contains_key("Effect"));
         assert!(registry.contains_key("RenderOperation"));
     }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    enum Response {
+        Value(Vec<u8>),
+        Missing,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    enum Count {
+        Total(u64),
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    enum Error {
+        Io(String),
+    }
+
+    #[test]
+    fn test_generic_instantiations() {
+        let mut gen = TypeGen::new();
+
+        gen.register_samples(vec![Event::SendUuid(Uuid::new_v4())])
+            .unwrap();
+        gen.register_app::<App>().unwrap();
+        gen.register_type::<Response>().unwrap();
+        gen.register_generic_type::<Result<Response, Error>>()
+            .unwrap();
+        gen.register_generic_type::<Result<Count, Error>>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        gen.swift("SharedTypes", temp.join("swift"))
+            .expect("swift type gen failed");
+
+        let crux_core::typegen::State::Generating(registry) = gen.state else {
+            panic!("Expected to be in generating stage");
+        };
+
+        assert!(registry.contains_key("ResultResponseError"));
+        assert!(registry.contains_key("ResultCountError"));
+        assert!(registry.contains_key("Response"));
+        assert!(registry.contains_key("Count"));
+        assert!(registry.contains_key("Error"));
+        assert!(!registry.contains_key("Result"));
+
+        let swift = std::fs::read_to_string(
+            temp.join("swift/SharedTypes/Sources/SharedTypes/SharedTypes.swift"),
+        )
+        .unwrap();
+        assert!(swift.contains("indirect public enum ResultResponseError"));
+    }
+
+    #[test]
+    fn test_generic_instantiation_needs_nested_enums_registered() {
+        let mut gen = TypeGen::new();
+
+        gen.register_generic_type::<Result<Response, Error>>()
+            .unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let result = gen.swift("SharedTypes", temp.join("swift"));
+
+        assert!(matches!(
+            result,
+            Err(crux_core::typegen::TypeGenError::Generation(message)) if message.contains("Response")
+        ));
+    }
+
+    #[test]
+    fn test_generic_instantiation_must_be_a_container() {
+        let mut gen = TypeGen::new();
+
+        let result = gen.register_generic_type::<Option<Response>>();
+
+        assert!(matches!(
+            result,
+            Err(crux_core::typegen::TypeGenError::TypeTracing(_))
+        ));
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Profile {
+        display_name: String,
+        #[serde(rename = "avatarURL")]
+        avatar_url: String,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Clash {
+        user_id: u32,
+        #[serde(rename = "userId")]
+        id: u32,
+    }
+
+    #[test]
+    fn test_rename_types_and_fields() {
+        let mut gen = TypeGen::new();
+
+        gen.register_type::<Profile>().unwrap();
+        gen.register_type::<Response>().unwrap();
+        gen.rename_type("Profile", "UserProfile").unwrap();
+        gen.rename_fields(crux_core::typegen::FieldCase::Camel);
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        gen.swift("SharedTypes", temp.join("swift"))
+            .expect("swift type gen failed");
+        gen.typescript_definitions("shared_types", &temp).unwrap();
+
+        let swift = std::fs::read_to_string(
+            temp.join("swift/SharedTypes/Sources/SharedTypes/SharedTypes.swift"),
+        )
+        .unwrap();
+        assert!(swift.contains("public struct UserProfile"));
+        assert!(swift.contains("@Indirect public var displayName: String"));
+        assert!(swift.contains("@Indirect public var avatarURL: String"));
+        assert!(!swift.contains("struct Profile"));
+
+        // field names are part of the JSON representation, so they're kept
+        let definitions = std::fs::read_to_string(temp.join("shared_types.ts")).unwrap();
+        assert!(definitions.contains("export interface UserProfile {\n  display_name: string;"));
+    }
+
+    #[test]
+    fn test_conflicting_renames() {
+        use crux_core::typegen::{FieldCase, TypeGenError};
+
+        let mut gen = TypeGen::new();
+
+        gen.rename_type("Profile", "UserProfile").unwrap();
+        gen.rename_type("Profile", "UserProfile").unwrap();
+        assert!(matches!(
+            gen.rename_type("Profile", "Account"),
+            Err(TypeGenError::Rename(_))
+        ));
+        assert!(matches!(
+            gen.rename_type("Response", "UserProfile"),
+            Err(TypeGenError::Rename(_))
+        ));
+
+        let mut gen = TypeGen::new();
+        gen.register_type::<Response>().unwrap();
+        gen.register_type::<Profile>().unwrap();
+        gen.rename_type("Response", "Profile").unwrap();
+        let temp = assert_fs::TempDir::new().unwrap();
+        assert!(matches!(
+            gen.typescript_definitions("shared_types", &temp),
+            Err(TypeGenError::Rename(_))
+        ));
+
+        let mut gen = TypeGen::new();
+        gen.register_type::<Clash>().unwrap();
+        gen.rename_fields(FieldCase::Camel);
+        assert!(matches!(
+            gen.swift("SharedTypes", temp.join("swift")),
+            Err(TypeGenError::Rename(_))
+        ));
+
+        let mut gen = TypeGen::new();
+        gen.rename_type("Missing", "Other").unwrap();
+        assert!(matches!(
+            gen.typescript_definitions("shared_types", &temp),
+            Err(TypeGenError::Rename(_))
+        ));
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Favorite {
+        name: String,
+        lat: f64,
+        lon: f64,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    enum Units {
+        Metric,
+        Imperial,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct FavoritesView {
+        favorites: std::collections::BTreeMap<String, Favorite>,
+        units: std::collections::HashMap<String, Units>,
+        by_country: std::collections::BTreeMap<String, std::collections::HashMap<u32, Favorite>>,
+    }
+
+    #[test]
+    fn test_maps() {
+        let mut gen = TypeGen::new();
+
+        gen.register_type::<Units>().unwrap();
+        gen.register_type::<FavoritesView>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        gen.swift("SharedTypes", temp.join("swift"))
+            .expect("swift type gen failed");
+        gen.java("com.example.shared_types", temp.join("java"))
+            .expect("java type gen failed");
+        gen.typescript_definitions("shared_types", &temp).unwrap();
+
+        let swift = std::fs::read_to_string(
+            temp.join("swift/SharedTypes/Sources/SharedTypes/SharedTypes.swift"),
+        )
+        .unwrap();
+        assert!(swift.contains("public var favorites: [String: SharedTypes.Favorite]"));
+        assert!(swift.contains("public var units: [String: SharedTypes.Units]"));
+        assert!(swift.contains("public var by_country: [String: [UInt32: SharedTypes.Favorite]]"));
+
+        let java =
+            std::fs::read_to_string(temp.join("java/com/example/shared_types/FavoritesView.java"))
+                .unwrap();
+        assert!(java.contains("public final java.util.Map<String, Favorite> favorites;"));
+        assert!(java.contains("public final java.util.Map<String, Units> units;"));
+        assert!(java.contains(
+            "public final java.util.Map<String, java.util.Map<@com.novi.serde.Unsigned Integer, Favorite>> by_country;"
+        ));
+
+        let definitions = std::fs::read_to_string(temp.join("shared_types.ts")).unwrap();
+        assert!(definitions.contains("  favorites: Record<string, Favorite>;"));
+        assert!(definitions.contains("  units: Record<string, Units>;"));
+        assert!(definitions.contains("  by_country: Record<string, Record<string, Favorite>>;"));
+    }
+
+    #[test]
+    fn test_map_of_unregistered_enum() {
+        let mut gen = TypeGen::new();
+
+        gen.register_type::<FavoritesView>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let result = gen.swift("SharedTypes", temp.join("swift"));
+
+        assert!(matches!(
+            result,
+            Err(crux_core::typegen::TypeGenError::Generation(message))
+                if message.contains("Units") && message.contains("register_type()")
+        ));
+    }
+
+    #[test]
+    fn test_manifest() {
+        use crux_core::typegen::{Language, Manifest};
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let generate = || {
+            let mut gen = TypeGen::new();
+            gen.register_samples(vec![Event::SendUuid(Uuid::new_v4())])
+                .unwrap();
+            gen.register_app::<App>().unwrap();
+            gen.swift("SharedTypes", &temp).unwrap();
+            gen.java("com.example.shared_types", &temp).unwrap();
+            gen.typescript_definitions("shared_types", &temp).unwrap();
+            std::fs::read_to_string(temp.join("manifest.json")).unwrap()
+        };
+
+        let first = generate();
+        assert_eq!(generate(), first);
+
+        let manifest = Manifest::read(&temp).unwrap();
+        let paths: Vec<_> = manifest.files.iter().map(|e| e.path.as_str()).collect();
+        let mut sorted = paths.clone();
+        sorted.sort_unstable();
+        assert_eq!(paths, sorted);
+
+        let entry = |path: &str| {
+            manifest
+                .files
+                .iter()
+                .find(|entry| entry.path == path)
+                .unwrap_or_else(|| panic!("{path} should be in the manifest"))
+        };
+        assert_eq!(entry("SharedTypes/Package.swift").language, Language::Swift);
+        assert_eq!(
+            entry("com/example/shared_types/Requests.java").language,
+            Language::Java
+        );
+        assert_eq!(entry("com/novi/serde/Unit.java").language, Language::Java);
+        let definitions = entry("shared_types.ts");
+        assert_eq!(definitions.language, Language::TypeScriptDefinitions);
+        assert_eq!(definitions.hash.len(), 16);
+
+        // regenerating one language only replaces its own entries
+        let mut gen = TypeGen::new();
+        gen.register_type::<super::shared::ViewModel>().unwrap();
+        gen.typescript_definitions("other_types", &temp).unwrap();
+
+        let manifest = Manifest::read(&temp).unwrap();
+        assert!(manifest.files.iter().any(|e| e.path == "other_types.ts"));
+        assert!(!manifest.files.iter().any(|e| e.path == "shared_types.ts"));
+        assert!(manifest
+            .files
+            .iter()
+            .any(|e| e.path == "SharedTypes/Package.swift"));
+    }
 }
@@ -0,0 +1,144 @@
+#[cfg(feature = "typegen")]
+mod counter {
+    use crux_core::macros::{Effect, Export};
+    use crux_core::render::Render;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Get,
+        Increment,
+        Decrement,
+        StartWatch,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ViewModel {
+        pub text: String,
+        pub confirmed: bool,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = ();
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, _event: Event, _model: &mut Self::Model, _caps: &Capabilities) {}
+
+        fn view(&self, _model: &Self::Model) -> Self::ViewModel {
+            unimplemented!();
+        }
+    }
+
+    #[derive(Effect, Export)]
+    pub struct Capabilities {
+        #[allow(dead_code)]
+        pub render: Render<Event>,
+    }
+}
+
+#[cfg(feature = "typegen")]
+mod test {
+    use std::collections::HashMap;
+
+    use crux_core::typegen::TypeGen;
+    use serde::{Deserialize, Serialize};
+
+    use super::counter::App;
+
+    #[test]
+    fn counter_definitions() {
+        let mut gen = TypeGen::new();
+        gen.register_app::<App>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        gen.typescript_definitions("shared_types", &temp).unwrap();
+
+        let definitions = std::fs::read_to_string(temp.join("shared_types.ts")).unwrap();
+        assert_eq!(
+            definitions,
+            r#"// Generated by crux_core::typegen. Do not edit.
+// These types describe the JSON representation used by the Bridge with JsonCodec.
+
+export type Effect =
+  | { Render: RenderOperation }
+;
+
+export type Event =
+  | "Get"
+  | "Increment"
+  | "Decrement"
+  | "StartWatch"
+;
+
+export type RenderOperation = null;
+
+export interface Request {
+  id: number;
+  effect: Effect;
+}
+
+export interface ViewModel {
+  text: string;
+  confirmed: boolean;
+}
+
+export type Requests = Request[];
+"#
+        );
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Mappings {
+        items: Vec<Option<u32>>,
+        labels: HashMap<String, Vec<u8>>,
+        pair: (String, bool),
+        shape: Shape,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    enum Shape {
+        Point,
+        Circle(f64),
+        Line(i32, i32),
+        Rect { width: u64, height: u64 },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Id(String);
+
+    #[test]
+    fn mappings() {
+        let mut gen = TypeGen::new();
+        gen.register_type::<Mappings>().unwrap();
+        gen.register_type::<Shape>().unwrap();
+        gen.register_type::<Id>().unwrap();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        gen.typescript_definitions("shared_types", &temp).unwrap();
+
+        let definitions = std::fs::read_to_string(temp.join("shared_types.ts")).unwrap();
+        assert!(definitions.contains("export type Id = string;\n"));
+        assert!(definitions.contains(
+            "export interface Mappings {\n  \
+               items: (number | null)[];\n  \
+               labels: Record<string, number[]>;\n  \
+               pair: [string, boolean];\n  \
+               shape: Shape;\n\
+             }\n"
+        ));
+        assert!(definitions.contains(
+            "export type Shape =\n  \
+               | \"Point\"\n  \
+               | { Circle: number }\n  \
+               | { Line: [number, number] }\n  \
+               | { Rect: { width: number; height: number } }\n\
+             ;\n"
+        ));
+        assert!(!definitions.contains("Requests"));
+    }
+}
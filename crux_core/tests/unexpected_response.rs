@@ -0,0 +1,123 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use crux_kv::{error::KeyValueError, KeyValue};
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Load,
+        Loaded(Result<Option<Vec<u8>>, KeyValueError>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        loaded: Option<Result<Option<Vec<u8>>, KeyValueError>>,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub key_value: KeyValue<Event>,
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = Option<Result<Option<Vec<u8>>, KeyValueError>>;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Load => caps.key_value.get("count".to_string(), Event::Loaded),
+                Event::Loaded(result) => {
+                    model.loaded = Some(result);
+                    caps.render.render();
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.loaded.clone()
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{Core, Request, ResolveError};
+    use crux_kv::{
+        error::KeyValueError, value::Value, KeyValueOperation, KeyValueResponse, KeyValueResult,
+    };
+
+    use super::app::{App, Effect, Event};
+
+    fn load(core: &Core<Effect, App>) -> Request<KeyValueOperation> {
+        let Effect::KeyValue(request) = core.process_event(Event::Load).remove(0) else {
+            panic!("expected a key value request");
+        };
+        request
+    }
+
+    #[test]
+    fn wrong_response_is_an_error() {
+        let core: Core<Effect, App> = Core::new();
+        let mut request = load(&core);
+
+        let error = core
+            .resolve(
+                &mut request,
+                KeyValueResult::Ok {
+                    response: KeyValueResponse::Exists { is_present: true },
+                },
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            ResolveError::UnexpectedResponse {
+                operation: std::any::type_name::<KeyValueOperation>()
+            }
+        );
+        assert_eq!(core.view(), None);
+    }
+
+    #[test]
+    fn request_can_be_resolved_after_wrong_response() {
+        let core: Core<Effect, App> = Core::new();
+        let mut request = load(&core);
+
+        let wrong = KeyValueResult::Ok {
+            response: KeyValueResponse::Increment { value: 1 },
+        };
+        assert!(core.resolve(&mut request, wrong).is_err());
+
+        let right = KeyValueResult::Ok {
+            response: KeyValueResponse::Get {
+                value: Value::Bytes(vec![1]),
+            },
+        };
+        let effects = core.resolve(&mut request, right).unwrap();
+
+        assert!(matches!(effects[..], [Effect::Render(_)]));
+        assert_eq!(core.view(), Some(Ok(Some(vec![1]))));
+    }
+
+    #[test]
+    fn error_is_a_valid_response() {
+        let core: Core<Effect, App> = Core::new();
+        let mut request = load(&core);
+
+        let error = KeyValueError::Io {
+            message: "disk full".to_string(),
+        };
+        core.resolve(
+            &mut request,
+            KeyValueResult::Err {
+                error: error.clone(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(core.view(), Some(Err(error)));
+    }
+}
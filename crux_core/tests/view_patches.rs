@@ -0,0 +1,201 @@
+mod app {
+    use crux_core::{macros::Effect, render::Render};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Rename(String),
+        AddItem(String),
+        Select(Option<usize>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub title: String,
+        pub items: Vec<String>,
+        pub selected: Option<usize>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct ViewModel {
+        pub header: Header,
+        pub items: Vec<String>,
+        pub selected: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct Header {
+        pub title: String,
+        pub count: usize,
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub render: Render<Event>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = ViewModel;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Self::Event, model: &mut Self::Model, caps: &Self::Capabilities) {
+            match event {
+                Event::Rename(title) => model.title = title,
+                Event::AddItem(item) => model.items.push(item),
+                Event::Select(selected) => model.selected = selected,
+            }
+            caps.render.render();
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            ViewModel {
+                header: Header {
+                    title: model.title.clone(),
+                    count: model.items.len(),
+                },
+                items: model.items.clone(),
+                selected: model
+                    .selected
+                    .and_then(|index| model.items.get(index).cloned()),
+            }
+        }
+    }
+}
+
+mod tests {
+    use crux_core::{
+        bridge::{Bridge, JsonCodec},
+        Core,
+    };
+    use serde_json::{json, Value};
+
+    use super::app::{App, Effect, Event};
+
+    type JsonBridge = Bridge<Effect, App, JsonCodec>;
+
+    fn bridge() -> JsonBridge {
+        Bridge::new_with_codec(Core::new(), JsonCodec).with_view_patches()
+    }
+
+    fn send(bridge: &JsonBridge, event: &Event) {
+        bridge.process_event(&serde_json::to_vec(event).unwrap());
+    }
+
+    // applies the subset of JSON Patch the bridge produces, as a shell would
+    fn apply(document: &mut Value, patch: &[u8]) {
+        let patch: Vec<Value> = serde_json::from_slice(patch).unwrap();
+
+        for operation in patch {
+            let path = operation["path"].as_str().unwrap();
+            if path.is_empty() {
+                *document = operation["value"].clone();
+                continue;
+            }
+
+            let (parent, key) = path.rsplit_once('/').unwrap();
+            let key = key.replace("~1", "/").replace("~0", "~");
+            let parent = document
+                .pointer_mut(parent)
+                .unwrap()
+                .as_object_mut()
+                .unwrap();
+
+            match operation["op"].as_str().unwrap() {
+                "add" | "replace" => {
+                    parent.insert(key, operation["value"].clone());
+                }
+                "remove" => {
+                    parent.remove(&key);
+                }
+                op => panic!("unexpected operation {op}"),
+            }
+        }
+    }
+
+    fn full(bridge: &JsonBridge) -> Value {
+        serde_json::from_slice(&bridge.view_full()).unwrap()
+    }
+
+    #[test]
+    fn patches_applied_match_full_view() {
+        let bridge = bridge();
+        let reference: JsonBridge = Bridge::new_with_codec(Core::new(), JsonCodec);
+        let mut shell_view = Value::Null;
+
+        apply(&mut shell_view, &bridge.view());
+        assert_eq!(shell_view, full(&reference));
+
+        let events = [
+            Event::Rename("Groceries".to_string()),
+            Event::AddItem("milk".to_string()),
+            Event::AddItem("bread".to_string()),
+            Event::Select(Some(1)),
+            Event::Select(None),
+            Event::Rename(String::new()),
+        ];
+        for event in &events {
+            send(&bridge, event);
+            send(&reference, event);
+
+            apply(&mut shell_view, &bridge.view());
+            assert_eq!(shell_view, full(&reference));
+        }
+    }
+
+    #[test]
+    fn patch_only_contains_changes() {
+        let bridge = bridge();
+        bridge.view();
+
+        send(&bridge, &Event::Rename("Groceries".to_string()));
+        let patch: Value = serde_json::from_slice(&bridge.view()).unwrap();
+
+        assert_eq!(
+            patch,
+            json!([{ "op": "replace", "path": "/header/title", "value": "Groceries" }])
+        );
+
+        let patch: Value = serde_json::from_slice(&bridge.view()).unwrap();
+        assert_eq!(patch, json!([]));
+    }
+
+    #[test]
+    fn patches_are_deterministic() {
+        let patches = || {
+            let bridge = bridge();
+            bridge.view();
+            send(&bridge, &Event::AddItem("milk".to_string()));
+            send(&bridge, &Event::Select(Some(0)));
+            bridge.view()
+        };
+
+        assert_eq!(patches(), patches());
+    }
+
+    #[test]
+    fn view_full_resets_the_baseline() {
+        let bridge = bridge();
+        bridge.view();
+
+        send(&bridge, &Event::AddItem("milk".to_string()));
+        let view = full(&bridge);
+        assert_eq!(view["items"], json!(["milk"]));
+
+        let patch: Value = serde_json::from_slice(&bridge.view()).unwrap();
+        assert_eq!(patch, json!([]));
+    }
+
+    #[test]
+    fn without_patches_view_is_the_full_view() {
+        let bridge: JsonBridge = Bridge::new_with_codec(Core::new(), JsonCodec);
+
+        send(&bridge, &Event::AddItem("milk".to_string()));
+
+        assert_eq!(bridge.view(), bridge.view_full());
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use crate::protocol::HttpRequestId;
+
+/// A handle which can abort an in-flight request, created with
+/// [`RequestBuilder::abortable`](crate::RequestBuilder::abortable).
+///
+/// Aborting a request resolves it with [`HttpError::Aborted`](crate::HttpError::Aborted)
+/// straight away, and sends the shell a
+/// [cancellation](crate::protocol::HttpRequest::cancellation) of the request, so that
+/// it can stop the network call. Any response the shell sends for the request anyway
+/// is ignored.
+#[derive(Clone, Debug)]
+pub struct AbortHandle {
+    id: HttpRequestId,
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    aborted: bool,
+    waker: Option<Waker>,
+}
+
+fn next_request_id() -> HttpRequestId {
+    static COUNTER: AtomicU64 = AtomicU64::new(1);
+    HttpRequestId(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+impl AbortHandle {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: next_request_id(),
+            inner: Arc::default(),
+        }
+    }
+
+    /// The id of the request, as sent to the shell.
+    #[must_use]
+    pub fn id(&self) -> HttpRequestId {
+        self.id
+    }
+
+    /// Abort the request. Has no effect if the request has already completed.
+    pub fn abort(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.aborted = true;
+        if let Some(waker) = inner.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Whether [`abort`](AbortHandle::abort) has been called.
+    #[must_use]
+    pub fn is_aborted(&self) -> bool {
+        self.inner.lock().unwrap().aborted
+    }
+
+    /// A future which completes when the request is aborted.
+    pub(crate) fn aborted(&self) -> Aborted {
+        Aborted {
+            handle: self.clone(),
+        }
+    }
+}
+
+pub(crate) struct Aborted {
+    handle: AbortHandle,
+}
+
+impl Future for Aborted {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.handle.inner.lock().unwrap();
+        if inner.aborted {
+            Poll::Ready(())
+        } else {
+            inner.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Compression of request bodies, see [`RequestBuilder::compress`](crate::RequestBuilder::compress).
+
+use std::io::Write;
+
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression as Level,
+};
+
+/// Bodies smaller than this many bytes are sent as they are by
+/// [`RequestBuilder::compress`](crate::RequestBuilder::compress), as compressing them
+/// saves little, or even makes them larger.
+pub const DEFAULT_COMPRESSION_THRESHOLD: u64 = 1024;
+
+/// An encoding to compress a request body with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    /// zlib wrapped deflate, as the `deflate` content encoding is specified
+    Deflate,
+}
+
+impl Encoding {
+    /// The name of the encoding in the `Content-Encoding` header
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    fn encode(self, body: &[u8]) -> Vec<u8> {
+        // writing to a Vec can't fail
+        match self {
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+                encoder.write_all(body).unwrap();
+                encoder.finish().unwrap()
+            }
+            Encoding::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Level::default());
+                encoder.write_all(body).unwrap();
+                encoder.finish().unwrap()
+            }
+        }
+    }
+}
+
+/// How a request asked for its body to be compressed
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Compression {
+    pub(crate) encoding: Encoding,
+    pub(crate) min_bytes: u64,
+}
+
+impl Compression {
+    /// Compress `body` if it is large enough, returning the encoding used
+    pub(crate) fn apply(self, body: &mut Vec<u8>) -> Option<Encoding> {
+        if (body.len() as u64) < self.min_bytes {
+            return None;
+        }
+
+        *body = self.encoding.encode(body);
+        Some(self.encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::{GzDecoder, ZlibDecoder};
+
+    use super::*;
+
+    #[test]
+    fn compresses_bodies_over_the_threshold() {
+        let original = "hello ".repeat(100).into_bytes();
+        let compression = Compression {
+            encoding: Encoding::Gzip,
+            min_bytes: 100,
+        };
+
+        let mut body = original.clone();
+        assert_eq!(compression.apply(&mut body), Some(Encoding::Gzip));
+        assert!(body.len() < original.len());
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(body.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn compresses_with_deflate() {
+        let original = "hello ".repeat(100).into_bytes();
+        let compression = Compression {
+            encoding: Encoding::Deflate,
+            min_bytes: 0,
+        };
+
+        let mut body = original.clone();
+        assert_eq!(compression.apply(&mut body), Some(Encoding::Deflate));
+
+        let mut decoded = Vec::new();
+        ZlibDecoder::new(body.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn leaves_bodies_under_the_threshold_alone() {
+        let compression = Compression {
+            encoding: Encoding::Gzip,
+            min_bytes: 100,
+        };
+
+        let mut body = b"hello".to_vec();
+        assert_eq!(compression.apply(&mut body), None);
+        assert_eq!(body, b"hello");
+    }
+}
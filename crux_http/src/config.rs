@@ -11,13 +11,13 @@ use crate::Result;
 #[non_exhaustive]
 #[derive(Clone, Debug, Default)]
 pub struct Config {
-    /// The base URL for a client. All request URLs will be relative to this URL.
-    ///
-    /// Note: a trailing slash is significant.
-    /// Without it, the last path component is considered to be a “file” name
-    /// to be removed to get at the “directory” that is used as the base.
+    /// The base URL for a client. Relative request URLs are appended to its path,
+    /// whether or not the base URL ends with a slash or the request URL starts with one,
+    /// so `https://example.com/api` and `/users` make `https://example.com/api/users`.
+    /// Absolute request URLs are used as they are.
     pub base_url: Option<Url>,
-    /// Headers to be applied to every request made by this client.
+    /// Headers to be applied to every request made by this client. A header set on
+    /// the request itself replaces the one here.
     pub headers: HashMap<HeaderName, HeaderValues>,
 }
 
@@ -42,11 +42,8 @@ impl Config {
         Ok(self)
     }
 
-    /// Sets the base URL for this config. All request URLs will be relative to this URL.
-    ///
-    /// Note: a trailing slash is significant.
-    /// Without it, the last path component is considered to be a “file” name
-    /// to be removed to get at the “directory” that is used as the base.
+    /// Sets the base URL for this config. Relative request URLs are appended to it,
+    /// see [`Config::base_url`].
     ///
     /// Default: `None` (internally).
     pub fn set_base_url(mut self, base: Url) -> Self {
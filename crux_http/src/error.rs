@@ -1,6 +1,17 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error as ThisError;
 
+/// The error a request resolves to.
+///
+/// Shells report a failed request with [`HttpResult::Err`](crate::protocol::HttpResult::Err),
+/// and are responsible for classifying what went wrong, so that the app can tell the user
+/// something useful, e.g. that they're offline rather than that the server's certificate
+/// is bad. Use the most specific variant which applies: [`Timeout`](HttpError::Timeout),
+/// [`ConnectionRefused`](HttpError::ConnectionRefused),
+/// [`DnsFailure`](HttpError::DnsFailure), [`Tls`](HttpError::Tls) or
+/// [`Io`](HttpError::Io) for other failures reading or writing the connection, and
+/// [`Other`](HttpError::Other) only for failures which fit none of them. Include the
+/// platform's own description of the error in the message.
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone, ThisError, Debug)]
 pub enum HttpError {
     #[error("HTTP error {code}: {message}")]
@@ -10,15 +21,81 @@ pub enum HttpError {
         message: String,
         body: Option<Vec<u8>>,
     },
+    #[error("HTTP error {code} after {attempts} attempts")]
+    #[serde(skip)]
+    RetriesExhausted {
+        code: crate::http::StatusCode,
+        attempts: u32,
+    },
+    #[error("Too many redirects: gave up after {hops} at {url}")]
+    #[serde(skip)]
+    TooManyRedirects { hops: u8, url: String },
     #[error("JSON serialisation error: {0}")]
     #[serde(skip)]
     Json(String),
+    #[error("Decoding error: {0}")]
+    #[serde(skip)]
+    Decode(String),
     #[error("URL parse error: {0}")]
     Url(String),
     #[error("IO error: {0}")]
     Io(String),
-    #[error("Timeout")]
-    Timeout,
+    #[error("Request aborted")]
+    Aborted,
+    #[error("Timeout after {elapsed:?}")]
+    Timeout {
+        #[serde(with = "crate::protocol::nanos")]
+        elapsed: std::time::Duration,
+    },
+    #[error("Response body of {actual} bytes is over the limit of {limit} bytes")]
+    BodyTooLarge { limit: u64, actual: u64 },
+    /// The server refused the connection, e.g. because nothing is listening on the port
+    #[error("Connection refused: {0}")]
+    ConnectionRefused(String),
+    /// The host name couldn't be resolved, often because the device is offline
+    #[error("DNS lookup failed: {0}")]
+    DnsFailure(String),
+    /// The TLS handshake failed, e.g. because the server's certificate is invalid or
+    /// has expired
+    #[error("TLS error: {0}")]
+    Tls(String),
+    /// A failure the shell couldn't classify as any of the other variants
+    #[error("Other error: {0}")]
+    Other(String),
+}
+
+/// The error returned by requests built with
+/// [`RequestBuilder::expect_json_or_error`](crate::RequestBuilder::expect_json_or_error).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, ThisError, Debug)]
+pub enum ApiError<E> {
+    /// The server responded with an error status, and a body which decoded as `E`.
+    #[error("HTTP error {code}")]
+    Api {
+        code: crate::http::StatusCode,
+        error: E,
+    },
+    /// The request failed in any other way.
+    #[error(transparent)]
+    Http(HttpError),
+}
+
+impl<E> From<HttpError> for ApiError<E>
+where
+    E: DeserializeOwned,
+{
+    fn from(e: HttpError) -> Self {
+        if let HttpError::Http {
+            code,
+            body: Some(body),
+            ..
+        } = &e
+        {
+            if let Ok(error) = serde_json::from_slice(body) {
+                return ApiError::Api { code: *code, error };
+            }
+        }
+        ApiError::Http(e)
+    }
 }
 
 impl From<crate::http::Error> for HttpError {
@@ -55,5 +132,59 @@ mod tests {
             body: None,
         };
         assert_eq!(error.to_string(), "HTTP error 400: Bad Request");
+
+        let error = HttpError::Timeout {
+            elapsed: std::time::Duration::from_secs(5),
+        };
+        assert_eq!(error.to_string(), "Timeout after 5s");
+
+        let error = HttpError::BodyTooLarge {
+            limit: 1024,
+            actual: 4096,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Response body of 4096 bytes is over the limit of 1024 bytes"
+        );
+
+        let error = HttpError::Tls("certificate has expired".to_string());
+        assert_eq!(error.to_string(), "TLS error: certificate has expired");
+    }
+
+    #[test]
+    fn test_transport_errors_serde_round_trip() {
+        let errors = [
+            (
+                HttpError::Timeout {
+                    elapsed: std::time::Duration::from_millis(1500),
+                },
+                r#"{"Timeout":{"elapsed":1500000000}}"#,
+            ),
+            (
+                HttpError::ConnectionRefused("connection refused".to_string()),
+                r#"{"ConnectionRefused":"connection refused"}"#,
+            ),
+            (
+                HttpError::DnsFailure("no such host".to_string()),
+                r#"{"DnsFailure":"no such host"}"#,
+            ),
+            (
+                HttpError::Tls("certificate has expired".to_string()),
+                r#"{"Tls":"certificate has expired"}"#,
+            ),
+            (
+                HttpError::Io("connection reset".to_string()),
+                r#"{"Io":"connection reset"}"#,
+            ),
+            (
+                HttpError::Other("something else".to_string()),
+                r#"{"Other":"something else"}"#,
+            ),
+        ];
+
+        for (error, json) in errors {
+            assert_eq!(serde_json::to_string(&error).unwrap(), json);
+            assert_eq!(serde_json::from_str::<HttpError>(json).unwrap(), error);
+        }
     }
 }
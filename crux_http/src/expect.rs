@@ -1,13 +1,17 @@
-use std::marker::PhantomData;
+use std::{any::type_name, marker::PhantomData};
 
-use http_types::convert::DeserializeOwned;
+use http_types::{convert::DeserializeOwned, StatusCode};
+use serde::{Deserialize, Serialize};
 
-use crate::{Response, Result};
+use crate::{DownloadedFile, HttpError, Response, Result};
 
 pub trait ResponseExpectation {
     type Body;
 
     fn decode(&self, resp: crate::Response<Vec<u8>>) -> Result<Response<Self::Body>>;
+
+    /// Called with the final result, decoded or not, before it is dispatched to the app.
+    fn inspect(&self, _result: &Result<Response<Self::Body>>) {}
 }
 
 pub struct ExpectBytes;
@@ -27,6 +31,10 @@ impl ResponseExpectation for ExpectString {
     type Body = String;
 
     fn decode(&self, mut resp: crate::Response<Vec<u8>>) -> Result<Response<String>> {
+        if resp.is_not_modified() {
+            return Ok(resp.without_body());
+        }
+
         let body = resp.body_string()?;
         Ok(resp.with_body(body))
     }
@@ -51,7 +59,163 @@ where
     type Body = T;
 
     fn decode(&self, mut resp: crate::Response<Vec<u8>>) -> Result<Response<T>> {
+        if resp.is_not_modified() {
+            return Ok(resp.without_body());
+        }
+
         let body = resp.body_json::<T>()?;
         Ok(resp.with_body(body))
     }
 }
+
+pub struct ExpectFile;
+
+impl ResponseExpectation for ExpectFile {
+    type Body = DownloadedFile;
+
+    fn decode(&self, mut resp: crate::Response<Vec<u8>>) -> Result<Response<DownloadedFile>> {
+        let file = resp.take_file().ok_or_else(|| {
+            HttpError::Io("shell returned the body instead of writing it to a file".to_string())
+        })?;
+
+        Ok(resp.with_body(file))
+    }
+}
+
+/// How to decode the body of a response, chosen by its status code with
+/// [`RequestBuilder::expect_with`](crate::RequestBuilder::expect_with).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decoder {
+    /// Decode the body as JSON, like [`expect_json`](crate::RequestBuilder::expect_json)
+    Json,
+    /// Ignore the body, e.g. for a `204 No Content` response. The response is
+    /// dispatched without a body.
+    NoBody,
+}
+
+pub struct ExpectWith<T, F> {
+    choose: F,
+    json: ExpectJson<T>,
+}
+
+impl<T, F> ExpectWith<T, F> {
+    pub fn new(choose: F) -> Self {
+        Self {
+            choose,
+            json: ExpectJson::default(),
+        }
+    }
+}
+
+impl<T, F> ResponseExpectation for ExpectWith<T, F>
+where
+    T: DeserializeOwned,
+    F: Fn(StatusCode) -> Decoder,
+{
+    type Body = T;
+
+    fn decode(&self, resp: crate::Response<Vec<u8>>) -> Result<Response<T>> {
+        match (self.choose)(resp.status()) {
+            Decoder::Json => self.json.decode(resp),
+            Decoder::NoBody => Ok(resp.without_body()),
+        }
+    }
+}
+
+/// A response body decoded according to its `Content-Type`, by
+/// [`RequestBuilder::expect_auto`](crate::RequestBuilder::expect_auto).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutoBody<T> {
+    /// An `application/json` body, or one with a `+json` suffix like
+    /// `application/problem+json`, decoded as a `T`
+    Json(T),
+    /// An `application/x-www-form-urlencoded` body, decoded as a `T`
+    Form(T),
+    /// A `text/*` body, decoded in the charset given by the `Content-Type`
+    Text(String),
+    /// A body of any other type, or without a `Content-Type`, as it was received
+    Bytes(#[serde(with = "serde_bytes")] Vec<u8>),
+}
+
+pub struct ExpectAuto<T> {
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for ExpectAuto<T> {
+    fn default() -> Self {
+        Self {
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl<T> ResponseExpectation for ExpectAuto<T>
+where
+    T: DeserializeOwned,
+{
+    type Body = AutoBody<T>;
+
+    fn decode(&self, mut resp: crate::Response<Vec<u8>>) -> Result<Response<AutoBody<T>>> {
+        if resp.is_not_modified() {
+            return Ok(resp.without_body());
+        }
+
+        let Some(mime) = resp.content_type() else {
+            let body = resp.body_bytes()?;
+            return Ok(resp.with_body(AutoBody::Bytes(body)));
+        };
+
+        let body = if mime.essence() == "application/json" || mime.subtype().ends_with("+json") {
+            let bytes = resp.body_bytes()?;
+            let body = serde_json::from_slice(&bytes).map_err(|e| {
+                HttpError::Json(format!(
+                    "{mime} body is not a valid {}: {e}",
+                    type_name::<T>()
+                ))
+            })?;
+            AutoBody::Json(body)
+        } else if mime.essence() == "application/x-www-form-urlencoded" {
+            let bytes = resp.body_bytes()?;
+            let body = serde_urlencoded::from_bytes(&bytes).map_err(|e| {
+                HttpError::Decode(format!(
+                    "{mime} body is not a valid {}: {e}",
+                    type_name::<T>()
+                ))
+            })?;
+            AutoBody::Form(body)
+        } else if mime.basetype() == "text" {
+            AutoBody::Text(resp.body_string()?)
+        } else {
+            AutoBody::Bytes(resp.body_bytes()?)
+        };
+
+        Ok(resp.with_body(body))
+    }
+}
+
+pub struct ExpectInspect<Body, F> {
+    inner: Box<dyn ResponseExpectation<Body = Body> + Send>,
+    inspect: F,
+}
+
+impl<Body, F> ExpectInspect<Body, F> {
+    pub fn new(inner: Box<dyn ResponseExpectation<Body = Body> + Send>, inspect: F) -> Self {
+        Self { inner, inspect }
+    }
+}
+
+impl<Body, F> ResponseExpectation for ExpectInspect<Body, F>
+where
+    F: Fn(&Result<Response<Body>>),
+{
+    type Body = Body;
+
+    fn decode(&self, resp: crate::Response<Vec<u8>>) -> Result<Response<Body>> {
+        self.inner.decode(resp)
+    }
+
+    fn inspect(&self, result: &Result<Response<Body>>) {
+        self.inner.inspect(result);
+        (self.inspect)(result);
+    }
+}
@@ -9,6 +9,9 @@ use crux_core::capability::CapabilityContext;
 use http::Method;
 use url::Url;
 
+mod abort;
+#[cfg(feature = "decompression")]
+mod compress;
 mod config;
 mod error;
 mod expect;
@@ -24,13 +27,18 @@ pub mod testing;
 pub use http_types::{self as http};
 
 pub use self::{
+    abort::AbortHandle,
     config::Config,
-    error::HttpError,
-    request::Request,
-    request_builder::RequestBuilder,
+    error::{ApiError, HttpError},
+    expect::{AutoBody, Decoder},
+    request::{DownloadedFile, Request, UploadProgress},
+    request_builder::{ApiRequestBuilder, RequestBuilder, TaggedRequestBuilder},
     response::{Response, ResponseAsync},
 };
 
+#[cfg(feature = "decompression")]
+pub use self::compress::{Encoding, DEFAULT_COMPRESSION_THRESHOLD};
+
 use client::Client;
 
 pub type Result<T> = std::result::Result<T, HttpError>;
@@ -52,7 +60,7 @@ impl<Ev> crux_core::Capability<Ev> for Http<Ev> {
         Ev: 'static,
         NewEv: 'static + Send,
     {
-        Http::new(self.context.map_event(f))
+        Http::new(self.context.map_event(f)).with_config(self.client.config().clone())
     }
 
     #[cfg(feature = "typegen")]
@@ -85,6 +93,41 @@ where
         }
     }
 
+    /// A copy of this capability which applies `config` to every request it makes.
+    ///
+    /// Use it to keep the base URL and headers shared by the endpoints of an API in
+    /// one place. Relative URLs given to the request methods of the returned capability
+    /// are appended to the [base URL](Config::base_url), and the configured
+    /// [headers](Config::headers) are added to each request which doesn't set them
+    /// itself.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) -> crux_http::Result<()> {
+    /// use crux_http::Config;
+    ///
+    /// let api = caps.http.with_config(
+    ///     Config::new()
+    ///         .set_base_url("https://api.example.com/v1".parse().unwrap())
+    ///         .add_header("Authorization", "Bearer mF_9.B5f-4.1JqM")?,
+    /// );
+    ///
+    /// // GET https://api.example.com/v1/users/42
+    /// api.get("/users/42").send(Event::ReceiveResponse);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_config(&self, config: Config) -> Self {
+        Self {
+            context: self.context.clone(),
+            client: self.client.clone().with_config(config),
+        }
+    }
+
     /// Instruct the Shell to perform a HTTP GET request to the provided `url`.
     ///
     /// The request can be configured via associated functions on `RequestBuilder`
@@ -107,7 +150,7 @@ where
     /// # }
     /// ```
     pub fn get(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Get, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Get, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP HEAD request to the provided `url`.
@@ -132,7 +175,7 @@ where
     /// # }
     /// ```
     pub fn head(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Head, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Head, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP POST request to the provided `url`.
@@ -157,7 +200,7 @@ where
     /// # }
     /// ```
     pub fn post(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Post, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Post, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP PUT request to the provided `url`.
@@ -182,7 +225,7 @@ where
     /// # }
     /// ```
     pub fn put(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Put, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Put, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP DELETE request to the provided `url`.
@@ -207,7 +250,7 @@ where
     /// # }
     /// ```
     pub fn delete(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Delete, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Delete, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP CONNECT request to the provided `url`.
@@ -232,7 +275,7 @@ where
     /// # }
     /// ```
     pub fn connect(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Connect, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Connect, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP OPTIONS request to the provided `url`.
@@ -257,7 +300,7 @@ where
     /// # }
     /// ```
     pub fn options(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Options, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Options, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP TRACE request to the provided `url`.
@@ -282,7 +325,7 @@ where
     /// # }
     /// ```
     pub fn trace(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Trace, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Trace, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform a HTTP PATCH request to the provided `url`.
@@ -297,7 +340,7 @@ where
     ///
     /// This will panic if a malformed URL is passed.
     pub fn patch(&self, url: impl AsRef<str>) -> RequestBuilder<Ev> {
-        RequestBuilder::new(Method::Patch, url.as_ref().parse().unwrap(), self.clone())
+        RequestBuilder::new(Method::Patch, self.client.url(url), self.clone())
     }
 
     /// Instruct the Shell to perform an HTTP request with the provided `method` and `url`.
@@ -52,9 +52,13 @@ use std::sync::Arc;
 
 use crate::{Client, Request, ResponseAsync, Result};
 
+mod logging;
 mod redirect;
+mod retry;
 
-pub use redirect::Redirect;
+pub use logging::Logging;
+pub use redirect::{FollowRedirects, Redirect};
+pub use retry::{Retry, RetryPolicy};
 
 use async_trait::async_trait;
 use futures_util::future::BoxFuture;
@@ -0,0 +1,69 @@
+//! HTTP Logging middleware.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+//! # struct Capabilities { http: crux_http::Http<Event> }
+//! # fn update(caps: &Capabilities) {
+//! use crux_http::middleware::{Logging, RetryPolicy};
+//!
+//! caps.http
+//!     .get("https://httpbin.org/status/503")
+//!     .middleware(Logging::new(|line| eprintln!("{line}")))
+//!     .retry(RetryPolicy::default())
+//!     .send(Event::ReceiveResponse)
+//! # }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use crate::middleware::{Middleware, Next, Request};
+use crate::{Client, ResponseAsync, Result};
+
+/// A middleware which reports each request it sees, and the response or error it
+/// gets back, as a line of text.
+///
+/// Middleware runs in the order it's added to the request, each wrapping the ones
+/// after it. Added before [`Retry`](crate::middleware::Retry), `Logging` sees the
+/// request once, with the final outcome. Added after it, `Logging` sees every attempt.
+///
+/// The lines look like this:
+///
+/// ```text
+/// GET https://example.com/
+/// GET https://example.com/ -> 200
+/// GET https://example.com/ failed: Timeout after 5s
+/// ```
+#[derive(Clone)]
+pub struct Logging {
+    log: Arc<dyn Fn(&str) + Send + Sync>,
+}
+
+impl Logging {
+    /// Create a new instance of the Logging middleware, which hands each line to `log`.
+    pub fn new(log: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self { log: Arc::new(log) }
+    }
+}
+
+impl fmt::Debug for Logging {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Logging")
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for Logging {
+    async fn handle(&self, req: Request, client: Client, next: Next<'_>) -> Result<ResponseAsync> {
+        let request = format!("{} {}", req.method(), req.url());
+        (self.log)(&request);
+
+        let res = next.run(req, client).await;
+        match &res {
+            Ok(response) => (self.log)(&format!("{request} -> {}", response.status())),
+            Err(error) => (self.log)(&format!("{request} failed: {error}")),
+        }
+        res
+    }
+}
@@ -14,9 +14,13 @@
 //! # }
 //! ```
 
-use crate::http::{self, headers, StatusCode, Url};
+use crate::http::{
+    self,
+    headers::{self, HeaderName},
+    StatusCode, Url,
+};
 use crate::middleware::{Middleware, Next, Request};
-use crate::{Client, ResponseAsync, Result};
+use crate::{Client, HttpError, ResponseAsync, Result};
 
 // List of acceptable 300-series redirect codes.
 const REDIRECT_CODES: &[StatusCode] = &[
@@ -27,6 +31,13 @@ const REDIRECT_CODES: &[StatusCode] = &[
     StatusCode::PermanentRedirect,
 ];
 
+// Headers which carry credentials, and must not follow a redirect to another origin.
+const CREDENTIAL_HEADERS: &[HeaderName] = &[
+    headers::AUTHORIZATION,
+    headers::PROXY_AUTHORIZATION,
+    headers::COOKIE,
+];
+
 /// A middleware which attempts to follow HTTP redirects.
 #[derive(Debug)]
 pub struct Redirect {
@@ -137,3 +148,91 @@ impl Default for Redirect {
         Self { attempts: 3 }
     }
 }
+
+/// A middleware which transparently follows HTTP redirects, sending a new request
+/// to the shell for each hop. Usually added with
+/// [`RequestBuilder::follow_redirects`](crate::RequestBuilder::follow_redirects).
+///
+/// Unlike [`Redirect`], no request is sent twice: the response to the final request
+/// is returned as is. Relative `Location` values are resolved against the URL of the
+/// request which was redirected. A 303 See Other is followed with a `GET` request
+/// without a body.
+///
+/// When a redirect leads to another origin (a different scheme, host or port), the
+/// `Authorization`, `Proxy-Authorization` and `Cookie` headers are removed from the
+/// request, including any set by the [`Config`](crate::Config) of the client, so that
+/// credentials meant for one server are never sent to another.
+///
+/// # Errors
+///
+/// [`HttpError::TooManyRedirects`] is returned if the server still redirects after
+/// `max` hops, and [`HttpError::Url`] if the `Location` header is not a valid URL.
+#[derive(Debug)]
+pub struct FollowRedirects {
+    max: u8,
+}
+
+impl FollowRedirects {
+    /// Create a new instance of the middleware, following up to `max` redirects.
+    #[must_use]
+    pub fn new(max: u8) -> Self {
+        Self { max }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for FollowRedirects {
+    async fn handle(
+        &self,
+        mut req: Request,
+        client: Client,
+        next: Next<'_>,
+    ) -> Result<ResponseAsync> {
+        let mut body = if req.is_empty() == Some(true) {
+            vec![]
+        } else {
+            req.take_body().into_bytes().await?
+        };
+
+        let mut hops: u8 = 0;
+        loop {
+            let mut attempt = req.clone();
+            if !body.is_empty() {
+                attempt.set_body(body.clone());
+            }
+
+            let res = next.run(attempt, client.clone()).await?;
+            if !REDIRECT_CODES.contains(&res.status()) {
+                return Ok(res);
+            }
+            let Some(location) = res.header(headers::LOCATION) else {
+                return Ok(res);
+            };
+
+            if hops == self.max {
+                return Err(HttpError::TooManyRedirects {
+                    hops,
+                    url: req.url().to_string(),
+                });
+            }
+            hops += 1;
+
+            let url = req.url().join(location.last().as_str())?;
+            let cross_origin = url.origin() != req.url().origin();
+            let http_req: &mut http::Request = req.as_mut();
+            *http_req.url_mut() = url;
+
+            if cross_origin {
+                for name in CREDENTIAL_HEADERS {
+                    http_req.remove_header(name.clone());
+                }
+            }
+
+            if res.status() == StatusCode::SeeOther {
+                http_req.set_method(http::Method::Get);
+                http_req.remove_header(headers::CONTENT_TYPE);
+                body.clear();
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! HTTP Retry middleware.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+//! # struct Capabilities { http: crux_http::Http<Event> }
+//! # fn update(caps: &Capabilities) {
+//! use crux_http::middleware::RetryPolicy;
+//!
+//! caps.http
+//!     .get("https://httpbin.org/status/503")
+//!     .retry(RetryPolicy::default())
+//!     .send(Event::ReceiveResponse)
+//! # }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::http::StatusCode;
+use crate::middleware::{Middleware, Next, Request};
+use crate::{Client, HttpError, ResponseAsync, Result};
+
+/// Describes when and how many times a request should be retried, and how long
+/// to wait before each retry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u8,
+    status_codes: Vec<StatusCode>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Create a policy which retries a request up to `max_retries` times
+    /// (not including the original attempt) when the server responds with
+    /// 502 Bad Gateway, 503 Service Unavailable or 504 Gateway Timeout.
+    ///
+    /// The first retry waits for 100ms, doubling the wait for each retry after
+    /// that up to 10s, with jitter.
+    #[must_use]
+    pub fn new(max_retries: u8) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Replace the status codes which cause the request to be retried.
+    #[must_use]
+    pub fn on_status(mut self, status_codes: impl IntoIterator<Item = StatusCode>) -> Self {
+        self.status_codes = status_codes.into_iter().collect();
+        self
+    }
+
+    /// Wait `initial` before the first retry, doubling the wait before each one
+    /// after that, up to `max`.
+    #[must_use]
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Whether to randomise the wait before each retry, so that clients which
+    /// failed at the same time don't all retry at the same time too. With jitter,
+    /// the wait is somewhere between half of the backoff and all of it.
+    #[must_use]
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// The backoff before the given retry, counting from 1, before any jitter.
+    #[must_use]
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// The wait before the given retry, counting from 1, with jitter if enabled.
+    fn delay_for(&self, retry: u32) -> Duration {
+        let backoff = self.backoff_for(retry);
+        if self.jitter {
+            with_jitter(backoff, random())
+        } else {
+            backoff
+        }
+    }
+
+    /// The maximum number of retries, not including the original attempt.
+    #[must_use]
+    pub fn max_retries(&self) -> u8 {
+        self.max_retries
+    }
+
+    /// Whether a response with the given status should be retried.
+    #[must_use]
+    pub fn should_retry(&self, status: StatusCode) -> bool {
+        self.status_codes.contains(&status)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retry up to 3 times on 502, 503 and 504 responses, with a jittered backoff
+    /// from 100ms up to 10s.
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            status_codes: vec![
+                StatusCode::BadGateway,
+                StatusCode::ServiceUnavailable,
+                StatusCode::GatewayTimeout,
+            ],
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+// somewhere between half of `backoff` and all of it, picked by `random`
+fn with_jitter(backoff: Duration, random: u64) -> Duration {
+    let nanos = backoff.as_nanos();
+    let half = nanos / 2;
+    let jittered = half + u128::from(random) % (nanos - half + 1);
+    Duration::from_nanos(u64::try_from(jittered).unwrap_or(u64::MAX))
+}
+
+// Good enough for spreading out retries, without depending on a random number
+// generator: every `RandomState` is seeded with fresh random keys.
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// A middleware which retries requests according to a [`RetryPolicy`].
+///
+/// Every retry sends a fresh request to the shell. The core has no timer of its
+/// own to wait on, so each retry asks the shell to wait out the backoff before
+/// sending it, with [`HttpRequest::delay`](crate::protocol::HttpRequest::delay).
+///
+/// # Errors
+///
+/// When the server still responds with a retryable status after the last retry,
+/// [`HttpError::RetriesExhausted`] is returned, carrying the number of attempts made.
+#[derive(Debug, Default)]
+pub struct Retry {
+    policy: RetryPolicy,
+}
+
+impl Retry {
+    /// Create a new instance of the Retry middleware with the given policy.
+    ///
+    /// Consider using `Retry::default()` for the default policy.
+    #[must_use]
+    pub fn new(policy: RetryPolicy) -> Self {
+        Retry { policy }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for Retry {
+    async fn handle(
+        &self,
+        mut req: Request,
+        client: Client,
+        next: Next<'_>,
+    ) -> Result<ResponseAsync> {
+        // The body can only be read once, so hold on to it and attach a copy
+        // to each attempt. A body of unknown length is read as well, in case it
+        // isn't empty.
+        let body = if req.is_empty() == Some(true) {
+            vec![]
+        } else {
+            req.take_body().into_bytes().await?
+        };
+
+        let mut attempts: u32 = 0;
+        loop {
+            attempts += 1;
+
+            let mut attempt = req.clone();
+            if !body.is_empty() {
+                attempt.set_body(body.clone());
+            }
+            if attempts > 1 {
+                attempt.set_delay(Some(self.policy.delay_for(attempts - 1)));
+            }
+
+            let res = next.run(attempt, client.clone()).await?;
+            if !self.policy.should_retry(res.status()) {
+                return Ok(res);
+            }
+
+            if attempts > u32::from(self.policy.max_retries) {
+                return Err(HttpError::RetriesExhausted {
+                    code: res.status(),
+                    attempts,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy =
+            RetryPolicy::new(5).backoff(Duration::from_millis(100), Duration::from_secs(1));
+
+        let backoffs: Vec<_> = (1..=6).map(|retry| policy.backoff_for(retry)).collect();
+        assert_eq!(
+            backoffs,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(policy.backoff_for(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn jitter_stays_between_half_and_all_of_the_backoff() {
+        let backoff = Duration::from_millis(100);
+
+        assert_eq!(with_jitter(backoff, 0), Duration::from_millis(50));
+        assert_eq!(with_jitter(backoff, 50_000_000), backoff);
+        for _ in 0..100 {
+            let delay = with_jitter(backoff, random());
+            assert!(delay >= Duration::from_millis(50) && delay <= backoff);
+        }
+    }
+
+    #[test]
+    fn jitter_can_be_turned_off() {
+        let policy = RetryPolicy::new(1).jitter(false);
+
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+    }
+}
@@ -6,9 +6,11 @@
 
 use async_trait::async_trait;
 use derive_builder::Builder;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-use crate::HttpError;
+use crate::{HttpError, UploadProgress};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct HttpHeader {
@@ -16,6 +18,10 @@ pub struct HttpHeader {
     pub value: String,
 }
 
+/// Identifies a request which can be cancelled, see [`HttpRequest::id`].
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HttpRequestId(pub u64);
+
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Builder)]
 #[builder(
     custom_constructor,
@@ -23,12 +29,57 @@ pub struct HttpHeader {
     setter(into)
 )]
 pub struct HttpRequest {
+    /// Set on requests which can be aborted, see
+    /// [`RequestBuilder::abortable`](crate::RequestBuilder::abortable), so that a later
+    /// cancellation can name the request it cancels.
+    #[serde(default)]
+    pub id: Option<HttpRequestId>,
+    /// Set when this is not a request to send, but a cancellation of the request in
+    /// flight with the same [`id`](HttpRequest::id), made with
+    /// [`HttpRequest::cancellation`]. The other fields are left empty. The shell should
+    /// check this first, abort the request if it is still in flight, and not respond to
+    /// either: the core has already resolved the cancelled request with
+    /// [`HttpError::Aborted`].
+    #[serde(default)]
+    pub cancel: bool,
     pub method: String,
     pub url: String,
     #[builder(setter(custom))]
     pub headers: Vec<HttpHeader>,
     #[serde(with = "serde_bytes")]
     pub body: Vec<u8>,
+    /// How long the shell should wait for a response before giving up,
+    /// serialized as a number of nanoseconds.
+    #[serde(default, with = "nanos::option")]
+    pub timeout: Option<Duration>,
+    /// How long the shell should wait before sending the request, serialized as a
+    /// number of nanoseconds. Set on retries, so that a struggling server isn't hit
+    /// again straight away, see [`RetryPolicy`](crate::middleware::RetryPolicy).
+    #[serde(default, with = "nanos::option")]
+    pub delay: Option<Duration>,
+    /// Whether the app would like to hear about the progress of uploading the body.
+    /// When set, the shell should resolve the request with [`HttpResult::UploadProgress`]
+    /// as it streams the body, before resolving it with the final result.
+    #[serde(default)]
+    pub upload_progress: bool,
+    /// The largest response body the app will accept, in bytes, or `None` (the default)
+    /// for no limit. The shell should stop reading the body once it has received more
+    /// than this, counting every chunk of a streamed body towards the limit, and resolve
+    /// the request with [`HttpError::BodyTooLarge`]. Bodies over the limit which reach
+    /// the core are rejected there as well.
+    #[serde(default)]
+    pub max_response_bytes: Option<u64>,
+    /// Whether the shell should write the response body to a file instead of returning
+    /// it. When set, the shell streams the body to a file of its choosing and resolves the
+    /// request with [`HttpResult::File`], so the body never passes through the core.
+    ///
+    /// The path is picked by the shell, never by the core, e.g. a fresh file in the app's
+    /// cache or downloads directory. Letting the core name the file would let anything
+    /// able to influence the core (a URL, a header, a server response) overwrite arbitrary
+    /// files the app has access to. The core should treat the path as an opaque handle
+    /// to give back to the shell, not build paths of its own from it.
+    #[serde(default)]
+    pub to_file: bool,
 }
 
 impl std::fmt::Debug for HttpRequest {
@@ -43,15 +94,35 @@ impl std::fmt::Debug for HttpRequest {
             format!("<binary data - {} bytes>", self.body.len())
         };
         let mut builder = f.debug_struct("HttpRequest");
+        if let Some(id) = self.id {
+            builder.field("id", &id);
+        }
+        if self.cancel {
+            builder.field("cancel", &self.cancel);
+        }
         builder
             .field("method", &self.method)
             .field("url", &self.url);
         if !self.headers.is_empty() {
             builder.field("headers", &self.headers);
         };
-        builder
-            .field("body", &format_args!("{}", body_repr))
-            .finish()
+        builder.field("body", &format_args!("{}", body_repr));
+        if let Some(timeout) = self.timeout {
+            builder.field("timeout", &timeout);
+        }
+        if let Some(delay) = self.delay {
+            builder.field("delay", &delay);
+        }
+        if self.upload_progress {
+            builder.field("upload_progress", &self.upload_progress);
+        }
+        if let Some(max_response_bytes) = self.max_response_bytes {
+            builder.field("max_response_bytes", &max_response_bytes);
+        }
+        if self.to_file {
+            builder.field("to_file", &self.to_file);
+        }
+        builder.finish()
     }
 }
 
@@ -59,10 +130,17 @@ macro_rules! http_method {
     ($name:ident, $method:expr) => {
         pub fn $name(url: impl Into<String>) -> HttpRequestBuilder {
             HttpRequestBuilder {
+                id: Some(None),
+                cancel: Some(false),
                 method: Some($method.to_string()),
                 url: Some(url.into()),
                 headers: Some(vec![]),
                 body: Some(vec![]),
+                timeout: Some(None),
+                delay: Some(None),
+                upload_progress: Some(false),
+                max_response_bytes: Some(None),
+                to_file: Some(false),
             }
         }
     };
@@ -76,6 +154,17 @@ impl HttpRequest {
     http_method!(patch, "PATCH");
     http_method!(head, "HEAD");
     http_method!(options, "OPTIONS");
+
+    /// A cancellation of the request in flight with the given `id`, see
+    /// [`HttpRequest::cancel`].
+    #[must_use]
+    pub fn cancellation(id: HttpRequestId) -> Self {
+        Self {
+            id: Some(id),
+            cancel: true,
+            ..Default::default()
+        }
+    }
 }
 
 impl HttpRequestBuilder {
@@ -110,6 +199,11 @@ pub struct HttpResponse {
     pub headers: Vec<HttpHeader>,
     #[serde(with = "serde_bytes")]
     pub body: Vec<u8>,
+    /// The URL the response came from, after any redirects the shell followed. The URL
+    /// of the request is used if this is left out.
+    #[serde(default)]
+    #[builder(setter(into, strip_option))]
+    pub url: Option<String>,
 }
 
 impl HttpResponse {
@@ -118,6 +212,7 @@ impl HttpResponse {
             status: Some(status),
             headers: Some(vec![]),
             body: Some(vec![]),
+            url: Some(None),
         }
     }
     pub fn ok() -> HttpResponseBuilder {
@@ -148,7 +243,24 @@ impl HttpResponseBuilder {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum HttpResult {
     Ok(HttpResponse),
+    /// The request failed before a response arrived. The shell should classify the
+    /// failure as precisely as it can, see [`HttpError`].
     Err(HttpError),
+    /// Intermediate progress of uploading the request body, only sent when the
+    /// request asked for [`upload_progress`](HttpRequest::upload_progress).
+    UploadProgress {
+        sent: u64,
+        total: u64,
+    },
+    /// The response to a request which asked for its body to be written
+    /// [`to_file`](HttpRequest::to_file). The `response` carries the status and headers,
+    /// with an empty body, and `path` is where the shell wrote the `bytes_written` bytes
+    /// of the body.
+    File {
+        response: HttpResponse,
+        path: String,
+        bytes_written: u64,
+    },
 }
 
 impl From<crate::Result<HttpResponse>> for HttpResult {
@@ -164,9 +276,24 @@ impl crux_core::capability::Operation for HttpRequest {
     type Output = HttpResult;
 }
 
+pub(crate) type ProgressCallback = dyn Fn(UploadProgress) + Send + Sync;
+
 #[async_trait]
 pub(crate) trait EffectSender {
     async fn send(&self, effect: HttpRequest) -> HttpResult;
+
+    /// Send a request the shell doesn't respond to, i.e. a cancellation.
+    async fn notify(&self, effect: HttpRequest);
+
+    /// Send the request, reporting any upload progress to `on_progress` until
+    /// the final result arrives.
+    async fn send_with_progress(
+        &self,
+        effect: HttpRequest,
+        _on_progress: &ProgressCallback,
+    ) -> HttpResult {
+        self.send(effect).await
+    }
 }
 
 #[async_trait]
@@ -177,6 +304,32 @@ where
     async fn send(&self, effect: HttpRequest) -> HttpResult {
         crux_core::capability::CapabilityContext::request_from_shell(self, effect).await
     }
+
+    async fn notify(&self, effect: HttpRequest) {
+        crux_core::capability::CapabilityContext::notify_shell(self, effect).await;
+    }
+
+    async fn send_with_progress(
+        &self,
+        effect: HttpRequest,
+        on_progress: &ProgressCallback,
+    ) -> HttpResult {
+        let mut results = crux_core::capability::CapabilityContext::stream_from_shell(self, effect);
+
+        while let Some(result) = results.next().await {
+            match result {
+                HttpResult::UploadProgress { sent, total } => on_progress(UploadProgress {
+                    bytes_sent: sent,
+                    total,
+                }),
+                result => return result,
+            }
+        }
+
+        HttpResult::Err(HttpError::Io(
+            "shell stopped responding before the request completed".to_string(),
+        ))
+    }
 }
 
 #[async_trait]
@@ -187,13 +340,26 @@ pub(crate) trait ProtocolRequestBuilder {
 #[async_trait]
 impl ProtocolRequestBuilder for crate::Request {
     async fn into_protocol_request(mut self) -> crate::Result<HttpRequest> {
-        let body = if self.is_empty() == Some(false) {
+        #[allow(unused_mut)]
+        let mut body = if self.is_empty() == Some(false) {
             self.take_body().into_bytes().await?
         } else {
             vec![]
         };
 
+        #[cfg(feature = "decompression")]
+        if self.header("content-encoding").is_none() {
+            if let Some(encoding) = self
+                .compression()
+                .and_then(|compression| compression.apply(&mut body))
+            {
+                self.insert_header("content-encoding", encoding.name());
+            }
+        }
+
         Ok(HttpRequest {
+            id: self.id(),
+            cancel: false,
             method: self.method().to_string(),
             url: self.url().to_string(),
             headers: self
@@ -206,6 +372,11 @@ impl ProtocolRequestBuilder for crate::Request {
                 })
                 .collect(),
             body,
+            timeout: self.timeout(),
+            delay: self.delay(),
+            upload_progress: self.progress_callback().is_some(),
+            max_response_bytes: self.max_response_bytes(),
+            to_file: self.to_file(),
         })
     }
 }
@@ -218,7 +389,49 @@ impl From<HttpResponse> for crate::ResponseAsync {
             res.append_header(header.name.as_str(), header.value);
         }
 
-        crate::ResponseAsync::new(res)
+        let mut res = crate::ResponseAsync::new(res);
+        if let Some(url) = effect_response.url.and_then(|url| url.parse().ok()) {
+            res.set_url(url);
+        }
+        res
+    }
+}
+
+/// Serde helpers representing a [`Duration`] as a whole number of nanoseconds,
+/// which is easier for shells to work with than a `{ secs, nanos }` pair.
+pub(crate) mod nanos {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        u64::try_from(duration.as_nanos())
+            .unwrap_or(u64::MAX)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
+
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+        use std::time::Duration;
+
+        #[allow(clippy::ref_option)]
+        pub fn serialize<S: Serializer>(
+            duration: &Option<Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            duration
+                .map(|d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX))
+                .serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_nanos))
+        }
     }
 }
 
@@ -257,10 +470,143 @@ mod tests {
                     value: "bar".to_string(),
                 }],
                 body: "123".as_bytes().to_vec(),
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn test_http_request_timeout_serializes_as_nanos() {
+        let req = HttpRequest::get("https://example.com")
+            .timeout(Duration::from_secs(5))
+            .build();
+
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["timeout"], 5_000_000_000_u64);
+
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, req);
+    }
+
+    #[test]
+    fn test_http_request_cancellation_serializes() {
+        let req = HttpRequest::get("https://example.com")
+            .id(HttpRequestId(7))
+            .build();
+
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["cancel"], false);
+
+        let cancellation = HttpRequest::cancellation(HttpRequestId(7));
+        let json = serde_json::to_value(&cancellation).unwrap();
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["cancel"], true);
+
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, cancellation);
+
+        // shells which don't know about cancellation can leave both fields out
+        let mut json =
+            serde_json::to_value(HttpRequest::get("https://example.com").build()).unwrap();
+        json.as_object_mut().unwrap().remove("id");
+        json.as_object_mut().unwrap().remove("cancel");
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.id, None);
+        assert!(!deserialized.cancel);
+    }
+
+    #[test]
+    fn test_http_request_delay_serializes_as_nanos() {
+        let req = HttpRequest::get("https://example.com")
+            .delay(Duration::from_millis(250))
+            .build();
+
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["delay"], 250_000_000_u64);
+
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, req);
+
+        let mut json =
+            serde_json::to_value(HttpRequest::get("https://example.com").build()).unwrap();
+        json.as_object_mut().unwrap().remove("delay");
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.delay, None);
+    }
+
+    #[test]
+    fn test_http_request_max_response_bytes_serializes() {
+        let req = HttpRequest::get("https://example.com")
+            .max_response_bytes(1024)
+            .build();
+
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["max_response_bytes"], 1024);
+
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, req);
+
+        let unlimited = HttpRequest::get("https://example.com").build();
+        let json = serde_json::to_value(&unlimited).unwrap();
+        assert_eq!(json["max_response_bytes"], serde_json::Value::Null);
+
+        let mut json = json;
+        json.as_object_mut().unwrap().remove("max_response_bytes");
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.max_response_bytes, None);
+    }
+
+    #[test]
+    fn test_http_request_to_file_serializes() {
+        let req = HttpRequest::get("https://example.com/video.mp4")
+            .to_file(true)
+            .build();
+
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["to_file"], true);
+
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, req);
+
+        let mut json =
+            serde_json::to_value(HttpRequest::get("https://example.com").build()).unwrap();
+        json.as_object_mut().unwrap().remove("to_file");
+        let deserialized: HttpRequest = serde_json::from_value(json).unwrap();
+        assert!(!deserialized.to_file);
+    }
+
+    #[test]
+    fn test_http_result_file_serializes() {
+        let result = HttpResult::File {
+            response: HttpResponse::ok()
+                .header("Content-Type", "video/mp4")
+                .build(),
+            path: "/cache/downloads/1.mp4".to_string(),
+            bytes_written: 1_048_576,
+        };
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "File": {
+                    "response": {
+                        "status": 200,
+                        "headers": [{ "name": "Content-Type", "value": "video/mp4" }],
+                        "body": [],
+                        "url": null
+                    },
+                    "path": "/cache/downloads/1.mp4",
+                    "bytes_written": 1_048_576
+                }
+            })
+        );
+
+        let deserialized: HttpResult = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, result);
+    }
+
     #[test]
     fn test_http_response_status() {
         let req = HttpResponse::status(302).build();
@@ -290,10 +636,21 @@ mod tests {
                     value: "bar".to_string(),
                 }],
                 body: "hello world".as_bytes().to_vec(),
+                url: None,
             }
         );
     }
 
+    #[test]
+    fn test_http_response_url_is_optional() {
+        let res: HttpResponse =
+            serde_json::from_str(r#"{"status":200,"headers":[],"body":[]}"#).unwrap();
+        assert_eq!(res.url, None);
+
+        let res = HttpResponse::ok().url("https://example.com/final").build();
+        assert_eq!(res.url.as_deref(), Some("https://example.com/final"));
+    }
+
     #[test]
     fn test_http_request_debug_repr() {
         {
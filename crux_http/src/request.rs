@@ -4,12 +4,15 @@ use crate::http::{
     Body, Method, Mime, Url,
 };
 use crate::middleware::Middleware;
+use crate::protocol::{HttpRequestId, ProgressCallback};
+use crate::AbortHandle;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use std::fmt;
 use std::ops::Index;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// An HTTP request, returns a `Response`.
 #[derive(Clone)]
@@ -18,6 +21,42 @@ pub struct Request {
     req: http::Request,
     /// Holds an optional per-request middleware stack.
     middleware: Option<Vec<Arc<dyn Middleware>>>,
+    /// How long the shell should wait for a response.
+    timeout: Option<Duration>,
+    /// How long the shell should wait before sending the request.
+    delay: Option<Duration>,
+    /// The largest response body to accept, in bytes.
+    max_response_bytes: Option<u64>,
+    /// Whether the shell should write the response body to a file.
+    to_file: bool,
+    /// Receives upload progress reported by the shell, if requested.
+    progress: Option<Arc<ProgressCallback>>,
+    /// Allows the request to be aborted while in flight.
+    abort: Option<AbortHandle>,
+    /// Identifies the request to the shell, if it can be aborted.
+    id: Option<HttpRequestId>,
+    /// How to compress the body before it is sent, if at all.
+    #[cfg(feature = "decompression")]
+    compression: Option<crate::compress::Compression>,
+}
+
+/// Progress of uploading a request body, as reported by the shell.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// The number of bytes of the body sent so far.
+    pub bytes_sent: u64,
+    /// The total size of the body in bytes.
+    pub total: u64,
+}
+
+/// Where the shell wrote the body of a response, for a request sent with
+/// [`RequestBuilder::to_file`](crate::RequestBuilder::to_file).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DownloadedFile {
+    /// The path of the file, chosen by the shell.
+    pub path: String,
+    /// The size of the body written to the file, in bytes.
+    pub bytes_written: u64,
 }
 
 impl Request {
@@ -42,6 +81,15 @@ impl Request {
         Self {
             req,
             middleware: None,
+            timeout: None,
+            delay: None,
+            max_response_bytes: None,
+            to_file: false,
+            progress: None,
+            abort: None,
+            id: None,
+            #[cfg(feature = "decompression")]
+            compression: None,
         }
     }
 
@@ -246,6 +294,94 @@ impl Request {
         self.req.set_content_type(mime);
     }
 
+    /// Get the timeout the shell has been asked to honor, if any.
+    #[must_use]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Ask the shell to give up on the request if no response arrived within `timeout`.
+    ///
+    /// When the shell gives up, the request resolves to [`HttpError::Timeout`](crate::HttpError::Timeout).
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Get how long the shell has been asked to wait before sending the request, if at all.
+    #[must_use]
+    pub fn delay(&self) -> Option<Duration> {
+        self.delay
+    }
+
+    /// Ask the shell to wait for `delay` before sending the request, e.g. to back off
+    /// before a retry, see [`RetryPolicy`](crate::middleware::RetryPolicy).
+    pub fn set_delay(&mut self, delay: Option<Duration>) {
+        self.delay = delay;
+    }
+
+    /// Get the largest response body, in bytes, the request accepts, if it has a limit.
+    #[must_use]
+    pub fn max_response_bytes(&self) -> Option<u64> {
+        self.max_response_bytes
+    }
+
+    /// Limit the size of the response body to `max_response_bytes`, or lift the limit
+    /// with `None`, which is the default.
+    ///
+    /// A larger body resolves the request to [`HttpError::BodyTooLarge`](crate::HttpError::BodyTooLarge).
+    pub fn set_max_response_bytes(&mut self, max_response_bytes: Option<u64>) {
+        self.max_response_bytes = max_response_bytes;
+    }
+
+    /// Get whether the shell has been asked to write the response body to a file.
+    #[must_use]
+    pub fn to_file(&self) -> bool {
+        self.to_file
+    }
+
+    /// Ask the shell to write the response body to a file of its choosing, rather than
+    /// returning it to the core. The response then carries a [`DownloadedFile`] saying
+    /// where the body was written.
+    pub fn set_to_file(&mut self, to_file: bool) {
+        self.to_file = to_file;
+    }
+
+    #[cfg(feature = "decompression")]
+    pub(crate) fn set_compression(&mut self, compression: crate::compress::Compression) {
+        self.compression = Some(compression);
+    }
+
+    #[cfg(feature = "decompression")]
+    pub(crate) fn compression(&self) -> Option<crate::compress::Compression> {
+        self.compression
+    }
+
+    pub(crate) fn set_progress_callback(&mut self, callback: Arc<ProgressCallback>) {
+        self.progress = Some(callback);
+    }
+
+    pub(crate) fn progress_callback(&self) -> Option<&Arc<ProgressCallback>> {
+        self.progress.as_ref()
+    }
+
+    /// Get a handle which aborts this request when [`AbortHandle::abort`] is called.
+    ///
+    /// This also gives the request an [`id`](crate::protocol::HttpRequest::id), which the
+    /// shell is sent along with the request, and with the cancellation when it is aborted.
+    pub fn abort_handle(&mut self) -> AbortHandle {
+        let handle = self.abort.get_or_insert_with(AbortHandle::new).clone();
+        self.id = Some(handle.id());
+        handle
+    }
+
+    pub(crate) fn id(&self) -> Option<HttpRequestId> {
+        self.id
+    }
+
+    pub(crate) fn take_abort_handle(&mut self) -> Option<AbortHandle> {
+        self.abort.take()
+    }
+
     /// Get the length of the body stream, if it has been set.
     ///
     /// This value is set when passing a fixed-size object into as the body.
@@ -288,6 +424,9 @@ impl Request {
     ///
     /// The `content-type` is set to `application/json`.
     ///
+    /// Keys are written in the order given by the value's `Serialize` implementation,
+    /// see [`RequestBuilder::body_json`](crate::RequestBuilder::body_json).
+    ///
     /// # Errors
     ///
     /// This method will return an error if the provided data could not be serialized to JSON.
@@ -392,6 +531,15 @@ impl From<http::Request> for Request {
         Self {
             req,
             middleware: None,
+            timeout: None,
+            delay: None,
+            max_response_bytes: None,
+            to_file: false,
+            progress: None,
+            abort: None,
+            id: None,
+            #[cfg(feature = "decompression")]
+            compression: None,
         }
     }
 }
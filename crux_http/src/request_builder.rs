@@ -1,19 +1,32 @@
-use crate::expect::{ExpectBytes, ExpectJson, ExpectString};
-use crate::middleware::Middleware;
+use crate::expect::{
+    AutoBody, Decoder, ExpectAuto, ExpectBytes, ExpectFile, ExpectInspect, ExpectJson,
+    ExpectString, ExpectWith,
+};
+use crate::middleware::{FollowRedirects, Middleware, Retry, RetryPolicy};
 use crate::{
     expect::ResponseExpectation,
     http::{
-        headers::{HeaderName, ToHeaderValues},
-        Body, Method, Mime, Url,
+        auth::{AuthenticationScheme, Authorization, BasicAuth},
+        conditional::IfModifiedSince,
+        headers::{self, HeaderName, HeaderValue, ToHeaderValues},
+        Body, Method, Mime, StatusCode, Url,
     },
 };
-use crate::{Client, HttpError, Request, Response, ResponseAsync, Result};
+use crate::{
+    AbortHandle, ApiError, Client, DownloadedFile, HttpError, Request, Response, ResponseAsync,
+    Result, UploadProgress,
+};
 
 use futures_util::future::BoxFuture;
 use http_types::convert::DeserializeOwned;
 use serde::Serialize;
 
-use std::{fmt, marker::PhantomData};
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 /// Request Builder
 ///
@@ -102,6 +115,61 @@ where
         self
     }
 
+    /// Sets the `Authorization` header to use HTTP Basic authentication, with the
+    /// base64 encoded `username:password` as the credentials.
+    ///
+    /// Any `Authorization` header set earlier is replaced. Setting it twice is usually a
+    /// mistake, so debug builds panic if the request already has one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/basic-auth/user/passwd")
+    ///     .basic_auth("user", "passwd")
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn basic_auth(self, username: impl AsRef<str>, password: impl AsRef<str>) -> Self {
+        let credentials = BasicAuth::new(username, password);
+        self.authorization(credentials.value())
+    }
+
+    /// Sets the `Authorization` header to `Bearer <token>`.
+    ///
+    /// Any `Authorization` header set earlier is replaced. Setting it twice is usually a
+    /// mistake, so debug builds panic if the request already has one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/bearer")
+    ///     .bearer_auth("secret-token")
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn bearer_auth(self, token: impl Into<String>) -> Self {
+        let credentials = Authorization::new(AuthenticationScheme::Bearer, token.into());
+        self.authorization(credentials.value())
+    }
+
+    fn authorization(mut self, value: HeaderValue) -> Self {
+        let req = self.req.as_mut().unwrap();
+        debug_assert!(
+            req.header(headers::AUTHORIZATION).is_none(),
+            "the request already has an Authorization header"
+        );
+        req.insert_header(headers::AUTHORIZATION, value);
+        self
+    }
+
     /// Sets the Content-Type header on the request.
     ///
     /// # Examples
@@ -156,6 +224,21 @@ where
     ///
     /// The encoding is set to `application/json`.
     ///
+    /// # Key order
+    ///
+    /// Keys are written in the order the value's `Serialize` implementation produces
+    /// them, so a struct's fields keep their declaration order and the body is the same,
+    /// byte for byte, every time. This makes it safe to sign the body, for example with
+    /// an HMAC.
+    ///
+    /// A [`serde_json::Value`] built with `serde_json::json!` may not keep the order its
+    /// keys were written in. Its objects are sorted by key, unless serde_json's
+    /// `preserve_order` feature is enabled, in which case they keep insertion order.
+    /// Any crate in the app's dependency graph can enable that feature, so the order of
+    /// a `Value` is not something to rely on. A `HashMap` has no stable order at all.
+    /// Use a struct, or [`body_json_preserving_order`](Self::body_json_preserving_order),
+    /// when the order matters.
+    ///
     /// # Errors
     ///
     /// This method will return an error if the provided data could not be serialized to JSON.
@@ -184,6 +267,50 @@ where
         Ok(self.body(Body::from_json(json)?))
     }
 
+    /// Pass a JSON object as the request body, with its fields written in exactly the
+    /// order given, whatever features serde_json is built with. This is for APIs which
+    /// sign the body, when the fields aren't known up front and can't be a struct.
+    ///
+    /// # Mime
+    ///
+    /// The encoding is set to `application/json`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if a value could not be serialized to JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// use serde_json::json;
+    ///
+    /// // sent as {"title":"New Post","body":"Hello!","tags":["news"]}
+    /// caps.http
+    ///     .post("https://httpbin.org/post")
+    ///     .body_json_preserving_order([
+    ///         ("title", json!("New Post")),
+    ///         ("body", json!("Hello!")),
+    ///         ("tags", json!(["news"])),
+    ///     ])
+    ///     .expect("could not serialize body")
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn body_json_preserving_order<K, V>(
+        self,
+        fields: impl IntoIterator<Item = (K, V)>,
+    ) -> crate::Result<Self>
+    where
+        K: AsRef<str>,
+        V: Serialize,
+    {
+        let fields: Vec<(K, V)> = fields.into_iter().collect();
+        self.body_json(&InOrder(&fields))
+    }
+
     /// Pass a string as the request body.
     ///
     /// # Mime
@@ -228,7 +355,16 @@ where
         self.body(Body::from(bytes.as_ref()))
     }
 
-    /// Set the URL querystring.
+    /// Append query parameters to the URL, serialized from `query` using
+    /// `application/x-www-form-urlencoded` encoding.
+    ///
+    /// Parameters already present on the URL are preserved, and the new ones are
+    /// added after them. Fields with a value of `None` are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HttpError::Url`] if `query` can't be serialized into query parameters,
+    /// for example because it contains nested structures.
     ///
     /// # Examples
     ///
@@ -238,24 +374,257 @@ where
     /// # struct Capabilities { http: crux_http::Http<Event> }
     /// # fn update(caps: &Capabilities) {
     /// #[derive(Serialize, Deserialize)]
-    /// struct Index {
-    ///     page: u32
+    /// struct Search {
+    ///     q: String,
+    ///     page: Option<u32>,
+    ///     sort: Option<String>,
     /// }
     ///
-    /// let query = Index { page: 2 };
+    /// let query = Search { q: "crux".to_string(), page: Some(2), sort: None };
     /// caps.http
-    ///     .post("https://httpbin.org/post")
+    ///     .get("https://httpbin.org/get?lang=en")
     ///     .query(&query)
     ///     .expect("could not serialize query string")
+    ///     // requests https://httpbin.org/get?lang=en&q=crux&page=2
     ///     .send(Event::ReceiveResponse)
     /// # }
     /// ```
     pub fn query(mut self, query: &impl Serialize) -> std::result::Result<Self, HttpError> {
-        self.req.as_mut().unwrap().set_query(query)?;
+        let encoded =
+            serde_urlencoded::to_string(query).map_err(|e| HttpError::Url(e.to_string()))?;
+        if encoded.is_empty() {
+            return Ok(self);
+        }
+
+        let url = self.url_mut();
+        let query = match url.query() {
+            Some(existing) if !existing.is_empty() => format!("{existing}&{encoded}"),
+            _ => encoded,
+        };
+        url.set_query(Some(&query));
 
         Ok(self)
     }
 
+    /// Append a single query parameter to the URL, keeping any which are already there.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/get")
+    ///     .query_pair("q", "crux")
+    ///     .query_pair("page", "2")
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn query_pair(mut self, key: &str, value: &str) -> Self {
+        self.url_mut().query_pairs_mut().append_pair(key, value);
+        self
+    }
+
+    /// Append `segment` to the path of the URL, percent-encoding it, so that any string,
+    /// e.g. one the user typed, becomes exactly one segment. A `/` in `segment` is encoded
+    /// as `%2F` rather than starting a new segment.
+    ///
+    /// A trailing slash on the URL is replaced by the segment, so
+    /// `https://example.com/users/` and `https://example.com/users` both become
+    /// `https://example.com/users/{segment}`. An empty `segment` adds a trailing slash,
+    /// which is in turn replaced by the next segment appended. URLs which can't have a
+    /// path, like `mailto:` URLs, are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://example.com/users")
+    ///     .path_segment("ann/bob")
+    ///     .path_segment("posts")
+    ///     // requests https://example.com/users/ann%2Fbob/posts
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn path_segment(self, segment: &str) -> Self {
+        self.path_segments([segment])
+    }
+
+    /// Append each of `segments` to the path of the URL, percent-encoding each one, as
+    /// for [`path_segment`](RequestBuilder::path_segment). Empty segments are kept, so
+    /// `["a", "", "b"]` appends `a//b`.
+    pub fn path_segments<I>(mut self, segments: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        if let Ok(mut path) = self.url_mut().path_segments_mut() {
+            path.pop_if_empty().extend(segments);
+        }
+        self
+    }
+
+    fn url_mut(&mut self) -> &mut Url {
+        let req: &mut crate::http::Request = self.req.as_mut().unwrap().as_mut();
+        req.url_mut()
+    }
+
+    /// Only fetch the resource if it no longer matches the given entity tag, by setting
+    /// the `If-None-Match` header. Pass the value of the `ETag` header from an earlier
+    /// response, see [`Response::etag`].
+    ///
+    /// If the resource hasn't changed, the server responds with `304 Not Modified`, which
+    /// can be detected with [`Response::is_not_modified`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/etag/abc")
+    ///     .if_none_match("\"abc\"")
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn if_none_match(mut self, etag: impl Into<String>) -> Self {
+        self.req
+            .as_mut()
+            .unwrap()
+            .insert_header(headers::IF_NONE_MATCH, etag.into());
+        self
+    }
+
+    /// Only fetch the resource if it has been modified after the given time, by setting
+    /// the `If-Modified-Since` header.
+    ///
+    /// If the resource hasn't changed, the server responds with `304 Not Modified`, which
+    /// can be detected with [`Response::is_not_modified`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::time::{Duration, SystemTime};
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// let last_fetched = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    /// caps.http
+    ///     .get("https://httpbin.org/cache")
+    ///     .if_modified_since(last_fetched)
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn if_modified_since(mut self, time: SystemTime) -> Self {
+        IfModifiedSince::new(time).apply(self.req.as_mut().unwrap());
+        self
+    }
+
+    /// Set a timeout for the request. The shell is expected to give up waiting
+    /// for a response after this long, in which case the request resolves
+    /// to [`HttpError::Timeout`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/delay/10")
+    ///     .timeout(Duration::from_secs(5))
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.req.as_mut().unwrap().set_timeout(Some(timeout));
+        self
+    }
+
+    /// Limit the size of the response body to `max_response_bytes`, to protect the app
+    /// from running out of memory on a huge response. By default there is no limit.
+    ///
+    /// The shell is expected to stop reading a larger body, counting all of it if it
+    /// arrives in chunks, and the request resolves to [`HttpError::BodyTooLarge`]. The
+    /// core checks the body it receives against the limit as well, and, with the
+    /// `decompression` feature, applies the limit to the decompressed body too.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/bytes/1024")
+    ///     .max_response_bytes(512)
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn max_response_bytes(mut self, max_response_bytes: u64) -> Self {
+        self.req
+            .as_mut()
+            .unwrap()
+            .set_max_response_bytes(Some(max_response_bytes));
+        self
+    }
+
+    /// Compress the request body with `encoding` and set the `Content-Encoding` header
+    /// before the request is sent, if the body is at least
+    /// [`DEFAULT_COMPRESSION_THRESHOLD`](crate::DEFAULT_COMPRESSION_THRESHOLD) bytes.
+    /// Smaller bodies are sent as they are. Use
+    /// [`compress_above`](RequestBuilder::compress_above) to choose the threshold.
+    ///
+    /// The body is compressed whenever it was set, before or after this call. A request
+    /// which already has a `Content-Encoding` header is sent as it is, as its body is
+    /// taken to be encoded already. Check that the server accepts compressed requests
+    /// first, many don't.
+    ///
+    /// Requires the `decompression` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities, report: String) {
+    /// use crux_http::Encoding;
+    ///
+    /// caps.http
+    ///     .post("https://example.com/reports")
+    ///     .body_string(report)
+    ///     .compress(Encoding::Gzip)
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    #[cfg(feature = "decompression")]
+    pub fn compress(self, encoding: crate::Encoding) -> Self {
+        self.compress_above(encoding, crate::DEFAULT_COMPRESSION_THRESHOLD)
+    }
+
+    /// Compress the request body with `encoding`, like
+    /// [`compress`](RequestBuilder::compress) does, if the body is at least `min_bytes`
+    /// bytes.
+    ///
+    /// Requires the `decompression` feature.
+    #[cfg(feature = "decompression")]
+    pub fn compress_above(mut self, encoding: crate::Encoding, min_bytes: u64) -> Self {
+        self.req
+            .as_mut()
+            .unwrap()
+            .set_compression(crate::compress::Compression {
+                encoding,
+                min_bytes,
+            });
+        self
+    }
+
     /// Push middleware onto a per-request middleware stack.
     ///
     /// **Important**: Setting per-request middleware incurs extra allocations.
@@ -285,6 +654,245 @@ where
         self
     }
 
+    /// Retry the request according to the given [`RetryPolicy`](crate::middleware::RetryPolicy).
+    ///
+    /// This is a shorthand for adding the [`Retry`](crate::middleware::Retry) middleware.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// use crux_http::middleware::RetryPolicy;
+    ///
+    /// caps.http
+    ///     .get("https://httpbin.org/status/503")
+    ///     .retry(RetryPolicy::new(5))
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn retry(self, policy: RetryPolicy) -> Self {
+        self.middleware(Retry::new(policy))
+    }
+
+    /// Follow up to `max` redirects, sending a new request to the location given by the
+    /// server each time it responds with 301, 302, 303, 307 or 308. By default redirect
+    /// responses are handed back to the app.
+    ///
+    /// This is a shorthand for adding the [`FollowRedirects`](crate::middleware::FollowRedirects)
+    /// middleware. If the server is still redirecting after `max` hops, the request fails
+    /// with [`HttpError::TooManyRedirects`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>) }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/redirect/2")
+    ///     .follow_redirects(5)
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn follow_redirects(self, max: u8) -> Self {
+        self.middleware(FollowRedirects::new(max))
+    }
+
+    /// Ask the shell to report progress of uploading the request body. Each report
+    /// is wrapped in an event using `make_event` and dispatched to the app's `update`
+    /// function, before the final response is delivered as usual.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called on a request builder created by middleware, which can't
+    /// send events to the app.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # enum Event {
+    /// #     Progress(crux_http::UploadProgress),
+    /// #     ReceiveResponse(crux_http::Result<crux_http::Response<Vec<u8>>>),
+    /// # }
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .put("https://httpbin.org/put")
+    ///     .body_bytes(vec![0; 10_000_000])
+    ///     .on_progress(Event::Progress)
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn on_progress<F>(mut self, make_event: F) -> Self
+    where
+        F: Fn(UploadProgress) -> Event + Send + Sync + 'static,
+    {
+        let CapOrClient::Capability(capability) = &self.cap_or_client else {
+            panic!("Called RequestBuilder::on_progress in a middleware context");
+        };
+        let context = capability.context.clone();
+
+        self.req
+            .as_mut()
+            .unwrap()
+            .set_progress_callback(Arc::new(move |progress| {
+                context.update_app(make_event(progress));
+            }));
+        self
+    }
+
+    /// Make the request abortable, returning the builder along with an [`AbortHandle`].
+    ///
+    /// Calling [`AbortHandle::abort`] while the request is in flight resolves it with
+    /// [`HttpError::Aborted`] straight away, and sends the shell a
+    /// [cancellation](crate::protocol::HttpRequest::cancellation) carrying the
+    /// [`id`](crate::protocol::HttpRequest::id) of the request, so that it can stop the
+    /// network call. Any response the shell still sends for the request is ignored.
+    ///
+    /// This is useful for search-as-you-type, where each keystroke makes the previous
+    /// request obsolete. Combined with a `crux_time` debounce, which only searches once
+    /// the user has stopped typing for a moment, it gives a search which sends few
+    /// requests and never delivers stale results.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use crux_http::AbortHandle;
+    /// # use crux_time::{Debounce, Duration, Time};
+    /// # enum Event {
+    /// #     QueryChanged(String),
+    /// #     Search(String),
+    /// #     Results(crux_http::Result<crux_http::Response<Vec<u8>>>),
+    /// # }
+    /// # struct Model { debounce: Debounce, search: Option<AbortHandle> }
+    /// # struct Capabilities { http: crux_http::Http<Event>, time: Time<Event> }
+    /// # fn update(event: Event, model: &mut Model, caps: &Capabilities) {
+    /// match event {
+    ///     Event::QueryChanged(query) => {
+    ///         // only search once the user has stopped typing for 300ms
+    ///         let pause = Duration::from_millis(300).expect("valid duration");
+    ///         caps.time
+    ///             .debounce(&model.debounce, pause, Event::Search(query));
+    ///     }
+    ///     Event::Search(query) => {
+    ///         // a newer search makes the one in flight obsolete
+    ///         if let Some(previous) = model.search.take() {
+    ///             previous.abort();
+    ///         }
+    ///
+    ///         let (request, handle) = caps
+    ///             .http
+    ///             .get("https://httpbin.org/get")
+    ///             .query_pair("q", &query)
+    ///             .abortable();
+    ///         request.send(Event::Results);
+    ///         model.search = Some(handle);
+    ///     }
+    ///     Event::Results(Err(crux_http::HttpError::Aborted)) => {
+    ///         // superseded by a newer search
+    ///     }
+    ///     Event::Results(_) => {
+    ///         model.search = None;
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn abortable(mut self) -> (Self, AbortHandle) {
+        let handle = self.req.as_mut().unwrap().abort_handle();
+        (self, handle)
+    }
+
+    /// Call `inspect` with the result of the request, after the body has been decoded and
+    /// before it is dispatched to the app's `update` function, leaving the result as it
+    /// is. Like [`Iterator::inspect`], this is meant for observing, e.g. logging or
+    /// counting responses in development builds. The closure should not change any
+    /// state, the app's state belongs in the model and changes to it in `update`.
+    ///
+    /// The closure sees the result as the app will, so call `inspect` after choosing how
+    /// to decode the body, e.g. with [`expect_json`](RequestBuilder::expect_json), which
+    /// would otherwise discard it. Calling `inspect` more than once calls each closure, in
+    /// order.
+    ///
+    /// This has no effect when used with the [async API](RequestBuilder::send_async).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// enum Event { ReceiveResponse(crux_http::Result<crux_http::Response<String>>) }
+    ///
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/get")
+    ///     .expect_string()
+    ///     .inspect(|result| {
+    ///         if let Ok(response) = result {
+    ///             let length = response.body().map_or(0, String::len);
+    ///             eprintln!("received {length} bytes");
+    ///         }
+    ///     })
+    ///     .send(Event::ReceiveResponse)
+    /// # }
+    /// ```
+    pub fn inspect<F>(self, inspect: F) -> Self
+    where
+        F: Fn(&Result<Response<ExpectBody>>) + Send + 'static,
+    {
+        RequestBuilder {
+            req: self.req,
+            cap_or_client: self.cap_or_client,
+            phantom: PhantomData,
+            expectation: Box::new(ExpectInspect::new(self.expectation, inspect)),
+        }
+    }
+
+    /// Attach a `tag` to the request, which is handed back alongside the response when the
+    /// event is made. This tells apart the responses to several requests made by the same
+    /// code, e.g. one for each row of a list, without capturing the row in a closure.
+    ///
+    /// Call `tag` last, as the [`TaggedRequestBuilder`] it returns can only be sent, with
+    /// a function making the event from both the tag and the response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # struct Model { rows: Vec<Row> }
+    /// # struct Row { id: u64, name: Option<String> }
+    /// enum Event {
+    ///     LoadRows,
+    ///     RowLoaded(u64, crux_http::Result<crux_http::Response<String>>),
+    /// }
+    ///
+    /// # fn update(event: Event, model: &mut Model, caps: &Capabilities) {
+    /// match event {
+    ///     Event::LoadRows => {
+    ///         for row in &model.rows {
+    ///             caps.http
+    ///                 .get(format!("https://example.com/rows/{}", row.id))
+    ///                 .expect_string()
+    ///                 .tag(row.id)
+    ///                 .send(Event::RowLoaded);
+    ///         }
+    ///     }
+    ///     Event::RowLoaded(id, Ok(mut response)) => {
+    ///         if let Some(row) = model.rows.iter_mut().find(|row| row.id == id) {
+    ///             row.name = response.take_body();
+    ///         }
+    ///     }
+    ///     Event::RowLoaded(_, Err(_)) => {}
+    /// }
+    /// # }
+    /// ```
+    pub fn tag<Tag>(self, tag: Tag) -> TaggedRequestBuilder<Event, ExpectBody, Tag>
+    where
+        Tag: Send + 'static,
+    {
+        TaggedRequestBuilder { builder: self, tag }
+    }
+
     /// Return the constructed `Request`.
     pub fn build(self) -> Request {
         self.req.unwrap()
@@ -360,6 +968,242 @@ where
         }
     }
 
+    /// Decode a `T` from a JSON response body when the request succeeds, or an `E` from
+    /// the JSON body of an error response, prior to dispatching either to the app's
+    /// `update` function.
+    ///
+    /// Use this with APIs which describe what went wrong in the body of their 4xx and
+    /// 5xx responses. An error response with a body which decodes as `E` is dispatched
+    /// as [`ApiError::Api`], with its status code. Any other failure, including an error
+    /// response with a body which doesn't decode as `E`, is dispatched as
+    /// [`ApiError::Http`].
+    ///
+    /// This has no effect when used with the [async API](RequestBuilder::send_async).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use serde::Deserialize;
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     name: String,
+    /// }
+    ///
+    /// // e.g. { "message": "No user with id 42" }
+    /// #[derive(Deserialize)]
+    /// struct Failure {
+    ///     message: String,
+    /// }
+    ///
+    /// enum Event {
+    ///     ReceiveUser(Result<crux_http::Response<User>, crux_http::ApiError<Failure>>),
+    /// }
+    ///
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://example.com/users/42")
+    ///     .expect_json_or_error::<User, Failure>()
+    ///     .send(Event::ReceiveUser)
+    /// # }
+    /// ```
+    pub fn expect_json_or_error<T, E>(self) -> ApiRequestBuilder<Event, T, E>
+    where
+        T: DeserializeOwned + 'static,
+        E: DeserializeOwned,
+    {
+        ApiRequestBuilder {
+            builder: self.expect_json(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Decode the JSON response body into a [`serde_json::Value`] prior to dispatching it
+    /// to the app's `update` function, for responses which don't have a fixed shape, or
+    /// aren't worth defining a type for.
+    ///
+    /// This is [`expect_json`](RequestBuilder::expect_json) for a `serde_json::Value`, so
+    /// a body which isn't valid JSON is dispatched as the same error.
+    ///
+    /// This has no effect when used with the [async API](RequestBuilder::send_async).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # struct Model { first_id: Option<u64> }
+    /// enum Event {
+    ///     Fetch,
+    ///     Fetched(crux_http::Result<crux_http::Response<serde_json::Value>>),
+    /// }
+    ///
+    /// # fn update(event: Event, model: &mut Model, caps: &Capabilities) {
+    /// match event {
+    ///     Event::Fetch => caps
+    ///         .http
+    ///         .get("https://example.com/items")
+    ///         .expect_json_value()
+    ///         .send(Event::Fetched),
+    ///     // e.g. { "items": [{ "id": 7 }] }
+    ///     Event::Fetched(Ok(response)) => {
+    ///         model.first_id = response.body().unwrap()["items"][0]["id"].as_u64();
+    ///     }
+    ///     Event::Fetched(Err(_)) => {}
+    /// }
+    /// # }
+    /// ```
+    pub fn expect_json_value(self) -> RequestBuilder<Event, serde_json::Value> {
+        self.expect_json()
+    }
+
+    /// Decode the response body according to its `Content-Type` prior to dispatching it
+    /// to the app's `update` function, for clients of APIs which answer in more than one
+    /// format.
+    ///
+    /// A JSON body is decoded as a `T` into [`AutoBody::Json`], and a form encoded body
+    /// into [`AutoBody::Form`]. A `text/*` body is decoded as a string into
+    /// [`AutoBody::Text`], and any other body, or one without a `Content-Type`, is kept
+    /// as bytes in [`AutoBody::Bytes`]. A body which doesn't decode as its `Content-Type`
+    /// says it should is dispatched as an error naming the content type and `T`.
+    ///
+    /// This has no effect when used with the [async API](RequestBuilder::send_async).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use serde::Deserialize;
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// # struct Model { user: Option<User>, notice: Option<String> }
+    /// use crux_http::AutoBody;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     name: String,
+    /// }
+    ///
+    /// enum Event {
+    ///     Fetch,
+    ///     Fetched(crux_http::Result<crux_http::Response<AutoBody<User>>>),
+    /// }
+    ///
+    /// # fn update(event: Event, model: &mut Model, caps: &Capabilities) {
+    /// match event {
+    ///     Event::Fetch => caps
+    ///         .http
+    ///         .get("https://example.com/users/42")
+    ///         .expect_auto::<User>()
+    ///         .send(Event::Fetched),
+    ///     Event::Fetched(Ok(mut response)) => match response.take_body() {
+    ///         Some(AutoBody::Json(user) | AutoBody::Form(user)) => model.user = Some(user),
+    ///         // e.g. a maintenance page from a proxy
+    ///         Some(AutoBody::Text(text)) => model.notice = Some(text),
+    ///         Some(AutoBody::Bytes(_)) | None => {}
+    ///     },
+    ///     Event::Fetched(Err(_)) => {}
+    /// }
+    /// # }
+    /// ```
+    pub fn expect_auto<T>(self) -> RequestBuilder<Event, AutoBody<T>>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let expectation = Box::<ExpectAuto<T>>::default();
+        RequestBuilder {
+            req: self.req,
+            cap_or_client: self.cap_or_client,
+            phantom: PhantomData,
+            expectation,
+        }
+    }
+
+    /// Decode a `T` from the response body, choosing how to decode it by the status code
+    /// of the response, prior to dispatching it to the app's `update` function.
+    ///
+    /// `choose` is called with the status of a successful response and returns the
+    /// [`Decoder`] to use. This suits endpoints which answer with `204 No Content` in
+    /// some cases and a JSON body in others, where
+    /// [`expect_json`](RequestBuilder::expect_json) would fail on the empty body.
+    /// Error responses are dispatched as errors before `choose` is called.
+    ///
+    /// This has no effect when used with the [async API](RequestBuilder::send_async).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use serde::Deserialize;
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// use crux_http::{http::StatusCode, Decoder};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct User {
+    ///     name: String,
+    /// }
+    ///
+    /// enum Event { UserSaved(crux_http::Result<crux_http::Response<User>>) }
+    ///
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .put("https://example.com/users/42")
+    ///     .expect_with::<User, _>(|status| match status {
+    ///         StatusCode::NoContent => Decoder::NoBody,
+    ///         _ => Decoder::Json,
+    ///     })
+    ///     .send(Event::UserSaved)
+    /// # }
+    /// ```
+    pub fn expect_with<T, F>(self, choose: F) -> RequestBuilder<Event, T>
+    where
+        T: DeserializeOwned + 'static,
+        F: Fn(StatusCode) -> Decoder + Send + 'static,
+    {
+        RequestBuilder {
+            req: self.req,
+            cap_or_client: self.cap_or_client,
+            phantom: PhantomData,
+            expectation: Box::new(ExpectWith::new(choose)),
+        }
+    }
+
+    /// Ask the shell to write the response body to a file instead of returning it, and
+    /// dispatch a [`DownloadedFile`] with the path of the file and its size to the app's
+    /// `update` function in place of the body. The body never passes through the core,
+    /// which makes this a good fit for downloading media and other large files.
+    ///
+    /// The shell chooses where to write the file, the core doesn't get a say, so that
+    /// nothing which can influence the core, like a URL from a server, can make the shell
+    /// overwrite files elsewhere. Treat the path as a handle to pass back to the shell,
+    /// e.g. to show or share the file.
+    ///
+    /// If the request has a [`max_response_bytes`](RequestBuilder::max_response_bytes)
+    /// limit, the shell should stop writing once the body goes over it.
+    ///
+    /// This has no effect when used with the [async API](RequestBuilder::send_async).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # struct Capabilities { http: crux_http::Http<Event> }
+    /// enum Event {
+    ///     Downloaded(crux_http::Result<crux_http::Response<crux_http::DownloadedFile>>),
+    /// }
+    ///
+    /// # fn update(caps: &Capabilities) {
+    /// caps.http
+    ///     .get("https://httpbin.org/image/png")
+    ///     .to_file()
+    ///     .send(Event::Downloaded)
+    /// # }
+    /// ```
+    pub fn to_file(mut self) -> RequestBuilder<Event, DownloadedFile> {
+        self.req.as_mut().unwrap().set_to_file(true);
+        RequestBuilder {
+            req: self.req,
+            cap_or_client: self.cap_or_client,
+            phantom: PhantomData,
+            expectation: Box::new(ExpectFile),
+        }
+    }
+
     /// Sends the constructed `Request` and returns its result as an update `Event`
     ///
     /// When finished, the response will wrapped in an event using `make_event` and
@@ -375,20 +1219,14 @@ where
 
         let ctx = capability.context.clone();
         ctx.spawn(async move {
-            let result = capability.client.send(request.unwrap()).await;
-
-            let resp = match result {
-                Ok(resp) => resp,
-                Err(e) => {
-                    capability.context.update_app(make_event(Err(e)));
-                    return;
-                }
+            let resp = match capability.client.send(request.unwrap()).await {
+                Ok(resp) => Response::<Vec<u8>>::new(resp)
+                    .await
+                    .and_then(|r| self.expectation.decode(r)),
+                Err(e) => Err(e),
             };
 
-            let resp = Response::<Vec<u8>>::new(resp)
-                .await
-                .and_then(|r| self.expectation.decode(r));
-
+            self.expectation.inspect(&resp);
             capability.context.update_app(make_event(resp));
         });
     }
@@ -425,12 +1263,88 @@ impl<T, Eb> std::future::IntoFuture for RequestBuilder<T, Eb> {
     }
 }
 
+/// A [`RequestBuilder`] which decodes the body of an error response as well as the
+/// body of a successful one, returned by
+/// [`RequestBuilder::expect_json_or_error`].
+#[must_use]
+pub struct ApiRequestBuilder<Event, T, E> {
+    builder: RequestBuilder<Event, T>,
+    phantom: PhantomData<fn() -> E>,
+}
+
+impl<Event, T, E> ApiRequestBuilder<Event, T, E>
+where
+    Event: 'static,
+    T: 'static,
+    E: DeserializeOwned + 'static,
+{
+    /// Sends the constructed `Request` and dispatches an event with the decoded
+    /// response, or the [`ApiError`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called in a middleware context.
+    pub fn send<F>(self, make_event: F)
+    where
+        F: FnOnce(std::result::Result<Response<T>, ApiError<E>>) -> Event + Send + 'static,
+    {
+        self.builder
+            .send(|result| make_event(result.map_err(ApiError::from)));
+    }
+}
+
+/// A [`RequestBuilder`] carrying a tag, which is handed back with the response,
+/// returned by [`RequestBuilder::tag`].
+#[must_use]
+pub struct TaggedRequestBuilder<Event, ExpectBody, Tag> {
+    builder: RequestBuilder<Event, ExpectBody>,
+    tag: Tag,
+}
+
+impl<Event, ExpectBody, Tag> TaggedRequestBuilder<Event, ExpectBody, Tag>
+where
+    Event: 'static,
+    ExpectBody: 'static,
+    Tag: Send + 'static,
+{
+    /// Sends the constructed `Request` and dispatches an event made by `make_event` from
+    /// the tag and the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called in a middleware context.
+    pub fn send<F>(self, make_event: F)
+    where
+        F: FnOnce(Tag, crate::Result<Response<ExpectBody>>) -> Event + Send + 'static,
+    {
+        let tag = self.tag;
+        self.builder.send(move |result| make_event(tag, result));
+    }
+}
+
 impl<Ev> fmt::Debug for RequestBuilder<Ev> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&self.req, f)
     }
 }
 
+// Serializes as a JSON object with the fields in the order given, rather than in
+// whatever order a map type would put them.
+struct InOrder<'a, K, V>(&'a [(K, V)]);
+
+impl<K, V> Serialize for InOrder<'_, K, V>
+where
+    K: AsRef<str>,
+    V: Serialize,
+{
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key.as_ref(), value)))
+    }
+}
+
 // impl From<RequestBuilder<Ev>> for Request {
 //     /// Converts a `crux_http::RequestBuilder` to a `crux_http::Request`.
 //     fn from(builder: RequestBuilder) -> Request {
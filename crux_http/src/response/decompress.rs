@@ -0,0 +1,195 @@
+//! Decoding of compressed response bodies, based on the `Content-Encoding` header.
+
+use std::io::Read;
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+
+use crate::{protocol::HttpResponse, HttpError};
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    Gzip,
+    Deflate,
+    Identity,
+}
+
+impl Encoding {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            "identity" | "" => Some(Self::Identity),
+            _ => None,
+        }
+    }
+
+    fn decode(self, body: Vec<u8>, limit: Option<u64>) -> crate::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => read_to_end(GzDecoder::new(body.as_slice()), limit),
+            Self::Deflate => {
+                // "deflate" is meant to be zlib wrapped, but some servers send a raw stream
+                match read_to_end(ZlibDecoder::new(body.as_slice()), limit) {
+                    Err(HttpError::Decode(_)) => {
+                        read_to_end(DeflateDecoder::new(body.as_slice()), limit)
+                    }
+                    decoded => decoded,
+                }
+            }
+            Self::Identity => Ok(body),
+        }
+    }
+}
+
+// Reads the whole of `reader`, keeping at most `limit` bytes. Past the limit the rest is
+// only counted, so a small compressed body can't expand into more memory than allowed.
+fn read_to_end(mut reader: impl Read, limit: Option<u64>) -> crate::Result<Vec<u8>> {
+    let decode_error = |e: std::io::Error| HttpError::Decode(e.to_string());
+    let mut decoded = Vec::new();
+
+    let Some(limit) = limit else {
+        reader.read_to_end(&mut decoded).map_err(decode_error)?;
+        return Ok(decoded);
+    };
+
+    (&mut reader)
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut decoded)
+        .map_err(decode_error)?;
+    if decoded.len() as u64 > limit {
+        let rest = std::io::copy(&mut reader, &mut std::io::sink()).map_err(decode_error)?;
+        return Err(HttpError::BodyTooLarge {
+            limit,
+            actual: decoded.len() as u64 + rest,
+        });
+    }
+
+    Ok(decoded)
+}
+
+/// Decode the body of the response if it carries a `Content-Encoding` we recognise,
+/// and strip the header afterwards. Responses with encodings we don't know about
+/// are returned untouched. A decoded body over `limit` bytes is an error.
+pub(crate) fn decompress(
+    mut response: HttpResponse,
+    limit: Option<u64>,
+) -> crate::Result<HttpResponse> {
+    let encodings: Option<Vec<Encoding>> = response
+        .headers
+        .iter()
+        .filter(|header| header.name.eq_ignore_ascii_case("content-encoding"))
+        .flat_map(|header| header.value.split(','))
+        .map(Encoding::parse)
+        .collect();
+
+    let Some(encodings) = encodings else {
+        return Ok(response);
+    };
+    if encodings.is_empty() {
+        return Ok(response);
+    }
+
+    // encodings are listed in the order they were applied
+    let mut body = std::mem::take(&mut response.body);
+    for encoding in encodings.into_iter().rev() {
+        body = encoding.decode(body, limit)?;
+    }
+
+    response.body = body;
+    response
+        .headers
+        .retain(|header| !header.name.eq_ignore_ascii_case("content-encoding"));
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+
+    use super::*;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decompresses_gzip_and_strips_header() {
+        let response = HttpResponse::ok()
+            .header("Content-Encoding", "gzip")
+            .header("content-type", "application/json")
+            .body(gzip(br#"{"hello":"world"}"#))
+            .build();
+
+        let response = decompress(response, None).unwrap();
+
+        assert_eq!(response.body, br#"{"hello":"world"}"#);
+        assert_eq!(
+            response.headers,
+            HttpResponse::ok()
+                .header("content-type", "application/json")
+                .build()
+                .headers
+        );
+    }
+
+    #[test]
+    fn decompresses_deflate() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello").unwrap();
+
+        let response = HttpResponse::ok()
+            .header("content-encoding", "deflate")
+            .body(encoder.finish().unwrap())
+            .build();
+
+        assert_eq!(decompress(response, None).unwrap().body, b"hello");
+    }
+
+    #[test]
+    fn leaves_unknown_encodings_alone() {
+        let response = HttpResponse::ok()
+            .header("content-encoding", "br")
+            .body("not really brotli")
+            .build();
+
+        assert_eq!(decompress(response.clone(), None).unwrap(), response);
+    }
+
+    #[test]
+    fn limits_the_decompressed_size() {
+        let body = vec![b'a'; 10_000];
+        let response = HttpResponse::ok()
+            .header("content-encoding", "gzip")
+            .body(gzip(&body))
+            .build();
+
+        assert!(matches!(
+            decompress(response.clone(), Some(1_000)),
+            Err(HttpError::BodyTooLarge {
+                limit: 1_000,
+                actual: 10_000
+            })
+        ));
+        assert_eq!(decompress(response, Some(10_000)).unwrap().body, body);
+    }
+
+    #[test]
+    fn reports_corrupt_bodies() {
+        let response = HttpResponse::ok()
+            .header("content-encoding", "gzip")
+            .body("definitely not gzip")
+            .build();
+
+        assert!(matches!(
+            decompress(response, None),
+            Err(HttpError::Decode(_))
+        ));
+    }
+}
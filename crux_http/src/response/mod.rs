@@ -1,4 +1,6 @@
 mod decode;
+#[cfg(feature = "decompression")]
+pub(crate) mod decompress;
 #[allow(clippy::module_inception)]
 mod response;
 mod response_async;
@@ -1,9 +1,10 @@
 use super::{decode::decode_body, new_headers};
 use crate::http::{
     self,
-    headers::{self, HeaderName, HeaderValues, ToHeaderValues},
-    Mime, StatusCode, Version,
+    headers::{self, HeaderName, HeaderValue, HeaderValues, ToHeaderValues},
+    Mime, StatusCode, Url, Version,
 };
+use crate::DownloadedFile;
 
 use http::{headers::CONTENT_TYPE, Headers};
 use serde::de::DeserializeOwned;
@@ -19,6 +20,11 @@ pub struct Response<Body> {
     #[serde(with = "header_serde")]
     headers: Headers,
     body: Option<Body>,
+    #[serde(default)]
+    url: Option<Url>,
+    // where the shell wrote the body, until the expectation for a file download takes it
+    #[serde(skip)]
+    file: Option<DownloadedFile>,
 }
 
 impl<Body> Response<Body> {
@@ -43,9 +49,38 @@ impl<Body> Response<Body> {
             headers,
             version: res.version(),
             body: Some(body),
+            url: res.url().cloned(),
+            file: res.ext::<DownloadedFile>().cloned(),
         })
     }
 
+    /// Get the URL the response came from. When the shell follows redirects, this is
+    /// the URL it was finally redirected to, if it reported it, which is useful for
+    /// resolving relative links in the body. Otherwise it is the URL of the request.
+    ///
+    /// Responses built with [`ResponseBuilder`](crate::testing::ResponseBuilder) only
+    /// have a URL if one is given with
+    /// [`ResponseBuilder::url`](crate::testing::ResponseBuilder::url). The URL is not
+    /// compared when testing responses for equality, so expected responses in tests
+    /// don't need one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let res = crux_http::testing::ResponseBuilder::ok()
+    /// #   .url("https://example.com/articles/1")
+    /// #   .build();
+    /// let link = res.url().unwrap().join("2").unwrap();
+    /// assert_eq!(link.as_str(), "https://example.com/articles/2");
+    /// ```
+    pub fn url(&self) -> Option<&Url> {
+        self.url.as_ref()
+    }
+
+    pub(crate) fn set_url(&mut self, url: Url) {
+        self.url = Some(url);
+    }
+
     /// Get the HTTP status code.
     ///
     /// # Examples
@@ -58,6 +93,35 @@ impl<Body> Response<Body> {
         self.status
     }
 
+    /// Whether the server responded with `304 Not Modified` to a conditional request,
+    /// in which case the response has no body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use crux_http::http::StatusCode;
+    /// # let res = crux_http::testing::ResponseBuilder::with_status(StatusCode::NotModified).build();
+    /// assert!(res.is_not_modified());
+    /// ```
+    pub fn is_not_modified(&self) -> bool {
+        self.status == StatusCode::NotModified
+    }
+
+    /// Get the value of the `ETag` header, for use with
+    /// [`RequestBuilder::if_none_match`](crate::RequestBuilder::if_none_match).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let res = crux_http::testing::ResponseBuilder::ok()
+    /// #   .header("ETag", "\"abc\"")
+    /// #   .build();
+    /// assert_eq!(res.etag(), Some("\"abc\""));
+    /// ```
+    pub fn etag(&self) -> Option<&str> {
+        Some(self.header(headers::ETAG)?.last().as_str())
+    }
+
     /// Get the HTTP protocol version.
     ///
     /// # Examples
@@ -118,6 +182,30 @@ impl<Body> Response<Body> {
         self.headers.iter_mut()
     }
 
+    /// An iterator visiting every header value in arbitrary order, paired with its name.
+    /// Headers with several values appear once per value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let res = crux_http::testing::ResponseBuilder::ok()
+    /// #   .header("X-Request-Id", "abc123")
+    /// #   .append_header("Set-Cookie", "a=1")
+    /// #   .append_header("Set-Cookie", "b=2")
+    /// #   .build();
+    /// let mut headers: Vec<_> = res
+    ///     .header_iter()
+    ///     .map(|(name, value)| format!("{name}: {value}"))
+    ///     .collect();
+    /// headers.sort();
+    /// assert_eq!(headers, ["set-cookie: a=1", "set-cookie: b=2", "x-request-id: abc123"]);
+    /// ```
+    pub fn header_iter(&self) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
+        self.headers
+            .iter()
+            .flat_map(|(name, values)| values.iter().map(move |value| (name, value)))
+    }
+
     /// An iterator visiting all header names in arbitrary order.
     #[must_use]
     pub fn header_names(&self) -> headers::Names<'_> {
@@ -161,12 +249,29 @@ impl<Body> Response<Body> {
         self.body.take()
     }
 
+    pub(crate) fn take_file(&mut self) -> Option<DownloadedFile> {
+        self.file.take()
+    }
+
+    pub(crate) fn without_body<NewBody>(self) -> Response<NewBody> {
+        Response {
+            body: None,
+            headers: self.headers,
+            status: self.status,
+            version: self.version,
+            url: self.url,
+            file: self.file,
+        }
+    }
+
     pub fn with_body<NewBody>(self, body: NewBody) -> Response<NewBody> {
         Response {
             body: Some(body),
             headers: self.headers,
             status: self.status,
             version: self.version,
+            url: self.url,
+            file: self.file,
         }
     }
 }
@@ -180,6 +285,8 @@ impl Response<Vec<u8>> {
             headers,
             version: None,
             body: None,
+            url: None,
+            file: None,
         }
     }
 
@@ -308,6 +415,7 @@ impl<Body> fmt::Debug for Response<Body> {
             .field("version", &self.version)
             .field("status", &self.status)
             .field("headers", &self.headers)
+            .field("url", &self.url)
             .finish_non_exhaustive()
     }
 }
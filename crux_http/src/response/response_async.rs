@@ -1,7 +1,7 @@
 use crate::http::{
     self,
     headers::{self, HeaderName, HeaderValues, ToHeaderValues},
-    Body, Mime, StatusCode, Version,
+    Body, Mime, StatusCode, Url, Version,
 };
 
 use futures_util::io::AsyncRead;
@@ -15,6 +15,10 @@ use std::task::{Context, Poll};
 
 use super::decode::decode_body;
 
+// kept in the extensions of the response, so it survives conversions to and from
+// `http::Response` in middleware
+struct ResponseUrl(Url);
+
 pin_project_lite::pin_project! {
     /// An HTTP response that exposes async methods. This is to support async
     /// use and middleware.
@@ -30,6 +34,19 @@ impl ResponseAsync {
         Self { res }
     }
 
+    /// Get the URL the response came from, after any redirects. The shell can provide it,
+    /// otherwise it is the URL of the request sent to the shell.
+    pub fn url(&self) -> Option<&Url> {
+        self.res
+            .ext()
+            .get::<ResponseUrl>()
+            .map(|ResponseUrl(url)| url)
+    }
+
+    pub(crate) fn set_url(&mut self, url: Url) {
+        self.res.ext_mut().insert(ResponseUrl(url));
+    }
+
     /// Get the HTTP status code.
     ///
     /// # Examples
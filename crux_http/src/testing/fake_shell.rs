@@ -47,4 +47,9 @@ impl EffectSender for FakeShell {
                 .expect("test tried to send an unexpected HttpRequest"),
         )
     }
+
+    async fn notify(&self, effect: HttpRequest) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.requests_received.push(effect);
+    }
 }
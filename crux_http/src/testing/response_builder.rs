@@ -38,6 +38,26 @@ impl<Body> ResponseBuilder<Body> {
         self
     }
 
+    /// Appends a header to the response, keeping any values it already has.
+    pub fn append_header(mut self, key: impl Into<HeaderName>, value: impl ToHeaderValues) -> Self {
+        self.response.append_header(key, value);
+        self
+    }
+
+    /// Sets the URL the response came from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `url` is not a valid absolute URL.
+    pub fn url(mut self, url: impl AsRef<str>) -> Self {
+        let url = url
+            .as_ref()
+            .parse()
+            .expect("the response URL should be valid");
+        self.response.set_url(url);
+        self
+    }
+
     /// Builds the response
     pub fn build(self) -> Response<Body> {
         self.response
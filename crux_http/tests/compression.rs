@@ -0,0 +1,107 @@
+#![cfg(feature = "decompression")]
+
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_http::{Encoding, Http};
+
+    #[derive(Default)]
+    pub struct App;
+
+    pub enum Event {
+        Upload(String),
+        Uploaded(crux_http::Result<crux_http::Response<String>>),
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub echo: String,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = String;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Upload(report) => {
+                    caps.http
+                        .post("https://example.com/echo")
+                        .body_string(report)
+                        .compress_above(Encoding::Gzip, 100)
+                        .expect_string()
+                        .send(Event::Uploaded);
+                }
+                Event::Uploaded(Ok(mut response)) => {
+                    model.echo = response.take_body().unwrap();
+                }
+                Event::Uploaded(Err(_)) => {}
+            }
+        }
+
+        fn view(&self, model: &Model) -> String {
+            model.echo.clone()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub http: Http<Event>,
+    }
+}
+
+mod tests {
+    use crux_core::testing::AppTester;
+    use crux_http::protocol::{HttpHeader, HttpResponse, HttpResult};
+
+    use crate::shared::{App, Event, Model};
+
+    fn content_encoding(headers: &[HttpHeader]) -> Option<&str> {
+        headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case("content-encoding"))
+            .map(|header| header.value.as_str())
+    }
+
+    #[test]
+    fn compresses_large_request_bodies() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let report = "all systems nominal\n".repeat(50);
+
+        let request = &mut app
+            .update(Event::Upload(report.clone()), &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(content_encoding(&request.operation.headers), Some("gzip"));
+        assert!(request.operation.body.len() < report.len());
+
+        // the server echoes the compressed body back, which the core decompresses
+        let response = HttpResponse::ok()
+            .header("content-encoding", "gzip")
+            .body(request.operation.body.clone())
+            .build();
+        let update = app.resolve(request, HttpResult::Ok(response)).unwrap();
+        for event in update.events {
+            app.update(event, &mut model).assert_empty();
+        }
+
+        assert_eq!(app.view(&model), report);
+    }
+
+    #[test]
+    fn sends_small_request_bodies_uncompressed() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::Upload("ok".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(content_encoding(&request.operation.headers), None);
+        assert_eq!(request.operation.body, b"ok");
+    }
+}
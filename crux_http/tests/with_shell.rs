@@ -68,7 +68,7 @@ mod shared {
                     "Status: {}, Body: {}, Json Body: {}",
                     model.status,
                     String::from_utf8_lossy(&model.body),
-                    &model.json_body
+                    model.json_body
                 ),
             }
         }
@@ -117,7 +117,8 @@ mod shell {
 
                         enqueue_effects(
                             &mut queue,
-                            core.resolve(&mut request, HttpResult::Ok(response)),
+                            core.resolve(&mut request, HttpResult::Ok(response))
+                                .expect("should resolve"),
                         );
                     }
                 },
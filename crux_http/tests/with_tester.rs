@@ -1,11 +1,20 @@
 mod shared {
 
-    use std::{cmp::max, future::IntoFuture};
+    use std::{
+        cmp::max,
+        future::IntoFuture,
+        time::{Duration, UNIX_EPOCH},
+    };
 
     use crux_core::compose::Compose;
     use crux_core::macros::Effect;
-    use crux_http::Http;
-    use futures_util::join;
+    use crux_http::{
+        client::Client,
+        middleware::{Logging, Next, RetryPolicy},
+        AbortHandle, ApiError, AutoBody, Config, Decoder, DownloadedFile, Http, Request,
+        ResponseAsync,
+    };
+    use futures_util::{future::BoxFuture, join};
     use http_types::StatusCode;
     use serde::{Deserialize, Serialize};
 
@@ -15,6 +24,32 @@ mod shared {
     #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
     pub enum Event {
         Get,
+        GetWithTimeout,
+        GetWithLimit,
+        Download,
+        GetWithRetry,
+        UploadWithRetry,
+        UploadFollowingRedirects,
+        GetWithLogging,
+        GetWithInspect,
+        GetPath(Vec<String>),
+        GetUser,
+        SaveUser,
+        GetItems,
+        GetRows(Vec<u64>),
+        GetAuto,
+        GetFollowingRedirects,
+        GetFollowingRedirectsWithCredentials,
+        ApiGet,
+        ApiGetWithToken,
+        Upload,
+        Search,
+        Poll,
+        StartSearch,
+        PostJson,
+        PostJsonFields,
+        CancelSearch,
+        Progress(crux_http::UploadProgress),
         Post,
         GetPostChain,
         ConcurrentGets,
@@ -22,12 +57,32 @@ mod shared {
 
         // events local to the core
         Set(crux_http::Result<crux_http::Response<String>>),
+        Polled(crux_http::Result<crux_http::Response<Vec<String>>>),
+        Downloaded(crux_http::Result<crux_http::Response<DownloadedFile>>),
+        GotUser(Result<crux_http::Response<User>, ApiError<Failure>>),
+        SavedUser(crux_http::Result<crux_http::Response<User>>),
+        GotItems(crux_http::Result<crux_http::Response<serde_json::Value>>),
+        GotRow(u64, crux_http::Result<crux_http::Response<String>>),
+        GotAuto(crux_http::Result<crux_http::Response<AutoBody<User>>>),
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+    pub struct User {
+        pub name: String,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+    pub struct Failure {
+        pub message: String,
     }
 
     #[derive(Default, Serialize, Deserialize)]
     pub struct Model {
         pub body: String,
         pub values: Vec<String>,
+        pub file: Option<DownloadedFile>,
+        #[serde(skip)]
+        pub search: Option<AbortHandle>,
     }
 
     #[derive(Serialize, Deserialize, Default)]
@@ -51,6 +106,252 @@ mod shared {
                         .expect_string()
                         .send(Event::Set);
                 }
+                Event::GetWithTimeout => {
+                    caps.http
+                        .get("http://example.com")
+                        .timeout(Duration::from_secs(5))
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::GetWithLimit => {
+                    caps.http
+                        .get("http://example.com")
+                        .max_response_bytes(8)
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::Download => {
+                    caps.http
+                        .get("http://example.com/video.mp4")
+                        .to_file()
+                        .send(Event::Downloaded);
+                }
+                Event::Downloaded(Ok(mut response)) => {
+                    model.file = response.take_body();
+                }
+                Event::Downloaded(Err(_)) => {}
+                Event::GetWithRetry => {
+                    caps.http
+                        .get("http://example.com")
+                        .retry(RetryPolicy::new(1).jitter(false))
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::UploadWithRetry => {
+                    // a body of unknown length
+                    let report = futures_util::io::Cursor::new(b"all systems nominal".to_vec());
+                    caps.http
+                        .post("http://example.com/reports")
+                        .body(crux_http::http::Body::from_reader(report, None))
+                        .retry(RetryPolicy::new(1).jitter(false))
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::UploadFollowingRedirects => {
+                    // a body of unknown length
+                    let report = futures_util::io::Cursor::new(b"all systems nominal".to_vec());
+                    caps.http
+                        .post("http://example.com/reports")
+                        .body(crux_http::http::Body::from_reader(report, None))
+                        .follow_redirects(1)
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::GetWithLogging => {
+                    caps.http
+                        .get("http://example.com")
+                        .middleware(Logging::new(|line| {
+                            LOG.with(|log| log.borrow_mut().push(line.to_string()));
+                        }))
+                        .middleware(authorize)
+                        .retry(RetryPolicy::new(1).jitter(false))
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::GetWithInspect => {
+                    caps.http
+                        .get("http://example.com")
+                        .expect_string()
+                        .inspect(|result| {
+                            let line = match result {
+                                Ok(response) => {
+                                    format!("{} bytes", response.body().map_or(0, String::len))
+                                }
+                                Err(error) => format!("failed: {error}"),
+                            };
+                            LOG.with(|log| log.borrow_mut().push(line));
+                        })
+                        .send(Event::Set);
+                }
+                Event::GetPath(segments) => {
+                    caps.http
+                        .get("http://example.com/users/")
+                        .path_segments(segments)
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::GetUser => {
+                    caps.http
+                        .get("http://example.com/users/42")
+                        .expect_json_or_error::<User, Failure>()
+                        .send(Event::GotUser);
+                }
+                Event::GotUser(_) => {}
+                Event::SaveUser => {
+                    caps.http
+                        .put("http://example.com/users/42")
+                        .body_json(&User {
+                            name: "Ann".to_string(),
+                        })
+                        .unwrap()
+                        .expect_with::<User, _>(|status| match status {
+                            StatusCode::NoContent => Decoder::NoBody,
+                            _ => Decoder::Json,
+                        })
+                        .send(Event::SavedUser);
+                }
+                Event::SavedUser(_) => {}
+                Event::GetItems => {
+                    caps.http
+                        .get("http://example.com/items")
+                        .expect_json_value()
+                        .send(Event::GotItems);
+                }
+                Event::GotItems(_) => {}
+                Event::GetRows(ids) => {
+                    for id in ids {
+                        caps.http
+                            .get(format!("http://example.com/rows/{id}"))
+                            .expect_string()
+                            .tag(id)
+                            .send(Event::GotRow);
+                    }
+                }
+                Event::GotRow(..) => {}
+                Event::GetAuto => {
+                    caps.http
+                        .get("http://example.com/users/42")
+                        .expect_auto::<User>()
+                        .send(Event::GotAuto);
+                }
+                Event::GotAuto(_) => {}
+                Event::ApiGet => {
+                    api(&caps.http)
+                        .get("/users/42")
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::ApiGetWithToken => {
+                    api(&caps.http)
+                        .get("users")
+                        .header("Authorization", "Bearer other-token")
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::GetFollowingRedirects => {
+                    caps.http
+                        .get("http://example.com/start")
+                        .follow_redirects(1)
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::GetFollowingRedirectsWithCredentials => {
+                    caps.http
+                        .get("https://example.com/start")
+                        .bearer_auth("secret-token")
+                        .header("Proxy-Authorization", "Basic cHJveHk6c2VjcmV0")
+                        .header("Cookie", "session=secret")
+                        .header("Accept", "text/plain")
+                        .follow_redirects(2)
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::Upload => {
+                    caps.http
+                        .put("http://example.com")
+                        .body_bytes("The Body".as_bytes())
+                        .on_progress(Event::Progress)
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::Progress(progress) => {
+                    model
+                        .values
+                        .push(format!("{}/{}", progress.bytes_sent, progress.total));
+                }
+                Event::Search => {
+                    #[derive(Serialize)]
+                    struct Search {
+                        q: &'static str,
+                        page: Option<u32>,
+                        sort: Option<&'static str>,
+                    }
+
+                    caps.http
+                        .get("http://example.com/search?lang=en")
+                        .query(&Search {
+                            q: "crux http",
+                            page: Some(2),
+                            sort: None,
+                        })
+                        .expect("query should serialize")
+                        .query_pair("tag", "a&b")
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::Poll => {
+                    caps.http
+                        .get("http://example.com/items")
+                        .if_none_match("\"v1\"")
+                        .if_modified_since(UNIX_EPOCH + Duration::from_secs(784_111_777))
+                        .expect_json()
+                        .send(Event::Polled);
+                }
+                Event::Polled(Ok(mut response)) => {
+                    if let Some(items) = response.take_body() {
+                        model.values = items;
+                    }
+                }
+                Event::Polled(Err(_)) => {}
+                Event::StartSearch => {
+                    let (request, handle) = caps
+                        .http
+                        .get("http://example.com/search")
+                        .expect_string()
+                        .abortable();
+                    request.send(Event::Set);
+                    model.search = Some(handle);
+                }
+                Event::CancelSearch => {
+                    if let Some(search) = model.search.take() {
+                        search.abort();
+                    }
+                }
+                Event::PostJson => {
+                    #[derive(Serialize)]
+                    struct Post {
+                        title: &'static str,
+                        body: &'static str,
+                    }
+
+                    caps.http
+                        .post("http://example.com")
+                        .body_json(&Post {
+                            title: "New Post",
+                            body: "Hello!",
+                        })
+                        .expect("body should serialize")
+                        .expect_string()
+                        .send(Event::Set);
+                }
+                Event::PostJsonFields => {
+                    caps.http
+                        .post("http://example.com")
+                        .body_json_preserving_order([("title", "New Post"), ("body", "Hello!")])
+                        .expect("body should serialize")
+                        .expect_string()
+                        .send(Event::Set);
+                }
                 Event::Post => {
                     caps.http
                         .post("http://example.com")
@@ -123,6 +424,30 @@ mod shared {
         }
     }
 
+    fn api(http: &Http<Event>) -> Http<Event> {
+        http.with_config(
+            Config::new()
+                .set_base_url("https://api.example.com/v1/".parse().unwrap())
+                .add_header("Authorization", "Bearer secret-token")
+                .unwrap(),
+        )
+    }
+
+    thread_local! {
+        pub(crate) static LOG: std::cell::RefCell<Vec<String>> = const {
+            std::cell::RefCell::new(Vec::new())
+        };
+    }
+
+    fn authorize<'a>(
+        mut req: Request,
+        client: Client,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, crux_http::Result<ResponseAsync>> {
+        req.insert_header("Authorization", "Bearer secret-token");
+        next.run(req, client)
+    }
+
     #[derive(Effect)]
     pub(crate) struct Capabilities {
         pub http: Http<Event>,
@@ -134,9 +459,12 @@ mod shared {
 mod tests {
     use assert_matches::assert_matches;
 
-    use crate::shared::{App, Effect, Event, Model};
+    use crate::shared::{App, Effect, Event, Failure, Model, User, LOG};
+    use std::time::Duration;
+
     use crux_core::testing::AppTester;
     use crux_http::protocol::{HttpRequest, HttpResponse, HttpResult};
+    use crux_http::{ApiError, AutoBody, DownloadedFile, HttpError};
 
     #[test]
     fn get() {
@@ -181,6 +509,93 @@ mod tests {
         assert_eq!(model.values, vec!["my_value1", "my_value2"]);
     }
 
+    #[test]
+    fn configured_get() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::ApiGet, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("https://api.example.com/v1/users/42")
+                .header("authorization", "Bearer secret-token")
+                .build()
+        );
+    }
+
+    #[test]
+    fn configured_headers_can_be_replaced() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::ApiGetWithToken, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("https://api.example.com/v1/users")
+                .header("authorization", "Bearer other-token")
+                .build()
+        );
+    }
+
+    #[test]
+    fn response_url_defaults_to_request_url() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Get, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(request, HttpResult::Ok(HttpResponse::ok().build()))
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(response)) => {
+            assert_eq!(response.url().unwrap().as_str(), "http://example.com/");
+        });
+    }
+
+    #[test]
+    fn response_url_reported_by_shell() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Get, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        // the shell followed a redirect itself
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::ok()
+                        .url("https://www.example.com/home")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(response)) => {
+            assert_eq!(
+                response.url().unwrap().as_str(),
+                "https://www.example.com/home"
+            );
+        });
+    }
+
     #[test]
     fn post() {
         let app = AppTester::<App, _>::default();
@@ -332,4 +747,1113 @@ mod tests {
 
         assert_eq!(error, "Socket shenanigans prevented the request")
     }
+
+    #[test]
+    fn get_with_timeout() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithTimeout, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("http://example.com/")
+                .timeout(Duration::from_secs(5))
+                .build()
+        );
+        assert_eq!(request.operation.timeout, Some(Duration::from_secs(5)));
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Err(crux_http::HttpError::Timeout {
+                    elapsed: Duration::from_secs(5),
+                }),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Err(crux_http::HttpError::Timeout { elapsed })) => {
+            assert_eq!(elapsed, Duration::from_secs(5));
+        });
+    }
+
+    #[test]
+    fn get_with_limit_rejects_large_body() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithLimit, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(request.operation.max_response_bytes, Some(8));
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("more than eight bytes").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(
+            actual,
+            Event::Set(Err(crux_http::HttpError::BodyTooLarge {
+                limit: 8,
+                actual: 21
+            }))
+        );
+    }
+
+    #[test]
+    fn get_with_limit_accepts_small_body() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithLimit, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("tiny").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(response)) => {
+            assert_eq!(response.body().unwrap(), "tiny");
+        });
+    }
+
+    #[test]
+    fn download_is_written_to_file_by_shell() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Download, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert!(request.operation.to_file);
+
+        let file = DownloadedFile {
+            path: "/cache/downloads/1.mp4".to_string(),
+            bytes_written: 1_048_576,
+        };
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::File {
+                    response: HttpResponse::ok().header("my_header", "my_value").build(),
+                    path: file.path.clone(),
+                    bytes_written: file.bytes_written,
+                },
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual.clone(), Event::Downloaded(Ok(response)) => {
+            assert_eq!(response.body(), Some(&file));
+            assert_eq!(response.header("my_header").unwrap(), "my_value");
+        });
+
+        let _ = app.update(actual, &mut model);
+        assert_eq!(model.file, Some(file));
+    }
+
+    #[test]
+    fn download_returned_in_memory_is_an_error() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Download, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("video").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Downloaded(Err(crux_http::HttpError::Io(_))));
+    }
+
+    #[test]
+    fn file_for_request_not_asking_for_one_is_an_error() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Get, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::File {
+                    response: HttpResponse::ok().build(),
+                    path: "/tmp/body".to_string(),
+                    bytes_written: 4,
+                },
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Err(crux_http::HttpError::Io(_))));
+    }
+
+    #[test]
+    fn get_with_retry_succeeds_after_retry() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithRetry, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("http://example.com/").build()
+        );
+
+        let retried = &mut app
+            .resolve(request, HttpResult::Ok(HttpResponse::status(503).build()))
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            retried.operation,
+            HttpRequest::get("http://example.com/")
+                .delay(Duration::from_millis(100))
+                .build()
+        );
+
+        let actual = app
+            .resolve(
+                retried,
+                HttpResult::Ok(HttpResponse::ok().body("hello").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), "hello");
+        });
+    }
+
+    #[test]
+    fn retries_keep_a_body_of_unknown_length() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::UploadWithRetry, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(request.operation.body, b"all systems nominal");
+
+        let retried = &mut app
+            .resolve(request, HttpResult::Ok(HttpResponse::status(503).build()))
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(retried.operation.body, b"all systems nominal");
+        assert_eq!(retried.operation.delay, Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn get_with_retry_reports_attempts_when_exhausted() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithRetry, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let retried = &mut app
+            .resolve(request, HttpResult::Ok(HttpResponse::status(503).build()))
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(retried, HttpResult::Ok(HttpResponse::status(502).build()))
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(
+            actual,
+            Event::Set(Err(crux_http::HttpError::RetriesExhausted { code, attempts })) => {
+                assert_eq!(code, 502);
+                assert_eq!(attempts, 2);
+            }
+        );
+    }
+
+    #[test]
+    fn logging_observes_request_and_response_around_other_middleware() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let expected = HttpRequest::get("http://example.com/")
+            .header("authorization", "Bearer secret-token")
+            .build();
+
+        let request = &mut app
+            .update(Event::GetWithLogging, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(request.operation, expected);
+        assert_eq!(
+            LOG.with(|log| log.borrow().clone()),
+            vec!["GET http://example.com/"]
+        );
+
+        let retried = &mut app
+            .resolve(request, HttpResult::Ok(HttpResponse::status(503).build()))
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(
+            retried.operation,
+            HttpRequest {
+                delay: Some(Duration::from_millis(100)),
+                ..expected
+            }
+        );
+
+        let actual = app
+            .resolve(
+                retried,
+                HttpResult::Ok(HttpResponse::ok().body("hello").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+        assert_matches!(actual, Event::Set(Ok(_)));
+
+        // the retry happens inside the logging, which only sees the final response
+        assert_eq!(
+            LOG.with(|log| log.take()),
+            vec!["GET http://example.com/", "GET http://example.com/ -> 200"]
+        );
+    }
+
+    fn requested_url(segments: &[&str]) -> String {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let segments = segments.iter().map(ToString::to_string).collect();
+        let request = app
+            .update(Event::GetPath(segments), &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        request.operation.url
+    }
+
+    fn expected_url(segments: &[&str]) -> String {
+        let mut url = url::Url::parse("http://example.com/users/").unwrap();
+        url.path_segments_mut()
+            .unwrap()
+            .pop_if_empty()
+            .extend(segments);
+
+        url.to_string()
+    }
+
+    #[test]
+    fn path_segments_are_appended() {
+        let url = requested_url(&["ann", "posts"]);
+
+        assert_eq!(url, "http://example.com/users/ann/posts");
+        assert_eq!(url, expected_url(&["ann", "posts"]));
+    }
+
+    #[test]
+    fn path_segment_with_slash_stays_one_segment() {
+        let url = requested_url(&["ann/../admin"]);
+
+        assert_eq!(url, "http://example.com/users/ann%2F..%2Fadmin");
+        assert_eq!(url, expected_url(&["ann/../admin"]));
+    }
+
+    #[test]
+    fn path_segment_with_unicode_is_percent_encoded() {
+        let url = requested_url(&["zoë 😀"]);
+
+        assert_eq!(url, "http://example.com/users/zo%C3%AB%20%F0%9F%98%80");
+        assert_eq!(url, expected_url(&["zoë 😀"]));
+    }
+
+    #[test]
+    fn empty_path_segments_are_kept() {
+        assert_eq!(requested_url(&[""]), "http://example.com/users/");
+        assert_eq!(requested_url(&[""]), expected_url(&[""]));
+
+        let url = requested_url(&["a", "", "b"]);
+        assert_eq!(url, "http://example.com/users/a//b");
+        assert_eq!(url, expected_url(&["a", "", "b"]));
+    }
+
+    #[test]
+    fn inspect_sees_the_decoded_response() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithInspect, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("hello").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), "hello");
+        });
+        assert_eq!(LOG.with(|log| log.take()), vec!["5 bytes"]);
+    }
+
+    #[test]
+    fn inspect_sees_errors() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithInspect, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Err(crux_http::HttpError::Io("offline".to_string())),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Err(_)));
+        assert_eq!(
+            LOG.with(|log| log.take()),
+            vec!["failed: IO error: offline"]
+        );
+    }
+
+    #[test]
+    fn expect_json_or_error_decodes_success() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetUser, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body(r#"{"name":"Ann"}"#).build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::GotUser(Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), User { name: "Ann".to_string() });
+        });
+    }
+
+    #[test]
+    fn expect_json_or_error_decodes_error_body() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetUser, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::status(404)
+                        .body(r#"{"message":"No user with id 42"}"#)
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::GotUser(Err(ApiError::Api { code, error })) => {
+            assert_eq!(code, 404);
+            assert_eq!(error, Failure { message: "No user with id 42".to_string() });
+        });
+    }
+
+    #[test]
+    fn expect_json_or_error_keeps_undecodable_error_body() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetUser, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::status(502).body("Bad Gateway").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(
+            actual,
+            Event::GotUser(Err(ApiError::Http(crux_http::HttpError::Http { code, body, .. }))) => {
+                assert_eq!(code, 502);
+                assert_eq!(body, Some(b"Bad Gateway".to_vec()));
+            }
+        );
+    }
+
+    #[test]
+    fn expect_with_decodes_json_for_ok() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::SaveUser, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body(r#"{"name":"Ann"}"#).build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::SavedUser(Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), User { name: "Ann".to_string() });
+        });
+    }
+
+    #[test]
+    fn expect_with_ignores_body_for_no_content() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::SaveUser, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(request, HttpResult::Ok(HttpResponse::status(204).build()))
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::SavedUser(Ok(mut response)) => {
+            assert_eq!(response.status(), 204);
+            assert_eq!(response.take_body(), None);
+        });
+    }
+
+    #[test]
+    fn expect_json_value_decodes_any_json() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetItems, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::ok()
+                        .body(r#"{"items":[{"id":7,"tags":["new"]}],"total":1}"#)
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::GotItems(Ok(response)) => {
+            let body = response.body().unwrap();
+            assert_eq!(body["items"][0]["id"], 7);
+            assert_eq!(body["items"][0]["tags"][0], "new");
+            assert_eq!(body["total"], 1);
+            assert!(body["missing"].is_null());
+        });
+    }
+
+    #[test]
+    fn expect_json_value_rejects_invalid_json() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetItems, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("not json").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::GotItems(Err(HttpError::Json(_))));
+    }
+
+    #[test]
+    fn tagged_requests_hand_the_tag_back_with_the_response() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut requests = app
+            .update(Event::GetRows(vec![1, 2]), &mut model)
+            .into_effects()
+            .map(Effect::expect_http)
+            .collect::<Vec<_>>();
+        assert_eq!(requests.len(), 2);
+
+        // answer the second row first
+        let request = &mut requests[1];
+        assert_eq!(request.operation.url, "http://example.com/rows/2");
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("two").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::GotRow(2, Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), "two");
+        });
+
+        let actual = app
+            .resolve(
+                &mut requests[0],
+                HttpResult::Ok(HttpResponse::status(404).build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::GotRow(1, Err(_)));
+    }
+
+    fn get_auto(content_type: Option<&str>, body: &[u8]) -> Event {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetAuto, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let mut response = HttpResponse::ok();
+        if let Some(content_type) = content_type {
+            response.header("content-type", content_type);
+        }
+        let response = response.body(body).build();
+
+        app.resolve(request, HttpResult::Ok(response))
+            .expect("Resolves successfully")
+            .expect_one_event()
+    }
+
+    #[test]
+    fn expect_auto_decodes_json() {
+        let bob = User {
+            name: "Bob".to_string(),
+        };
+
+        let actual = get_auto(Some("application/json"), br#"{"name":"Bob"}"#);
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(response.take_body(), Some(AutoBody::Json(bob.clone())));
+        });
+
+        let actual = get_auto(
+            Some("application/vnd.api+json; charset=utf-8"),
+            br#"{"name":"Bob"}"#,
+        );
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(response.take_body(), Some(AutoBody::Json(bob)));
+        });
+    }
+
+    #[test]
+    fn expect_auto_decodes_form() {
+        let actual = get_auto(Some("application/x-www-form-urlencoded"), b"name=Bob+Smith");
+
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(
+                response.take_body(),
+                Some(AutoBody::Form(User {
+                    name: "Bob Smith".to_string()
+                }))
+            );
+        });
+    }
+
+    #[test]
+    fn expect_auto_decodes_text() {
+        let actual = get_auto(Some("text/plain; charset=utf-8"), b"Down for maintenance");
+
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(
+                response.take_body(),
+                Some(AutoBody::Text("Down for maintenance".to_string()))
+            );
+        });
+
+        let actual = get_auto(Some("text/html"), b"<p>Hello</p>");
+
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(
+                response.take_body(),
+                Some(AutoBody::Text("<p>Hello</p>".to_string()))
+            );
+        });
+    }
+
+    #[test]
+    fn expect_auto_falls_back_to_bytes() {
+        let actual = get_auto(Some("image/png"), &[0x89, b'P', b'N', b'G']);
+
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(
+                response.take_body(),
+                Some(AutoBody::Bytes(vec![0x89, b'P', b'N', b'G']))
+            );
+        });
+
+        let actual = get_auto(None, b"{}");
+
+        assert_matches!(actual, Event::GotAuto(Ok(mut response)) => {
+            assert_eq!(response.take_body(), Some(AutoBody::Bytes(b"{}".to_vec())));
+        });
+    }
+
+    #[test]
+    fn expect_auto_rejects_a_body_which_does_not_match_its_content_type() {
+        let actual = get_auto(Some("application/json"), b"<p>Not JSON</p>");
+
+        assert_matches!(actual, Event::GotAuto(Err(HttpError::Json(message))) => {
+            assert!(message.starts_with("application/json body is not a valid"));
+            assert!(message.contains("User"));
+        });
+
+        let actual = get_auto(Some("application/x-www-form-urlencoded"), b"age=42");
+
+        assert_matches!(actual, Event::GotAuto(Err(HttpError::Decode(message))) => {
+            assert!(message.starts_with("application/x-www-form-urlencoded body is not a valid"));
+        });
+    }
+
+    #[test]
+    fn get_following_relative_redirect() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetFollowingRedirects, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let redirected = &mut app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::status(302)
+                        .header("location", "/elsewhere?page=2")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            redirected.operation,
+            HttpRequest::get("http://example.com/elsewhere?page=2").build()
+        );
+
+        let actual = app
+            .resolve(
+                redirected,
+                HttpResult::Ok(HttpResponse::ok().body("hello").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), "hello");
+            assert_eq!(
+                response.url().unwrap().as_str(),
+                "http://example.com/elsewhere?page=2"
+            );
+        });
+    }
+
+    #[test]
+    fn redirects_keep_a_body_of_unknown_length() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::UploadFollowingRedirects, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(request.operation.body, b"all systems nominal");
+
+        let redirected = app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::status(307)
+                        .header("location", "/reports/new")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(redirected.operation.url, "http://example.com/reports/new");
+        assert_eq!(redirected.operation.method, "POST");
+        assert_eq!(redirected.operation.body, b"all systems nominal");
+    }
+
+    #[test]
+    fn redirects_to_another_origin_drop_credentials() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let credentials = ["authorization", "proxy-authorization", "cookie"];
+        let header_names = |request: &HttpRequest| -> Vec<String> {
+            request
+                .headers
+                .iter()
+                .map(|header| header.name.to_lowercase())
+                .collect()
+        };
+
+        let request = &mut app
+            .update(Event::GetFollowingRedirectsWithCredentials, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        let sent = header_names(&request.operation);
+        assert!(credentials
+            .iter()
+            .all(|name| sent.contains(&name.to_string())));
+
+        // the same origin keeps the credentials
+        let same_origin = &mut app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::status(302)
+                        .header("location", "/next")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(same_origin.operation.url, "https://example.com/next");
+        assert_eq!(header_names(&same_origin.operation), sent);
+
+        // another host, even on the same scheme and port, doesn't
+        let other_origin = app
+            .resolve(
+                same_origin,
+                HttpResult::Ok(
+                    HttpResponse::status(307)
+                        .header("location", "https://attacker.example.net/collect")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(
+            other_origin.operation.url,
+            "https://attacker.example.net/collect"
+        );
+        assert_eq!(header_names(&other_origin.operation), vec!["accept"]);
+    }
+
+    #[test]
+    fn get_following_redirects_gives_up_after_max_hops() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetFollowingRedirects, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        let redirected = &mut app
+            .resolve(
+                request,
+                HttpResult::Ok(
+                    HttpResponse::status(301)
+                        .header("location", "http://example.org/loop")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            redirected.operation,
+            HttpRequest::get("http://example.org/loop").build()
+        );
+
+        let actual = app
+            .resolve(
+                redirected,
+                HttpResult::Ok(
+                    HttpResponse::status(301)
+                        .header("location", "http://example.com/start")
+                        .build(),
+                ),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(
+            actual,
+            Event::Set(Err(crux_http::HttpError::TooManyRedirects { hops, url })) => {
+                assert_eq!(hops, 1);
+                assert_eq!(url, "http://example.org/loop");
+            }
+        );
+    }
+
+    #[test]
+    fn upload_with_progress() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Upload, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::put("http://example.com/")
+                .header("content-type", "application/octet-stream")
+                .body("The Body")
+                .upload_progress(true)
+                .build()
+        );
+
+        for sent in [4, 8] {
+            let event = app
+                .resolve(request, HttpResult::UploadProgress { sent, total: 8 })
+                .expect("Resolves successfully")
+                .expect_one_event();
+
+            assert_eq!(
+                event,
+                Event::Progress(crux_http::UploadProgress {
+                    bytes_sent: sent,
+                    total: 8
+                })
+            );
+            app.update(event, &mut model).assert_empty();
+        }
+        assert_eq!(model.values, vec!["4/8", "8/8"]);
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("done").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(actual, Event::Set(Ok(mut response)) => {
+            assert_eq!(response.take_body().unwrap(), "done");
+        });
+    }
+
+    #[test]
+    fn search_appends_query_parameters() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::Search, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("http://example.com/search?lang=en&q=crux+http&page=2&tag=a%26b")
+                .build()
+        );
+    }
+
+    #[test]
+    fn poll_not_modified() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model {
+            values: vec!["cached".to_string()],
+            ..Default::default()
+        };
+
+        let request = &mut app
+            .update(Event::Poll, &mut model)
+            .expect_one_effect()
+            .expect_http();
+
+        // header order isn't stable, so compare them sorted
+        request
+            .operation
+            .headers
+            .sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            request.operation,
+            HttpRequest::get("http://example.com/items")
+                .header("if-modified-since", "Sun, 06 Nov 1994 08:49:37 GMT")
+                .header("if-none-match", "\"v1\"")
+                .build()
+        );
+
+        let actual = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::status(304).header("etag", "\"v1\"").build()),
+            )
+            .expect("Resolves successfully")
+            .expect_one_event();
+
+        assert_matches!(&actual, Event::Polled(Ok(response)) => {
+            assert!(response.is_not_modified());
+            assert_eq!(response.etag(), Some("\"v1\""));
+            assert_eq!(response.body(), None);
+        });
+
+        app.update(actual, &mut model).assert_empty();
+        assert_eq!(model.values, vec!["cached"]);
+    }
+
+    #[test]
+    fn abort_in_flight_request() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::StartSearch, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        let id = request.operation.id.expect("abortable requests have an id");
+        assert!(!request.operation.cancel);
+
+        let mut update = app.update(Event::CancelSearch, &mut model);
+        let actual = update.events.pop().expect("one event");
+        assert_matches!(actual, Event::Set(Err(crux_http::HttpError::Aborted)));
+
+        // the shell is asked to cancel the request
+        let cancellation = update.expect_one_effect().expect_http();
+        assert_eq!(cancellation.operation, HttpRequest::cancellation(id));
+
+        // a late response from the shell is ignored
+        let update = app
+            .resolve(
+                request,
+                HttpResult::Ok(HttpResponse::ok().body("stale").build()),
+            )
+            .expect("Resolves successfully");
+        assert!(update.events.is_empty());
+    }
+
+    #[test]
+    fn requests_which_cannot_be_aborted_have_no_id() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::Get, &mut model)
+            .expect_one_effect()
+            .expect_http();
+        assert_eq!(request.operation.id, None);
+    }
+
+    #[test]
+    fn post_json_keeps_field_order() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        for _ in 0..3 {
+            let request = app
+                .update(Event::PostJson, &mut model)
+                .expect_one_effect()
+                .expect_http();
+
+            assert_eq!(
+                request.operation.body,
+                br#"{"title":"New Post","body":"Hello!"}"#
+            );
+        }
+    }
+
+    #[test]
+    fn post_json_fields_keeps_the_order_given() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        for _ in 0..3 {
+            let request = app
+                .update(Event::PostJsonFields, &mut model)
+                .expect_one_effect()
+                .expect_http();
+
+            assert_eq!(
+                request.operation.body,
+                br#"{"title":"New Post","body":"Hello!"}"#
+            );
+            assert!(request
+                .operation
+                .headers
+                .iter()
+                .any(|header| header.name == "content-type"
+                    && header.value.starts_with("application/json")));
+        }
+    }
 }
@@ -13,4 +13,16 @@ pub enum KeyValueError {
     CursorNotFound,
     #[error("other error: {message}")]
     Other { message: String },
+    #[error("expiring keys are not supported")]
+    TtlUnsupported,
+    #[error("operation is not supported")]
+    Unsupported,
+    #[error("transaction conflict at operation {index}")]
+    TransactionConflict { index: u64 },
+    #[error("empty prefix, use delete_all to remove every key")]
+    InvalidPrefix,
+    #[error("stored value is not an integer")]
+    NotAnInteger,
+    #[error("failed to deserialize value: {message}")]
+    Deserialize { message: String },
 }
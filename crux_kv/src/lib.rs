@@ -6,6 +6,9 @@
 pub mod error;
 pub mod value;
 
+use std::{pin::pin, time::Duration};
+
+use futures::{stream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 
 use crux_core::capability::{CapabilityContext, Operation};
@@ -40,39 +43,249 @@ pub enum KeyValueOperation {
         /// If the cursor is not found for the specified prefix, the response will include
         /// a `KeyValueError::CursorNotFound` error.
         cursor: u64,
+        /// The maximum number of keys to return in this page, or `None` to let the
+        /// shell choose.
+        #[serde(default)]
+        limit: Option<u64>,
+    },
+    /// Atomically replace the value under a key with `new`, but only if the currently
+    /// stored value is `expected` (`Value::None` meaning the key must not be present)
+    CompareAndSwap {
+        key: String,
+        expected: Value,
+        #[serde(with = "serde_bytes")]
+        new: Vec<u8>,
     },
+    /// Write bytes under a key, which expires after `ttl_millis` milliseconds.
+    ///
+    /// Enforcing the expiry is the shell's responsibility: once the time is up, the key
+    /// should behave as if it had been deleted, so `Get` returns `Value::None` and `Exists`
+    /// returns false. Shells which can't honor a TTL should respond with
+    /// `KeyValueError::TtlUnsupported` rather than storing the value forever.
+    SetWithTtl {
+        key: String,
+        #[serde(with = "serde_bytes")]
+        value: Vec<u8>,
+        ttl_millis: u64,
+    },
+    /// Atomically add `delta` to the integer stored under a key.
+    ///
+    /// The integer is stored as its decimal representation in UTF-8 (e.g. `b"42"`), and a
+    /// missing key counts as 0. If the stored bytes aren't a valid integer, the shell should
+    /// leave them alone and respond with `KeyValueError::NotAnInteger`.
+    Increment { key: String, delta: i64 },
+    /// Remove every key which starts with `prefix`, and its value.
+    ///
+    /// Keys are compared byte for byte, with no separators implied: the prefix `user:`
+    /// matches `user:1` but not `users:1`. An empty prefix removes every key in the store.
+    DeletePrefix { prefix: String },
+    /// Read the bytes stored under each of the keys
+    GetMany { keys: Vec<String> },
+    /// Write bytes under each of the keys. This is all-or-nothing: if any of the
+    /// writes fail, the shell should leave the store unchanged and respond with an error
+    SetMany { pairs: Vec<KeyValuePair> },
+    /// Watch a key for changes made outside the core, e.g. by another process or a sync
+    /// service.
+    ///
+    /// The shell should respond with a `KeyValueResponse::Changed` every time the value
+    /// under the key changes, for as long as the core is listening. Shells which can't watch
+    /// keys should respond with `KeyValueError::Unsupported` straight away.
+    Watch { key: String },
+    /// Watch every key which starts with `prefix` for changes made outside the core, in the
+    /// same way as `Watch`.
+    WatchPrefix { prefix: String },
+    /// Apply each of the operations in order, atomically: either all of them take effect,
+    /// or none of them do.
+    ///
+    /// If the value under the key of a `TxOp::CompareAndSwap` isn't the expected one, the
+    /// shell should leave the store unchanged and respond with
+    /// `KeyValueError::TransactionConflict`, giving the index of that operation. Shells
+    /// whose store can't apply several writes atomically should respond with
+    /// `KeyValueError::Unsupported`, rather than apply them one by one.
+    Transaction { ops: Vec<TxOp> },
+    /// Measure the keys which start with `prefix`, and their values: how many there are, and
+    /// how many bytes the values take up in total. An empty prefix measures the whole store.
+    ///
+    /// Shells which can't work this out cheaply should respond with
+    /// `KeyValueError::Unsupported`, rather than read every value in the store.
+    Size { prefix: String },
+    /// Measure the value stored under a key, in bytes
+    KeySize { key: String },
 }
 
-impl std::fmt::Debug for KeyValueOperation {
+/// One of the operations of a `KeyValueOperation::Transaction`
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TxOp {
+    /// Write bytes under a key
+    Set {
+        key: String,
+        #[serde(with = "serde_bytes")]
+        value: Vec<u8>,
+    },
+    /// Remove a key and its value
+    Delete { key: String },
+    /// Replace the value under a key with `new`, but only if the currently stored value is
+    /// `expected` (`Value::None` meaning the key must not be present). Otherwise, the whole
+    /// transaction fails.
+    CompareAndSwap {
+        key: String,
+        expected: Value,
+        #[serde(with = "serde_bytes")]
+        new: Vec<u8>,
+    },
+}
+
+impl TxOp {
+    fn scoped(self, scope: &str) -> Self {
+        match self {
+            TxOp::Set { key, value } => TxOp::Set {
+                key: scoped(scope, key),
+                value,
+            },
+            TxOp::Delete { key } => TxOp::Delete {
+                key: scoped(scope, key),
+            },
+            TxOp::CompareAndSwap { key, expected, new } => TxOp::CompareAndSwap {
+                key: scoped(scope, key),
+                expected,
+                new,
+            },
+        }
+    }
+}
+
+impl std::fmt::Debug for TxOp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            KeyValueOperation::Get { key } => f.debug_struct("Get").field("key", key).finish(),
-            KeyValueOperation::Set { key, value } => {
-                let body_repr = if let Ok(s) = std::str::from_utf8(value) {
-                    if s.len() < 50 {
-                        format!("\"{s}\"")
-                    } else {
-                        format!("\"{}\"...", s.chars().take(50).collect::<String>())
+            TxOp::Set { key, value } => f
+                .debug_struct("Set")
+                .field("key", key)
+                .field("value", &format_args!("{}", bytes_repr(value)))
+                .finish(),
+            TxOp::Delete { key } => f.debug_struct("Delete").field("key", key).finish(),
+            TxOp::CompareAndSwap { key, expected, new } => {
+                let mut builder = f.debug_struct("CompareAndSwap");
+                builder.field("key", key);
+                match expected {
+                    Value::None => builder.field("expected", &format_args!("None")),
+                    Value::Bytes(bytes) => {
+                        builder.field("expected", &format_args!("{}", bytes_repr(bytes)))
                     }
-                } else {
-                    format!("<binary data - {} bytes>", value.len())
                 };
-                f.debug_struct("Set")
-                    .field("key", key)
-                    .field("value", &format_args!("{}", body_repr))
+                builder
+                    .field("new", &format_args!("{}", bytes_repr(new)))
                     .finish()
             }
+        }
+    }
+}
+
+/// A key and the bytes to store under it
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyValuePair {
+    pub key: String,
+    #[serde(with = "serde_bytes")]
+    pub value: Vec<u8>,
+}
+
+impl std::fmt::Debug for KeyValuePair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyValuePair")
+            .field("key", &self.key)
+            .field("value", &format_args!("{}", bytes_repr(&self.value)))
+            .finish()
+    }
+}
+
+fn bytes_repr(value: &[u8]) -> String {
+    if let Ok(s) = std::str::from_utf8(value) {
+        if s.len() < 50 {
+            format!("\"{s}\"")
+        } else {
+            format!("\"{}\"...", s.chars().take(50).collect::<String>())
+        }
+    } else {
+        format!("<binary data - {} bytes>", value.len())
+    }
+}
+
+impl std::fmt::Debug for KeyValueOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyValueOperation::Get { key } => f.debug_struct("Get").field("key", key).finish(),
+            KeyValueOperation::Set { key, value } => f
+                .debug_struct("Set")
+                .field("key", key)
+                .field("value", &format_args!("{}", bytes_repr(value)))
+                .finish(),
             KeyValueOperation::Delete { key } => {
                 f.debug_struct("Delete").field("key", key).finish()
             }
             KeyValueOperation::Exists { key } => {
                 f.debug_struct("Exists").field("key", key).finish()
             }
-            KeyValueOperation::ListKeys { prefix, cursor } => f
+            KeyValueOperation::ListKeys {
+                prefix,
+                cursor,
+                limit,
+            } => f
                 .debug_struct("ListKeys")
                 .field("prefix", prefix)
                 .field("cursor", cursor)
+                .field("limit", limit)
+                .finish(),
+            KeyValueOperation::CompareAndSwap { key, expected, new } => {
+                let mut builder = f.debug_struct("CompareAndSwap");
+                builder.field("key", key);
+                match expected {
+                    Value::None => builder.field("expected", &format_args!("None")),
+                    Value::Bytes(bytes) => {
+                        builder.field("expected", &format_args!("{}", bytes_repr(bytes)))
+                    }
+                };
+                builder
+                    .field("new", &format_args!("{}", bytes_repr(new)))
+                    .finish()
+            }
+            KeyValueOperation::SetWithTtl {
+                key,
+                value,
+                ttl_millis,
+            } => f
+                .debug_struct("SetWithTtl")
+                .field("key", key)
+                .field("value", &format_args!("{}", bytes_repr(value)))
+                .field("ttl_millis", ttl_millis)
+                .finish(),
+            KeyValueOperation::Increment { key, delta } => f
+                .debug_struct("Increment")
+                .field("key", key)
+                .field("delta", delta)
                 .finish(),
+            KeyValueOperation::DeletePrefix { prefix } => f
+                .debug_struct("DeletePrefix")
+                .field("prefix", prefix)
+                .finish(),
+            KeyValueOperation::GetMany { keys } => {
+                f.debug_struct("GetMany").field("keys", keys).finish()
+            }
+            KeyValueOperation::SetMany { pairs } => {
+                f.debug_struct("SetMany").field("pairs", pairs).finish()
+            }
+            KeyValueOperation::Watch { key } => f.debug_struct("Watch").field("key", key).finish(),
+            KeyValueOperation::WatchPrefix { prefix } => f
+                .debug_struct("WatchPrefix")
+                .field("prefix", prefix)
+                .finish(),
+            KeyValueOperation::Transaction { ops } => {
+                f.debug_struct("Transaction").field("ops", ops).finish()
+            }
+            KeyValueOperation::Size { prefix } => {
+                f.debug_struct("Size").field("prefix", prefix).finish()
+            }
+            KeyValueOperation::KeySize { key } => {
+                f.debug_struct("KeySize").field("key", key).finish()
+            }
         }
     }
 }
@@ -92,7 +305,7 @@ pub enum KeyValueResponse {
     /// Response to a `KeyValueOperation::Get`,
     /// returning the value stored under the key, which may be empty
     Get { value: Value },
-    /// Response to a `KeyValueOperation::Set`,
+    /// Response to a `KeyValueOperation::Set` or `KeyValueOperation::SetWithTtl`,
     /// returning the value that was previously stored under the key, may be empty
     Set { previous: Value },
     /// Response to a `KeyValueOperation::Delete`,
@@ -113,14 +326,115 @@ pub enum KeyValueResponse {
         /// include a `KeyValueError::CursorNotFound` error.
         next_cursor: u64,
     },
+    /// Response to a `KeyValueOperation::CompareAndSwap`,
+    /// returning whether the value was replaced, and the value now stored under the key
+    /// (the new value if it was swapped, otherwise the value which didn't match)
+    CompareAndSwap { swapped: bool, current: Value },
+    /// Response to a `KeyValueOperation::Increment`,
+    /// returning the value after the increment
+    Increment { value: i64 },
+    /// Response to a `KeyValueOperation::DeletePrefix`,
+    /// returning the number of keys which were removed
+    DeletePrefix { deleted: u64 },
+    /// Response to a `KeyValueOperation::GetMany`,
+    /// returning the value stored under each key, in the same order as the keys
+    GetMany { values: Vec<Value> },
+    /// Response to a `KeyValueOperation::SetMany`, once all the values have been written
+    SetMany,
+    /// Response to a `KeyValueOperation::Watch` or `KeyValueOperation::WatchPrefix`, sent
+    /// each time a watched key changes, with the value now stored under it
+    /// (`Value::None` if the key was deleted)
+    Changed { key: String, value: Value },
+    /// Response to a `KeyValueOperation::Transaction`, once all the operations have been
+    /// applied
+    Transaction,
+    /// Response to a `KeyValueOperation::Size`,
+    /// returning the total size of the values in bytes, and the number of keys
+    Size { bytes: u64, keys: u64 },
+    /// Response to a `KeyValueOperation::KeySize`,
+    /// returning the size of the value stored under the key in bytes, or `None` if the key
+    /// is not present
+    KeySize { bytes: Option<u64> },
+}
+
+/// The space taken up by a store, or the part of it in a scope,
+/// see [`KeyValue::size`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreSize {
+    /// The total size of the stored values, in bytes
+    pub bytes: u64,
+    /// The number of keys
+    pub keys: u64,
 }
 
 impl Operation for KeyValueOperation {
     type Output = KeyValueResult;
+
+    fn is_valid_output(&self, output: &Self::Output) -> bool {
+        let KeyValueResult::Ok { response } = output else {
+            // any operation can fail
+            return true;
+        };
+
+        matches!(
+            (self, response),
+            (KeyValueOperation::Get { .. }, KeyValueResponse::Get { .. })
+                | (
+                    KeyValueOperation::Set { .. } | KeyValueOperation::SetWithTtl { .. },
+                    KeyValueResponse::Set { .. }
+                )
+                | (
+                    KeyValueOperation::Delete { .. },
+                    KeyValueResponse::Delete { .. }
+                )
+                | (
+                    KeyValueOperation::Exists { .. },
+                    KeyValueResponse::Exists { .. }
+                )
+                | (
+                    KeyValueOperation::ListKeys { .. },
+                    KeyValueResponse::ListKeys { .. }
+                )
+                | (
+                    KeyValueOperation::CompareAndSwap { .. },
+                    KeyValueResponse::CompareAndSwap { .. }
+                )
+                | (
+                    KeyValueOperation::Increment { .. },
+                    KeyValueResponse::Increment { .. }
+                )
+                | (
+                    KeyValueOperation::DeletePrefix { .. },
+                    KeyValueResponse::DeletePrefix { .. }
+                )
+                | (
+                    KeyValueOperation::GetMany { .. },
+                    KeyValueResponse::GetMany { .. }
+                )
+                | (KeyValueOperation::SetMany { .. }, KeyValueResponse::SetMany)
+                | (
+                    KeyValueOperation::Watch { .. } | KeyValueOperation::WatchPrefix { .. },
+                    KeyValueResponse::Changed { .. }
+                )
+                | (
+                    KeyValueOperation::Transaction { .. },
+                    KeyValueResponse::Transaction
+                )
+                | (
+                    KeyValueOperation::Size { .. },
+                    KeyValueResponse::Size { .. }
+                )
+                | (
+                    KeyValueOperation::KeySize { .. },
+                    KeyValueResponse::KeySize { .. }
+                )
+        )
+    }
 }
 
 pub struct KeyValue<Ev> {
     context: CapabilityContext<KeyValueOperation, Ev>,
+    scope: String,
 }
 
 impl<Ev> crux_core::Capability<Ev> for KeyValue<Ev> {
@@ -134,7 +448,10 @@ impl<Ev> crux_core::Capability<Ev> for KeyValue<Ev> {
         Ev: 'static,
         NewEv: 'static + Send,
     {
-        KeyValue::new(self.context.map_event(f))
+        KeyValue {
+            context: self.context.map_event(f),
+            scope: self.scope.clone(),
+        }
     }
 
     #[cfg(feature = "typegen")]
@@ -142,6 +459,7 @@ impl<Ev> crux_core::Capability<Ev> for KeyValue<Ev> {
         generator.register_type::<KeyValueResponse>()?;
         generator.register_type::<KeyValueError>()?;
         generator.register_type::<Value>()?;
+        generator.register_type::<TxOp>()?;
         generator.register_type::<Self::Operation>()?;
         generator.register_type::<<Self::Operation as Operation>::Output>()?;
         Ok(())
@@ -152,6 +470,7 @@ impl<Ev> Clone for KeyValue<Ev> {
     fn clone(&self) -> Self {
         Self {
             context: self.context.clone(),
+            scope: self.scope.clone(),
         }
     }
 }
@@ -161,7 +480,46 @@ where
     Ev: 'static,
 {
     pub fn new(context: CapabilityContext<KeyValueOperation, Ev>) -> Self {
-        Self { context }
+        Self {
+            context,
+            scope: String::new(),
+        }
+    }
+
+    /// A handle to the part of the store whose keys start with `prefix`.
+    ///
+    /// Every key given to the returned handle has `prefix` prepended to it, and the keys it
+    /// lists or returns have it removed again, so `notes.get("42".to_string(), ..)` reads
+    /// the key `notes:42` for `let notes = caps.key_value.scope("notes:");`, and listing
+    /// all the keys of `notes` returns `42` rather than `notes:42`. Deleting all the keys
+    /// of a scoped handle only deletes the keys in its scope.
+    ///
+    /// Scoping a scoped handle appends to its prefix, so
+    /// `caps.key_value.scope("user:1:").scope("notes:")` uses keys starting with
+    /// `user:1:notes:`.
+    ///
+    /// The scope only exists in the core, the shell sees the full keys.
+    #[must_use]
+    pub fn scope(&self, prefix: impl AsRef<str>) -> Self {
+        Self {
+            context: self.context.clone(),
+            scope: format!("{}{}", self.scope, prefix.as_ref()),
+        }
+    }
+
+    fn scoped(&self, key: String) -> String {
+        scoped(&self.scope, key)
+    }
+
+    fn scoped_pairs(&self, pairs: Vec<(String, Vec<u8>)>) -> Vec<(String, Vec<u8>)> {
+        pairs
+            .into_iter()
+            .map(|(key, value)| (self.scoped(key), value))
+            .collect()
+    }
+
+    fn scoped_ops(&self, ops: Vec<TxOp>) -> Vec<TxOp> {
+        ops.into_iter().map(|op| op.scoped(&self.scope)).collect()
     }
 
     /// Read a value under `key`, will dispatch the event with a
@@ -170,6 +528,7 @@ where
     where
         F: FnOnce(Result<Option<Vec<u8>>, KeyValueError>) -> Ev + Send + Sync + 'static,
     {
+        let key = self.scoped(key);
         self.context.spawn({
             let context = self.context.clone();
             async move {
@@ -184,6 +543,7 @@ where
     ///
     /// Returns the value stored under the key, or `None` if the key is not present.
     pub async fn get_async(&self, key: String) -> Result<Option<Vec<u8>>, KeyValueError> {
+        let key = self.scoped(key);
         get(&self.context, key).await
     }
 
@@ -195,6 +555,7 @@ where
     where
         F: FnOnce(Result<Option<Vec<u8>>, KeyValueError>) -> Ev + Send + Sync + 'static,
     {
+        let key = self.scoped(key);
         self.context.spawn({
             let context = self.context.clone();
             async move {
@@ -213,15 +574,81 @@ where
         key: String,
         value: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, KeyValueError> {
+        let key = self.scoped(key);
         set(&self.context, key, value).await
     }
 
+    /// Set `key` to be the provided `value`, expiring after `ttl`. Once expired, the key
+    /// behaves as if it had been deleted.
+    ///
+    /// Expiring the key is the shell's responsibility. Shells which can't do that
+    /// respond with `KeyValueError::TtlUnsupported`.
+    ///
+    /// Will dispatch the event with a `KeyValueResult::Set { previous: Vec<u8> }` as payload
+    pub fn set_with_ttl<F>(&self, key: String, value: Vec<u8>, ttl: Duration, make_event: F)
+    where
+        F: FnOnce(Result<Option<Vec<u8>>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let key = self.scoped(key);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = set_with_ttl(&context, key, value, ttl).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Set `key` to be the provided `value`, expiring after `ttl`, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the previous value stored under the key, if any.
+    pub async fn set_with_ttl_async(
+        &self,
+        key: String,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> Result<Option<Vec<u8>>, KeyValueError> {
+        let key = self.scoped(key);
+        set_with_ttl(&self.context, key, value, ttl).await
+    }
+
+    /// Atomically add `delta` (which may be negative) to the integer stored under `key`,
+    /// treating a missing key as 0. Will dispatch the event with a
+    /// `KeyValueResult::Increment { value: i64 }` as payload, holding the value after
+    /// the increment.
+    ///
+    /// If the stored value is not an integer, the result is `KeyValueError::NotAnInteger`.
+    pub fn increment<F>(&self, key: String, delta: i64, make_event: F)
+    where
+        F: FnOnce(Result<i64, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let key = self.scoped(key);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = increment(&context, key, delta).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Atomically add `delta` to the integer stored under `key`, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the value after the increment.
+    pub async fn increment_async(&self, key: String, delta: i64) -> Result<i64, KeyValueError> {
+        let key = self.scoped(key);
+        increment(&self.context, key, delta).await
+    }
+
     /// Remove a `key` and its value, will dispatch the event with a
     /// `KeyValueResult::Delete { previous: Vec<u8> }` as payload
     pub fn delete<F>(&self, key: String, make_event: F)
     where
         F: FnOnce(Result<Option<Vec<u8>>, KeyValueError>) -> Ev + Send + Sync + 'static,
     {
+        let key = self.scoped(key);
         self.context.spawn({
             let context = self.context.clone();
             async move {
@@ -236,15 +663,76 @@ where
     ///
     /// Returns the previous value stored under the key, if any.
     pub async fn delete_async(&self, key: String) -> Result<Option<Vec<u8>>, KeyValueError> {
+        let key = self.scoped(key);
         delete(&self.context, key).await
     }
 
+    /// Remove every key which starts with `prefix`, e.g. all the keys namespaced to a user
+    /// when they log out. Will dispatch the event with a
+    /// `KeyValueResult::DeletePrefix { deleted: u64 }` as payload, holding the number of
+    /// keys removed.
+    ///
+    /// Keys are compared byte for byte, so the prefix `user:` does not match `users:1`.
+    ///
+    /// To guard against wiping the whole store by accident, an empty `prefix` is rejected
+    /// with `KeyValueError::InvalidPrefix` without asking the shell. Use
+    /// [`KeyValue::delete_all`] instead.
+    pub fn delete_prefix<F>(&self, prefix: String, make_event: F)
+    where
+        F: FnOnce(Result<u64, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let response = delete_prefix(&context, &scope, prefix).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Remove every key which starts with `prefix`, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the number of keys removed. An empty `prefix` is rejected, see
+    /// [`KeyValue::delete_prefix`].
+    pub async fn delete_prefix_async(&self, prefix: String) -> Result<u64, KeyValueError> {
+        delete_prefix(&self.context, &self.scope, prefix).await
+    }
+
+    /// Remove every key in the store, or in the scope of a handle made with
+    /// [`KeyValue::scope`]. Will dispatch the event with a
+    /// `KeyValueResult::DeletePrefix { deleted: u64 }` as payload, holding the number of
+    /// keys removed.
+    pub fn delete_all<F>(&self, make_event: F)
+    where
+        F: FnOnce(Result<u64, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let response = delete_all(&context, &scope).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Remove every key in the store, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the number of keys removed.
+    pub async fn delete_all_async(&self) -> Result<u64, KeyValueError> {
+        delete_all(&self.context, &self.scope).await
+    }
+
     /// Check to see if a `key` exists, will dispatch the event with a
     /// `KeyValueResult::Exists { is_present: bool }` as payload
     pub fn exists<F>(&self, key: String, make_event: F)
     where
         F: FnOnce(Result<bool, KeyValueError>) -> Ev + Send + Sync + 'static,
     {
+        let key = self.scoped(key);
         self.context.spawn({
             let context = self.context.clone();
             async move {
@@ -259,9 +747,61 @@ where
     ///
     /// Returns `true` if the key exists, `false` otherwise.
     pub async fn exists_async(&self, key: String) -> Result<bool, KeyValueError> {
+        let key = self.scoped(key);
         exists(&self.context, key).await
     }
 
+    /// Measure the store, will dispatch the event with a `StoreSize`, giving the total size
+    /// of the stored values in bytes and the number of keys, e.g. to keep a cache within a
+    /// budget. A scoped handle only measures the keys in its scope.
+    ///
+    /// Shells which can't measure the store cheaply respond with a
+    /// `KeyValueError::Unsupported` error.
+    pub fn size<F>(&self, make_event: F)
+    where
+        F: FnOnce(Result<StoreSize, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let response = size(&context, &scope).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Measure the store, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    pub async fn size_async(&self) -> Result<StoreSize, KeyValueError> {
+        size(&self.context, &self.scope).await
+    }
+
+    /// Measure the value stored under a `key`, will dispatch the event with its size in
+    /// bytes, or `None` if the key is not present
+    pub fn key_size<F>(&self, key: String, make_event: F)
+    where
+        F: FnOnce(Result<Option<u64>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let key = self.scoped(key);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = key_size(&context, key).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Measure the value stored under a `key`, while in an async context. This is used
+    /// together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the size in bytes, or `None` if the key is not present.
+    pub async fn key_size_async(&self, key: String) -> Result<Option<u64>, KeyValueError> {
+        let key = self.scoped(key);
+        key_size(&self.context, key).await
+    }
+
     /// List keys that start with the provided `prefix`, starting from the provided `cursor`.
     /// Will dispatch the event with a `KeyValueResult::ListKeys { keys: Vec<String>, cursor: u64 }`
     /// as payload.
@@ -273,14 +813,17 @@ where
     ///
     /// If the cursor is found the result will be a tuple of the keys and the next cursor
     /// (if there are more keys to list, the cursor will be non-zero, otherwise it will be zero)
-    pub fn list_keys<F>(&self, prefix: String, cursor: u64, make_event: F)
+    ///
+    /// At most `limit` keys are returned, if given. Otherwise the shell chooses the page size.
+    pub fn list_keys<F>(&self, prefix: String, cursor: u64, limit: Option<u64>, make_event: F)
     where
         F: FnOnce(Result<(Vec<String>, u64), KeyValueError>) -> Ev + Send + Sync + 'static,
     {
         self.context.spawn({
             let context = self.context.clone();
+            let scope = self.scope.clone();
             async move {
-                let response = list_keys(&context, prefix, cursor).await;
+                let response = list_keys(&context, &scope, prefix, cursor, limit).await;
                 context.update_app(make_event(response))
             }
         });
@@ -296,12 +839,269 @@ where
     ///
     /// If the cursor is found the result will be a tuple of the keys and the next cursor
     /// (if there are more keys to list, the cursor will be non-zero, otherwise it will be zero)
+    ///
+    /// At most `limit` keys are returned, if given. Otherwise the shell chooses the page size.
     pub async fn list_keys_async(
         &self,
         prefix: String,
         cursor: u64,
+        limit: Option<u64>,
     ) -> Result<(Vec<String>, u64), KeyValueError> {
-        list_keys(&self.context, prefix, cursor).await
+        list_keys(&self.context, &self.scope, prefix, cursor, limit).await
+    }
+
+    /// List all the keys that start with the provided `prefix`, requesting pages of at most
+    /// `limit` keys until the shell returns a cursor of 0. Will dispatch the event with all
+    /// the keys once the last page has been received.
+    pub fn list_keys_all<F>(&self, prefix: String, limit: Option<u64>, make_event: F)
+    where
+        F: FnOnce(Result<Vec<String>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let response = list_keys_all(&context, &scope, prefix, limit).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// List all the keys that start with the provided `prefix`, requesting pages of at most
+    /// `limit` keys, while in an async context. This is used together with
+    /// [`crux_core::compose::Compose`].
+    pub async fn list_keys_all_async(
+        &self,
+        prefix: String,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, KeyValueError> {
+        list_keys_all(&self.context, &self.scope, prefix, limit).await
+    }
+
+    /// Atomically set `key` to `new`, but only if the value currently stored under it is
+    /// `expected` (`None` meaning the key must not be present). Will dispatch the event
+    /// with a `KeyValueResult::CompareAndSwap { swapped: bool, current: Value }` as payload.
+    ///
+    /// The result is a tuple of whether the value was swapped, and the value now stored
+    /// under the key. When the swap didn't happen, that is the value which didn't match,
+    /// so the caller can retry with it as the new `expected` value.
+    pub fn compare_and_swap<F>(
+        &self,
+        key: String,
+        expected: Option<Vec<u8>>,
+        new: Vec<u8>,
+        make_event: F,
+    ) where
+        F: FnOnce(Result<(bool, Option<Vec<u8>>), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let key = self.scoped(key);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = compare_and_swap(&context, key, expected, new).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Atomically set `key` to `new` if the value currently stored under it is `expected`,
+    /// while in an async context. This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns whether the value was swapped, and the value now stored under the key.
+    pub async fn compare_and_swap_async(
+        &self,
+        key: String,
+        expected: Option<Vec<u8>>,
+        new: Vec<u8>,
+    ) -> Result<(bool, Option<Vec<u8>>), KeyValueError> {
+        let key = self.scoped(key);
+        compare_and_swap(&self.context, key, expected, new).await
+    }
+
+    /// Read the values under each of the `keys` with a single effect, will dispatch the event
+    /// with a `KeyValueResult::GetMany { values: Vec<Value> }` as payload.
+    ///
+    /// The result pairs each key with its value, or `None` if the key is not present,
+    /// in the same order as `keys`.
+    pub fn get_many<F>(&self, keys: Vec<String>, make_event: F)
+    where
+        F: FnOnce(Result<Vec<(String, Option<Vec<u8>>)>, KeyValueError>) -> Ev
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let response = get_many(&context, &scope, keys).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Read the values under each of the `keys` with a single effect, while in an async
+    /// context. This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns each key paired with its value, or `None` if the key is not present,
+    /// in the same order as `keys`.
+    pub async fn get_many_async(
+        &self,
+        keys: Vec<String>,
+    ) -> Result<Vec<(String, Option<Vec<u8>>)>, KeyValueError> {
+        get_many(&self.context, &self.scope, keys).await
+    }
+
+    /// Write each of the `pairs` with a single effect, will dispatch the event with a
+    /// `KeyValueResult::SetMany` as payload.
+    ///
+    /// The write is all-or-nothing: if it fails, none of the values have been stored.
+    pub fn set_many<F>(&self, pairs: Vec<(String, Vec<u8>)>, make_event: F)
+    where
+        F: FnOnce(Result<(), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let pairs = self.scoped_pairs(pairs);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = set_many(&context, pairs).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Write each of the `pairs` with a single effect, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// The write is all-or-nothing: if it fails, none of the values have been stored.
+    pub async fn set_many_async(&self, pairs: Vec<(String, Vec<u8>)>) -> Result<(), KeyValueError> {
+        let pairs = self.scoped_pairs(pairs);
+        set_many(&self.context, pairs).await
+    }
+
+    /// Apply each of the `ops` in order with a single effect, will dispatch the event with a
+    /// `KeyValueResult::Transaction` as payload.
+    ///
+    /// The transaction is all-or-nothing, e.g. to move an item from one list to another
+    /// without it ending up in both or neither. If the value under the key of a
+    /// `TxOp::CompareAndSwap` isn't the expected one, none of the operations take effect
+    /// and the result is `KeyValueError::TransactionConflict`, with the index of the
+    /// operation in `ops`. If the shell's store can't apply them atomically, the result is
+    /// `KeyValueError::Unsupported` and the store is left unchanged.
+    pub fn transaction<F>(&self, ops: Vec<TxOp>, make_event: F)
+    where
+        F: FnOnce(Result<(), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let ops = self.scoped_ops(ops);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                let response = transaction(&context, ops).await;
+                context.update_app(make_event(response))
+            }
+        });
+    }
+
+    /// Apply each of the `ops` in order with a single effect, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// The transaction is all-or-nothing: if it fails, none of the operations have taken
+    /// effect.
+    pub async fn transaction_async(&self, ops: Vec<TxOp>) -> Result<(), KeyValueError> {
+        let ops = self.scoped_ops(ops);
+        transaction(&self.context, ops).await
+    }
+
+    /// Watch `key` for changes made outside the core, e.g. by another window or a sync
+    /// service. Will dispatch the event with the key and its new value (`None` if it was
+    /// deleted) every time the shell sees it change.
+    ///
+    /// If the shell can't watch keys, the event is dispatched once with
+    /// `KeyValueError::Unsupported`. After any error, no more events are dispatched.
+    pub fn watch<F>(&self, key: String, make_event: F)
+    where
+        F: Fn(Result<(String, Option<Vec<u8>>), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let key = self.scoped(key);
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let mut changes = pin!(watch(&context, scope, KeyValueOperation::Watch { key }));
+                while let Some(change) = changes.next().await {
+                    context.update_app(make_event(change));
+                }
+            }
+        });
+    }
+
+    /// Watch `key` for changes made outside the core, while in an async context.
+    ///
+    /// Returns a stream of the key and its new value (`None` if it was deleted) for each
+    /// change. If the shell can't watch keys, the stream yields
+    /// `KeyValueError::Unsupported` and ends, and it also ends after any other error.
+    ///
+    /// Dropping the stream stops listening for changes, and the shell will get an error
+    /// if it tries to report any more of them.
+    pub fn watch_async(
+        &self,
+        key: String,
+    ) -> impl Stream<Item = Result<(String, Option<Vec<u8>>), KeyValueError>> {
+        let key = self.scoped(key);
+        watch(
+            &self.context,
+            self.scope.clone(),
+            KeyValueOperation::Watch { key },
+        )
+    }
+
+    /// Watch every key starting with `prefix` for changes made outside the core. Will
+    /// dispatch the event with the changed key and its new value (`None` if it was deleted)
+    /// every time the shell sees one of them change.
+    ///
+    /// If the shell can't watch keys, the event is dispatched once with
+    /// `KeyValueError::Unsupported`. After any error, no more events are dispatched.
+    pub fn watch_prefix<F>(&self, prefix: String, make_event: F)
+    where
+        F: Fn(Result<(String, Option<Vec<u8>>), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        let prefix = self.scoped(prefix);
+        self.context.spawn({
+            let context = self.context.clone();
+            let scope = self.scope.clone();
+            async move {
+                let operation = KeyValueOperation::WatchPrefix { prefix };
+                let mut changes = pin!(watch(&context, scope, operation));
+                while let Some(change) = changes.next().await {
+                    context.update_app(make_event(change));
+                }
+            }
+        });
+    }
+
+    /// Watch every key starting with `prefix` for changes made outside the core, while in
+    /// an async context.
+    ///
+    /// Returns a stream of the changed keys and their new values, which ends in the same
+    /// way as the one returned by [`KeyValue::watch_async`].
+    pub fn watch_prefix_async(
+        &self,
+        prefix: String,
+    ) -> impl Stream<Item = Result<(String, Option<Vec<u8>>), KeyValueError>> {
+        let prefix = self.scoped(prefix);
+        watch(
+            &self.context,
+            self.scope.clone(),
+            KeyValueOperation::WatchPrefix { prefix },
+        )
+    }
+}
+
+fn scoped(scope: &str, key: String) -> String {
+    if scope.is_empty() {
+        key
+    } else {
+        format!("{scope}{key}")
     }
 }
 
@@ -326,6 +1126,35 @@ async fn set<Ev: 'static>(
         .unwrap_set()
 }
 
+async fn set_with_ttl<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    key: String,
+    value: Vec<u8>,
+    ttl: Duration,
+) -> Result<Option<Vec<u8>>, KeyValueError> {
+    let ttl_millis = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX);
+
+    context
+        .request_from_shell(KeyValueOperation::SetWithTtl {
+            key,
+            value,
+            ttl_millis,
+        })
+        .await
+        .unwrap_set()
+}
+
+async fn increment<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    key: String,
+    delta: i64,
+) -> Result<i64, KeyValueError> {
+    context
+        .request_from_shell(KeyValueOperation::Increment { key, delta })
+        .await
+        .unwrap_increment()
+}
+
 async fn delete<Ev: 'static>(
     context: &CapabilityContext<KeyValueOperation, Ev>,
     key: String,
@@ -336,6 +1165,35 @@ async fn delete<Ev: 'static>(
         .unwrap_delete()
 }
 
+async fn delete_prefix<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: &str,
+    prefix: String,
+) -> Result<u64, KeyValueError> {
+    if prefix.is_empty() {
+        return Err(KeyValueError::InvalidPrefix);
+    }
+
+    context
+        .request_from_shell(KeyValueOperation::DeletePrefix {
+            prefix: scoped(scope, prefix),
+        })
+        .await
+        .unwrap_delete_prefix()
+}
+
+async fn delete_all<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: &str,
+) -> Result<u64, KeyValueError> {
+    context
+        .request_from_shell(KeyValueOperation::DeletePrefix {
+            prefix: scope.to_string(),
+        })
+        .await
+        .unwrap_delete_prefix()
+}
+
 async fn exists<Ev: 'static>(
     context: &CapabilityContext<KeyValueOperation, Ev>,
     key: String,
@@ -346,15 +1204,157 @@ async fn exists<Ev: 'static>(
         .unwrap_exists()
 }
 
+async fn size<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: &str,
+) -> Result<StoreSize, KeyValueError> {
+    context
+        .request_from_shell(KeyValueOperation::Size {
+            prefix: scope.to_string(),
+        })
+        .await
+        .unwrap_size()
+}
+
+async fn key_size<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    key: String,
+) -> Result<Option<u64>, KeyValueError> {
+    context
+        .request_from_shell(KeyValueOperation::KeySize { key })
+        .await
+        .unwrap_key_size()
+}
+
 async fn list_keys<Ev: 'static>(
     context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: &str,
     prefix: String,
     cursor: u64,
+    limit: Option<u64>,
 ) -> Result<(Vec<String>, u64), KeyValueError> {
+    let (keys, next_cursor) = context
+        .request_from_shell(KeyValueOperation::ListKeys {
+            prefix: scoped(scope, prefix),
+            cursor,
+            limit,
+        })
+        .await
+        .unwrap_list_keys()?;
+
+    let keys = keys
+        .into_iter()
+        .filter_map(|key| key.strip_prefix(scope).map(ToString::to_string))
+        .collect();
+
+    Ok((keys, next_cursor))
+}
+
+async fn list_keys_all<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: &str,
+    prefix: String,
+    limit: Option<u64>,
+) -> Result<Vec<String>, KeyValueError> {
+    let mut all_keys = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let (keys, next_cursor) = list_keys(context, scope, prefix.clone(), cursor, limit).await?;
+        all_keys.extend(keys);
+        if next_cursor == 0 {
+            return Ok(all_keys);
+        }
+        cursor = next_cursor;
+    }
+}
+
+async fn compare_and_swap<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    key: String,
+    expected: Option<Vec<u8>>,
+    new: Vec<u8>,
+) -> Result<(bool, Option<Vec<u8>>), KeyValueError> {
     context
-        .request_from_shell(KeyValueOperation::ListKeys { prefix, cursor })
+        .request_from_shell(KeyValueOperation::CompareAndSwap {
+            key,
+            expected: expected.into(),
+            new,
+        })
         .await
-        .unwrap_list_keys()
+        .unwrap_compare_and_swap()
+}
+
+async fn get_many<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: &str,
+    keys: Vec<String>,
+) -> Result<Vec<(String, Option<Vec<u8>>)>, KeyValueError> {
+    let values = context
+        .request_from_shell(KeyValueOperation::GetMany {
+            keys: keys.iter().map(|key| scoped(scope, key.clone())).collect(),
+        })
+        .await
+        .unwrap_get_many()?;
+
+    if values.len() != keys.len() {
+        return Err(KeyValueError::Other {
+            message: format!("expected {} values, got {}", keys.len(), values.len()),
+        });
+    }
+
+    Ok(keys.into_iter().zip(values).collect())
+}
+
+async fn set_many<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    pairs: Vec<(String, Vec<u8>)>,
+) -> Result<(), KeyValueError> {
+    let pairs = pairs
+        .into_iter()
+        .map(|(key, value)| KeyValuePair { key, value })
+        .collect();
+
+    context
+        .request_from_shell(KeyValueOperation::SetMany { pairs })
+        .await
+        .unwrap_set_many()
+}
+
+async fn transaction<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    ops: Vec<TxOp>,
+) -> Result<(), KeyValueError> {
+    context
+        .request_from_shell(KeyValueOperation::Transaction { ops })
+        .await
+        .unwrap_transaction()
+}
+
+fn watch<Ev: 'static>(
+    context: &CapabilityContext<KeyValueOperation, Ev>,
+    scope: String,
+    operation: KeyValueOperation,
+) -> impl Stream<Item = Result<(String, Option<Vec<u8>>), KeyValueError>> {
+    let changes = context.stream_from_shell(operation);
+
+    // the shell stops watching after an error, so the stream ends with it, dropping
+    // the shell stream to stop listening
+    stream::unfold(Some(changes), move |changes| {
+        let scope = scope.clone();
+        async move {
+            let mut changes = changes?;
+            loop {
+                match changes.next().await?.unwrap_changed() {
+                    Ok((key, value)) => {
+                        if let Some(key) = key.strip_prefix(scope.as_str()) {
+                            return Some((Ok((key.to_string(), value)), Some(changes)));
+                        }
+                    }
+                    Err(error) => return Some((Err(error), None)),
+                }
+            }
+        }
+    })
 }
 
 impl KeyValueResult {
@@ -418,6 +1418,108 @@ impl KeyValueResult {
             KeyValueResult::Err { error } => Err(error.clone()),
         }
     }
+
+    fn unwrap_compare_and_swap(self) -> Result<(bool, Option<Vec<u8>>), KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::CompareAndSwap { swapped, current } => {
+                    Ok((swapped, current.into()))
+                }
+                _ => panic!(
+                    "attempt to convert KeyValueResponse other than CompareAndSwap to (bool, Option<Vec<u8>>)"
+                ),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_increment(self) -> Result<i64, KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::Increment { value } => Ok(value),
+                _ => panic!("attempt to convert KeyValueResponse other than Increment to i64"),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_delete_prefix(self) -> Result<u64, KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::DeletePrefix { deleted } => Ok(deleted),
+                _ => panic!("attempt to convert KeyValueResponse other than DeletePrefix to u64"),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_get_many(self) -> Result<Vec<Option<Vec<u8>>>, KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::GetMany { values } => {
+                    Ok(values.into_iter().map(Into::into).collect())
+                }
+                _ => panic!(
+                    "attempt to convert KeyValueResponse other than GetMany to Vec<Option<Vec<u8>>>"
+                ),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_set_many(self) -> Result<(), KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::SetMany => Ok(()),
+                _ => panic!("attempt to convert KeyValueResponse other than SetMany to ()"),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_transaction(self) -> Result<(), KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::Transaction => Ok(()),
+                _ => panic!("attempt to convert KeyValueResponse other than Transaction to ()"),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_changed(self) -> Result<(String, Option<Vec<u8>>), KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::Changed { key, value } => Ok((key, value.into())),
+                _ => panic!(
+                    "attempt to convert KeyValueResponse other than Changed to (String, Option<Vec<u8>>)"
+                ),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_size(self) -> Result<StoreSize, KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::Size { bytes, keys } => Ok(StoreSize { bytes, keys }),
+                _ => panic!("attempt to convert KeyValueResponse other than Size to StoreSize"),
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
+
+    fn unwrap_key_size(self) -> Result<Option<u64>, KeyValueError> {
+        match self {
+            KeyValueResult::Ok { response } => match response {
+                KeyValueResponse::KeySize { bytes } => Ok(bytes),
+                _ => {
+                    panic!("attempt to convert KeyValueResponse other than KeySize to Option<u64>")
+                }
+            },
+            KeyValueResult::Err { error } => Err(error.clone()),
+        }
+    }
 }
 
 #[cfg(test)]
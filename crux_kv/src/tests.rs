@@ -3,10 +3,12 @@ use crux_core::{macros::Effect, render::Render, testing::AppTester};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    error::KeyValueError, value::Value, KeyValue, KeyValueOperation, KeyValueResponse,
-    KeyValueResult,
+    error::KeyValueError, value::Value, KeyValue, KeyValueOperation, KeyValuePair,
+    KeyValueResponse, KeyValueResult, StoreSize, TxOp,
 };
 
+type Entries = Vec<(String, Option<Vec<u8>>)>;
+
 #[derive(Default)]
 pub struct App;
 
@@ -17,12 +19,49 @@ pub enum Event {
     Delete,
     Exists,
     ListKeys,
+    ListAllKeys,
     GetThenSet,
+    CompareAndSwap,
+    SetWithTtl,
+    Increment,
+    GetTyped,
+    SetTyped,
+    GetMany,
+    SetMany,
+    DeletePrefix(String),
+    DeleteAll,
+    ScopedGet,
+    ScopedListKeys,
+    ScopedGetMany,
+    ScopedDeleteAll,
+    Watch,
+    ScopedWatchPrefix,
+    MoveItem,
+    Size,
+    ScopedSize,
+    KeySize,
 
     GetResponse(Result<Option<Vec<u8>>, KeyValueError>),
     SetResponse(Result<Option<Vec<u8>>, KeyValueError>),
     ExistsResponse(Result<bool, KeyValueError>),
     ListKeysResponse(Result<(Vec<String>, u64), KeyValueError>),
+    CompareAndSwapResponse(Result<(bool, Option<Vec<u8>>), KeyValueError>),
+    GetManyResponse(Result<Entries, KeyValueError>),
+    SetManyResponse(Result<(), KeyValueError>),
+    IncrementResponse(Result<i64, KeyValueError>),
+    GetTypedResponse(Result<Option<Settings>, KeyValueError>),
+    ListAllKeysResponse(Result<Vec<String>, KeyValueError>),
+    DeletePrefixResponse(Result<u64, KeyValueError>),
+    WatchResponse(Result<(String, Option<Vec<u8>>), KeyValueError>),
+    TransactionResponse(Result<(), KeyValueError>),
+    SizeResponse(Result<StoreSize, KeyValueError>),
+    KeySizeResponse(Result<Option<u64>, KeyValueError>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub theme: String,
+    pub font_size: u8,
 }
 
 #[derive(Debug, Default)]
@@ -31,6 +70,12 @@ pub struct Model {
     pub keys: Vec<String>,
     pub cursor: u64,
     pub successful: bool,
+    pub values: Entries,
+    pub error: Option<KeyValueError>,
+    pub settings: Option<Settings>,
+    pub deleted: u64,
+    pub size: Option<StoreSize>,
+    pub key_size: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -57,8 +102,94 @@ impl crux_core::App for App {
             Event::Exists => caps.key_value.exists(key, Event::ExistsResponse),
             Event::ListKeys => {
                 caps.key_value
-                    .list_keys("test:".to_string(), 0, Event::ListKeysResponse)
+                    .list_keys("test:".to_string(), 0, Some(2), Event::ListKeysResponse)
             }
+            Event::CompareAndSwap => caps.key_value.compare_and_swap(
+                key,
+                Some(42i32.to_ne_bytes().to_vec()),
+                43i32.to_ne_bytes().to_vec(),
+                Event::CompareAndSwapResponse,
+            ),
+            Event::SetWithTtl => caps.key_value.set_with_ttl(
+                key,
+                42i32.to_ne_bytes().to_vec(),
+                std::time::Duration::from_secs(60),
+                Event::SetResponse,
+            ),
+            Event::Increment => caps.key_value.increment(key, -2, Event::IncrementResponse),
+            Event::ListAllKeys => caps.key_value.list_keys_all(
+                "test:".to_string(),
+                Some(2),
+                Event::ListAllKeysResponse,
+            ),
+            Event::GetTyped => caps.key_value.get_typed(key, Event::GetTypedResponse),
+            Event::SetTyped => caps.key_value.set_typed(
+                key,
+                &Settings {
+                    theme: "dark".to_string(),
+                    font_size: 14,
+                },
+                Event::SetManyResponse,
+            ),
+            Event::GetMany => caps.key_value.get_many(
+                vec!["b".to_string(), "a".to_string(), "c".to_string()],
+                Event::GetManyResponse,
+            ),
+            Event::SetMany => caps.key_value.set_many(
+                vec![
+                    ("b".to_string(), b"2".to_vec()),
+                    ("a".to_string(), b"1".to_vec()),
+                ],
+                Event::SetManyResponse,
+            ),
+            Event::DeletePrefix(prefix) => caps
+                .key_value
+                .delete_prefix(prefix, Event::DeletePrefixResponse),
+            Event::DeleteAll => caps.key_value.delete_all(Event::DeletePrefixResponse),
+            Event::ScopedGet => caps
+                .key_value
+                .scope("user:1:")
+                .scope("notes:")
+                .get("42".to_string(), Event::GetResponse),
+            Event::ScopedListKeys => caps.key_value.scope("notes:").list_keys(
+                String::new(),
+                0,
+                None,
+                Event::ListKeysResponse,
+            ),
+            Event::ScopedGetMany => caps.key_value.scope("notes:").get_many(
+                vec!["1".to_string(), "2".to_string()],
+                Event::GetManyResponse,
+            ),
+            Event::ScopedDeleteAll => caps
+                .key_value
+                .scope("notes:")
+                .delete_all(Event::DeletePrefixResponse),
+            Event::Watch => caps.key_value.watch(key, Event::WatchResponse),
+            Event::ScopedWatchPrefix => caps
+                .key_value
+                .scope("notes:")
+                .watch_prefix(String::new(), Event::WatchResponse),
+            Event::MoveItem => caps.key_value.scope("lists:").transaction(
+                vec![
+                    TxOp::CompareAndSwap {
+                        key: "todo".to_string(),
+                        expected: Value::Bytes(b"milk,bread".to_vec()),
+                        new: b"bread".to_vec(),
+                    },
+                    TxOp::Set {
+                        key: "done".to_string(),
+                        value: b"milk".to_vec(),
+                    },
+                    TxOp::Delete {
+                        key: "draft".to_string(),
+                    },
+                ],
+                Event::TransactionResponse,
+            ),
+            Event::Size => caps.key_value.size(Event::SizeResponse),
+            Event::ScopedSize => caps.key_value.scope("notes:").size(Event::SizeResponse),
+            Event::KeySize => caps.key_value.key_size(key, Event::KeySizeResponse),
 
             Event::GetThenSet => caps.compose.spawn(|ctx| {
                 let kv = caps.key_value.clone();
@@ -102,9 +233,78 @@ impl crux_core::App for App {
                 caps.render.render()
             }
 
+            Event::CompareAndSwapResponse(Ok((swapped, current))) => {
+                model.successful = swapped;
+                if let Some(current) = current {
+                    model.value = i32::from_ne_bytes(current.try_into().unwrap());
+                }
+                caps.render.render()
+            }
+
+            Event::GetManyResponse(Ok(values)) => {
+                model.values = values;
+                caps.render.render()
+            }
+
+            Event::IncrementResponse(Ok(value)) => {
+                model.value = i32::try_from(value).unwrap();
+                caps.render.render()
+            }
+
+            Event::ListAllKeysResponse(Ok(keys)) => {
+                model.keys = keys;
+                caps.render.render()
+            }
+
+            Event::GetTypedResponse(Ok(settings)) => {
+                model.settings = settings;
+                caps.render.render()
+            }
+
+            Event::DeletePrefixResponse(Ok(deleted)) => {
+                model.deleted = deleted;
+                caps.render.render()
+            }
+
+            Event::SetManyResponse(Ok(())) | Event::TransactionResponse(Ok(())) => {
+                model.successful = true;
+                caps.render.render()
+            }
+
+            Event::WatchResponse(Ok(change)) => {
+                model.values.push(change);
+                caps.render.render()
+            }
+
+            Event::SizeResponse(Ok(size)) => {
+                model.size = Some(size);
+                caps.render.render()
+            }
+
+            Event::KeySizeResponse(Ok(bytes)) => {
+                model.key_size = bytes;
+                caps.render.render()
+            }
+
+            Event::GetManyResponse(Err(error))
+            | Event::SetManyResponse(Err(error))
+            | Event::IncrementResponse(Err(error))
+            | Event::GetTypedResponse(Err(error))
+            | Event::ListAllKeysResponse(Err(error))
+            | Event::DeletePrefixResponse(Err(error))
+            | Event::WatchResponse(Err(error))
+            | Event::TransactionResponse(Err(error))
+            | Event::SizeResponse(Err(error))
+            | Event::KeySizeResponse(Err(error)) => {
+                model.error = Some(error);
+            }
+
             Event::GetResponse(Err(error)) => {
                 panic!("error: {:?}", error);
             }
+            Event::SetResponse(Err(KeyValueError::TtlUnsupported)) => {
+                model.error = Some(KeyValueError::TtlUnsupported);
+            }
             Event::SetResponse(Err(error)) => {
                 panic!("error: {:?}", error);
             }
@@ -114,6 +314,9 @@ impl crux_core::App for App {
             Event::ListKeysResponse(Err(error)) => {
                 panic!("Error: {:?}", error);
             }
+            Event::CompareAndSwapResponse(Err(error)) => {
+                panic!("Error: {:?}", error);
+            }
         }
     }
 
@@ -176,7 +379,7 @@ fn test_set() {
         request.operation,
         KeyValueOperation::Set {
             key: "test".to_string(),
-            value: 42i32.to_ne_bytes().to_vec().into(),
+            value: 42i32.to_ne_bytes().to_vec(),
         }
     );
 
@@ -266,6 +469,7 @@ fn test_list_keys() {
         KeyValueOperation::ListKeys {
             prefix: "test:".to_string(),
             cursor: 0,
+            limit: Some(2),
         }
     );
 
@@ -285,40 +489,217 @@ fn test_list_keys() {
 }
 
 #[test]
-pub fn test_kv_async() -> Result<()> {
+fn test_compare_and_swap() {
     let app = AppTester::<App, _>::default();
     let mut model = Model::default();
 
     let request = &mut app
-        .update(Event::GetThenSet, &mut model)
+        .update(Event::CompareAndSwap, &mut model)
         .expect_one_effect()
         .expect_key_value();
 
     assert_eq!(
         request.operation,
-        KeyValueOperation::Get {
-            key: "test_num".to_string()
+        KeyValueOperation::CompareAndSwap {
+            key: "test".to_string(),
+            expected: 42i32.to_ne_bytes().to_vec().into(),
+            new: 43i32.to_ne_bytes().to_vec(),
         }
     );
 
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::CompareAndSwap {
+                swapped: true,
+                current: 43i32.to_ne_bytes().to_vec().into(),
+            },
+        },
+        &mut model,
+    );
+
+    assert!(model.successful);
+    assert_eq!(model.value, 43);
+}
+
+#[test]
+fn test_compare_and_swap_mismatch() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
     let request = &mut app
-        .resolve(
-            request,
-            KeyValueResult::Ok {
-                response: KeyValueResponse::Get {
-                    value: 17u32.to_ne_bytes().to_vec().into(),
-                },
+        .update(Event::CompareAndSwap, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::CompareAndSwap {
+                swapped: false,
+                current: 7i32.to_ne_bytes().to_vec().into(),
             },
-        )
-        .unwrap()
+        },
+        &mut model,
+    );
+
+    assert!(!model.successful);
+    assert_eq!(model.value, 7);
+}
+
+#[test]
+fn test_compare_and_swap_serde_round_trip() {
+    let op = KeyValueOperation::CompareAndSwap {
+        key: "test".to_string(),
+        expected: Value::None,
+        new: b"new".to_vec(),
+    };
+    let json = serde_json::to_string(&op).unwrap();
+    assert_eq!(
+        json,
+        r#"{"CompareAndSwap":{"key":"test","expected":"None","new":[110,101,119]}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<KeyValueOperation>(&json).unwrap(),
+        op
+    );
+
+    let result = KeyValueResult::Ok {
+        response: KeyValueResponse::CompareAndSwap {
+            swapped: false,
+            current: b"old".to_vec().into(),
+        },
+    };
+    let json = serde_json::to_string(&result).unwrap();
+    assert_eq!(
+        json,
+        r#"{"Ok":{"response":{"CompareAndSwap":{"swapped":false,"current":{"Bytes":[111,108,100]}}}}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<KeyValueResult>(&json).unwrap(),
+        result
+    );
+}
+
+#[test]
+fn test_set_with_ttl() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::SetWithTtl, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::SetWithTtl {
+            key: "test".to_string(),
+            value: 42i32.to_ne_bytes().to_vec(),
+            ttl_millis: 60_000,
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Set {
+                previous: Value::None,
+            },
+        },
+        &mut model,
+    );
+
+    assert!(model.successful);
+}
+
+#[test]
+fn test_set_with_ttl_unsupported() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::SetWithTtl, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: KeyValueError::TtlUnsupported,
+        },
+        &mut model,
+    );
+
+    assert!(!model.successful);
+    assert_eq!(model.error, Some(KeyValueError::TtlUnsupported));
+}
+
+#[test]
+fn test_increment() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Increment, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Increment {
+            key: "test".to_string(),
+            delta: -2,
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Increment { value: 40 },
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.value, 40);
+}
+
+#[test]
+fn test_increment_not_an_integer() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Increment, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: KeyValueError::NotAnInteger,
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.error, Some(KeyValueError::NotAnInteger));
+}
+
+#[test]
+fn test_set_typed() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::SetTyped, &mut model)
         .expect_one_effect()
         .expect_key_value();
 
     assert_eq!(
         request.operation,
         KeyValueOperation::Set {
-            key: "test_num".to_string(),
-            value: 18u32.to_ne_bytes().to_vec().into(),
+            key: "test".to_string(),
+            value: br#"{"theme":"dark","font_size":14}"#.to_vec(),
         }
     );
 
@@ -333,56 +714,1007 @@ pub fn test_kv_async() -> Result<()> {
     );
 
     assert!(model.successful);
+}
 
-    Ok(())
+#[test]
+fn test_get_typed() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::GetTyped, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Get {
+                value: br#"{"theme":"light","font_size":12}"#.to_vec().into(),
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(
+        model.settings,
+        Some(Settings {
+            theme: "light".to_string(),
+            font_size: 12
+        })
+    );
 }
 
 #[test]
-fn test_kv_operation_debug_repr() {
-    {
-        // get
-        let op = KeyValueOperation::Get {
-            key: "my key".into(),
-        };
-        let repr = format!("{op:?}");
-        assert_eq!(repr, r#"Get { key: "my key" }"#);
-    }
+fn test_get_typed_decode_failure() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
 
-    {
-        // set small
-        let op = KeyValueOperation::Set {
-            key: "my key".into(),
-            value: b"my value".to_vec(),
-        };
-        let repr = format!("{op:?}");
-        assert_eq!(repr, r#"Set { key: "my key", value: "my value" }"#);
-    }
+    let request = &mut app
+        .update(Event::GetTyped, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
 
-    {
-        // set big
-        let op = KeyValueOperation::Set {
-            key: "my key".into(),
-            value:
-                // we check that we handle unicode boundaries correctly
-                "abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstu😀😀😀😀😀😀".as_bytes().to_vec(),
-        };
-        let repr = format!("{op:?}");
-        assert_eq!(
-            repr,
-            r#"Set { key: "my key", value: "abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstu😀😀"... }"#
-        );
-    }
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Get {
+                value: b"not json".to_vec().into(),
+            },
+        },
+        &mut model,
+    );
 
-    {
-        // set binary
-        let op = KeyValueOperation::Set {
-            key: "my key".into(),
-            value: vec![255, 255],
-        };
+    assert!(matches!(
+        model.error,
+        Some(KeyValueError::Deserialize { .. })
+    ));
+}
+
+#[test]
+fn test_get_many() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::GetMany, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::GetMany {
+            keys: vec!["b".to_string(), "a".to_string(), "c".to_string()]
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::GetMany {
+                values: vec![b"2".to_vec().into(), b"1".to_vec().into(), Value::None],
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(
+        model.values,
+        vec![
+            ("b".to_string(), Some(b"2".to_vec())),
+            ("a".to_string(), Some(b"1".to_vec())),
+            ("c".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_get_many_with_missing_values() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::GetMany, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::GetMany {
+                values: vec![Value::None],
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(
+        model.error,
+        Some(KeyValueError::Other {
+            message: "expected 3 values, got 1".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_set_many() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::SetMany, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::SetMany {
+            pairs: vec![
+                KeyValuePair {
+                    key: "b".to_string(),
+                    value: b"2".to_vec()
+                },
+                KeyValuePair {
+                    key: "a".to_string(),
+                    value: b"1".to_vec()
+                },
+            ]
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::SetMany,
+        },
+        &mut model,
+    );
+
+    assert!(model.successful);
+}
+
+#[test]
+fn test_set_many_failure() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::SetMany, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let error = KeyValueError::Io {
+        message: "disk full".to_string(),
+    };
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: error.clone(),
+        },
+        &mut model,
+    );
+
+    assert!(!model.successful);
+    assert_eq!(model.error, Some(error));
+}
+
+#[test]
+fn test_transaction() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::MoveItem, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Transaction {
+            ops: vec![
+                TxOp::CompareAndSwap {
+                    key: "lists:todo".to_string(),
+                    expected: Value::Bytes(b"milk,bread".to_vec()),
+                    new: b"bread".to_vec(),
+                },
+                TxOp::Set {
+                    key: "lists:done".to_string(),
+                    value: b"milk".to_vec(),
+                },
+                TxOp::Delete {
+                    key: "lists:draft".to_string(),
+                },
+            ]
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Transaction,
+        },
+        &mut model,
+    );
+
+    assert!(model.successful);
+}
+
+#[test]
+fn test_transaction_conflict() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::MoveItem, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let error = KeyValueError::TransactionConflict { index: 0 };
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: error.clone(),
+        },
+        &mut model,
+    );
+
+    assert!(!model.successful);
+    assert_eq!(model.error, Some(error));
+}
+
+#[test]
+fn test_transaction_unsupported() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::MoveItem, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: KeyValueError::Unsupported,
+        },
+        &mut model,
+    );
+
+    assert!(!model.successful);
+    assert_eq!(model.error, Some(KeyValueError::Unsupported));
+}
+
+#[test]
+fn test_transaction_serde_round_trip() {
+    let op = KeyValueOperation::Transaction {
+        ops: vec![
+            TxOp::Delete {
+                key: "a".to_string(),
+            },
+            TxOp::CompareAndSwap {
+                key: "b".to_string(),
+                expected: Value::None,
+                new: vec![1],
+            },
+        ],
+    };
+
+    let json = serde_json::to_string(&op).unwrap();
+    assert_eq!(
+        json,
+        r#"{"Transaction":{"ops":[{"Delete":{"key":"a"}},{"CompareAndSwap":{"key":"b","expected":"None","new":[1]}}]}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<KeyValueOperation>(&json).unwrap(),
+        op
+    );
+
+    let error = KeyValueError::TransactionConflict { index: 1 };
+    let json = serde_json::to_string(&error).unwrap();
+    assert_eq!(json, r#"{"transactionConflict":{"index":1}}"#);
+    assert_eq!(error.to_string(), "transaction conflict at operation 1");
+}
+
+#[test]
+fn test_size() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Size, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Size {
+            prefix: String::new()
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Size {
+                bytes: 1024,
+                keys: 3,
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(
+        model.size,
+        Some(StoreSize {
+            bytes: 1024,
+            keys: 3
+        })
+    );
+}
+
+#[test]
+fn test_size_unsupported() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Size, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: KeyValueError::Unsupported,
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.size, None);
+    assert_eq!(model.error, Some(KeyValueError::Unsupported));
+}
+
+#[test]
+fn test_scoped_size_only_measures_scope() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ScopedSize, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Size {
+            prefix: "notes:".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_key_size() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::KeySize, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::KeySize {
+            key: "test".to_string()
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::KeySize { bytes: Some(4) },
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.key_size, Some(4));
+}
+
+#[test]
+fn test_size_serde_round_trip() {
+    let result = KeyValueResult::Ok {
+        response: KeyValueResponse::Size {
+            bytes: 1024,
+            keys: 3,
+        },
+    };
+    let json = serde_json::to_string(&result).unwrap();
+    assert_eq!(
+        json,
+        r#"{"Ok":{"response":{"Size":{"bytes":1024,"keys":3}}}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<KeyValueResult>(&json).unwrap(),
+        result
+    );
+
+    for bytes in [Some(4), None] {
+        let result = KeyValueResult::Ok {
+            response: KeyValueResponse::KeySize { bytes },
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(
+            serde_json::from_str::<KeyValueResult>(&json).unwrap(),
+            result
+        );
+    }
+    assert_eq!(
+        serde_json::to_string(&KeyValueResponse::KeySize { bytes: None }).unwrap(),
+        r#"{"KeySize":{"bytes":null}}"#
+    );
+
+    let error = KeyValueError::Unsupported;
+    let json = serde_json::to_string(&error).unwrap();
+    assert_eq!(json, r#""unsupported""#);
+    assert_eq!(serde_json::from_str::<KeyValueError>(&json).unwrap(), error);
+}
+
+#[test]
+fn test_invalid_prefix_serde_round_trip() {
+    let error = KeyValueError::InvalidPrefix;
+    let json = serde_json::to_string(&error).unwrap();
+    assert_eq!(json, r#""invalidPrefix""#);
+    assert_eq!(serde_json::from_str::<KeyValueError>(&json).unwrap(), error);
+}
+
+// shells built before the newer variants were added still decode the original ones
+#[cfg(feature = "bincode")]
+#[test]
+fn test_error_variant_indices_are_stable() {
+    let index = |error: KeyValueError| bincode::serialize(&error).unwrap()[..4].to_vec();
+
+    assert_eq!(
+        index(KeyValueError::Io {
+            message: String::new()
+        }),
+        [0, 0, 0, 0]
+    );
+    assert_eq!(index(KeyValueError::Timeout), [1, 0, 0, 0]);
+    assert_eq!(index(KeyValueError::CursorNotFound), [2, 0, 0, 0]);
+    assert_eq!(
+        index(KeyValueError::Other {
+            message: String::new()
+        }),
+        [3, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_list_all_keys() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ListAllKeys, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::ListKeys {
+            prefix: "test:".to_string(),
+            cursor: 0,
+            limit: Some(2),
+        }
+    );
+
+    let request = &mut app
+        .resolve(
+            request,
+            KeyValueResult::Ok {
+                response: KeyValueResponse::ListKeys {
+                    keys: vec!["test:1".to_string(), "test:2".to_string()],
+                    next_cursor: 2,
+                },
+            },
+        )
+        .unwrap()
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::ListKeys {
+            prefix: "test:".to_string(),
+            cursor: 2,
+            limit: Some(2),
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::ListKeys {
+                keys: vec!["test:3".to_string()],
+                next_cursor: 0,
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.keys, vec!["test:1", "test:2", "test:3"]);
+}
+
+#[test]
+fn test_delete_prefix() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::DeletePrefix("user:".to_string()), &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::DeletePrefix {
+            prefix: "user:".to_string()
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::DeletePrefix { deleted: 3 },
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.deleted, 3);
+}
+
+#[test]
+fn test_delete_prefix_is_sent_as_given() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    // no separator is added and the case is kept, matching is up to the shell
+    let request = &mut app
+        .update(Event::DeletePrefix("User".to_string()), &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::DeletePrefix {
+            prefix: "User".to_string()
+        }
+    );
+
+    let error = KeyValueError::Io {
+        message: "disk full".to_string(),
+    };
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: error.clone(),
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.error, Some(error));
+    assert_eq!(model.deleted, 0);
+}
+
+#[test]
+fn test_delete_prefix_rejects_empty_prefix() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let update = app.update(Event::DeletePrefix(String::new()), &mut model);
+
+    assert!(update.effects().next().is_none());
+
+    let event = update.expect_one_event();
+    let _updated = app.update(event, &mut model);
+
+    assert_eq!(model.error, Some(KeyValueError::InvalidPrefix));
+    assert_eq!(model.deleted, 0);
+}
+
+#[test]
+fn test_delete_all() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::DeleteAll, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::DeletePrefix {
+            prefix: String::new()
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::DeletePrefix { deleted: 7 },
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.deleted, 7);
+}
+
+#[test]
+fn test_scoped_get() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ScopedGet, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Get {
+            key: "user:1:notes:42".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_scoped_list_keys_strips_prefix() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ScopedListKeys, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::ListKeys {
+            prefix: "notes:".to_string(),
+            cursor: 0,
+            limit: None,
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::ListKeys {
+                keys: vec!["notes:1".to_string(), "notes:notes:2".to_string()],
+                next_cursor: 0,
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.keys, vec!["1".to_string(), "notes:2".to_string()]);
+}
+
+#[test]
+fn test_scoped_get_many_returns_unscoped_keys() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ScopedGetMany, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::GetMany {
+            keys: vec!["notes:1".to_string(), "notes:2".to_string()]
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::GetMany {
+                values: vec![Value::Bytes(b"one".to_vec()), Value::None],
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(
+        model.values,
+        vec![
+            ("1".to_string(), Some(b"one".to_vec())),
+            ("2".to_string(), None)
+        ]
+    );
+}
+
+#[test]
+fn test_scoped_delete_all_only_deletes_scope() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ScopedDeleteAll, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::DeletePrefix {
+            prefix: "notes:".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_watch() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Watch, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Watch {
+            key: "test".to_string()
+        }
+    );
+
+    for value in [Value::Bytes(b"1".to_vec()), Value::None] {
+        let _updated = app.resolve_to_event_then_update(
+            request,
+            KeyValueResult::Ok {
+                response: KeyValueResponse::Changed {
+                    key: "test".to_string(),
+                    value,
+                },
+            },
+            &mut model,
+        );
+    }
+
+    assert_eq!(
+        model.values,
+        vec![
+            ("test".to_string(), Some(b"1".to_vec())),
+            ("test".to_string(), None)
+        ]
+    );
+}
+
+#[test]
+fn test_scoped_watch_prefix_returns_unscoped_keys() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::ScopedWatchPrefix, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::WatchPrefix {
+            prefix: "notes:".to_string()
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Changed {
+                key: "notes:42".to_string(),
+                value: Value::Bytes(b"hello".to_vec()),
+            },
+        },
+        &mut model,
+    );
+
+    assert_eq!(
+        model.values,
+        vec![("42".to_string(), Some(b"hello".to_vec()))]
+    );
+}
+
+#[test]
+fn test_watch_unsupported_ends_the_watch() {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::Watch, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Err {
+            error: KeyValueError::Unsupported,
+        },
+        &mut model,
+    );
+
+    assert_eq!(model.error, Some(KeyValueError::Unsupported));
+
+    let changed = KeyValueResult::Ok {
+        response: KeyValueResponse::Changed {
+            key: "test".to_string(),
+            value: Value::None,
+        },
+    };
+    assert!(app.resolve(request, changed).is_err());
+    assert!(model.values.is_empty());
+}
+
+#[test]
+pub fn test_kv_async() -> Result<()> {
+    let app = AppTester::<App, _>::default();
+    let mut model = Model::default();
+
+    let request = &mut app
+        .update(Event::GetThenSet, &mut model)
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Get {
+            key: "test_num".to_string()
+        }
+    );
+
+    let request = &mut app
+        .resolve(
+            request,
+            KeyValueResult::Ok {
+                response: KeyValueResponse::Get {
+                    value: 17u32.to_ne_bytes().to_vec().into(),
+                },
+            },
+        )
+        .unwrap()
+        .expect_one_effect()
+        .expect_key_value();
+
+    assert_eq!(
+        request.operation,
+        KeyValueOperation::Set {
+            key: "test_num".to_string(),
+            value: 18u32.to_ne_bytes().to_vec(),
+        }
+    );
+
+    let _updated = app.resolve_to_event_then_update(
+        request,
+        KeyValueResult::Ok {
+            response: KeyValueResponse::Set {
+                previous: Value::None,
+            },
+        },
+        &mut model,
+    );
+
+    assert!(model.successful);
+
+    Ok(())
+}
+
+#[test]
+fn test_kv_operation_debug_repr() {
+    {
+        // get
+        let op = KeyValueOperation::Get {
+            key: "my key".into(),
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(repr, r#"Get { key: "my key" }"#);
+    }
+
+    {
+        // set small
+        let op = KeyValueOperation::Set {
+            key: "my key".into(),
+            value: b"my value".to_vec(),
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(repr, r#"Set { key: "my key", value: "my value" }"#);
+    }
+
+    {
+        // set big
+        let op = KeyValueOperation::Set {
+            key: "my key".into(),
+            value:
+                // we check that we handle unicode boundaries correctly
+                "abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstu😀😀😀😀😀😀".as_bytes().to_vec(),
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(
+            repr,
+            r#"Set { key: "my key", value: "abcdefghijklmnopqrstuvwxyz abcdefghijklmnopqrstu😀😀"... }"#
+        );
+    }
+
+    {
+        // set binary
+        let op = KeyValueOperation::Set {
+            key: "my key".into(),
+            value: vec![255, 255],
+        };
         let repr = format!("{op:?}");
         assert_eq!(
             repr,
             r#"Set { key: "my key", value: <binary data - 2 bytes> }"#
         );
     }
+
+    {
+        // compare and swap
+        let op = KeyValueOperation::CompareAndSwap {
+            key: "my key".into(),
+            expected: Value::None,
+            new: b"my value".to_vec(),
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(
+            repr,
+            r#"CompareAndSwap { key: "my key", expected: None, new: "my value" }"#
+        );
+    }
+
+    {
+        // set many
+        let op = KeyValueOperation::SetMany {
+            pairs: vec![KeyValuePair {
+                key: "my key".into(),
+                value: vec![255, 255],
+            }],
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(
+            repr,
+            r#"SetMany { pairs: [KeyValuePair { key: "my key", value: <binary data - 2 bytes> }] }"#
+        );
+    }
+
+    {
+        // watch prefix
+        let op = KeyValueOperation::WatchPrefix {
+            prefix: "notes:".into(),
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(repr, r#"WatchPrefix { prefix: "notes:" }"#);
+    }
+
+    {
+        // transaction
+        let op = KeyValueOperation::Transaction {
+            ops: vec![
+                TxOp::Set {
+                    key: "my key".into(),
+                    value: vec![255, 255],
+                },
+                TxOp::Delete {
+                    key: "other key".into(),
+                },
+            ],
+        };
+        let repr = format!("{op:?}");
+        assert_eq!(
+            repr,
+            r#"Transaction { ops: [Set { key: "my key", value: <binary data - 2 bytes> }, Delete { key: "other key" }] }"#
+        );
+    }
 }
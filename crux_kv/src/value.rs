@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{error::KeyValueError, KeyValue};
 
 /// The value stored under a key.
 ///
@@ -35,3 +37,158 @@ impl From<Option<Vec<u8>>> for Value {
         }
     }
 }
+
+/// Converts typed values to and from the bytes kept in the store,
+/// for use with [`KeyValue::get_typed_with`] and [`KeyValue::set_typed_with`].
+pub trait Codec {
+    /// Encode a value into bytes to store.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyValueError::Other` if the value can't be encoded.
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, KeyValueError>;
+
+    /// Decode stored bytes back into a value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyValueError::Deserialize` if the bytes don't hold a valid `T`.
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, KeyValueError>;
+}
+
+/// Stores values as JSON. This is the codec used by [`KeyValue::get_typed`]
+/// and [`KeyValue::set_typed`].
+pub struct Json;
+
+impl Codec for Json {
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, KeyValueError> {
+        serde_json::to_vec(value).map_err(|e| KeyValueError::Other {
+            message: e.to_string(),
+        })
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, KeyValueError> {
+        serde_json::from_slice(bytes).map_err(|e| KeyValueError::Deserialize {
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Stores values in the compact bincode format.
+#[cfg(feature = "bincode")]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl Codec for Bincode {
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, KeyValueError> {
+        bincode::serialize(value).map_err(|e| KeyValueError::Other {
+            message: e.to_string(),
+        })
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, KeyValueError> {
+        bincode::deserialize(bytes).map_err(|e| KeyValueError::Deserialize {
+            message: e.to_string(),
+        })
+    }
+}
+
+impl<Ev> KeyValue<Ev>
+where
+    Ev: 'static,
+{
+    /// Read a value under `key` and decode it from JSON, will dispatch the event with
+    /// the decoded value, or `None` if the key is not present.
+    ///
+    /// If the stored bytes can't be decoded as a `T`, the result is
+    /// `KeyValueError::Deserialize`.
+    pub fn get_typed<T, F>(&self, key: String, make_event: F)
+    where
+        T: DeserializeOwned,
+        F: FnOnce(Result<Option<T>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.get_typed_with::<Json, T, F>(key, make_event);
+    }
+
+    /// Read a value under `key` and decode it from JSON, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    ///
+    /// Returns the decoded value, or `None` if the key is not present.
+    pub async fn get_typed_async<T>(&self, key: String) -> Result<Option<T>, KeyValueError>
+    where
+        T: DeserializeOwned,
+    {
+        self.get_typed_with_async::<Json, T>(key).await
+    }
+
+    /// Encode `value` as JSON and store it under `key`, will dispatch the event
+    /// once the value has been stored.
+    pub fn set_typed<T, F>(&self, key: String, value: &T, make_event: F)
+    where
+        T: Serialize,
+        F: FnOnce(Result<(), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.set_typed_with::<Json, T, F>(key, value, make_event);
+    }
+
+    /// Encode `value` as JSON and store it under `key`, while in an async context.
+    /// This is used together with [`crux_core::compose::Compose`].
+    pub async fn set_typed_async<T>(&self, key: String, value: &T) -> Result<(), KeyValueError>
+    where
+        T: Serialize,
+    {
+        self.set_typed_with_async::<Json, T>(key, value).await
+    }
+
+    /// Like [`KeyValue::get_typed`], decoding the value with the codec `C`.
+    pub fn get_typed_with<C, T, F>(&self, key: String, make_event: F)
+    where
+        C: Codec,
+        T: DeserializeOwned,
+        F: FnOnce(Result<Option<T>, KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        self.get(key, |result| {
+            make_event(result.and_then(|bytes| bytes.as_deref().map(C::decode).transpose()))
+        });
+    }
+
+    /// Like [`KeyValue::get_typed_async`], decoding the value with the codec `C`.
+    pub async fn get_typed_with_async<C, T>(&self, key: String) -> Result<Option<T>, KeyValueError>
+    where
+        C: Codec,
+        T: DeserializeOwned,
+    {
+        let bytes = self.get_async(key).await?;
+        bytes.as_deref().map(C::decode).transpose()
+    }
+
+    /// Like [`KeyValue::set_typed`], encoding the value with the codec `C`.
+    pub fn set_typed_with<C, T, F>(&self, key: String, value: &T, make_event: F)
+    where
+        C: Codec,
+        T: Serialize,
+        F: FnOnce(Result<(), KeyValueError>) -> Ev + Send + Sync + 'static,
+    {
+        match C::encode(value) {
+            Ok(bytes) => self.set(key, bytes, |result| make_event(result.map(|_| ()))),
+            Err(error) => self.context.spawn({
+                let context = self.context.clone();
+                async move { context.update_app(make_event(Err(error))) }
+            }),
+        }
+    }
+
+    /// Like [`KeyValue::set_typed_async`], encoding the value with the codec `C`.
+    pub async fn set_typed_with_async<C, T>(
+        &self,
+        key: String,
+        value: &T,
+    ) -> Result<(), KeyValueError>
+    where
+        C: Codec,
+        T: Serialize,
+    {
+        let bytes = C::encode(value)?;
+        self.set_async(key, bytes).await.map(|_| ())
+    }
+}
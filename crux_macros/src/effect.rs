@@ -3,7 +3,7 @@ use proc_macro2::{Literal, TokenStream};
 use proc_macro_error::{abort_call_site, OptionExt};
 use quote::{format_ident, quote};
 use std::collections::BTreeMap;
-use syn::{DeriveInput, GenericArgument, Ident, PathArguments, Type};
+use syn::{DeriveInput, GenericArgument, Ident, Path, PathArguments, Type};
 
 #[derive(FromDeriveInput, Debug)]
 #[darling(attributes(effect), supports(struct_named))]
@@ -20,6 +20,7 @@ pub struct EffectFieldReceiver {
     ty: Type,
     #[darling(default)]
     skip: bool,
+    child: Option<Path>,
 }
 
 struct Field {
@@ -41,6 +42,27 @@ impl From<&EffectFieldReceiver> for Field {
     }
 }
 
+// an app embedded in the app these capabilities are for, see `#[effect(child = ...)]`
+struct Child {
+    app: Path,
+    variant: Ident,
+}
+
+impl From<&Path> for Child {
+    fn from(app: &Path) -> Self {
+        let variant = app
+            .segments
+            .last()
+            .expect_or_abort("child app path has no segments")
+            .ident
+            .clone();
+        Child {
+            app: app.clone(),
+            variant,
+        }
+    }
+}
+
 impl ToTokens for EffectStructReceiver {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ident = &self.ident;
@@ -62,11 +84,19 @@ impl ToTokens for EffectStructReceiver {
             .expect_or_abort("should be a struct")
             .fields;
 
+        let (children, fields): (Vec<_>, Vec<_>) =
+            fields.into_iter().partition(|f| f.child.is_some());
+
         let fields: BTreeMap<Ident, Field> = fields
             .into_iter()
             .map(|f| (f.ident.clone().unwrap(), f.into()))
             .collect();
 
+        let children: BTreeMap<Ident, Child> = children
+            .into_iter()
+            .map(|f| (f.ident.clone().unwrap(), f.child.as_ref().unwrap().into()))
+            .collect();
+
         let events: Vec<_> = fields.values().map(|Field { event, .. }| event).collect();
         if !events
             .windows(2)
@@ -82,6 +112,9 @@ impl ToTokens for EffectStructReceiver {
         let mut with_context_fields = Vec::new();
         let mut ffi_variants = Vec::new();
         let mut match_arms = Vec::new();
+        let mut render_arms = Vec::new();
+        let mut hint_arms = Vec::new();
+        let mut downcast_arms = Vec::new();
         let mut filters = Vec::new();
 
         for (
@@ -112,6 +145,20 @@ impl ToTokens for EffectStructReceiver {
 
                 match_arms.push(quote! { #effect_name::#variant(request) => request.serialize(#ffi_effect_name::#variant) });
 
+                render_arms.push(quote! {
+                    #effect_name::#variant(ref request) => (&request.operation as &dyn ::std::any::Any)
+                        .downcast_ref::<::crux_core::render::RenderOperation>()
+                });
+
+                hint_arms.push(quote! {
+                    #effect_name::#variant(ref request) => (&request.operation as &dyn ::std::any::Any)
+                        .downcast_ref::<::crux_core::render::RenderHint>()
+                });
+
+                downcast_arms.push(quote! {
+                    #effect_name::#variant(request) => request.downcast().map_err(#effect_name::#variant)
+                });
+
                 let filter_fn = format_ident!("is_{}", field_name);
                 let map_fn = format_ident!("into_{}", field_name);
                 let expect_fn = format_ident!("expect_{}", field_name);
@@ -144,6 +191,78 @@ impl ToTokens for EffectStructReceiver {
             }
         }
 
+        for (field_name, Child { app, variant }) in &children {
+            let child_event = quote!(<#app as ::crux_core::App>::Event);
+            let child_effect = quote! {
+                <<#app as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect
+            };
+
+            with_context_fields.push(quote! {
+                #field_name: ::crux_core::WithContext::new_with_context(
+                    context.map_child(#effect_name::#variant, |event: #child_event| <#event as ::std::convert::From<#child_event>>::from(event))
+                )
+            });
+
+            variants.push(quote! { #variant(#child_effect) });
+
+            ffi_variants.push(quote! { #variant(<#child_effect as ::crux_core::Effect>::Ffi) });
+
+            match_arms.push(quote! {
+                #effect_name::#variant(effect) => {
+                    let (ffi, resolve) = ::crux_core::Effect::serialize(effect);
+                    (#ffi_effect_name::#variant(ffi), resolve)
+                }
+            });
+
+            render_arms.push(quote! {
+                #effect_name::#variant(ref effect) => ::crux_core::Effect::render_operation(effect)
+            });
+
+            hint_arms.push(quote! {
+                #effect_name::#variant(ref effect) => ::crux_core::Effect::render_hint(effect)
+            });
+
+            downcast_arms.push(quote! {
+                #effect_name::#variant(effect) => ::crux_core::Effect::downcast(effect).map_err(#effect_name::#variant)
+            });
+
+            let filter_fn = format_ident!("is_{}", field_name);
+            let map_fn = format_ident!("into_{}", field_name);
+            let expect_fn = format_ident!("expect_{}", field_name);
+            let name_as_str = field_name.to_string();
+            filters.push(quote! {
+                impl #effect_name {
+                    pub fn #filter_fn(&self) -> bool {
+                        if let #effect_name::#variant(_) = self {
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    pub fn #map_fn(self) -> Option<#child_effect> {
+                        if let #effect_name::#variant(effect) = self {
+                            Some(effect)
+                        } else {
+                            None
+                        }
+                    }
+                    pub fn #expect_fn(self) -> #child_effect {
+                        if let #effect_name::#variant(effect) = self {
+                            effect
+                        } else {
+                            panic!("not a {} effect", #name_as_str)
+                        }
+                    }
+                }
+
+                impl ::std::convert::From<#child_effect> for #effect_name {
+                    fn from(effect: #child_effect) -> Self {
+                        #effect_name::#variant(effect)
+                    }
+                }
+            });
+        }
+
         tokens.extend(quote! {
             #[derive(Debug)]
             pub enum #effect_name {
@@ -164,6 +283,27 @@ impl ToTokens for EffectStructReceiver {
                         #(#match_arms ,)*
                     }
                 }
+
+                fn render_operation(&self) -> Option<&::crux_core::render::RenderOperation> {
+                    match *self {
+                        #(#render_arms ,)*
+                    }
+                }
+
+                fn render_hint(&self) -> Option<&::crux_core::render::RenderHint> {
+                    match *self {
+                        #(#hint_arms ,)*
+                    }
+                }
+
+                fn downcast<Op>(self) -> Result<::crux_core::Request<Op>, Self>
+                where
+                    Op: ::crux_core::capability::Operation,
+                {
+                    match self {
+                        #(#downcast_arms ,)*
+                    }
+                }
             }
 
             impl ::crux_core::WithContext<#event, #effect_name> for #ident {
@@ -174,6 +314,10 @@ impl ToTokens for EffectStructReceiver {
                 }
             }
 
+            impl ::crux_core::capability::CapabilitiesEffect for #ident {
+                type Effect = #effect_name;
+            }
+
             #(#filters)*
         })
     }
@@ -261,6 +405,30 @@ mod tests {
                     Effect::Render(request) => request.serialize(EffectFfi::Render),
                 }
             }
+            fn render_operation(&self) -> Option<&::crux_core::render::RenderOperation> {
+                match *self {
+                    Effect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                }
+            }
+            fn render_hint(&self) -> Option<&::crux_core::render::RenderHint> {
+                match *self {
+                    Effect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                }
+            }
+            fn downcast<Op>(self) -> Result<::crux_core::Request<Op>, Self>
+            where
+                Op: ::crux_core::capability::Operation,
+            {
+                match self {
+                    Effect::Render(request) => request.downcast().map_err(Effect::Render),
+                }
+            }
         }
         impl ::crux_core::WithContext<Event, Effect> for Capabilities {
             fn new_with_context(
@@ -271,6 +439,9 @@ mod tests {
                 }
             }
         }
+        impl ::crux_core::capability::CapabilitiesEffect for Capabilities {
+            type Effect = Effect;
+        }
         impl Effect {
             pub fn is_render(&self) -> bool {
                 if let Effect::Render(_) = self { true } else { false }
@@ -335,6 +506,30 @@ mod tests {
                     Effect::Render(request) => request.serialize(EffectFfi::Render),
                 }
             }
+            fn render_operation(&self) -> Option<&::crux_core::render::RenderOperation> {
+                match *self {
+                    Effect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                }
+            }
+            fn render_hint(&self) -> Option<&::crux_core::render::RenderHint> {
+                match *self {
+                    Effect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                }
+            }
+            fn downcast<Op>(self) -> Result<::crux_core::Request<Op>, Self>
+            where
+                Op: ::crux_core::capability::Operation,
+            {
+                match self {
+                    Effect::Render(request) => request.downcast().map_err(Effect::Render),
+                }
+            }
         }
         impl ::crux_core::WithContext<Event, Effect> for Capabilities {
             fn new_with_context(
@@ -353,6 +548,9 @@ mod tests {
                 }
             }
         }
+        impl ::crux_core::capability::CapabilitiesEffect for Capabilities {
+            type Effect = Effect;
+        }
         impl Effect {
             pub fn is_render(&self) -> bool {
                 if let Effect::Render(_) = self { true } else { false }
@@ -462,6 +660,66 @@ mod tests {
                     MyEffect::Time(request) => request.serialize(MyEffectFfi::Time),
                 }
             }
+            fn render_operation(&self) -> Option<&::crux_core::render::RenderOperation> {
+                match *self {
+                    MyEffect::Http(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                    MyEffect::KeyValue(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                    MyEffect::Platform(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                    MyEffect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                    MyEffect::Time(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                }
+            }
+            fn render_hint(&self) -> Option<&::crux_core::render::RenderHint> {
+                match *self {
+                    MyEffect::Http(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                    MyEffect::KeyValue(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                    MyEffect::Platform(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                    MyEffect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                    MyEffect::Time(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                }
+            }
+            fn downcast<Op>(self) -> Result<::crux_core::Request<Op>, Self>
+            where
+                Op: ::crux_core::capability::Operation,
+            {
+                match self {
+                    MyEffect::Http(request) => request.downcast().map_err(MyEffect::Http),
+                    MyEffect::KeyValue(request) => request.downcast().map_err(MyEffect::KeyValue),
+                    MyEffect::Platform(request) => request.downcast().map_err(MyEffect::Platform),
+                    MyEffect::Render(request) => request.downcast().map_err(MyEffect::Render),
+                    MyEffect::Time(request) => request.downcast().map_err(MyEffect::Time),
+                }
+            }
         }
         impl ::crux_core::WithContext<MyEvent, MyEffect> for MyCapabilities {
             fn new_with_context(
@@ -476,6 +734,9 @@ mod tests {
                 }
             }
         }
+        impl ::crux_core::capability::CapabilitiesEffect for MyCapabilities {
+            type Effect = MyEffect;
+        }
         impl MyEffect {
             pub fn is_http(&self) -> bool {
                 if let MyEffect::Http(_) = self { true } else { false }
@@ -612,6 +873,161 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn effect_child() {
+        let input = r#"
+            #[derive(Effect)]
+            pub struct Capabilities {
+                pub render: Render<Event>,
+                #[effect(child = counter::Counter)]
+                pub counter: counter::Capabilities,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = EffectStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        #[derive(Debug)]
+        pub enum Effect {
+            Render(
+                ::crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            ),
+            Counter(
+                <<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect,
+            ),
+        }
+        #[derive(::serde::Serialize, ::serde::Deserialize)]
+        #[serde(rename = "Effect")]
+        pub enum EffectFfi {
+            Render(<Render<Event> as ::crux_core::capability::Capability<Event>>::Operation),
+            Counter(
+                <<<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect as ::crux_core::Effect>::Ffi,
+            ),
+        }
+        impl ::crux_core::Effect for Effect {
+            type Ffi = EffectFfi;
+            fn serialize(self) -> (Self::Ffi, ::crux_core::bridge::ResolveSerialized) {
+                match self {
+                    Effect::Render(request) => request.serialize(EffectFfi::Render),
+                    Effect::Counter(effect) => {
+                        let (ffi, resolve) = ::crux_core::Effect::serialize(effect);
+                        (EffectFfi::Counter(ffi), resolve)
+                    }
+                }
+            }
+            fn render_operation(&self) -> Option<&::crux_core::render::RenderOperation> {
+                match *self {
+                    Effect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderOperation>()
+                    }
+                    Effect::Counter(ref effect) => ::crux_core::Effect::render_operation(effect),
+                }
+            }
+            fn render_hint(&self) -> Option<&::crux_core::render::RenderHint> {
+                match *self {
+                    Effect::Render(ref request) => {
+                        (&request.operation as &dyn ::std::any::Any)
+                            .downcast_ref::<::crux_core::render::RenderHint>()
+                    }
+                    Effect::Counter(ref effect) => ::crux_core::Effect::render_hint(effect),
+                }
+            }
+            fn downcast<Op>(self) -> Result<::crux_core::Request<Op>, Self>
+            where
+                Op: ::crux_core::capability::Operation,
+            {
+                match self {
+                    Effect::Render(request) => request.downcast().map_err(Effect::Render),
+                    Effect::Counter(effect) => {
+                        ::crux_core::Effect::downcast(effect).map_err(Effect::Counter)
+                    }
+                }
+            }
+        }
+        impl ::crux_core::WithContext<Event, Effect> for Capabilities {
+            fn new_with_context(
+                context: ::crux_core::capability::ProtoContext<Effect, Event>,
+            ) -> Capabilities {
+                Capabilities {
+                    render: Render::new(context.specialize(Effect::Render)),
+                    counter: ::crux_core::WithContext::new_with_context(
+                        context
+                            .map_child(
+                                Effect::Counter,
+                                |event: <counter::Counter as ::crux_core::App>::Event| <Event as ::std::convert::From<
+                                    <counter::Counter as ::crux_core::App>::Event,
+                                >>::from(event),
+                            ),
+                    ),
+                }
+            }
+        }
+        impl ::crux_core::capability::CapabilitiesEffect for Capabilities {
+            type Effect = Effect;
+        }
+        impl Effect {
+            pub fn is_render(&self) -> bool {
+                if let Effect::Render(_) = self { true } else { false }
+            }
+            pub fn into_render(
+                self,
+            ) -> Option<
+                crux_core::Request<
+                    <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+                >,
+            > {
+                if let Effect::Render(request) = self { Some(request) } else { None }
+            }
+            pub fn expect_render(
+                self,
+            ) -> crux_core::Request<
+                <Render<Event> as ::crux_core::capability::Capability<Event>>::Operation,
+            > {
+                if let Effect::Render(request) = self {
+                    request
+                } else {
+                    panic!("not a {} effect", "render")
+                }
+            }
+        }
+        impl Effect {
+            pub fn is_counter(&self) -> bool {
+                if let Effect::Counter(_) = self { true } else { false }
+            }
+            pub fn into_counter(
+                self,
+            ) -> Option<
+                <<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect,
+            > {
+                if let Effect::Counter(effect) = self { Some(effect) } else { None }
+            }
+            pub fn expect_counter(
+                self,
+            ) -> <<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect {
+                if let Effect::Counter(effect) = self {
+                    effect
+                } else {
+                    panic!("not a {} effect", "counter")
+                }
+            }
+        }
+        impl ::std::convert::From<
+            <<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect,
+        > for Effect {
+            fn from(
+                effect: <<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect,
+            ) -> Self {
+                Effect::Counter(effect)
+            }
+        }
+        "###);
+    }
+
     #[test]
     #[should_panic]
     fn should_panic_when_multiple_event_types() {
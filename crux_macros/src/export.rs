@@ -2,7 +2,7 @@ use darling::{ast, util, FromDeriveInput, FromField, ToTokens};
 use proc_macro2::TokenStream;
 use proc_macro_error::OptionExt;
 use quote::{format_ident, quote};
-use syn::{DeriveInput, GenericArgument, Ident, PathArguments, Type};
+use syn::{DeriveInput, GenericArgument, Ident, Path, PathArguments, Type};
 
 #[derive(FromDeriveInput, Debug)]
 #[darling(attributes(effect), supports(struct_named))]
@@ -18,6 +18,7 @@ pub struct ExportFieldReceiver {
     ty: Type,
     #[darling(default)]
     skip: bool,
+    child: Option<Path>,
 }
 
 impl ToTokens for ExportStructReceiver {
@@ -45,19 +46,34 @@ impl ToTokens for ExportStructReceiver {
 
         let mut output_type_exports = Vec::new();
 
-        for (capability, event) in fields.iter().map(|f| split_on_generic(&f.ty)) {
-            output_type_exports.push(quote! {
-                #capability::<#event>::register_types(generator)?;
-            });
+        for field in fields {
+            if let Some(app) = &field.child {
+                let child_capabilities = quote!(<#app as ::crux_core::App>::Capabilities);
+                output_type_exports.push(quote! {
+                    <#child_capabilities as ::crux_core::typegen::Export>::register_capability_types(generator)?;
+                    generator.register_type::<<<#child_capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect as ::crux_core::Effect>::Ffi>()?;
+                });
+            } else {
+                let (capability, event) = split_on_generic(&field.ty);
+                output_type_exports.push(quote! {
+                    #capability::<#event>::register_types(generator)?;
+                });
+            }
         }
 
         tokens.extend(quote! {
 
             impl ::crux_core::typegen::Export for #ident {
                 #[cfg(feature = "typegen")]
-                fn register_types(generator: &mut ::crux_core::typegen::TypeGen) -> ::crux_core::typegen::Result {
+                fn register_capability_types(generator: &mut ::crux_core::typegen::TypeGen) -> ::crux_core::typegen::Result {
                     use ::crux_core::capability::Capability;
                     #(#output_type_exports)*
+                    Ok(())
+                }
+
+                #[cfg(feature = "typegen")]
+                fn register_types(generator: &mut ::crux_core::typegen::TypeGen) -> ::crux_core::typegen::Result {
+                    Self::register_capability_types(generator)?;
                     generator.register_type::<#ffi_export_name>()?;
                     generator.register_type::<::crux_core::bridge::Request<#ffi_export_name>>()?;
 
@@ -131,11 +147,18 @@ mod tests {
         insta::assert_snapshot!(pretty_print(&actual), @r###"
         impl ::crux_core::typegen::Export for Capabilities {
             #[cfg(feature = "typegen")]
-            fn register_types(
+            fn register_capability_types(
                 generator: &mut ::crux_core::typegen::TypeGen,
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
                 Render::<Event>::register_types(generator)?;
+                Ok(())
+            }
+            #[cfg(feature = "typegen")]
+            fn register_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                Self::register_capability_types(generator)?;
                 generator.register_type::<EffectFfi>()?;
                 generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
                 Ok(())
@@ -179,7 +202,7 @@ mod tests {
         insta::assert_snapshot!(pretty_print(&actual), @r###"
         impl ::crux_core::typegen::Export for MyCapabilities {
             #[cfg(feature = "typegen")]
-            fn register_types(
+            fn register_capability_types(
                 generator: &mut ::crux_core::typegen::TypeGen,
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
@@ -187,6 +210,13 @@ mod tests {
                 KeyValue::<MyEvent>::register_types(generator)?;
                 Platform::<MyEvent>::register_types(generator)?;
                 Render::<MyEvent>::register_types(generator)?;
+                Ok(())
+            }
+            #[cfg(feature = "typegen")]
+            fn register_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                Self::register_capability_types(generator)?;
                 generator.register_type::<EffectFfi>()?;
                 generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
                 Ok(())
@@ -215,7 +245,7 @@ mod tests {
         insta::assert_snapshot!(pretty_print(&actual), @r###"
         impl ::crux_core::typegen::Export for MyCapabilities {
             #[cfg(feature = "typegen")]
-            fn register_types(
+            fn register_capability_types(
                 generator: &mut ::crux_core::typegen::TypeGen,
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
@@ -224,6 +254,58 @@ mod tests {
                 Platform::<MyEvent>::register_types(generator)?;
                 Render::<MyEvent>::register_types(generator)?;
                 Time::<MyEvent>::register_types(generator)?;
+                Ok(())
+            }
+            #[cfg(feature = "typegen")]
+            fn register_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                Self::register_capability_types(generator)?;
+                generator.register_type::<EffectFfi>()?;
+                generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
+                Ok(())
+            }
+        }
+        "###);
+    }
+
+    #[test]
+    fn export_child() {
+        let input = r#"
+            #[derive(Export)]
+            pub struct Capabilities {
+                pub render: Render<Event>,
+                #[effect(child = counter::Counter)]
+                pub counter: counter::Capabilities,
+            }
+        "#;
+        let input = parse_str(input).unwrap();
+        let input = ExportStructReceiver::from_derive_input(&input).unwrap();
+
+        let actual = quote!(#input);
+
+        insta::assert_snapshot!(pretty_print(&actual), @r###"
+        impl ::crux_core::typegen::Export for Capabilities {
+            #[cfg(feature = "typegen")]
+            fn register_capability_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                use ::crux_core::capability::Capability;
+                Render::<Event>::register_types(generator)?;
+                <<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::typegen::Export>::register_capability_types(
+                    generator,
+                )?;
+                generator
+                    .register_type::<
+                        <<<counter::Counter as ::crux_core::App>::Capabilities as ::crux_core::capability::CapabilitiesEffect>::Effect as ::crux_core::Effect>::Ffi,
+                    >()?;
+                Ok(())
+            }
+            #[cfg(feature = "typegen")]
+            fn register_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                Self::register_capability_types(generator)?;
                 generator.register_type::<EffectFfi>()?;
                 generator.register_type::<::crux_core::bridge::Request<EffectFfi>>()?;
                 Ok(())
@@ -250,11 +332,18 @@ mod tests {
         insta::assert_snapshot!(pretty_print(&actual), @r###"
         impl ::crux_core::typegen::Export for Capabilities {
             #[cfg(feature = "typegen")]
-            fn register_types(
+            fn register_capability_types(
                 generator: &mut ::crux_core::typegen::TypeGen,
             ) -> ::crux_core::typegen::Result {
                 use ::crux_core::capability::Capability;
                 Render::<Event>::register_types(generator)?;
+                Ok(())
+            }
+            #[cfg(feature = "typegen")]
+            fn register_types(
+                generator: &mut ::crux_core::typegen::TypeGen,
+            ) -> ::crux_core::typegen::Result {
+                Self::register_capability_types(generator)?;
                 generator.register_type::<MyEffectFfi>()?;
                 generator.register_type::<::crux_core::bridge::Request<MyEffectFfi>>()?;
                 Ok(())
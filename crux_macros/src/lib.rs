@@ -53,7 +53,40 @@ use syn::parse_macro_input;
 ///     #[effect(skip)]
 ///     pub compose: Compose<MyEvent>,
 /// }
-
+/// ```
+///
+/// An app can embed another app, by adding a field for the child app's capabilities
+/// annotated with `#[effect(child = ChildApp)]`. The child's capabilities are created
+/// along with the parent's, and the parent passes them to the child's `update`. The
+/// Effect enum gets a variant named after the child app, carrying the child's own
+/// effect, and a `From` impl to wrap the child's effect in it. The child's events are
+/// wrapped into the parent's with a `From` impl the parent provides, e.g.
+///
+/// ```rust,ignore
+/// impl From<counter::Event> for Event {
+///     fn from(event: counter::Event) -> Self {
+///         Event::Counter(event)
+///     }
+/// }
+///
+/// #[derive(Effect)]
+/// pub struct Capabilities {
+///     pub render: Render<Event>,
+///     #[effect(child = Counter)]
+///     pub counter: counter::Capabilities,
+/// }
+///
+/// // in the parent's update
+/// Event::Counter(event) => self.counter.update(event, &mut model.counter, &caps.counter),
+/// ```
+///
+/// Because each child's effects stay nested in their own variant, two children using the
+/// same capability don't clash. A child's variant is named after the last segment of its
+/// app's path, so two child apps with the same type name, or a child app named like one
+/// of the parent's capabilities, produce duplicate variants. Give one of them a different
+/// name with a type alias, e.g. `type Weather = weather::App;`. For type generation, the
+/// child's effect must also be named differently from the parent's, with the `name`
+/// attribute, e.g. `#[effect(name = "CounterEffect")]`.
 #[proc_macro_derive(Effect, attributes(effect))]
 #[proc_macro_error]
 pub fn effect(input: TokenStream) -> TokenStream {
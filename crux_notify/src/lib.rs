@@ -0,0 +1,146 @@
+//! Transient notifications for Crux apps
+//!
+//! Showing the user a short message, such as a toast or a snackbar, is a side-effect
+//! like any other, so the core asks the shell to show it with this capability. The
+//! shell decides how the message looks, and doesn't respond.
+
+use crux_core::capability::{CapabilityContext, Operation};
+use serde::{Deserialize, Serialize};
+
+/// How important a notification is. Shells typically pick the styling of the message,
+/// e.g. its color or icon, based on the level.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotifyLevel {
+    /// Something the user might like to know, e.g. that an item was saved
+    #[default]
+    Info,
+    /// Something the user should pay attention to, e.g. that they're offline
+    Warn,
+    /// Something which went wrong, e.g. that an item couldn't be saved
+    Error,
+}
+
+/// How long a notification stays on screen. The exact timings are up to the shell,
+/// following the conventions of the platform.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotifyDuration {
+    /// A few seconds, suitable for a brief message
+    #[default]
+    Short,
+    /// A little longer, for messages which take longer to read
+    Long,
+}
+
+/// The single operation `Notify` implements, asking the shell to show a notification.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifyOperation {
+    /// The text to show
+    pub message: String,
+    pub level: NotifyLevel,
+    pub duration: NotifyDuration,
+}
+
+impl Operation for NotifyOperation {
+    type Output = ();
+}
+
+/// The Notify capability API
+///
+/// Use an instance of `Notify` to ask the shell to show the user a short message,
+/// e.g. a toast on Android or a snackbar on the web.
+pub struct Notify<Ev> {
+    context: CapabilityContext<NotifyOperation, Ev>,
+}
+
+impl<Ev> crux_core::Capability<Ev> for Notify<Ev> {
+    type Operation = NotifyOperation;
+    type MappedSelf<MappedEv> = Notify<MappedEv>;
+
+    fn map_event<F, NewEv>(&self, f: F) -> Self::MappedSelf<NewEv>
+    where
+        F: Fn(NewEv) -> Ev + Send + Sync + 'static,
+        Ev: 'static,
+        NewEv: 'static + Send,
+    {
+        Notify::new(self.context.map_event(f))
+    }
+
+    #[cfg(feature = "typegen")]
+    fn register_types(generator: &mut crux_core::typegen::TypeGen) -> crux_core::typegen::Result {
+        generator.register_type::<NotifyLevel>()?;
+        generator.register_type::<NotifyDuration>()?;
+        generator.register_type::<Self::Operation>()?;
+        generator.register_type::<<Self::Operation as Operation>::Output>()?;
+        Ok(())
+    }
+}
+
+impl<Ev> Clone for Notify<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> Notify<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<NotifyOperation, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Ask the shell to show `message` at the given `level`, for a short time.
+    ///
+    /// ```rust,ignore
+    /// Event::Saved(Err(error)) => {
+    ///     caps.notify.show(format!("Couldn't save: {error}"), NotifyLevel::Error);
+    /// }
+    /// ```
+    pub fn show(&self, message: impl Into<String>, level: NotifyLevel) {
+        self.show_for(message, level, NotifyDuration::Short);
+    }
+
+    /// Ask the shell to show `message` at the given `level`, for the given `duration`.
+    pub fn show_for(
+        &self,
+        message: impl Into<String>,
+        level: NotifyLevel,
+        duration: NotifyDuration,
+    ) {
+        let operation = NotifyOperation {
+            message: message.into(),
+            level,
+            duration,
+        };
+
+        let context = self.context.clone();
+        self.context.spawn(async move {
+            context.notify_shell(operation).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serializing_the_operation_as_json() {
+        let operation = NotifyOperation {
+            message: "Saved".to_string(),
+            level: NotifyLevel::Warn,
+            duration: NotifyDuration::Long,
+        };
+
+        let serialized = serde_json::to_string(&operation).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"message":"Saved","level":"Warn","duration":"Long"}"#
+        );
+
+        let deserialized: NotifyOperation = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(operation, deserialized);
+    }
+}
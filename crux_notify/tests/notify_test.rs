@@ -0,0 +1,118 @@
+mod shared {
+    use crux_core::macros::Effect;
+    use crux_core::render::Render;
+    use crux_notify::{Notify, NotifyDuration, NotifyLevel};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Default)]
+    pub struct App;
+
+    #[derive(Serialize, Deserialize)]
+    pub enum Event {
+        Saved(Result<String, String>),
+        Offline,
+    }
+
+    #[derive(Default)]
+    pub struct Model {
+        pub saved: Vec<String>,
+    }
+
+    impl crux_core::App for App {
+        type Event = Event;
+        type Model = Model;
+        type ViewModel = usize;
+        type Capabilities = Capabilities;
+
+        fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+            match event {
+                Event::Saved(Ok(name)) => {
+                    caps.notify.show(format!("Saved {name}"), NotifyLevel::Info);
+                    model.saved.push(name);
+                    caps.render.render();
+                }
+                Event::Saved(Err(error)) => {
+                    caps.notify
+                        .show(format!("Couldn't save: {error}"), NotifyLevel::Error);
+                }
+                Event::Offline => {
+                    caps.notify
+                        .show_for("You're offline", NotifyLevel::Warn, NotifyDuration::Long)
+                }
+            }
+        }
+
+        fn view(&self, model: &Self::Model) -> Self::ViewModel {
+            model.saved.len()
+        }
+    }
+
+    #[derive(Effect)]
+    pub struct Capabilities {
+        pub notify: Notify<Event>,
+        pub render: Render<Event>,
+    }
+}
+
+mod tests {
+    use crate::shared::{App, Effect, Event, Model};
+    use crux_core::{testing::AppTester, ResolveError};
+    use crux_notify::{NotifyDuration, NotifyLevel, NotifyOperation};
+
+    #[test]
+    fn shows_notification_in_call_order() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let update = app.update(Event::Saved(Ok("notes".to_string())), &mut model);
+        let effects: Vec<_> = update.effects().collect();
+
+        let Effect::Notify(request) = effects[0] else {
+            panic!("expected a notification first");
+        };
+        assert_eq!(
+            request.operation,
+            NotifyOperation {
+                message: "Saved notes".to_string(),
+                level: NotifyLevel::Info,
+                duration: NotifyDuration::Short,
+            }
+        );
+        assert!(matches!(effects[1], Effect::Render(_)));
+    }
+
+    #[test]
+    fn shows_notification_for_longer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = app
+            .update(Event::Offline, &mut model)
+            .expect_one_effect()
+            .expect_notify();
+
+        assert_eq!(request.operation.level, NotifyLevel::Warn);
+        assert_eq!(request.operation.duration, NotifyDuration::Long);
+    }
+
+    #[test]
+    fn notification_expects_no_response() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::Saved(Err("disk full".to_string())), &mut model)
+            .expect_one_effect()
+            .expect_notify();
+        assert_eq!(request.operation.message, "Couldn't save: disk full");
+        assert_eq!(request.operation.level, NotifyLevel::Error);
+
+        let Err(error) = app.resolve(&mut request, ()) else {
+            panic!("expected the notification not to be resolvable");
+        };
+        assert_eq!(
+            error.downcast_ref::<ResolveError>(),
+            Some(&ResolveError::Never)
+        );
+    }
+}
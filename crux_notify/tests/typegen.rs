@@ -0,0 +1,21 @@
+#[cfg(feature = "typegen")]
+mod test {
+    use crux_core::{typegen::TypeGen, Capability};
+    use crux_notify::Notify;
+
+    #[test]
+    fn registers_the_operation_and_its_enums() {
+        let mut gen = TypeGen::new();
+
+        Notify::<()>::register_types(&mut gen).expect("should register types");
+
+        let crux_core::typegen::State::Registering(tracer, _) = gen.state else {
+            panic!("expected to still be in registering stage");
+        };
+        let registry = tracer.registry().expect("should get registry");
+
+        assert!(registry.contains_key("NotifyOperation"));
+        assert!(registry.contains_key("NotifyLevel"));
+        assert!(registry.contains_key("NotifyDuration"));
+    }
+}
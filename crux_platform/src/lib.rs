@@ -1,18 +1,128 @@
-//! TODO mod docs
+//! Information about the platform the shell is running on
+//!
+//! Which platform an app runs on, the locale the user has chosen, the size of the
+//! display and the state of the battery are side-effects as far as the core is concerned, so the core asks the shell
+//! for them with this capability.
 
 use crux_core::capability::{CapabilityContext, Operation};
 use crux_core::macros::Capability;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
-pub struct PlatformRequest;
+pub enum PlatformRequest {
+    /// A description of the platform as a single string, answered with
+    /// [`PlatformOutput::Name`]. Superseded by [`PlatformRequest::Info`].
+    #[default]
+    Name,
+    /// Structured information about the device and OS, answered with
+    /// [`PlatformOutput::Info`]
+    Info,
+    /// The size and density of the display, answered with [`PlatformOutput::Display`]
+    Display,
+    /// The state of the battery and power saving settings, answered with
+    /// [`PlatformOutput::Power`]
+    Power,
+}
 
-// TODO revisit this
+/// The description of the platform returned by the deprecated [`Platform::get`]
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlatformResponse(pub String);
 
+/// Information about the device and operating system the shell is running on.
+///
+/// Shells fill in what they can find out. Fields the shell can't supply are left as
+/// `None` or as an empty string.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    /// The name of the operating system, e.g. `"iOS"`, `"Android"` or `"macOS"`
+    pub os: String,
+    /// The version of the operating system, e.g. `"17.4"`
+    pub os_version: String,
+    /// The model of the device, e.g. `"iPhone15,2"`, if the shell can tell
+    pub model: Option<String>,
+    /// The user's preferred locale as a BCP 47 language tag, e.g. `"en-GB"`
+    pub locale: String,
+}
+
+/// The size and density of the display the app is shown on.
+///
+/// The size is in physical pixels. Layout decisions are usually made in logical pixels
+/// (points on iOS, density-independent pixels on Android, CSS pixels on the web), which
+/// are the physical size divided by the `scale`, see [`DisplayMetrics::logical_width`]
+/// and [`DisplayMetrics::logical_height`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DisplayMetrics {
+    /// The width of the display in physical pixels
+    pub width_px: u32,
+    /// The height of the display in physical pixels
+    pub height_px: u32,
+    /// The number of physical pixels per logical pixel, e.g. `2.0` or `3.0` on a
+    /// high density display, and `1.0` on a standard one
+    pub scale: f32,
+}
+
+impl DisplayMetrics {
+    /// The width of the display in logical pixels
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn logical_width(&self) -> f32 {
+        self.width_px as f32 / self.scale()
+    }
+
+    /// The height of the display in logical pixels
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn logical_height(&self) -> f32 {
+        self.height_px as f32 / self.scale()
+    }
+
+    // a shell which can't tell the density sends 0, treat that as a standard display
+    fn scale(&self) -> f32 {
+        if self.scale > 0.0 {
+            self.scale
+        } else {
+            1.0
+        }
+    }
+}
+
+/// The state of the device's power supply.
+///
+/// Shells fill in what they can find out. A device without a battery, e.g. a desktop
+/// computer, reports that it's charging with no `level`, and platforms without a
+/// power saving mode always report `low_power_mode` as `false`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PowerState {
+    /// Whether the device is connected to a power source
+    pub charging: bool,
+    /// How full the battery is, from `0.0` (empty) to `1.0` (full), if the shell can tell
+    pub level: Option<f32>,
+    /// Whether the user has turned on a power saving mode, e.g. Low Power Mode on iOS
+    /// or Battery Saver on Android
+    pub low_power_mode: bool,
+}
+
+/// The shell's answer to a [`PlatformRequest`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PlatformOutput {
+    Name(String),
+    Info(PlatformInfo),
+    Display(DisplayMetrics),
+    Power(PowerState),
+}
+
 impl Operation for PlatformRequest {
-    type Output = PlatformResponse;
+    type Output = PlatformOutput;
+
+    fn is_valid_output(&self, output: &Self::Output) -> bool {
+        matches!(
+            (self, output),
+            (PlatformRequest::Name, PlatformOutput::Name(_))
+                | (PlatformRequest::Info, PlatformOutput::Info(_))
+                | (PlatformRequest::Display, PlatformOutput::Display(_))
+                | (PlatformRequest::Power, PlatformOutput::Power(_))
+        )
+    }
 }
 
 #[derive(Capability)]
@@ -28,6 +138,9 @@ where
         Self { context }
     }
 
+    /// Request a description of the platform as a single string, which will be passed
+    /// to the app wrapped in the event produced by the `callback`.
+    #[deprecated(note = "use `Platform::info`, which returns structured information")]
     pub fn get<F>(&self, callback: F)
     where
         F: FnOnce(PlatformResponse) -> Ev + Send + Sync + 'static,
@@ -35,10 +148,292 @@ where
         self.context.spawn({
             let context = self.context.clone();
             async move {
-                let response = context.request_from_shell(PlatformRequest).await;
+                let response = match context.request_from_shell(PlatformRequest::Name).await {
+                    PlatformOutput::Name(name) => PlatformResponse(name),
+                    _ => {
+                        panic!(
+                            "attempt to convert PlatformOutput other than Name to PlatformResponse"
+                        )
+                    }
+                };
 
                 context.update_app(callback(response));
             }
         });
     }
+
+    /// Request information about the device and operating system, which will be passed
+    /// to the app as a [`PlatformInfo`] wrapped in the event produced by the `callback`.
+    pub fn info<F>(&self, callback: F)
+    where
+        F: FnOnce(PlatformInfo) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.update_app(callback(info(&context).await));
+            }
+        });
+    }
+
+    /// Request information about the device and operating system.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn info_async(&self) -> PlatformInfo {
+        info(&self.context).await
+    }
+
+    /// Request the size and density of the display, which will be passed to the app as
+    /// [`DisplayMetrics`] wrapped in the event produced by the `callback`.
+    ///
+    /// For example, to show a grid instead of a list on wider screens:
+    ///
+    /// ```rust,ignore
+    /// fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+    ///     match event {
+    ///         Event::Start => caps.platform.display(Event::DisplayMetrics),
+    ///         Event::DisplayMetrics(metrics) => {
+    ///             // compare logical pixels, so a phone with a high density display
+    ///             // still gets the list
+    ///             model.layout = if metrics.logical_width() >= 600.0 {
+    ///                 Layout::Grid
+    ///             } else {
+    ///                 Layout::List
+    ///             };
+    ///             caps.render.render();
+    ///         }
+    ///         // ...
+    ///     }
+    /// }
+    /// ```
+    pub fn display<F>(&self, callback: F)
+    where
+        F: FnOnce(DisplayMetrics) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.update_app(callback(display(&context).await));
+            }
+        });
+    }
+
+    /// Request the size and density of the display.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn display_async(&self) -> DisplayMetrics {
+        display(&self.context).await
+    }
+
+    /// Request the state of the battery and power saving settings, which will be passed
+    /// to the app as a [`PowerState`] wrapped in the event produced by the `callback`.
+    ///
+    /// For example, to only prefetch images when the user isn't trying to save battery:
+    ///
+    /// ```rust,ignore
+    /// fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+    ///     match event {
+    ///         Event::FeedLoaded(feed) => {
+    ///             model.feed = feed;
+    ///             caps.platform.power(Event::PowerState);
+    ///             caps.render.render();
+    ///         }
+    ///         Event::PowerState(power) => {
+    ///             let battery_low = power.level.is_some_and(|level| level < 0.2);
+    ///             if power.low_power_mode || (battery_low && !power.charging) {
+    ///                 return; // images will be fetched when they're shown instead
+    ///             }
+    ///             for url in model.feed.image_urls() {
+    ///                 caps.http.get(url).send(Event::ImagePrefetched);
+    ///             }
+    ///         }
+    ///         // ...
+    ///     }
+    /// }
+    /// ```
+    pub fn power<F>(&self, callback: F)
+    where
+        F: FnOnce(PowerState) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.update_app(callback(power(&context).await));
+            }
+        });
+    }
+
+    /// Request the state of the battery and power saving settings.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn power_async(&self) -> PowerState {
+        power(&self.context).await
+    }
+}
+
+async fn info<Ev: 'static>(context: &CapabilityContext<PlatformRequest, Ev>) -> PlatformInfo {
+    match context.request_from_shell(PlatformRequest::Info).await {
+        PlatformOutput::Info(info) => info,
+        _ => panic!("attempt to convert PlatformOutput other than Info to PlatformInfo"),
+    }
+}
+
+async fn display<Ev: 'static>(context: &CapabilityContext<PlatformRequest, Ev>) -> DisplayMetrics {
+    match context.request_from_shell(PlatformRequest::Display).await {
+        PlatformOutput::Display(metrics) => metrics,
+        _ => panic!("attempt to convert PlatformOutput other than Display to DisplayMetrics"),
+    }
+}
+
+async fn power<Ev: 'static>(context: &CapabilityContext<PlatformRequest, Ev>) -> PowerState {
+    match context.request_from_shell(PlatformRequest::Power).await {
+        PlatformOutput::Power(power) => power,
+        _ => panic!("attempt to convert PlatformOutput other than Power to PowerState"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serializing_platform_info_as_json() {
+        let info = PlatformInfo {
+            os: "iOS".to_string(),
+            os_version: "17.4".to_string(),
+            model: Some("iPhone15,2".to_string()),
+            locale: "en-GB".to_string(),
+        };
+
+        let serialized = serde_json::to_string(&info).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"os":"iOS","os_version":"17.4","model":"iPhone15,2","locale":"en-GB"}"#
+        );
+
+        let deserialized: PlatformInfo = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(info, deserialized);
+    }
+
+    #[test]
+    fn test_output_must_match_request() {
+        let name = PlatformOutput::Name("iOS".to_string());
+        let info = PlatformOutput::Info(PlatformInfo::default());
+
+        assert!(PlatformRequest::Name.is_valid_output(&name));
+        assert!(PlatformRequest::Info.is_valid_output(&info));
+        assert!(!PlatformRequest::Info.is_valid_output(&name));
+        assert!(!PlatformRequest::Display.is_valid_output(&info));
+
+        let power = PlatformOutput::Power(PowerState::default());
+        assert!(PlatformRequest::Power.is_valid_output(&power));
+        assert!(!PlatformRequest::Power.is_valid_output(&info));
+    }
+
+    #[test]
+    fn test_serializing_partial_platform_info_as_json() {
+        let info = PlatformInfo {
+            os: "Web".to_string(),
+            ..Default::default()
+        };
+
+        let serialized = serde_json::to_string(&info).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"os":"Web","os_version":"","model":null,"locale":""}"#
+        );
+
+        let deserialized: PlatformInfo = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(info, deserialized);
+    }
+
+    #[test]
+    fn test_serializing_the_request_and_output_as_json() {
+        let request = PlatformRequest::Info;
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(&serialized, r#""Info""#);
+
+        let deserialized: PlatformRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(request, deserialized);
+
+        let output = PlatformOutput::Info(PlatformInfo {
+            os: "Android".to_string(),
+            os_version: "14".to_string(),
+            model: None,
+            locale: "fr-FR".to_string(),
+        });
+
+        let serialized = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"Info":{"os":"Android","os_version":"14","model":null,"locale":"fr-FR"}}"#
+        );
+
+        let deserialized: PlatformOutput = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(output, deserialized);
+
+        let output = PlatformOutput::Display(DisplayMetrics {
+            width_px: 1170,
+            height_px: 2532,
+            scale: 3.0,
+        });
+
+        let serialized = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"Display":{"width_px":1170,"height_px":2532,"scale":3.0}}"#
+        );
+
+        let deserialized: PlatformOutput = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(output, deserialized);
+    }
+
+    #[test]
+    fn test_serializing_power_state_as_json() {
+        let output = PlatformOutput::Power(PowerState {
+            charging: false,
+            level: Some(0.5),
+            low_power_mode: true,
+        });
+
+        let serialized = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"Power":{"charging":false,"level":0.5,"low_power_mode":true}}"#
+        );
+
+        let deserialized: PlatformOutput = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(output, deserialized);
+
+        // a desktop without a battery
+        let power = PowerState {
+            charging: true,
+            ..Default::default()
+        };
+
+        let serialized = serde_json::to_string(&power).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"charging":true,"level":null,"low_power_mode":false}"#
+        );
+
+        let deserialized: PowerState = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(power, deserialized);
+    }
+
+    #[test]
+    fn test_logical_display_size() {
+        let retina = DisplayMetrics {
+            width_px: 1170,
+            height_px: 2532,
+            scale: 3.0,
+        };
+        assert!((retina.logical_width() - 390.0).abs() < f32::EPSILON);
+        assert!((retina.logical_height() - 844.0).abs() < f32::EPSILON);
+
+        let unknown_scale = DisplayMetrics {
+            width_px: 800,
+            height_px: 600,
+            scale: 0.0,
+        };
+        assert!((unknown_scale.logical_width() - 800.0).abs() < f32::EPSILON);
+    }
 }
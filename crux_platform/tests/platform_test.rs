@@ -1,7 +1,7 @@
 mod shared {
     use crux_core::macros::Effect;
     use crux_core::render::Render;
-    use crux_platform::{Platform, PlatformResponse};
+    use crux_platform::{DisplayMetrics, Platform, PlatformInfo, PlatformResponse, PowerState};
     use serde::{Deserialize, Serialize};
 
     #[derive(Default)]
@@ -11,16 +11,28 @@ mod shared {
     pub enum Event {
         PlatformGet,
         PlatformSet(PlatformResponse),
+        InfoGet,
+        InfoSet(PlatformInfo),
+        DisplayGet,
+        DisplaySet(DisplayMetrics),
+        PowerGet,
+        PowerSet(PowerState),
     }
 
     #[derive(Default, Serialize, Deserialize)]
     pub struct Model {
         pub platform: String,
+        pub info: Option<PlatformInfo>,
+        pub grid: bool,
+        pub prefetch: bool,
     }
 
     #[derive(Serialize, Deserialize, Default)]
     pub struct ViewModel {
         pub platform: String,
+        pub locale: Option<String>,
+        pub grid: bool,
+        pub prefetch: bool,
     }
 
     impl crux_core::App for App {
@@ -31,17 +43,36 @@ mod shared {
 
         fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
             match event {
+                #[allow(deprecated)]
                 Event::PlatformGet => caps.platform.get(Event::PlatformSet),
                 Event::PlatformSet(platform) => {
                     model.platform = platform.0;
                     caps.render.render()
                 }
+                Event::InfoGet => caps.platform.info(Event::InfoSet),
+                Event::InfoSet(info) => {
+                    model.info = Some(info);
+                    caps.render.render()
+                }
+                Event::DisplayGet => caps.platform.display(Event::DisplaySet),
+                Event::DisplaySet(metrics) => {
+                    model.grid = metrics.logical_width() >= 600.0;
+                    caps.render.render()
+                }
+                Event::PowerGet => caps.platform.power(Event::PowerSet),
+                Event::PowerSet(power) => {
+                    model.prefetch = power.charging && !power.low_power_mode;
+                    caps.render.render()
+                }
             }
         }
 
         fn view(&self, model: &Self::Model) -> Self::ViewModel {
             ViewModel {
                 platform: model.platform.clone(),
+                locale: model.info.as_ref().map(|info| info.locale.clone()),
+                grid: model.grid,
+                prefetch: model.prefetch,
             }
         }
     }
@@ -56,11 +87,13 @@ mod shared {
 mod shell {
     use super::shared::{App, Effect, Event};
     use crux_core::{Core, Request};
-    use crux_platform::{PlatformRequest, PlatformResponse};
+    use crux_platform::{
+        DisplayMetrics, PlatformInfo, PlatformOutput, PlatformRequest, PowerState,
+    };
     use std::collections::VecDeque;
 
     pub enum Outcome {
-        Platform(Request<PlatformRequest>, PlatformResponse),
+        Platform(Request<PlatformRequest>, PlatformOutput),
     }
 
     enum CoreMessage {
@@ -68,10 +101,10 @@ mod shell {
         Response(Outcome),
     }
 
-    pub fn run(core: &Core<Effect, App>) {
+    pub fn run(core: &Core<Effect, App>, event: Event) {
         let mut queue: VecDeque<CoreMessage> = VecDeque::new();
 
-        queue.push_back(CoreMessage::Event(Event::PlatformGet));
+        queue.push_back(CoreMessage::Event(event));
 
         while !queue.is_empty() {
             let msg = queue.pop_front();
@@ -79,7 +112,7 @@ mod shell {
             let effs = match msg {
                 Some(CoreMessage::Event(m)) => core.process_event(m),
                 Some(CoreMessage::Response(Outcome::Platform(mut request, outcome))) => {
-                    core.resolve(&mut request, outcome)
+                    core.resolve(&mut request, outcome).expect("should resolve")
                 }
 
                 _ => vec![],
@@ -87,10 +120,26 @@ mod shell {
 
             for effect in effs {
                 if let Effect::Platform(request) = effect {
-                    queue.push_back(CoreMessage::Response(Outcome::Platform(
-                        request,
-                        PlatformResponse("test shell".to_string()),
-                    )));
+                    let output = match request.operation {
+                        PlatformRequest::Name => PlatformOutput::Name("test shell".to_string()),
+                        PlatformRequest::Info => PlatformOutput::Info(PlatformInfo {
+                            os: "test OS".to_string(),
+                            os_version: "1.0".to_string(),
+                            model: None,
+                            locale: "en-GB".to_string(),
+                        }),
+                        PlatformRequest::Display => PlatformOutput::Display(DisplayMetrics {
+                            width_px: 2048,
+                            height_px: 1536,
+                            scale: 2.0,
+                        }),
+                        PlatformRequest::Power => PlatformOutput::Power(PowerState {
+                            charging: true,
+                            level: Some(0.8),
+                            low_power_mode: false,
+                        }),
+                    };
+                    queue.push_back(CoreMessage::Response(Outcome::Platform(request, output)));
                 }
             }
         }
@@ -99,7 +148,7 @@ mod shell {
 
 mod tests {
     use crate::{
-        shared::{App, Effect},
+        shared::{App, Effect, Event},
         shell::run,
     };
     use crux_core::Core;
@@ -108,8 +157,35 @@ mod tests {
     pub fn test_platform() {
         let core: Core<Effect, App> = Core::default();
 
-        run(&core);
+        run(&core, Event::PlatformGet);
 
         assert_eq!(core.view().platform, "test shell");
     }
+
+    #[test]
+    pub fn test_platform_info() {
+        let core: Core<Effect, App> = Core::default();
+
+        run(&core, Event::InfoGet);
+
+        assert_eq!(core.view().locale.as_deref(), Some("en-GB"));
+    }
+
+    #[test]
+    pub fn test_display_metrics() {
+        let core: Core<Effect, App> = Core::default();
+
+        run(&core, Event::DisplayGet);
+
+        assert!(core.view().grid);
+    }
+
+    #[test]
+    pub fn test_power_state() {
+        let core: Core<Effect, App> = Core::default();
+
+        run(&core, Event::PowerGet);
+
+        assert!(core.view().prefetch);
+    }
 }
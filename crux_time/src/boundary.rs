@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Instant;
+
+/// A boundary on the wall clock for a timer to fire on, e.g. the start of every minute,
+/// see [`Time::notify_at_next`](crate::Time::notify_at_next).
+///
+/// Boundaries are in UTC. The start of every second and minute is the same in every
+/// time zone, and so is the start of every hour, except in the few zones whose offset
+/// from UTC isn't a whole number of hours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+    Second,
+    Minute,
+    Hour,
+}
+
+impl Boundary {
+    /// The first boundary strictly after `now`. An instant exactly on a boundary is
+    /// followed by the next one, so a timer set for it never fires straight away.
+    #[must_use]
+    pub fn next_after(self, now: Instant) -> Instant {
+        let step = self.seconds();
+
+        Instant {
+            seconds: (now.seconds / step + 1) * step,
+            nanos: 0,
+        }
+    }
+
+    fn seconds(self) -> u64 {
+        match self {
+            Boundary::Second => 1,
+            Boundary::Minute => 60,
+            Boundary::Hour => 60 * 60,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 2024-01-01T12:34:56.789Z
+    const NOW: Instant = Instant {
+        seconds: 1_704_112_496,
+        nanos: 789_000_000,
+    };
+
+    #[test]
+    fn next_boundary_after_an_instant() {
+        assert_eq!(
+            Boundary::Second.next_after(NOW),
+            Instant::new(1_704_112_497, 0).unwrap()
+        );
+        // 12:35:00
+        assert_eq!(
+            Boundary::Minute.next_after(NOW),
+            Instant::new(1_704_112_500, 0).unwrap()
+        );
+        // 13:00:00
+        assert_eq!(
+            Boundary::Hour.next_after(NOW),
+            Instant::new(1_704_114_000, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_boundary_after_an_instant_on_a_boundary() {
+        // 12:35:00
+        let now = Instant::new(1_704_112_500, 0).unwrap();
+
+        assert_eq!(
+            Boundary::Second.next_after(now),
+            Instant::new(1_704_112_501, 0).unwrap()
+        );
+        assert_eq!(
+            Boundary::Minute.next_after(now),
+            Instant::new(1_704_112_560, 0).unwrap()
+        );
+    }
+}
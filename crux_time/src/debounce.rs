@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{Duration, Time, TimeResponse, TimerId};
+
+/// Tracks the pending timer of a debounced event, see [`Time::debounce`].
+///
+/// Keep one of these in the model for each debounced input. Clones share the same
+/// pending timer, so a model which derives `Clone` can hold one.
+#[derive(Clone, Debug, Default)]
+pub struct Debounce {
+    pending: Arc<Mutex<Option<TimerId>>>,
+}
+
+impl Debounce {
+    /// Whether a debounced event is waiting for its quiet window to elapse.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.pending.lock().unwrap().is_some()
+    }
+}
+
+impl<Ev> Time<Ev>
+where
+    Ev: Send + 'static,
+{
+    /// Dispatch `event` once `duration` has elapsed without another call to `debounce`
+    /// with the same `debounce` state.
+    ///
+    /// Each call supersedes the previous one: its timer is cleared and its event is
+    /// dropped, and a new timer is started for the latest `event`. Once a timer has
+    /// fired, it is forgotten, so the next call only starts a new one and doesn't ask
+    /// the shell to clear a timer which is already gone.
+    ///
+    /// For example, to search as the user types, but only once they pause:
+    ///
+    /// ```rust,ignore
+    /// fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+    ///     match event {
+    ///         Event::QueryChanged(query) => {
+    ///             model.query = query.clone();
+    ///             caps.time.debounce(
+    ///                 &model.search,
+    ///                 Duration::from_millis(300).expect("valid duration"),
+    ///                 Event::Search(query),
+    ///             );
+    ///         }
+    ///         Event::Search(query) => {
+    ///             caps.http
+    ///                 .get(SEARCH_URL)
+    ///                 .query(&Search { query })
+    ///                 .expect("query should serialize")
+    ///                 .expect_json()
+    ///                 .send(Event::SearchResults);
+    ///         }
+    ///         // ...
+    ///     }
+    /// }
+    /// ```
+    pub fn debounce(&self, debounce: &Debounce, duration: Duration, event: Ev) {
+        let superseded = debounce.pending.lock().unwrap().take();
+        if let Some(id) = superseded {
+            self.clear(id);
+        }
+
+        let (timer, id) = self.notify_after_async(duration);
+        *debounce.pending.lock().unwrap() = Some(id);
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let pending = debounce.pending.clone();
+
+            async move {
+                if let TimeResponse::DurationElapsed { .. } = timer.await {
+                    {
+                        let mut pending = pending.lock().unwrap();
+                        if *pending == Some(id) {
+                            *pending = None;
+                        }
+                    }
+                    context.update_app(event);
+                }
+            }
+        });
+    }
+}
@@ -41,6 +41,12 @@ impl Duration {
             .ok_or(TimeError::InvalidDuration)?;
         Ok(Self { nanos })
     }
+
+    /// The total number of nanoseconds in this `Duration`.
+    #[must_use]
+    pub fn as_nanos(&self) -> u64 {
+        self.nanos
+    }
 }
 
 #[cfg(feature = "chrono")]
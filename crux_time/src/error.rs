@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::Duration;
+
 pub type TimeResult<T> = Result<T, TimeError>;
 
 /// Error type for time operations
@@ -13,4 +15,15 @@ pub enum TimeError {
     InvalidDuration,
     #[error("invalid Instant")]
     InvalidInstant,
+    #[error("invalid UTC offset")]
+    InvalidUtcOffset,
+}
+
+/// Error returned by [`Time::timeout`](crate::Time::timeout) when the deadline passes
+/// before the wrapped future completes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[serde(rename_all = "camelCase")]
+#[error("timed out after {after:?}")]
+pub struct Timeout {
+    pub after: Duration,
 }
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use crate::{Time, TimerId};
+
+/// A handle to a running timer, returned by [`Time::every`].
+///
+/// Dropping the handle leaves the timer running, like a [`TimerId`] returned by the
+/// one-shot timers. To stop it, call [`TimerHandle::clear`].
+pub struct TimerHandle<Ev> {
+    id: TimerId,
+    time: Time<Ev>,
+}
+
+impl<Ev> TimerHandle<Ev>
+where
+    Ev: 'static,
+{
+    pub(crate) fn new(id: TimerId, time: Time<Ev>) -> Self {
+        Self { id, time }
+    }
+
+    /// The id of the timer, as it appears in [`TimeRequest`](crate::TimeRequest)s and
+    /// [`TimeResponse`](crate::TimeResponse)s.
+    #[must_use]
+    pub fn id(&self) -> TimerId {
+        self.id
+    }
+
+    /// Stop the timer, sending a [`TimeRequest::Clear`](crate::TimeRequest::Clear) to
+    /// the shell, exactly as [`Time::clear`] does.
+    pub fn clear(&self) {
+        self.time.clear(self.id);
+    }
+
+    /// Pause the timer, see [`Time::pause`].
+    pub fn pause(&self) {
+        self.time.pause(self.id);
+    }
+
+    /// Resume the timer, see [`Time::resume`].
+    pub fn resume(&self) {
+        self.time.resume(self.id);
+    }
+}
+
+impl<Ev> Clone for TimerHandle<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            time: self.time.clone(),
+        }
+    }
+}
+
+impl<Ev> fmt::Debug for TimerHandle<Ev> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerHandle").field("id", &self.id).finish()
+    }
+}
+
+/// A collection of [`TimerHandle`]s to clear together, e.g. all the timers of a screen
+/// the user navigates away from.
+///
+/// [`TimerHandleGroup::clear_all`] clears them with a single
+/// [`TimeRequest::ClearMany`](crate::TimeRequest::ClearMany), rather than a request
+/// per timer.
+///
+/// ```rust,ignore
+/// Event::OpenQuiz => {
+///     let ticker = caps.time.every(one_second, Event::Tick);
+///     let deadline = caps.time.notify_after(five_minutes, Event::TimeUp);
+///
+///     model.timers.push(ticker);
+///     model.timers.push(caps.time.handle(deadline));
+/// }
+/// Event::CloseQuiz => model.timers.clear_all(),
+/// ```
+pub struct TimerHandleGroup<Ev> {
+    handles: Vec<TimerHandle<Ev>>,
+}
+
+impl<Ev> TimerHandleGroup<Ev>
+where
+    Ev: 'static,
+{
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            handles: Vec::new(),
+        }
+    }
+
+    /// Add a timer to the group
+    pub fn push(&mut self, handle: TimerHandle<Ev>) {
+        self.handles.push(handle);
+    }
+
+    /// The ids of the timers in the group
+    pub fn ids(&self) -> impl Iterator<Item = TimerId> + '_ {
+        self.handles.iter().map(TimerHandle::id)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Clear all the timers in the group with [`Time::clear_many`], leaving the group
+    /// empty. Each of them resolves with
+    /// [`TimeResponse::Cleared`](crate::TimeResponse::Cleared).
+    pub fn clear_all(&mut self) {
+        let Some(first) = self.handles.first() else {
+            return;
+        };
+        let time = first.time.clone();
+
+        time.clear_many(self.handles.drain(..).map(|handle| handle.id));
+    }
+}
+
+impl<Ev> Default for TimerHandleGroup<Ev>
+where
+    Ev: 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ev> fmt::Debug for TimerHandleGroup<Ev> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.handles).finish()
+    }
+}
@@ -0,0 +1,140 @@
+//! Formatting elapsed time for people to read, e.g. "3 minutes ago"
+//!
+//! Formatting is pure, it doesn't need the current time. Get the elapsed [`Duration`]
+//! with [`Time::duration_since`](crate::Time::duration_since), or by subtracting one
+//! [`Instant`](crate::Instant) from another, and pass it to [`humanize`], or to
+//! [`humanize_with`] to use a [`Locale`] other than English.
+
+use crate::{duration::NANOS_PER_SEC, Duration};
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+const YEAR: u64 = 365 * DAY;
+
+/// The unit an elapsed time is described in, the largest one which fits at least once
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    /// 30 days
+    Month,
+    /// 365 days
+    Year,
+}
+
+/// The words used to describe an elapsed time, implement this to support a language
+pub trait Locale {
+    /// Describe `count` whole `unit`s in the past, e.g. "3 minutes ago". `count` is
+    /// never zero.
+    fn ago(&self, count: u64, unit: Unit) -> String;
+
+    /// Describe a time less than a minute in the past, e.g. "just now"
+    fn just_now(&self) -> String;
+}
+
+/// English descriptions, e.g. "1 hour ago" or "3 minutes ago"
+#[derive(Clone, Copy, Debug, Default)]
+pub struct English;
+
+impl Locale for English {
+    fn ago(&self, count: u64, unit: Unit) -> String {
+        let unit = match unit {
+            Unit::Minute => "minute",
+            Unit::Hour => "hour",
+            Unit::Day => "day",
+            Unit::Week => "week",
+            Unit::Month => "month",
+            Unit::Year => "year",
+        };
+        let plural = if count == 1 { "" } else { "s" };
+
+        format!("{count} {unit}{plural} ago")
+    }
+
+    fn just_now(&self) -> String {
+        "just now".to_string()
+    }
+}
+
+/// Describe the `elapsed` time in English, e.g. "3 minutes ago". See [`humanize_with`].
+#[must_use]
+pub fn humanize(elapsed: Duration) -> String {
+    humanize_with(elapsed, &English)
+}
+
+/// Describe the `elapsed` time in the given `locale`, in the largest [`Unit`] which
+/// fits at least once, rounded down, so 119 seconds is "1 minute ago". Anything under
+/// a minute is described as "just now".
+#[must_use]
+pub fn humanize_with(elapsed: Duration, locale: &impl Locale) -> String {
+    let seconds = elapsed.as_nanos() / u64::from(NANOS_PER_SEC);
+
+    [
+        (YEAR, Unit::Year),
+        (MONTH, Unit::Month),
+        (WEEK, Unit::Week),
+        (DAY, Unit::Day),
+        (HOUR, Unit::Hour),
+        (MINUTE, Unit::Minute),
+    ]
+    .into_iter()
+    .find(|(length, _)| seconds >= *length)
+    .map_or_else(
+        || locale.just_now(),
+        |(length, unit)| locale.ago(seconds / length, unit),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds).unwrap()
+    }
+
+    #[test]
+    fn humanize_in_english() {
+        assert_eq!(humanize(secs(0)), "just now");
+        assert_eq!(humanize(secs(59)), "just now");
+        assert_eq!(humanize(secs(60)), "1 minute ago");
+        assert_eq!(humanize(secs(119)), "1 minute ago");
+        assert_eq!(humanize(secs(3 * MINUTE)), "3 minutes ago");
+        assert_eq!(humanize(secs(HOUR)), "1 hour ago");
+        assert_eq!(humanize(secs(2 * DAY + HOUR)), "2 days ago");
+        assert_eq!(humanize(secs(13 * DAY)), "1 week ago");
+        assert_eq!(humanize(secs(65 * DAY)), "2 months ago");
+        assert_eq!(humanize(secs(3 * YEAR)), "3 years ago");
+    }
+
+    struct Abbreviated;
+
+    impl Locale for Abbreviated {
+        fn ago(&self, count: u64, unit: Unit) -> String {
+            let unit = match unit {
+                Unit::Minute => "m",
+                Unit::Hour => "h",
+                Unit::Day => "d",
+                Unit::Week => "w",
+                Unit::Month => "mo",
+                Unit::Year => "y",
+            };
+            format!("{count}{unit}")
+        }
+
+        fn just_now(&self) -> String {
+            "now".to_string()
+        }
+    }
+
+    #[test]
+    fn humanize_with_a_locale() {
+        assert_eq!(humanize_with(secs(30), &Abbreviated), "now");
+        assert_eq!(humanize_with(secs(5 * HOUR), &Abbreviated), "5h");
+    }
+}
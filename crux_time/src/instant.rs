@@ -1,12 +1,14 @@
+use std::ops::Sub;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{duration::NANOS_PER_SEC, error::TimeResult, TimeError};
+use crate::{duration::NANOS_PER_SEC, error::TimeResult, Duration, TimeError};
 
 /// Represents a point in time (UTC):
 ///
 /// - seconds: number of seconds since the Unix epoch (1970-01-01T00:00:00Z)
 /// - nanos: number of nanoseconds since the last second
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Instant {
     pub seconds: u64,
@@ -29,6 +31,37 @@ impl Instant {
     }
 }
 
+/// The [`Duration`] between two instants.
+///
+/// Subtracting a later instant from an earlier one saturates to a zero
+/// `Duration`, so a delta can never be negative.
+impl Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let nanos = |instant: Self| {
+            u128::from(instant.seconds) * u128::from(NANOS_PER_SEC) + u128::from(instant.nanos)
+        };
+        let delta = nanos(self).saturating_sub(nanos(rhs));
+
+        Duration::new(u64::try_from(delta).unwrap_or(u64::MAX))
+    }
+}
+
+impl TryFrom<std::time::SystemTime> for Instant {
+    type Error = TimeError;
+
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        let since_epoch = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|_| TimeError::InvalidTime)?;
+        Ok(Instant {
+            seconds: since_epoch.as_secs(),
+            nanos: since_epoch.subsec_nanos(),
+        })
+    }
+}
+
 #[cfg(feature = "chrono")]
 impl TryFrom<Instant> for chrono::DateTime<chrono::Utc> {
     type Error = TimeError;
@@ -70,6 +103,32 @@ mod test {
         let instant = Instant::new(1_000_000_000, 1_000_000_000);
         assert_eq!(instant.unwrap_err(), TimeError::InvalidInstant);
     }
+
+    #[test]
+    fn instant_from_system_time() {
+        let time = std::time::UNIX_EPOCH + std::time::Duration::new(1_000_000_000, 10);
+        let instant: Instant = time.try_into().unwrap();
+        assert_eq!(instant, Instant::new(1_000_000_000, 10).unwrap());
+
+        let before_epoch = std::time::UNIX_EPOCH - std::time::Duration::from_secs(1);
+        let instant: Result<Instant, _> = before_epoch.try_into();
+        assert_eq!(instant.unwrap_err(), TimeError::InvalidTime);
+    }
+
+    #[test]
+    fn instant_sub() {
+        let earlier = Instant::new(1, 999_999_999).unwrap();
+        let later = Instant::new(3, 1).unwrap();
+        assert_eq!(later - earlier, Duration::new(1_000_000_002));
+        assert_eq!(earlier - later, Duration::new(0));
+    }
+
+    #[test]
+    fn instants_are_ordered() {
+        let earlier = Instant::new(1, 999_999_999).unwrap();
+        let later = Instant::new(2, 0).unwrap();
+        assert!(earlier < later);
+    }
 }
 
 #[cfg(feature = "chrono")]
@@ -4,33 +4,61 @@
 //! more of a side-cause) by Crux, and has to be obtained externally. This capability provides a simple
 //! interface to do so.
 
+mod boundary;
+mod debounce;
 pub mod duration;
 pub mod error;
+mod handle;
+pub mod humanize;
 pub mod instant;
+pub mod monotonic;
+mod retry;
+pub mod testing;
+mod throttle;
+pub mod utc_offset;
 
+pub use boundary::Boundary;
+pub use debounce::Debounce;
 pub use duration::Duration;
-pub use error::TimeError;
+pub use error::{TimeError, Timeout};
+pub use handle::{TimerHandle, TimerHandleGroup};
 pub use instant::Instant;
+pub use monotonic::Monotonic;
+pub use retry::{Backoff, RetryError, RetryPolicy};
+pub use throttle::{Throttle, ThrottlePolicy};
+pub use utc_offset::UtcOffset;
 
+use futures::{
+    channel::oneshot,
+    future::{self, BoxFuture, Either},
+    Stream, StreamExt,
+};
 use serde::{Deserialize, Serialize};
 
 use crux_core::capability::{CapabilityContext, Operation};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     future::Future,
     pin::Pin,
     sync::atomic::{AtomicUsize, Ordering},
-    sync::{LazyLock, Mutex},
-    task::Poll,
+    sync::{Arc, Mutex},
+    task::{Poll, Waker},
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TimeRequest {
     Now,
+    MonotonicNow,
+    UtcOffset,
     NotifyAt { id: TimerId, instant: Instant },
     NotifyAfter { id: TimerId, duration: Duration },
+    NotifyEvery { id: TimerId, duration: Duration },
     Clear { id: TimerId },
+    ClearMany { ids: Vec<TimerId> },
+    Remaining { id: TimerId },
+    Pause { id: TimerId },
+    Resume { id: TimerId },
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -41,17 +69,59 @@ fn get_timer_id() -> TimerId {
     TimerId(COUNTER.fetch_add(1, Ordering::Relaxed))
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TimeResponse {
-    Now { instant: Instant },
-    InstantArrived { id: TimerId },
-    DurationElapsed { id: TimerId },
-    Cleared { id: TimerId },
+    Now {
+        instant: Instant,
+    },
+    Monotonic {
+        nanos: u64,
+    },
+    UtcOffset {
+        seconds: i32,
+        zone: Option<String>,
+    },
+    InstantArrived {
+        id: TimerId,
+    },
+    DurationElapsed {
+        id: TimerId,
+    },
+    Tick {
+        id: TimerId,
+    },
+    Cleared {
+        id: TimerId,
+    },
+    ClearedMany {
+        ids: Vec<TimerId>,
+    },
+    Remaining {
+        id: TimerId,
+        duration: Option<Duration>,
+    },
 }
 
 impl Operation for TimeRequest {
     type Output = TimeResponse;
+
+    fn is_valid_output(&self, output: &Self::Output) -> bool {
+        match self {
+            TimeRequest::Now => matches!(output, TimeResponse::Now { .. }),
+            TimeRequest::MonotonicNow => matches!(output, TimeResponse::Monotonic { .. }),
+            TimeRequest::UtcOffset => matches!(output, TimeResponse::UtcOffset { .. }),
+            TimeRequest::Remaining { .. } => matches!(output, TimeResponse::Remaining { .. }),
+            TimeRequest::ClearMany { .. } => matches!(output, TimeResponse::ClearedMany { .. }),
+            // timer responses are passed on to the app as they are
+            TimeRequest::NotifyAt { .. }
+            | TimeRequest::NotifyAfter { .. }
+            | TimeRequest::NotifyEvery { .. }
+            | TimeRequest::Clear { .. }
+            | TimeRequest::Pause { .. }
+            | TimeRequest::Resume { .. } => true,
+        }
+    }
 }
 
 /// The Time capability API
@@ -60,6 +130,7 @@ impl Operation for TimeRequest {
 /// notifications when a specific instant has arrived or a duration has elapsed.
 pub struct Time<Ev> {
     context: CapabilityContext<TimeRequest, Ev>,
+    timers: Arc<Timers>,
 }
 
 impl<Ev> crux_core::Capability<Ev> for Time<Ev> {
@@ -72,7 +143,10 @@ impl<Ev> crux_core::Capability<Ev> for Time<Ev> {
         Ev: 'static,
         NewEv: 'static + Send,
     {
-        Time::new(self.context.map_event(f))
+        Time {
+            context: self.context.map_event(f),
+            timers: self.timers.clone(),
+        }
     }
 
     #[cfg(feature = "typegen")]
@@ -89,6 +163,7 @@ impl<Ev> Clone for Time<Ev> {
     fn clone(&self) -> Self {
         Self {
             context: self.context.clone(),
+            timers: self.timers.clone(),
         }
     }
 }
@@ -98,7 +173,16 @@ where
     Ev: 'static,
 {
     pub fn new(context: CapabilityContext<TimeRequest, Ev>) -> Self {
-        Self { context }
+        Self {
+            context,
+            timers: Arc::default(),
+        }
+    }
+
+    /// Number the timers started from now on from 1, separately from other instances
+    /// of the capability. See [`testing::reset_timer_ids`].
+    pub(crate) fn reset_timer_ids(&self) {
+        *self.timers.next_id.lock().unwrap() = Some(1);
     }
 
     /// Request current time, which will be passed to the app as a [`TimeResponse`] containing an [`Instant`]
@@ -123,6 +207,112 @@ where
         self.context.request_from_shell(TimeRequest::Now).await
     }
 
+    /// Request the time elapsed since `earlier`, which will be passed to the app as a
+    /// [`Duration`] wrapped in the event produced by the `callback`.
+    ///
+    /// Only the current time comes from the shell, in a single [`Time::now`] request.
+    /// The difference is computed in the core, and is zero if `earlier` is in the
+    /// future. A [`std::time::SystemTime`] can be converted with [`Instant::try_from`].
+    /// To show the duration to the user, see [`humanize`].
+    ///
+    /// ```rust,ignore
+    /// Event::ShowPost(post) => {
+    ///     caps.time.duration_since(post.posted_at, Event::PostAge);
+    /// }
+    /// Event::PostAge(age) => {
+    ///     model.posted = humanize::humanize(age); // e.g. "3 minutes ago"
+    ///     caps.render.render();
+    /// }
+    /// ```
+    pub fn duration_since<F>(&self, earlier: Instant, callback: F)
+    where
+        F: FnOnce(Duration) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.duration_since_async(earlier).await));
+            }
+        });
+    }
+
+    /// Request the time elapsed since `earlier`.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn duration_since_async(&self, earlier: Instant) -> Duration {
+        match self.now_async().await {
+            TimeResponse::Now { instant } => instant - earlier,
+            _ => panic!("attempt to convert TimeResponse other than Now to Duration"),
+        }
+    }
+
+    /// Request a reading of the shell's monotonic clock, which will be passed to the app as
+    /// a [`Monotonic`] wrapped in the event produced by the `callback`.
+    ///
+    /// Unlike [`Time::now`], successive readings never go backwards, even if the wall clock
+    /// is adjusted, so the difference between two readings can be used to measure elapsed time.
+    /// The shell should use a monotonic source such as `CLOCK_MONOTONIC` or `performance.now()`.
+    pub fn instant_now<F>(&self, callback: F)
+    where
+        F: FnOnce(Monotonic) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.instant_now_async().await));
+            }
+        });
+    }
+
+    /// Request a reading of the shell's monotonic clock.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn instant_now_async(&self) -> Monotonic {
+        match self
+            .context
+            .request_from_shell(TimeRequest::MonotonicNow)
+            .await
+        {
+            TimeResponse::Monotonic { nanos } => Monotonic::new(nanos),
+            _ => panic!("attempt to convert TimeResponse other than Monotonic to Monotonic"),
+        }
+    }
+
+    /// Request the device's current offset from UTC, which will be passed to the app as
+    /// a [`UtcOffset`] wrapped in the event produced by the `callback`.
+    ///
+    /// The shell should compute the offset at the moment of the request, so that it reflects
+    /// any daylight saving time currently in effect, and include the IANA time zone name
+    /// if it is available.
+    pub fn utc_offset<F>(&self, callback: F)
+    where
+        F: FnOnce(UtcOffset) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.utc_offset_async().await));
+            }
+        });
+    }
+
+    /// Request the device's current offset from UTC.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn utc_offset_async(&self) -> UtcOffset {
+        match self
+            .context
+            .request_from_shell(TimeRequest::UtcOffset)
+            .await
+        {
+            TimeResponse::UtcOffset { seconds, zone } => UtcOffset::new(seconds, zone),
+            _ => panic!("attempt to convert TimeResponse other than UtcOffset to UtcOffset"),
+        }
+    }
+
     /// Ask to receive a notification when the specified [`Instant`] has arrived.
     pub fn notify_at<F>(&self, instant: Instant, callback: F) -> TimerId
     where
@@ -140,15 +330,29 @@ where
 
     /// Ask to receive a notification when the specified [`Instant`] has arrived.
     /// This is an async call to use with [`crux_core::compose::Compose`].
+    ///
+    /// The current time is requested from the shell first, and if `instant` is not
+    /// after it, the timer resolves with [`TimeResponse::InstantArrived`] straight away,
+    /// without the shell being asked to set a timer for an instant which has passed.
     pub fn notify_at_async(
         &self,
         instant: Instant,
     ) -> (TimerFuture<impl Future<Output = TimeResponse>>, TimerId) {
-        let id = get_timer_id();
-        let future = self
-            .context
-            .request_from_shell(TimeRequest::NotifyAt { id, instant });
-        (TimerFuture::new(id, future), id)
+        let id = self.timers.next_id();
+        let context = self.context.clone();
+        let future = Box::pin(async move {
+            match context.request_from_shell(TimeRequest::Now).await {
+                TimeResponse::Now { instant: now } if instant <= now => {
+                    TimeResponse::InstantArrived { id }
+                }
+                _ => {
+                    context
+                        .request_from_shell(TimeRequest::NotifyAt { id, instant })
+                        .await
+                }
+            }
+        });
+        (TimerFuture::new(self.timers.clone(), id, future), id)
     }
 
     /// Ask to receive a notification when the specified duration has elapsed.
@@ -172,19 +376,472 @@ where
         &self,
         duration: Duration,
     ) -> (TimerFuture<impl Future<Output = TimeResponse>>, TimerId) {
-        let id = get_timer_id();
+        let id = self.timers.next_id();
         let future = self
             .context
             .request_from_shell(TimeRequest::NotifyAfter { id, duration });
-        (TimerFuture::new(id, future), id)
+        (TimerFuture::new(self.timers.clone(), id, future), id)
+    }
+
+    /// Ask to receive a notification on the next `boundary` of the wall clock, e.g. at
+    /// the start of the next minute, rather than a minute from now.
+    ///
+    /// The current time is requested from the shell first, and the timer is set for the
+    /// first boundary after it with [`TimeRequest::NotifyAt`], so it resolves with
+    /// [`TimeResponse::InstantArrived`]. To keep firing on every boundary, e.g. for a
+    /// clock, use [`Time::notify_at_every`].
+    pub fn notify_at_next<F>(&self, boundary: Boundary, callback: F) -> TimerId
+    where
+        F: FnOnce(TimeResponse) -> Ev + Send + Sync + 'static,
+    {
+        let (future, id) = self.notify_at_next_async(boundary);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                context.update_app(callback(future.await));
+            }
+        });
+        id
+    }
+
+    /// Ask to receive a notification on the next `boundary` of the wall clock.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub fn notify_at_next_async(
+        &self,
+        boundary: Boundary,
+    ) -> (TimerFuture<impl Future<Output = TimeResponse>>, TimerId) {
+        let id = self.timers.next_id();
+        let future = Box::pin(notify_at_next(self.context.clone(), id, boundary));
+        (TimerFuture::new(self.timers.clone(), id, future), id)
+    }
+
+    /// Ask to receive a notification on every `boundary` of the wall clock, e.g. at the
+    /// start of every minute, until the timer is cleared with [`Time::clear`].
+    ///
+    /// ```rust,ignore
+    /// Event::StartClock => {
+    ///     model.clock = Some(caps.time.notify_at_every(Boundary::Minute, Event::Tick));
+    /// }
+    /// Event::Tick(TimeResponse::Tick { .. }) => caps.time.now(Event::ShowTime),
+    /// ```
+    ///
+    /// Unlike [`Time::every`], which fires each time a duration elapses and drifts by
+    /// however late each tick is, each notification is set for the next boundary after
+    /// the current time, fetched anew from the shell, so lateness doesn't add up.
+    ///
+    /// The `callback` is called with a [`TimeResponse::Tick`] on each boundary, and
+    /// finally with [`TimeResponse::Cleared`] once the timer has been cleared.
+    pub fn notify_at_every<F>(&self, boundary: Boundary, callback: F) -> TimerId
+    where
+        F: Fn(TimeResponse) -> Ev + Send + Sync + 'static,
+    {
+        let id = self.timers.next_id();
+        self.context.spawn({
+            let context = self.context.clone();
+            let timers = self.timers.clone();
+            async move {
+                loop {
+                    let future = Box::pin(notify_at_next(context.clone(), id, boundary));
+                    match TimerFuture::new(timers.clone(), id, future).await {
+                        TimeResponse::InstantArrived { id } => {
+                            context.update_app(callback(TimeResponse::Tick { id }));
+                        }
+                        response => {
+                            context.update_app(callback(response));
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        id
+    }
+
+    /// Wait for `duration` to elapse, then dispatch `event` to the app, e.g. to keep a
+    /// message on screen for a couple of seconds before moving on.
+    ///
+    /// This is a shorthand for [`Time::notify_after`] for when the app only needs to
+    /// know the time is up. The sleep is cancelled by passing the returned [`TimerId`]
+    /// to [`Time::clear`], and `event` is then never dispatched.
+    ///
+    /// ```rust
+    /// # mod app {
+    /// # use crux_core::{macros::Effect, render::Render};
+    /// # use crux_time::{Duration, Time, TimerId};
+    /// # #[derive(Default)]
+    /// # pub struct App;
+    /// pub enum Event {
+    ///     Saved,
+    ///     HideFlash,
+    /// }
+    ///
+    /// #[derive(Default)]
+    /// pub struct Model {
+    ///     flash: Option<String>,
+    ///     flash_timer: Option<TimerId>,
+    /// }
+    /// # #[derive(Effect)]
+    /// # pub struct Capabilities {
+    /// #     time: Time<Event>,
+    /// #     render: Render<Event>,
+    /// # }
+    /// # impl crux_core::App for App {
+    /// #     type Event = Event;
+    /// #     type Model = Model;
+    /// #     type ViewModel = Option<String>;
+    /// #     type Capabilities = Capabilities;
+    ///
+    /// fn update(&self, event: Event, model: &mut Model, caps: &Capabilities) {
+    ///     match event {
+    ///         Event::Saved => {
+    ///             // a newer message replaces the one on screen, and gets the full two seconds
+    ///             if let Some(timer) = model.flash_timer.take() {
+    ///                 caps.time.clear(timer);
+    ///             }
+    ///             model.flash = Some("Saved".to_string());
+    ///
+    ///             let two_seconds = Duration::from_secs(2).expect("valid duration");
+    ///             model.flash_timer = Some(caps.time.sleep(two_seconds, Event::HideFlash));
+    ///         }
+    ///         Event::HideFlash => {
+    ///             model.flash = None;
+    ///             model.flash_timer = None;
+    ///         }
+    ///     }
+    ///     caps.render.render();
+    /// }
+    /// #     fn view(&self, model: &Model) -> Self::ViewModel {
+    /// #         model.flash.clone()
+    /// #     }
+    /// # }
+    /// # }
+    /// ```
+    pub fn sleep(&self, duration: Duration, event: Ev) -> TimerId
+    where
+        Ev: Send,
+    {
+        let (future, id) = self.notify_after_async(duration);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                if let TimeResponse::DurationElapsed { .. } = future.await {
+                    context.update_app(event);
+                }
+            }
+        });
+        id
+    }
+
+    /// Wait until `instant` has arrived, then dispatch `event` to the app, e.g. for a
+    /// reminder which should go off at a set time.
+    ///
+    /// Unlike the duration of [`Time::sleep`], the deadline is absolute, so it still
+    /// holds if the app is suspended in the meantime. Shells often can't keep timers
+    /// running while the app is in the background, so call [`Time::check_deadlines`]
+    /// when the app comes back to the foreground: a deadline which passed while the app
+    /// was suspended fires straight away, and the others are set again from the current
+    /// time.
+    ///
+    /// The deadline is checked against the wall clock ([`TimeRequest::Now`]), not the
+    /// monotonic clock ([`TimeRequest::MonotonicNow`]). On most platforms the monotonic
+    /// clock stops while the device is asleep, so a deadline measured with it would be
+    /// late by the time spent asleep. The wall clock keeps going, but can be changed by
+    /// the user or by time synchronisation, and the deadline moves with it. For a delay
+    /// which should only count the time the app is running, use [`Time::sleep`].
+    ///
+    /// The sleep is cancelled by passing the returned [`TimerId`] to [`Time::clear`],
+    /// and `event` is then never dispatched.
+    pub fn sleep_until(&self, instant: Instant, event: Ev) -> TimerId
+    where
+        Ev: Send,
+    {
+        let (timer, id) = self.notify_at_async(instant);
+        let mut wake = self.timers.watch_deadline(id, instant);
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let timers = self.timers.clone();
+            async move {
+                let mut timer: BoxFuture<'static, TimeResponse> = Box::pin(timer);
+                let arrived = loop {
+                    match future::select(timer, wake).await {
+                        Either::Left((response, _)) => {
+                            break matches!(response, TimeResponse::InstantArrived { .. })
+                        }
+                        Either::Right((Ok(Wake::Fire), _)) => break true,
+                        Either::Right((Ok(Wake::Rearm), _)) => {
+                            let request = TimeRequest::NotifyAt { id, instant };
+                            timer = Box::pin(TimerFuture::new(
+                                timers.clone(),
+                                id,
+                                context.request_from_shell(request),
+                            ));
+                            wake = timers.watch_deadline(id, instant);
+                        }
+                        // the sleep was cleared
+                        Either::Right((Err(oneshot::Canceled), _)) => break false,
+                    }
+                };
+                timers.forget_deadline(id);
+
+                if arrived {
+                    context.update_app(event);
+                }
+            }
+        });
+        id
+    }
+
+    /// Check the deadlines of the pending [`Time::sleep_until`] calls against the current
+    /// time, e.g. when the shell lets the app know it has come back to the foreground.
+    ///
+    /// The shell is asked to clear the timers it was keeping for them. A deadline which
+    /// has passed then fires straight away, and the others are set again, for the shell
+    /// to work out the time remaining from the current time. Paused timers are left as
+    /// they are.
+    pub fn check_deadlines(&self) {
+        let context = self.context.clone();
+        let timers = self.timers.clone();
+        self.context.spawn(async move {
+            let TimeResponse::Now { instant: now } =
+                context.request_from_shell(TimeRequest::Now).await
+            else {
+                return;
+            };
+
+            let deadlines: Vec<(TimerId, Deadline)> = {
+                let paused = timers.paused.lock().unwrap();
+                let mut lock = timers.deadlines.lock().unwrap();
+                let mut ids: Vec<TimerId> = lock
+                    .keys()
+                    .filter(|id| !paused.contains_key(id))
+                    .copied()
+                    .collect();
+                ids.sort_by_key(|id| id.0);
+                ids.into_iter()
+                    .filter_map(|id| Some((id, lock.remove(&id)?)))
+                    .collect()
+            };
+            if deadlines.is_empty() {
+                return;
+            }
+
+            // the shell's timers are cleared before any of them are set again
+            let ids = deadlines.iter().map(|(id, _)| *id).collect();
+            context
+                .request_from_shell(TimeRequest::ClearMany { ids })
+                .await;
+
+            for (_, deadline) in deadlines {
+                let wake = if deadline.instant <= now {
+                    Wake::Fire
+                } else {
+                    Wake::Rearm
+                };
+                // the sleep may have been cleared in the meantime
+                let _ = deadline.wake.send(wake);
+            }
+        });
+    }
+
+    /// Race `future` against a timer, dispatching the event produced by `make_event` with
+    /// the output of `future` if it completes within `duration`, or with a [`Timeout`]
+    /// error otherwise.
+    ///
+    /// See [`Time::timeout_async`] for how the losing side is cleaned up.
+    pub fn timeout<T, Fut, F>(&self, duration: Duration, future: Fut, make_event: F)
+    where
+        T: Send,
+        Fut: Future<Output = T> + Send + 'static,
+        F: FnOnce(Result<T, Timeout>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(make_event(this.timeout_async(duration, future).await));
+            }
+        });
+    }
+
+    /// Race `future` against a timer, returning the output of `future` if it completes
+    /// within `duration`, or a [`Timeout`] error otherwise.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    ///
+    /// Whichever side loses is cleaned up: if `future` wins, the shell is asked to clear
+    /// the timer; if the timer wins, `future` is dropped. Any effects `future` has already
+    /// sent to the shell are not recalled, but their responses are ignored.
+    ///
+    /// ```rust,ignore
+    /// let response = time
+    ///     .timeout_async(Duration::from_secs(10)?, http.get(url).send_async())
+    ///     .await;
+    /// ```
+    pub async fn timeout_async<T>(
+        &self,
+        duration: Duration,
+        future: impl Future<Output = T>,
+    ) -> Result<T, Timeout> {
+        let (timer, id) = self.notify_after_async(duration);
+
+        match future::select(std::pin::pin!(future), timer).await {
+            Either::Left((output, _timer)) => {
+                self.context.notify_shell(TimeRequest::Clear { id }).await;
+                Ok(output)
+            }
+            Either::Right((_response, _future)) => Err(Timeout { after: duration }),
+        }
+    }
+
+    /// Ask to receive a notification every time the specified duration elapses,
+    /// until the timer is cleared with [`Time::clear`].
+    ///
+    /// The `callback` is called with a [`TimeResponse::Tick`] for each elapsed interval,
+    /// and finally with [`TimeResponse::Cleared`] once the timer has been cleared.
+    ///
+    /// The returned [`TimerHandle`] stops the interval with [`TimerHandle::clear`].
+    /// Dropping it leaves the interval running.
+    ///
+    /// ```rust,ignore
+    /// Event::StartPolling => {
+    ///     let every_minute = Duration::from_secs(60).expect("valid duration");
+    ///     model.polling = Some(caps.time.every(every_minute, Event::Poll));
+    /// }
+    /// Event::StopPolling => {
+    ///     if let Some(polling) = model.polling.take() {
+    ///         polling.clear();
+    ///     }
+    /// }
+    /// ```
+    pub fn every<F>(&self, duration: Duration, callback: F) -> TimerHandle<Ev>
+    where
+        F: Fn(TimeResponse) -> Ev + Send + Sync + 'static,
+    {
+        let (mut stream, handle) = self.every_async(duration);
+        self.context.spawn({
+            let context = self.context.clone();
+            async move {
+                while let Some(response) = stream.next().await {
+                    context.update_app(callback(response));
+                }
+            }
+        });
+        handle
+    }
+
+    /// Ask to receive a notification every time the specified duration elapses.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    ///
+    /// The returned stream yields a [`TimeResponse::Tick`] for each elapsed interval,
+    /// and ends after yielding [`TimeResponse::Cleared`] once the timer has been cleared.
+    /// The shell should stop resolving the request when it receives the matching
+    /// [`TimeRequest::Clear`].
+    pub fn every_async(
+        &self,
+        duration: Duration,
+    ) -> (
+        TimerStream<impl Stream<Item = TimeResponse>>,
+        TimerHandle<Ev>,
+    ) {
+        let id = self.timers.next_id();
+        let stream = self
+            .context
+            .stream_from_shell(TimeRequest::NotifyEvery { id, duration });
+        (
+            TimerStream::new(self.timers.clone(), id, stream),
+            TimerHandle::new(id, self.clone()),
+        )
+    }
+
+    /// Ask how much time is left before the timer with the given `id` fires, without
+    /// clearing it. The result will be passed to the app wrapped in the event produced
+    /// by the `callback`.
+    ///
+    /// The result is `None` if the timer has already fired or has been cleared. The shell
+    /// should also respond with `None` if the timer fires while the query is in flight.
+    pub fn remaining<F>(&self, id: TimerId, callback: F)
+    where
+        F: FnOnce(Option<Duration>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                context.update_app(callback(this.remaining_async(id).await));
+            }
+        });
+    }
+
+    /// Ask how much time is left before the timer with the given `id` fires.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    pub async fn remaining_async(&self, id: TimerId) -> Option<Duration> {
+        if self.timers.cleared.lock().unwrap().contains(&id) {
+            return None;
+        }
+
+        match self
+            .context
+            .request_from_shell(TimeRequest::Remaining { id })
+            .await
+        {
+            TimeResponse::Remaining {
+                id: response_id,
+                duration,
+            } if response_id == id => duration,
+            TimeResponse::Remaining { .. } => None,
+            _ => panic!("attempt to convert TimeResponse other than Remaining to Option<Duration>"),
+        }
+    }
+
+    /// Pause the timer with the given `id`. A paused timer will not resolve until it is
+    /// resumed with [`Time::resume`], but can still be cleared with [`Time::clear`].
+    ///
+    /// The shell is expected to keep track of the time remaining on the timer when it is
+    /// paused, and restart it with that remaining duration when it is resumed.
+    ///
+    /// Intervals started with [`Time::every`] can be paused too. The shell should stop
+    /// ticking while the interval is paused, and any tick which still arrives is held
+    /// back until it is resumed. Pausing a timer which has already finished does nothing.
+    pub fn pause(&self, id: TimerId) {
+        self.context.spawn({
+            self.timers.pause(id);
+
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(TimeRequest::Pause { id }).await;
+            }
+        });
+    }
+
+    /// Resume the timer with the given `id`, previously paused with [`Time::pause`].
+    pub fn resume(&self, id: TimerId) {
+        self.context.spawn({
+            self.timers.unpause(id);
+
+            let context = self.context.clone();
+            async move {
+                context.notify_shell(TimeRequest::Resume { id }).await;
+            }
+        });
+    }
+
+    /// Wrap the id of a timer started with one of the other methods in a
+    /// [`TimerHandle`], e.g. to collect it in a [`TimerHandleGroup`].
+    #[must_use]
+    pub fn handle(&self, id: TimerId) -> TimerHandle<Ev> {
+        TimerHandle::new(id, self.clone())
     }
 
     pub fn clear(&self, id: TimerId) {
         self.context.spawn({
             {
-                let mut lock = CLEARED_TIMER_IDS.lock().unwrap();
+                let mut lock = self.timers.cleared.lock().unwrap();
                 lock.insert(id);
             }
+            self.timers.unpause(id);
+            self.timers.forget_deadline(id);
 
             let context = self.context.clone();
             async move {
@@ -192,15 +849,67 @@ where
             }
         });
     }
+
+    /// Clear several timers at once, with a single request to the shell, e.g. the
+    /// timers of a screen the user navigates away from. See also [`TimerHandleGroup`].
+    ///
+    /// Each of the cleared timers resolves with [`TimeResponse::Cleared`], exactly as
+    /// if it had been cleared individually with [`Time::clear`]. The shell should
+    /// respond to the [`TimeRequest::ClearMany`] with [`TimeResponse::ClearedMany`]
+    /// once it has cleared them.
+    pub fn clear_many(&self, ids: impl IntoIterator<Item = TimerId>) {
+        let ids: Vec<TimerId> = ids.into_iter().collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        self.context.spawn({
+            {
+                let mut lock = self.timers.cleared.lock().unwrap();
+                lock.extend(ids.iter().copied());
+            }
+            for id in &ids {
+                self.timers.unpause(*id);
+                self.timers.forget_deadline(*id);
+            }
+
+            let context = self.context.clone();
+            async move {
+                context
+                    .request_from_shell(TimeRequest::ClearMany { ids })
+                    .await;
+            }
+        });
+    }
+}
+
+// fetches the current time, then sets the timer `id` for the next `boundary` after it
+async fn notify_at_next<Ev: 'static>(
+    context: CapabilityContext<TimeRequest, Ev>,
+    id: TimerId,
+    boundary: Boundary,
+) -> TimeResponse {
+    match context.request_from_shell(TimeRequest::Now).await {
+        TimeResponse::Now { instant: now } => {
+            let instant = boundary.next_after(now);
+            context
+                .request_from_shell(TimeRequest::NotifyAt { id, instant })
+                .await
+        }
+        _ => panic!("attempt to convert TimeResponse other than Now to Instant"),
+    }
 }
 
 pub struct TimerFuture<F>
 where
     F: Future<Output = TimeResponse> + Unpin,
 {
+    timers: Arc<Timers>,
     timer_id: TimerId,
     is_cleared: bool,
     future: F,
+    // the response from the shell, held back while the timer is paused
+    held: Option<TimeResponse>,
 }
 
 impl<F> Future for TimerFuture<F>
@@ -219,7 +928,7 @@ where
         };
         // see if the timer has been cleared
         let timer_is_cleared = {
-            let mut lock = CLEARED_TIMER_IDS.lock().unwrap();
+            let mut lock = self.timers.cleared.lock().unwrap();
             lock.remove(&self.timer_id)
         };
         let this = self.get_mut();
@@ -227,11 +936,21 @@ where
         if timer_is_cleared {
             // if the timer has been cleared, immediately return 'Ready' without
             // waiting for the timer to elapse
-            Poll::Ready(TimeResponse::Cleared { id: this.timer_id })
-        } else {
-            // otherwise, defer to the inner future
-            Pin::new(&mut this.future).poll(cx)
+            return Poll::Ready(TimeResponse::Cleared { id: this.timer_id });
+        }
+        // otherwise, defer to the inner future
+        if this.held.is_none() {
+            match Pin::new(&mut this.future).poll(cx) {
+                Poll::Ready(response) => this.held = Some(response),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        // if the timer is paused, hold on to the response until it is resumed
+        if let Some(waker) = this.timers.paused.lock().unwrap().get_mut(&this.timer_id) {
+            *waker = Some(cx.waker().clone());
+            return Poll::Pending;
         }
+        Poll::Ready(this.held.take().expect("timer response"))
     }
 }
 
@@ -239,26 +958,228 @@ impl<F> TimerFuture<F>
 where
     F: Future<Output = TimeResponse> + Unpin,
 {
-    fn new(timer_id: TimerId, future: F) -> Self {
+    fn new(timers: Arc<Timers>, timer_id: TimerId, future: F) -> Self {
+        timers.track(timer_id);
         Self {
+            timers,
             timer_id,
             future,
             is_cleared: false,
+            held: None,
+        }
+    }
+}
+
+impl<F> Drop for TimerFuture<F>
+where
+    F: Future<Output = TimeResponse> + Unpin,
+{
+    fn drop(&mut self) {
+        self.timers.untrack(self.timer_id);
+    }
+}
+
+pub struct TimerStream<S>
+where
+    S: Stream<Item = TimeResponse> + Unpin,
+{
+    timers: Arc<Timers>,
+    timer_id: TimerId,
+    is_finished: bool,
+    stream: S,
+    // the next response from the shell, held back while the timer is paused
+    held: Option<TimeResponse>,
+}
+
+impl<S> Stream for TimerStream<S>
+where
+    S: Stream<Item = TimeResponse> + Unpin,
+{
+    type Item = TimeResponse;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.is_finished {
+            return Poll::Ready(None);
+        }
+        // see if the timer has been cleared
+        let timer_is_cleared = {
+            let mut lock = self.timers.cleared.lock().unwrap();
+            lock.remove(&self.timer_id)
+        };
+        let this = self.get_mut();
+        if timer_is_cleared {
+            // yield a final 'Cleared' without waiting for the next tick
+            this.is_finished = true;
+            return Poll::Ready(Some(TimeResponse::Cleared { id: this.timer_id }));
+        }
+        // otherwise, defer to the inner stream
+        if this.held.is_none() {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(response)) => this.held = Some(response),
+                Poll::Ready(None) => {
+                    this.is_finished = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        // if the timer is paused, hold on to the response until it is resumed
+        if let Some(waker) = this.timers.paused.lock().unwrap().get_mut(&this.timer_id) {
+            *waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let next = this.held.take().expect("timer response");
+        if let TimeResponse::Cleared { .. } = next {
+            this.is_finished = true;
         }
+        Poll::Ready(Some(next))
     }
 }
 
-// Global HashSet containing the ids of timers which have been _cleared_
-// but the whose futures have _not since been polled_. When the future is next
-// polled, the timer id is evicted from this set and the timer is 'poisoned'
-// so as to return immediately without waiting on the shell.
-static CLEARED_TIMER_IDS: LazyLock<Mutex<HashSet<TimerId>>> =
-    LazyLock::new(|| Mutex::new(HashSet::new()));
+impl<S> TimerStream<S>
+where
+    S: Stream<Item = TimeResponse> + Unpin,
+{
+    fn new(timers: Arc<Timers>, timer_id: TimerId, stream: S) -> Self {
+        timers.track(timer_id);
+        Self {
+            timers,
+            timer_id,
+            stream,
+            is_finished: false,
+            held: None,
+        }
+    }
+}
+
+impl<S> Drop for TimerStream<S>
+where
+    S: Stream<Item = TimeResponse> + Unpin,
+{
+    fn drop(&mut self) {
+        self.timers.untrack(self.timer_id);
+    }
+}
+
+// The bookkeeping for the timers started through a `Time` capability, shared by its
+// clones and the capabilities mapped from it.
+#[derive(Default)]
+struct Timers {
+    // where the next timer id comes from: the global counter while this is `None`
+    next_id: Mutex<Option<usize>>,
+    // The ids of timers which have been _cleared_ but whose futures have _not since
+    // been polled_. When the future is next polled, the timer id is evicted from
+    // this set and the timer is 'poisoned' so as to return immediately without
+    // waiting on the shell.
+    cleared: Mutex<HashSet<TimerId>>,
+    // The ids of timers which have been _paused_. If the shell responds while the
+    // timer is paused, the future holds on to the response and parks its waker here,
+    // to be woken when the timer is resumed or cleared. Only timers which are still
+    // live can be paused, and the entry goes when the timer finishes.
+    paused: Mutex<HashMap<TimerId, Option<Waker>>>,
+    // The number of live futures and streams for each timer. There can be more than
+    // one for a while, when a `Time::sleep_until` timer is set again.
+    live: Mutex<HashMap<TimerId, usize>>,
+    // The deadlines of pending `Time::sleep_until` calls, so that
+    // `Time::check_deadlines` can wake them up. Dropping the channel (when the
+    // timer is cleared) ends the sleep without dispatching the event.
+    deadlines: Mutex<HashMap<TimerId, Deadline>>,
+}
+
+impl Timers {
+    fn next_id(&self) -> TimerId {
+        match self.next_id.lock().unwrap().as_mut() {
+            Some(next) => {
+                let id = TimerId(*next);
+                *next += 1;
+                id
+            }
+            None => get_timer_id(),
+        }
+    }
+
+    fn track(&self, id: TimerId) {
+        *self.live.lock().unwrap().entry(id).or_default() += 1;
+    }
+
+    fn untrack(&self, id: TimerId) {
+        let mut live = self.live.lock().unwrap();
+        if let Some(count) = live.get_mut(&id) {
+            *count -= 1;
+            if *count == 0 {
+                live.remove(&id);
+                self.paused.lock().unwrap().remove(&id);
+            }
+        }
+    }
+
+    fn pause(&self, id: TimerId) {
+        if self.live.lock().unwrap().contains_key(&id) {
+            self.paused.lock().unwrap().entry(id).or_default();
+        }
+    }
+
+    fn unpause(&self, id: TimerId) {
+        let waker = {
+            let mut lock = self.paused.lock().unwrap();
+            lock.remove(&id).flatten()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn watch_deadline(&self, id: TimerId, instant: Instant) -> oneshot::Receiver<Wake> {
+        let (wake, receiver) = oneshot::channel();
+        self.deadlines
+            .lock()
+            .unwrap()
+            .insert(id, Deadline { instant, wake });
+        receiver
+    }
+
+    fn forget_deadline(&self, id: TimerId) {
+        self.deadlines.lock().unwrap().remove(&id);
+    }
+}
+
+struct Deadline {
+    instant: Instant,
+    wake: oneshot::Sender<Wake>,
+}
+
+enum Wake {
+    // the deadline has passed, fire straight away
+    Fire,
+    // the deadline is still ahead, ask the shell for a new timer
+    Rearm,
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_paused_timers_are_forgotten_when_they_finish() {
+        let timers = Arc::new(Timers::default());
+        let id = TimerId(1);
+
+        // a timer which isn't running can't be paused
+        timers.pause(id);
+        assert!(timers.paused.lock().unwrap().is_empty());
+
+        let future = TimerFuture::new(timers.clone(), id, future::pending());
+        timers.pause(id);
+        assert!(timers.paused.lock().unwrap().contains_key(&id));
+
+        drop(future);
+        assert!(timers.paused.lock().unwrap().is_empty());
+        assert!(timers.live.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_serializing_the_request_types_as_json() {
         let now = TimeRequest::Now;
@@ -296,6 +1217,70 @@ mod test {
 
         let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
         assert_eq!(now, deserialized);
+
+        let every = TimeRequest::NotifyEvery {
+            id: TimerId(3),
+            duration: Duration::from_secs(1).expect("valid duration"),
+        };
+
+        let serialized = serde_json::to_string(&every).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"notifyEvery":{"id":3,"duration":{"nanos":1000000000}}}"#
+        );
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(every, deserialized);
+
+        let monotonic_now = TimeRequest::MonotonicNow;
+
+        let serialized = serde_json::to_string(&monotonic_now).unwrap();
+        assert_eq!(&serialized, "\"monotonicNow\"");
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(monotonic_now, deserialized);
+
+        let utc_offset = TimeRequest::UtcOffset;
+
+        let serialized = serde_json::to_string(&utc_offset).unwrap();
+        assert_eq!(&serialized, "\"utcOffset\"");
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(utc_offset, deserialized);
+
+        let clear_many = TimeRequest::ClearMany {
+            ids: vec![TimerId(4), TimerId(5)],
+        };
+
+        let serialized = serde_json::to_string(&clear_many).unwrap();
+        assert_eq!(&serialized, r#"{"clearMany":{"ids":[4,5]}}"#);
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(clear_many, deserialized);
+
+        let remaining = TimeRequest::Remaining { id: TimerId(6) };
+
+        let serialized = serde_json::to_string(&remaining).unwrap();
+        assert_eq!(&serialized, r#"{"remaining":{"id":6}}"#);
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(remaining, deserialized);
+
+        let pause = TimeRequest::Pause { id: TimerId(7) };
+
+        let serialized = serde_json::to_string(&pause).unwrap();
+        assert_eq!(&serialized, r#"{"pause":{"id":7}}"#);
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(pause, deserialized);
+
+        let resume = TimeRequest::Resume { id: TimerId(7) };
+
+        let serialized = serde_json::to_string(&resume).unwrap();
+        assert_eq!(&serialized, r#"{"resume":{"id":7}}"#);
+
+        let deserialized: TimeRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(resume, deserialized);
     }
 
     #[test]
@@ -328,5 +1313,59 @@ mod test {
 
         let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
         assert_eq!(now, deserialized);
+
+        let tick = TimeResponse::Tick { id: TimerId(3) };
+
+        let serialized = serde_json::to_string(&tick).unwrap();
+        assert_eq!(&serialized, r#"{"tick":{"id":3}}"#);
+
+        let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(tick, deserialized);
+
+        let monotonic = TimeResponse::Monotonic { nanos: 42 };
+
+        let serialized = serde_json::to_string(&monotonic).unwrap();
+        assert_eq!(&serialized, r#"{"monotonic":{"nanos":42}}"#);
+
+        let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(monotonic, deserialized);
+
+        let utc_offset = TimeResponse::UtcOffset {
+            seconds: 3600,
+            zone: Some("Europe/London".to_string()),
+        };
+
+        let serialized = serde_json::to_string(&utc_offset).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"utcOffset":{"seconds":3600,"zone":"Europe/London"}}"#
+        );
+
+        let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(utc_offset, deserialized);
+
+        let remaining = TimeResponse::Remaining {
+            id: TimerId(6),
+            duration: Some(Duration::new(500)),
+        };
+
+        let serialized = serde_json::to_string(&remaining).unwrap();
+        assert_eq!(
+            &serialized,
+            r#"{"remaining":{"id":6,"duration":{"nanos":500}}}"#
+        );
+
+        let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(remaining, deserialized);
+
+        let cleared_many = TimeResponse::ClearedMany {
+            ids: vec![TimerId(4), TimerId(5)],
+        };
+
+        let serialized = serde_json::to_string(&cleared_many).unwrap();
+        assert_eq!(&serialized, r#"{"clearedMany":{"ids":[4,5]}}"#);
+
+        let deserialized: TimeResponse = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(cleared_many, deserialized);
     }
 }
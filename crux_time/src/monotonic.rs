@@ -0,0 +1,53 @@
+use std::ops::Sub;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Duration;
+
+/// Represents a reading of a monotonic clock, in nanoseconds since an arbitrary
+/// (but fixed) point in the past.
+///
+/// Unlike an [`Instant`](crate::Instant), a `Monotonic` reading is not affected
+/// by changes to the wall clock, so it is suitable for measuring elapsed time.
+/// Readings are only meaningful relative to each other, and only within the
+/// same run of the app.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Monotonic(pub u64);
+
+impl Monotonic {
+    /// Create a new `Monotonic` reading from the given number of nanoseconds.
+    pub fn new(nanos: u64) -> Self {
+        Self(nanos)
+    }
+}
+
+/// The elapsed [`Duration`] between two readings.
+///
+/// Subtracting a later reading from an earlier one saturates to a zero
+/// `Duration`, so a delta can never be negative.
+impl Sub for Monotonic {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Duration::new(self.0.saturating_sub(rhs.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn monotonic_sub() {
+        let earlier = Monotonic::new(1_000);
+        let later = Monotonic::new(1_500);
+        assert_eq!(later - earlier, Duration::new(500));
+    }
+
+    #[test]
+    fn monotonic_sub_saturates() {
+        let earlier = Monotonic::new(1_000);
+        let later = Monotonic::new(1_500);
+        assert_eq!(earlier - later, Duration::new(0));
+    }
+}
@@ -0,0 +1,220 @@
+use std::{fmt, future::Future, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Duration, Time};
+
+/// How long [`Time::retry`] waits before each retry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backoff {
+    /// Retry straight away.
+    #[default]
+    None,
+    /// Wait the same amount of time before every retry.
+    Constant(Duration),
+    /// Wait `initial` before the first retry, doubling the wait before each one after
+    /// that, up to `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    /// The wait before the given retry, counting from 1.
+    #[must_use]
+    pub fn delay(&self, retry: u32) -> Duration {
+        match *self {
+            Backoff::None => Duration::new(0),
+            Backoff::Constant(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 1u64
+                    .checked_shl(retry.saturating_sub(1))
+                    .unwrap_or(u64::MAX);
+                let nanos = initial
+                    .as_nanos()
+                    .saturating_mul(factor)
+                    .min(max.as_nanos());
+                Duration::new(nanos)
+            }
+        }
+    }
+}
+
+type Retryable<E> = dyn Fn(&E) -> bool + Send + Sync;
+
+/// Describes when and how many times [`Time::retry`] retries a failed operation.
+pub struct RetryPolicy<E> {
+    max_retries: u32,
+    backoff: Backoff,
+    retryable: Option<Arc<Retryable<E>>>,
+}
+
+impl<E> RetryPolicy<E> {
+    /// Create a policy which retries any error up to `max_retries` times
+    /// (not including the original attempt), straight away.
+    #[must_use]
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            backoff: Backoff::None,
+            retryable: None,
+        }
+    }
+
+    /// Wait according to `backoff` before each retry.
+    #[must_use]
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Only retry errors for which `retryable` returns `true`. Any other error is
+    /// returned straight away.
+    #[must_use]
+    pub fn retry_if(mut self, retryable: impl Fn(&E) -> bool + Send + Sync + 'static) -> Self {
+        self.retryable = Some(Arc::new(retryable));
+        self
+    }
+
+    /// The maximum number of retries, not including the original attempt.
+    #[must_use]
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Whether the given error should be retried, not counting the number of retries.
+    #[must_use]
+    pub fn should_retry(&self, error: &E) -> bool {
+        self.retryable
+            .as_ref()
+            .map_or(true, |retryable| retryable(error))
+    }
+}
+
+impl<E> Clone for RetryPolicy<E> {
+    fn clone(&self) -> Self {
+        Self {
+            max_retries: self.max_retries,
+            backoff: self.backoff,
+            retryable: self.retryable.clone(),
+        }
+    }
+}
+
+impl<E> fmt::Debug for RetryPolicy<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_retries", &self.max_retries)
+            .field("backoff", &self.backoff)
+            .field("retry_if", &self.retryable.is_some())
+            .finish()
+    }
+}
+
+/// The error returned by [`Time::retry`], once an operation has failed with an error
+/// which should not be retried, or has run out of retries.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[error("failed after {attempts} attempt(s): {error}")]
+pub struct RetryError<E> {
+    /// The number of attempts made, including the original one
+    pub attempts: u32,
+    /// The error from the last attempt
+    pub error: E,
+}
+
+impl<Ev> Time<Ev>
+where
+    Ev: 'static,
+{
+    /// Run the future produced by `make_future`, and retry it according to `policy` for
+    /// as long as it fails, then dispatch the event produced by `make_event` with the
+    /// final result.
+    ///
+    /// See [`Time::retry_async`] for details.
+    pub fn retry<T, E, Fut, M, F>(&self, policy: RetryPolicy<E>, make_future: M, make_event: F)
+    where
+        T: Send,
+        E: Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send,
+        M: FnMut() -> Fut + Send + 'static,
+        F: FnOnce(Result<T, RetryError<E>>) -> Ev + Send + Sync + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+
+            async move {
+                let result = this.retry_async(&policy, make_future).await;
+                context.update_app(make_event(result));
+            }
+        });
+    }
+
+    /// Run the future produced by `make_future`, and retry it according to `policy` for
+    /// as long as it fails.
+    /// This is an async call to use with [`crux_core::compose::Compose`].
+    ///
+    /// `make_future` is called again for each attempt, so any effects are requested
+    /// afresh. Before each retry, a timer is requested from the shell for the wait given
+    /// by the policy's [`Backoff`], unless that wait is zero.
+    ///
+    /// ```rust,ignore
+    /// let policy = RetryPolicy::new(3)
+    ///     .backoff(Backoff::Exponential { initial, max })
+    ///     .retry_if(|error: &KeyValueError| matches!(error, KeyValueError::Io { .. }));
+    ///
+    /// let value = time.retry_async(&policy, || kv.get_async(key.clone())).await;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RetryError`] with the last error and the number of attempts made,
+    /// if the policy gives up.
+    pub async fn retry_async<T, E, Fut>(
+        &self,
+        policy: &RetryPolicy<E>,
+        mut make_future: impl FnMut() -> Fut,
+    ) -> Result<T, RetryError<E>>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+
+            let error = match make_future().await {
+                Ok(output) => return Ok(output),
+                Err(error) => error,
+            };
+
+            if attempts > policy.max_retries || !policy.should_retry(&error) {
+                return Err(RetryError { attempts, error });
+            }
+
+            let delay = policy.backoff.delay(attempts);
+            if delay.as_nanos() > 0 {
+                let (timer, _) = self.notify_after_async(delay);
+                timer.await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff_doubles_up_to_max() {
+        let backoff = Backoff::Exponential {
+            initial: Duration::new(100),
+            max: Duration::new(500),
+        };
+
+        let delays: Vec<_> = (1..=5)
+            .map(|retry| backoff.delay(retry).as_nanos())
+            .collect();
+        assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+
+        assert_eq!(backoff.delay(100).as_nanos(), 500);
+    }
+}
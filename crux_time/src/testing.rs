@@ -0,0 +1,391 @@
+//! Helpers for testing apps which use the [`Time`](crate::Time) capability.
+
+use std::collections::VecDeque;
+
+use crux_core::{
+    testing::{AppTester, Update},
+    App, Effect, Request,
+};
+
+use crate::{Duration, Instant, Time, TimeRequest, TimeResponse, TimerId};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Number the timers started through `time` from 1, instead of taking their ids from
+/// the counter shared by the whole process, so that tests which check timer ids get
+/// the same ids however many other tests run alongside them.
+///
+/// The numbering is shared by clones of `time` and the capabilities mapped from it, so
+/// resetting it on the app's capability covers its child apps too. Timers started
+/// before the reset keep their ids, which later timers may reuse.
+///
+/// ```rust,ignore
+/// let app = AppTester::<App, _>::default();
+/// reset_timer_ids(&app.as_ref().time);
+///
+/// let request = app.update(Event::Start, &mut model).expect_one_effect().expect_time();
+/// assert_eq!(request.operation, TimeRequest::NotifyAfter { id: TimerId(1), duration });
+/// ```
+pub fn reset_timer_ids<Ev>(time: &Time<Ev>)
+where
+    Ev: 'static,
+{
+    time.reset_timer_ids();
+}
+
+/// A clock which stands in for the shell in tests, resolving time requests against
+/// virtual time, which only moves when the test calls [`VirtualClock::advance`].
+///
+/// Hand the clock each [`Update`] from the [`AppTester`] with [`VirtualClock::settle`].
+/// It takes out the time effects and resolves the ones which don't need to wait, such
+/// as [`TimeRequest::Now`], straight away. Timers are held until virtual time reaches
+/// their deadline.
+///
+/// ```rust,ignore
+/// let app = AppTester::<App, _>::default();
+/// let mut model = Model::default();
+/// let mut clock = VirtualClock::new();
+///
+/// for query in ["c", "ca", "cat"] {
+///     let update = app.update(Event::QueryChanged(query.to_string()), &mut model);
+///     clock.settle(&app, &mut model, update).expect_no_effects();
+/// }
+///
+/// clock.advance(&app, &mut model, Duration::from_millis(300)?);
+/// assert_eq!(model.searches, vec!["cat"]);
+/// ```
+///
+/// The clock runs any events dispatched along the way through the app, so the
+/// [`Update`]s it returns only hold the effects other than time.
+pub struct VirtualClock {
+    start: u128,
+    elapsed: u128,
+    // time spent asleep, which the monotonic clock doesn't count
+    asleep: u128,
+    seq: u64,
+    timers: Vec<Timer>,
+}
+
+struct Timer {
+    id: TimerId,
+    kind: TimerKind,
+    state: TimerState,
+    // breaks ties between timers with the same deadline, in the order they were started
+    seq: u64,
+    request: Request<TimeRequest>,
+}
+
+#[derive(Clone, Copy)]
+enum TimerKind {
+    After,
+    At,
+    Every(u128),
+}
+
+#[derive(Clone, Copy)]
+enum TimerState {
+    Running { deadline: u128 },
+    Paused { remaining: u128 },
+}
+
+impl VirtualClock {
+    /// Create a clock starting at the Unix epoch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::starting_at(Instant {
+            seconds: 0,
+            nanos: 0,
+        })
+    }
+
+    /// Create a clock starting at the given `instant`.
+    #[must_use]
+    pub fn starting_at(instant: Instant) -> Self {
+        Self {
+            start: to_nanos(instant),
+            elapsed: 0,
+            asleep: 0,
+            seq: 0,
+            timers: Vec::new(),
+        }
+    }
+
+    /// The current virtual time.
+    #[must_use]
+    pub fn now(&self) -> Instant {
+        let nanos = self.start + self.elapsed;
+        Instant {
+            seconds: u64::try_from(nanos / NANOS_PER_SEC).expect("virtual time overflowed"),
+            nanos: u32::try_from(nanos % NANOS_PER_SEC).expect("less than a second"),
+        }
+    }
+
+    /// The number of timers which have been started and have not yet fired or been
+    /// cleared, including paused ones. A repeating timer is pending until cleared.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Take the time effects out of `update` and handle them, then keep running the
+    /// app with any resulting events until it settles.
+    ///
+    /// Returns the effects other than time requested along the way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a time request can't be resolved.
+    pub fn settle<A, Ef>(
+        &mut self,
+        app: &AppTester<A, Ef>,
+        model: &mut A::Model,
+        update: Update<Ef, A::Event>,
+    ) -> Update<Ef, A::Event>
+    where
+        A: App,
+        Ef: Effect,
+    {
+        let mut effects = Vec::new();
+        let mut updates = VecDeque::from([update]);
+
+        while let Some(update) = updates.pop_front() {
+            for effect in update.effects {
+                match effect.downcast::<TimeRequest>() {
+                    Ok(request) => updates.extend(self.handle(app, request)),
+                    Err(effect) => effects.push(effect),
+                }
+            }
+            for event in update.events {
+                updates.push_back(app.update(event, model));
+            }
+        }
+
+        Update {
+            effects,
+            events: Vec::new(),
+        }
+    }
+
+    /// Move virtual time forward by `duration`, firing every timer whose deadline
+    /// is reached on the way, in deadline order. Timers started by the app in
+    /// response fire too, if they are due before the end of `duration`.
+    ///
+    /// Returns the effects other than time requested along the way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a time request can't be resolved.
+    pub fn advance<A, Ef>(
+        &mut self,
+        app: &AppTester<A, Ef>,
+        model: &mut A::Model,
+        duration: Duration,
+    ) -> Update<Ef, A::Event>
+    where
+        A: App,
+        Ef: Effect,
+    {
+        let target = self.elapsed + u128::from(duration.as_nanos());
+        let mut effects = Vec::new();
+
+        while let Some(index) = self.next_due(target) {
+            let timer = &mut self.timers[index];
+            let TimerState::Running { deadline } = timer.state else {
+                unreachable!("paused timers are never due");
+            };
+            self.elapsed = deadline;
+
+            let update = match timer.kind {
+                TimerKind::Every(interval) => {
+                    let id = timer.id;
+                    self.seq += 1;
+                    timer.seq = self.seq;
+                    // a zero interval would otherwise tick forever
+                    timer.state = TimerState::Running {
+                        deadline: deadline + interval.max(1),
+                    };
+                    resolve(app, &mut timer.request, TimeResponse::Tick { id })
+                }
+                TimerKind::After | TimerKind::At => {
+                    let mut timer = self.timers.remove(index);
+                    let response = match timer.kind {
+                        TimerKind::At => TimeResponse::InstantArrived { id: timer.id },
+                        _ => TimeResponse::DurationElapsed { id: timer.id },
+                    };
+                    resolve(app, &mut timer.request, response)
+                }
+            };
+
+            effects.extend(self.settle(app, model, update).effects);
+        }
+        self.elapsed = target;
+
+        Update {
+            effects,
+            events: Vec::new(),
+        }
+    }
+
+    /// Move virtual time forward by `duration` as if the device was asleep, with the app
+    /// suspended. The wall clock moves on, but the monotonic clock doesn't, and the
+    /// running timers stand still like the shell's would, so none of them fire.
+    pub fn suspend(&mut self, duration: Duration) {
+        let duration = u128::from(duration.as_nanos());
+        self.elapsed += duration;
+        self.asleep += duration;
+
+        for timer in &mut self.timers {
+            if let TimerState::Running { deadline } = &mut timer.state {
+                *deadline += duration;
+            }
+        }
+    }
+
+    fn next_due(&self, target: u128) -> Option<usize> {
+        self.timers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, timer)| match timer.state {
+                TimerState::Running { deadline } if deadline <= target => {
+                    Some((deadline, timer.seq, index))
+                }
+                _ => None,
+            })
+            .min()
+            .map(|(_, _, index)| index)
+    }
+
+    fn handle<A, Ef>(
+        &mut self,
+        app: &AppTester<A, Ef>,
+        mut request: Request<TimeRequest>,
+    ) -> Option<Update<Ef, A::Event>>
+    where
+        A: App,
+    {
+        let response = match request.operation {
+            TimeRequest::Now => TimeResponse::Now {
+                instant: self.now(),
+            },
+            TimeRequest::MonotonicNow => TimeResponse::Monotonic {
+                nanos: u64::try_from(self.elapsed - self.asleep).expect("virtual time overflowed"),
+            },
+            TimeRequest::UtcOffset => TimeResponse::UtcOffset {
+                seconds: 0,
+                zone: None,
+            },
+            TimeRequest::NotifyAfter { id, duration } => {
+                let deadline = self.elapsed + u128::from(duration.as_nanos());
+                self.start_timer(id, TimerKind::After, deadline, request);
+                return None;
+            }
+            TimeRequest::NotifyAt { id, instant } => {
+                // an instant in the past fires on the next advance
+                let deadline = to_nanos(instant)
+                    .saturating_sub(self.start)
+                    .max(self.elapsed);
+                self.start_timer(id, TimerKind::At, deadline, request);
+                return None;
+            }
+            TimeRequest::NotifyEvery { id, duration } => {
+                let interval = u128::from(duration.as_nanos());
+                let deadline = self.elapsed + interval;
+                self.start_timer(id, TimerKind::Every(interval), deadline, request);
+                return None;
+            }
+            TimeRequest::Clear { id } => {
+                self.timers.retain(|timer| timer.id != id);
+                return None;
+            }
+            TimeRequest::ClearMany { ref ids } => {
+                self.timers.retain(|timer| !ids.contains(&timer.id));
+                TimeResponse::ClearedMany { ids: ids.clone() }
+            }
+            TimeRequest::Remaining { id } => TimeResponse::Remaining {
+                id,
+                duration: self.remaining(id),
+            },
+            TimeRequest::Pause { id } => {
+                let elapsed = self.elapsed;
+                if let Some(timer) = self.timer_mut(id) {
+                    if let TimerState::Running { deadline } = timer.state {
+                        timer.state = TimerState::Paused {
+                            remaining: deadline - elapsed,
+                        };
+                    }
+                }
+                return None;
+            }
+            TimeRequest::Resume { id } => {
+                let elapsed = self.elapsed;
+                self.seq += 1;
+                let seq = self.seq;
+                if let Some(timer) = self.timer_mut(id) {
+                    if let TimerState::Paused { remaining } = timer.state {
+                        timer.state = TimerState::Running {
+                            deadline: elapsed + remaining,
+                        };
+                        timer.seq = seq;
+                    }
+                }
+                return None;
+            }
+        };
+
+        Some(resolve(app, &mut request, response))
+    }
+
+    fn start_timer(
+        &mut self,
+        id: TimerId,
+        kind: TimerKind,
+        deadline: u128,
+        request: Request<TimeRequest>,
+    ) {
+        self.seq += 1;
+        self.timers.push(Timer {
+            id,
+            kind,
+            state: TimerState::Running { deadline },
+            seq: self.seq,
+            request,
+        });
+    }
+
+    fn remaining(&self, id: TimerId) -> Option<Duration> {
+        let timer = self.timers.iter().find(|timer| timer.id == id)?;
+        let remaining = match timer.state {
+            TimerState::Running { deadline } => deadline - self.elapsed,
+            TimerState::Paused { remaining } => remaining,
+        };
+        Some(Duration::new(
+            u64::try_from(remaining).expect("remaining time overflowed"),
+        ))
+    }
+
+    fn timer_mut(&mut self, id: TimerId) -> Option<&mut Timer> {
+        self.timers.iter_mut().find(|timer| timer.id == id)
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_nanos(instant: Instant) -> u128 {
+    u128::from(instant.seconds) * NANOS_PER_SEC + u128::from(instant.nanos)
+}
+
+fn resolve<A, Ef>(
+    app: &AppTester<A, Ef>,
+    request: &mut Request<TimeRequest>,
+    response: TimeResponse,
+) -> Update<Ef, A::Event>
+where
+    A: App,
+{
+    app.resolve(request, response)
+        .expect("time request should resolve")
+}
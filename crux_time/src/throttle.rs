@@ -0,0 +1,141 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Duration, Time};
+
+/// What [`Time::throttle`] does with events which arrive while throttled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThrottlePolicy {
+    /// Drop them. Only the event which opened the interval is dispatched.
+    #[default]
+    Leading,
+    /// Keep the latest of them and dispatch it when the interval ends, which opens
+    /// a new interval. Any earlier ones are dropped.
+    LeadingAndTrailing,
+}
+
+/// Tracks the interval of a throttled event, see [`Time::throttle`].
+///
+/// Keep one of these in the model for each throttled input. Clones share the same
+/// interval, so a model which derives `Clone` can hold one.
+pub struct Throttle<Ev> {
+    state: Arc<Mutex<State<Ev>>>,
+}
+
+struct State<Ev> {
+    throttled: bool,
+    trailing: Option<Ev>,
+}
+
+impl<Ev> Throttle<Ev> {
+    /// Whether an interval is running, during which events will not be dispatched
+    /// straight away.
+    #[must_use]
+    pub fn is_throttled(&self) -> bool {
+        self.state.lock().unwrap().throttled
+    }
+}
+
+impl<Ev> Default for Throttle<Ev> {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                throttled: false,
+                trailing: None,
+            })),
+        }
+    }
+}
+
+impl<Ev> Clone for Throttle<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<Ev> fmt::Debug for Throttle<Ev> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Throttle")
+            .field("throttled", &state.throttled)
+            .field("trailing", &state.trailing.is_some())
+            .finish()
+    }
+}
+
+impl<Ev> Time<Ev>
+where
+    Ev: Send + 'static,
+{
+    /// Dispatch at most one `event` per `duration` for the same `throttle` state.
+    ///
+    /// If no interval is running, `event` is dispatched straight away and an interval of
+    /// `duration` starts. Events which arrive during the interval are handled according
+    /// to `policy`: either dropped, or coalesced into the latest one, which is dispatched
+    /// when the interval ends.
+    ///
+    /// For example, to recompute a layout while the window is being resized:
+    ///
+    /// ```rust,ignore
+    /// Event::Resized(size) => {
+    ///     caps.time.throttle(
+    ///         &model.layout,
+    ///         Duration::from_millis(100).expect("valid duration"),
+    ///         ThrottlePolicy::LeadingAndTrailing,
+    ///         Event::Relayout(size),
+    ///     );
+    /// }
+    /// ```
+    pub fn throttle(
+        &self,
+        throttle: &Throttle<Ev>,
+        duration: Duration,
+        policy: ThrottlePolicy,
+        event: Ev,
+    ) {
+        {
+            let mut state = throttle.state.lock().unwrap();
+            if state.throttled {
+                if policy == ThrottlePolicy::LeadingAndTrailing {
+                    state.trailing = Some(event);
+                }
+                return;
+            }
+            state.throttled = true;
+        }
+
+        self.context.spawn({
+            let context = self.context.clone();
+            let this = self.clone();
+            let state = throttle.state.clone();
+
+            async move {
+                context.update_app(event);
+
+                loop {
+                    let (timer, _) = this.notify_after_async(duration);
+                    timer.await;
+
+                    let trailing = {
+                        let mut state = state.lock().unwrap();
+                        let trailing = state.trailing.take();
+                        state.throttled = trailing.is_some();
+                        trailing
+                    };
+
+                    match trailing {
+                        Some(event) => context.update_app(event),
+                        None => break,
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "chrono")]
+use crate::TimeError;
+
+/// Represents the device's offset from UTC at a particular moment:
+///
+/// - seconds: the offset from UTC in seconds, positive east of Greenwich
+/// - zone: the IANA time zone name (e.g. `"Europe/London"`), if the shell can provide one
+///
+/// The offset includes any daylight saving adjustment in effect at the time it was
+/// requested, so it should not be cached for long.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtcOffset {
+    pub seconds: i32,
+    pub zone: Option<String>,
+}
+
+impl UtcOffset {
+    /// Create a new `UtcOffset` from the given number of seconds east of UTC
+    /// and an optional IANA time zone name.
+    pub fn new(seconds: i32, zone: Option<String>) -> Self {
+        Self { seconds, zone }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<UtcOffset> for chrono::FixedOffset {
+    type Error = TimeError;
+
+    fn try_from(offset: UtcOffset) -> Result<Self, Self::Error> {
+        chrono::FixedOffset::east_opt(offset.seconds).ok_or(TimeError::InvalidUtcOffset)
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg(test)]
+mod chrono_test {
+    use super::*;
+
+    #[test]
+    fn utc_offset_to_fixed_offset() {
+        let offset = UtcOffset::new(3_600, Some("Europe/London".to_string()));
+        let fixed: chrono::FixedOffset = offset.try_into().unwrap();
+        assert_eq!(fixed.local_minus_utc(), 3_600);
+    }
+
+    #[test]
+    fn utc_offset_out_of_range() {
+        let offset = UtcOffset::new(86_400, None);
+        let fixed: Result<chrono::FixedOffset, _> = offset.try_into();
+        assert_eq!(fixed.unwrap_err(), TimeError::InvalidUtcOffset);
+    }
+}
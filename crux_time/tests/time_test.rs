@@ -3,7 +3,10 @@ mod shared {
     use chrono::{DateTime, Utc};
     use crux_core::macros::Effect;
     use crux_core::render::Render;
-    use crux_time::{Time, TimeResponse, TimerId};
+    use crux_time::{
+        humanize, Backoff, Boundary, Instant, RetryError, RetryPolicy, Throttle, ThrottlePolicy,
+        Time, TimeResponse, Timeout, TimerHandle, TimerHandleGroup, TimerId,
+    };
     use serde::{Deserialize, Serialize};
 
     #[derive(Default)]
@@ -18,6 +21,49 @@ mod shared {
         StartDebounce,
         DurationElapsed(usize, TimeResponse),
         Cancel(TimerId),
+        CancelMany(Vec<TimerId>),
+        QueryRemaining(TimerId),
+        Pause(TimerId),
+        Resume(TimerId),
+        Remaining(Option<crux_time::Duration>),
+
+        StartInterval,
+        IntervalTick(TimeResponse),
+        StopInterval,
+        PauseInterval,
+        ResumeInterval,
+
+        OpenScreen,
+        ScreenTimer(TimeResponse),
+        LeaveScreen,
+
+        GetWithTimeout,
+        GetOrTimeout(Result<TimeResponse, Timeout>),
+
+        QueryChanged(String),
+        Search(String),
+
+        Scrolled(u32, ThrottlePolicy),
+        Recompute(u32),
+
+        GetWithRetry,
+        GotWithRetry(Result<TimeResponse, RetryError<String>>),
+
+        NotifyAt(Instant),
+        Arrived(TimeResponse),
+
+        Flash(String),
+        HideFlash,
+
+        SetReminder(Instant),
+        Remind,
+        Foregrounded,
+
+        ShowPost(Instant),
+        PostAge(crux_time::Duration),
+
+        StartClock,
+        ClockTick(TimeResponse),
     }
 
     #[derive(Default)]
@@ -42,6 +88,24 @@ mod shared {
         debounce: Debounce,
         pub debounce_complete: bool,
         pub debounce_time_id: Option<TimerId>,
+        pub interval: Option<TimerHandle<Event>>,
+        pub screen_timers: TimerHandleGroup<Event>,
+        pub screen_responses: Vec<TimeResponse>,
+        pub ticks: usize,
+        pub remaining: Option<crux_time::Duration>,
+        pub timed_out: bool,
+        pub search: crux_time::Debounce,
+        pub searches: Vec<String>,
+        pub scroll: Throttle<Event>,
+        pub recomputed: Vec<u32>,
+        pub retried: Option<Result<TimeResponse, RetryError<String>>>,
+        pub arrived: Option<TimeResponse>,
+        pub flash: Option<String>,
+        pub flash_timer: Option<TimerId>,
+        pub reminder: Option<TimerId>,
+        pub reminded: usize,
+        pub posted: String,
+        pub clock_ticks: usize,
     }
 
     #[derive(Serialize, Deserialize, Default)]
@@ -100,6 +164,182 @@ mod shared {
                 Event::Cancel(timer_id) => {
                     caps.time.clear(timer_id);
                 }
+                Event::CancelMany(timer_ids) => {
+                    caps.time.clear_many(timer_ids);
+                }
+                Event::Pause(timer_id) => {
+                    caps.time.pause(timer_id);
+                }
+                Event::Resume(timer_id) => {
+                    caps.time.resume(timer_id);
+                }
+                Event::QueryRemaining(timer_id) => {
+                    caps.time.remaining(timer_id, Event::Remaining);
+                }
+                Event::Remaining(remaining) => {
+                    model.remaining = remaining;
+                }
+                Event::StartInterval => {
+                    let handle = caps.time.every(
+                        crux_time::Duration::from_secs(1).expect("valid duration"),
+                        Event::IntervalTick,
+                    );
+
+                    model.interval = Some(handle);
+                }
+                Event::IntervalTick(TimeResponse::Tick { .. }) => {
+                    model.ticks += 1;
+                }
+                Event::IntervalTick(TimeResponse::Cleared { id }) => {
+                    if model.interval.as_ref().map(TimerHandle::id) == Some(id) {
+                        model.interval = None;
+                    }
+                }
+                Event::IntervalTick(_) => {
+                    panic!("Unexpected interval event")
+                }
+                Event::StopInterval => {
+                    if let Some(interval) = &model.interval {
+                        interval.clear();
+                    }
+                }
+                Event::PauseInterval => {
+                    if let Some(interval) = &model.interval {
+                        interval.pause();
+                    }
+                }
+                Event::ResumeInterval => {
+                    if let Some(interval) = &model.interval {
+                        interval.resume();
+                    }
+                }
+                Event::OpenScreen => {
+                    let second = crux_time::Duration::from_secs(1).expect("valid duration");
+
+                    let ticker = caps.time.every(second, Event::ScreenTimer);
+                    let timeout = caps.time.notify_after(second, Event::ScreenTimer);
+
+                    model.screen_timers.push(ticker);
+                    model.screen_timers.push(caps.time.handle(timeout));
+                }
+                Event::ScreenTimer(response) => {
+                    model.screen_responses.push(response);
+                }
+                Event::LeaveScreen => {
+                    model.screen_timers.clear_all();
+                }
+                Event::GetWithTimeout => {
+                    let time = caps.time.clone();
+
+                    caps.time.timeout(
+                        crux_time::Duration::from_secs(10).expect("valid duration"),
+                        async move { time.now_async().await },
+                        Event::GetOrTimeout,
+                    );
+                }
+                Event::GetOrTimeout(Ok(response)) => {
+                    self.update(Event::Set(response), model, caps);
+                }
+                Event::GetOrTimeout(Err(Timeout { .. })) => {
+                    model.timed_out = true;
+                }
+                Event::QueryChanged(query) => {
+                    caps.time.debounce(
+                        &model.search,
+                        crux_time::Duration::from_millis(300).expect("valid duration"),
+                        Event::Search(query),
+                    );
+                }
+                Event::Search(query) => {
+                    model.searches.push(query);
+                }
+                Event::Scrolled(offset, policy) => {
+                    caps.time.throttle(
+                        &model.scroll,
+                        crux_time::Duration::from_millis(100).expect("valid duration"),
+                        policy,
+                        Event::Recompute(offset),
+                    );
+                }
+                Event::Recompute(offset) => {
+                    model.recomputed.push(offset);
+                }
+                Event::GetWithRetry => {
+                    let policy = RetryPolicy::new(2)
+                        .backoff(Backoff::Constant(
+                            crux_time::Duration::from_millis(100).expect("valid duration"),
+                        ))
+                        .retry_if(|error: &String| error != "fatal");
+                    let time = caps.time.clone();
+
+                    caps.time.retry(
+                        policy,
+                        move || {
+                            let time = time.clone();
+                            async move {
+                                // pretend the epoch is a transient failure, and a second
+                                // past it a permanent one
+                                match time.now_async().await {
+                                    TimeResponse::Now { instant } if instant == epoch(0) => {
+                                        Err("unavailable".to_string())
+                                    }
+                                    TimeResponse::Now { instant } if instant == epoch(1) => {
+                                        Err("fatal".to_string())
+                                    }
+                                    response => Ok(response),
+                                }
+                            }
+                        },
+                        Event::GotWithRetry,
+                    );
+                }
+                Event::GotWithRetry(result) => {
+                    model.retried = Some(result);
+                }
+                Event::NotifyAt(instant) => {
+                    caps.time.notify_at(instant, Event::Arrived);
+                }
+                Event::Arrived(response) => {
+                    model.arrived = Some(response);
+                }
+                Event::Flash(message) => {
+                    if let Some(timer) = model.flash_timer.take() {
+                        caps.time.clear(timer);
+                    }
+                    model.flash = Some(message);
+                    model.flash_timer = Some(
+                        caps.time
+                            .sleep(crux_time::Duration::from_secs(2).unwrap(), Event::HideFlash),
+                    );
+                }
+                Event::HideFlash => {
+                    model.flash = None;
+                    model.flash_timer = None;
+                }
+                Event::SetReminder(instant) => {
+                    model.reminder = Some(caps.time.sleep_until(instant, Event::Remind));
+                }
+                Event::Remind => {
+                    model.reminder = None;
+                    model.reminded += 1;
+                }
+                Event::Foregrounded => {
+                    caps.time.check_deadlines();
+                }
+                Event::ShowPost(posted_at) => {
+                    caps.time.duration_since(posted_at, Event::PostAge);
+                }
+                Event::PostAge(age) => {
+                    model.posted = humanize::humanize(age);
+                }
+                Event::StartClock => {
+                    caps.time
+                        .notify_at_every(Boundary::Minute, Event::ClockTick);
+                }
+                Event::ClockTick(TimeResponse::Tick { .. }) => {
+                    model.clock_ticks += 1;
+                }
+                Event::ClockTick(_) => {}
             }
         }
 
@@ -118,6 +358,10 @@ mod shared {
         pub compose: crux_core::compose::Compose<Event>,
     }
 
+    pub fn epoch(seconds: u64) -> Instant {
+        Instant::new(seconds, 0).expect("valid instant")
+    }
+
     /// Helper to create an event with additional user info captured
     /// this is effectively partially applying the event constructor
     pub fn event_with_user_info<E, F, U, T>(user_info: U, make_event: F) -> impl FnOnce(T) -> E
@@ -155,9 +399,9 @@ mod shell {
 
             let effs = match msg {
                 Some(CoreMessage::Event(m)) => core.process_event(m),
-                Some(CoreMessage::Response(Outcome::Time(mut request, instant))) => {
-                    core.resolve(&mut request, TimeResponse::Now { instant })
-                }
+                Some(CoreMessage::Response(Outcome::Time(mut request, instant))) => core
+                    .resolve(&mut request, TimeResponse::Now { instant })
+                    .expect("should resolve"),
                 _ => vec![],
             };
 
@@ -178,12 +422,17 @@ mod shell {
 #[cfg(feature = "chrono")]
 mod tests {
     use crate::{
-        shared::{App, Effect, Event, Model},
+        shared::{epoch, App, Effect, Event, Model},
         shell::run,
     };
     use chrono::{DateTime, Utc};
     use crux_core::{testing::AppTester, Core};
-    use crux_time::TimeResponse;
+    use crux_core::{testing::Update, Request};
+    use crux_time::{
+        testing::{reset_timer_ids, VirtualClock},
+        Instant, RetryError, ThrottlePolicy, TimeRequest, TimeResponse, Timeout, TimerId,
+    };
+    use std::time::SystemTime;
 
     #[test]
     pub fn test_time() {
@@ -284,4 +533,1066 @@ mod tests {
         };
         assert_eq!(id, timer_id);
     }
+
+    #[test]
+    pub fn test_interval_ticks_until_cleared() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut interval = app
+            .update(Event::StartInterval, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let timer_id = model.interval.as_ref().unwrap().id();
+
+        for _ in 0..3 {
+            let ev = app
+                .resolve(&mut interval, TimeResponse::Tick { id: timer_id })
+                .unwrap()
+                .expect_one_event();
+            app.update(ev, &mut model).assert_empty();
+        }
+        assert_eq!(model.ticks, 3);
+
+        let cancel = app
+            .update(Event::StopInterval, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(cancel.operation, TimeRequest::Clear { id: timer_id });
+
+        // as with one-shot timers, the cleared interval finishes the next time it is polled
+        let ev = app
+            .resolve(&mut interval, TimeResponse::Tick { id: timer_id })
+            .unwrap()
+            .expect_one_event();
+        let Event::IntervalTick(TimeResponse::Cleared { id }) = ev else {
+            panic!()
+        };
+        assert_eq!(id, timer_id);
+        app.update(ev, &mut model).assert_empty();
+
+        assert_eq!(model.ticks, 3);
+        assert!(model.interval.is_none());
+
+        // the stream has ended, so the shell can no longer resolve the request
+        assert!(app
+            .resolve(&mut interval, TimeResponse::Tick { id: timer_id })
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_clear_many_timers_at_once() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut debounce = app
+            .update(Event::StartDebounce, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let debounce_id = model.debounce_time_id.unwrap();
+
+        let mut interval = app
+            .update(Event::StartInterval, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let interval_id = model.interval.as_ref().unwrap().id();
+
+        let mut cancel = app
+            .update(
+                Event::CancelMany(vec![debounce_id, interval_id]),
+                &mut model,
+            )
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(
+            cancel.operation,
+            TimeRequest::ClearMany {
+                ids: vec![debounce_id, interval_id]
+            }
+        );
+        app.resolve(
+            &mut cancel,
+            TimeResponse::ClearedMany {
+                ids: vec![debounce_id, interval_id],
+            },
+        )
+        .unwrap()
+        .assert_empty();
+
+        let ev = app
+            .resolve(
+                &mut debounce,
+                TimeResponse::DurationElapsed { id: debounce_id },
+            )
+            .unwrap()
+            .expect_one_event();
+        let Event::DurationElapsed(_, TimeResponse::Cleared { id }) = ev else {
+            panic!()
+        };
+        assert_eq!(id, debounce_id);
+
+        let ev = app
+            .resolve(&mut interval, TimeResponse::Tick { id: interval_id })
+            .unwrap()
+            .expect_one_event();
+        let Event::IntervalTick(TimeResponse::Cleared { id }) = ev else {
+            panic!()
+        };
+        assert_eq!(id, interval_id);
+    }
+
+    #[test]
+    pub fn test_clear_timer_group_with_one_request() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::OpenScreen, &mut model);
+        assert_eq!(update.effects.len(), 2);
+        let mut timeout = update.effects.pop().unwrap().expect_time();
+        let mut ticker = update.effects.pop().unwrap().expect_time();
+        let ids: Vec<TimerId> = model.screen_timers.ids().collect();
+        assert_eq!(ids.len(), 2);
+
+        let mut cancel = app
+            .update(Event::LeaveScreen, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(
+            cancel.operation,
+            TimeRequest::ClearMany { ids: ids.clone() }
+        );
+        assert!(model.screen_timers.is_empty());
+
+        app.resolve(&mut cancel, TimeResponse::ClearedMany { ids: ids.clone() })
+            .unwrap()
+            .assert_empty();
+
+        for (request, response) in [
+            (&mut ticker, TimeResponse::Tick { id: ids[0] }),
+            (&mut timeout, TimeResponse::DurationElapsed { id: ids[1] }),
+        ] {
+            let ev = app.resolve(request, response).unwrap().expect_one_event();
+            app.update(ev, &mut model).assert_empty();
+        }
+        assert_eq!(
+            model.screen_responses,
+            vec![
+                TimeResponse::Cleared { id: ids[0] },
+                TimeResponse::Cleared { id: ids[1] }
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_remaining_time_on_pending_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let _debounce = app
+            .update(Event::StartDebounce, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let timer_id = model.debounce_time_id.unwrap();
+
+        let mut query = app
+            .update(Event::QueryRemaining(timer_id), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(query.operation, TimeRequest::Remaining { id: timer_id });
+
+        let remaining = Some(crux_time::Duration::from_millis(100).unwrap());
+        app.resolve_to_event_then_update(
+            &mut query,
+            TimeResponse::Remaining {
+                id: timer_id,
+                duration: remaining,
+            },
+            &mut model,
+        )
+        .assert_empty();
+        assert_eq!(model.remaining, remaining);
+
+        // the timer fires while the query is in flight
+        let mut query = app
+            .update(Event::QueryRemaining(timer_id), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        app.resolve_to_event_then_update(
+            &mut query,
+            TimeResponse::Remaining {
+                id: timer_id,
+                duration: None,
+            },
+            &mut model,
+        )
+        .assert_empty();
+        assert_eq!(model.remaining, None);
+    }
+
+    #[test]
+    pub fn test_remaining_time_on_cleared_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let _debounce = app
+            .update(Event::StartDebounce, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let timer_id = model.debounce_time_id.unwrap();
+
+        let _cancel = app
+            .update(Event::Cancel(timer_id), &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        // a cleared timer has no time remaining, without asking the shell
+        let ev = app
+            .update(Event::QueryRemaining(timer_id), &mut model)
+            .expect_one_event();
+        let Event::Remaining(None) = ev else { panic!() };
+    }
+
+    #[test]
+    pub fn test_pause_then_resume_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut debounce = app
+            .update(Event::StartDebounce, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let timer_id = model.debounce_time_id.unwrap();
+
+        let pause = app
+            .update(Event::Pause(timer_id), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(pause.operation, TimeRequest::Pause { id: timer_id });
+
+        // the shell responds while the timer is paused, which is held back
+        app.resolve(
+            &mut debounce,
+            TimeResponse::DurationElapsed { id: timer_id },
+        )
+        .unwrap()
+        .assert_empty();
+        assert!(!model.debounce_complete);
+
+        let mut update = app.update(Event::Resume(timer_id), &mut model);
+        assert_eq!(update.effects.len(), 1);
+        let resume = update.effects.pop().unwrap().expect_time();
+        assert_eq!(resume.operation, TimeRequest::Resume { id: timer_id });
+
+        // only now does the timer resolve
+        let ev = update.expect_one_event();
+        let Event::DurationElapsed(_, TimeResponse::DurationElapsed { id }) = ev else {
+            panic!()
+        };
+        assert_eq!(id, timer_id);
+        app.update(ev, &mut model).assert_empty();
+        assert!(model.debounce_complete);
+    }
+
+    #[test]
+    pub fn test_pause_then_resume_interval() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut interval = app
+            .update(Event::StartInterval, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let timer_id = model.interval.as_ref().unwrap().id();
+
+        let pause = app
+            .update(Event::PauseInterval, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(pause.operation, TimeRequest::Pause { id: timer_id });
+
+        // a tick which arrives while the interval is paused is held back
+        app.resolve(&mut interval, TimeResponse::Tick { id: timer_id })
+            .unwrap()
+            .assert_empty();
+        assert_eq!(model.ticks, 0);
+
+        let mut update = app.update(Event::ResumeInterval, &mut model);
+        assert_eq!(update.effects.len(), 1);
+        let resume = update.effects.pop().unwrap().expect_time();
+        assert_eq!(resume.operation, TimeRequest::Resume { id: timer_id });
+
+        let ev = update.expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+        assert_eq!(model.ticks, 1);
+
+        // and the interval keeps ticking after it is resumed
+        let ev = app
+            .resolve(&mut interval, TimeResponse::Tick { id: timer_id })
+            .unwrap()
+            .expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+        assert_eq!(model.ticks, 2);
+    }
+
+    #[test]
+    pub fn test_clear_paused_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut debounce = app
+            .update(Event::StartDebounce, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let timer_id = model.debounce_time_id.unwrap();
+
+        let _pause = app
+            .update(Event::Pause(timer_id), &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        let _cancel = app
+            .update(Event::Cancel(timer_id), &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        let ev = app
+            .resolve(
+                &mut debounce,
+                TimeResponse::DurationElapsed { id: timer_id },
+            )
+            .unwrap()
+            .expect_one_event();
+        let Event::DurationElapsed(_, TimeResponse::Cleared { id }) = ev else {
+            panic!()
+        };
+        assert_eq!(id, timer_id);
+    }
+
+    #[test]
+    pub fn test_timeout_not_reached() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::GetWithTimeout, &mut model);
+        assert_eq!(update.effects.len(), 2);
+        let timer = update.effects.pop().unwrap().expect_time();
+        let TimeRequest::NotifyAfter { id: timer_id, .. } = timer.operation else {
+            panic!("expected a timer request");
+        };
+        let mut now = update.effects.pop().unwrap().expect_time();
+        assert_eq!(now.operation, TimeRequest::Now);
+
+        let instant: DateTime<Utc> = "2022-12-01T01:47:12.746202562+00:00".parse().unwrap();
+        let mut update = app
+            .resolve(
+                &mut now,
+                TimeResponse::Now {
+                    instant: instant.try_into().unwrap(),
+                },
+            )
+            .unwrap();
+
+        // the timer is no longer needed, so the shell is asked to clear it
+        let clear = update.effects.pop().unwrap().expect_time();
+        assert_eq!(clear.operation, TimeRequest::Clear { id: timer_id });
+
+        let ev = update.expect_one_event();
+        let _update = app.update(ev, &mut model);
+        assert!(!model.timed_out);
+        assert_eq!(app.view(&model).time, "2022-12-01T01:47:12.746202562+00:00");
+    }
+
+    #[test]
+    pub fn test_timeout_reached() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::GetWithTimeout, &mut model);
+        assert_eq!(update.effects.len(), 2);
+        let mut timer = update.effects.pop().unwrap().expect_time();
+        let TimeRequest::NotifyAfter { id: timer_id, .. } = timer.operation else {
+            panic!("expected a timer request");
+        };
+        let mut now = update.effects.pop().unwrap().expect_time();
+
+        let ev = app
+            .resolve(&mut timer, TimeResponse::DurationElapsed { id: timer_id })
+            .unwrap()
+            .expect_one_event();
+        let Event::GetOrTimeout(Err(Timeout { after })) = ev else {
+            panic!("expected a timeout");
+        };
+        assert_eq!(after, crux_time::Duration::from_secs(10).unwrap());
+        app.update(ev, &mut model).assert_empty();
+        assert!(model.timed_out);
+
+        // the late response is ignored
+        let instant: DateTime<Utc> = "2022-12-01T01:47:12.746202562+00:00".parse().unwrap();
+        app.resolve(
+            &mut now,
+            TimeResponse::Now {
+                instant: instant.try_into().unwrap(),
+            },
+        )
+        .unwrap()
+        .assert_empty();
+        assert_eq!(app.view(&model).time, "");
+    }
+
+    #[test]
+    pub fn test_debounce_fires_latest_event_only() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut timers = Vec::new();
+        for query in ["c", "ca", "cat"] {
+            let update = app.update(Event::QueryChanged(query.to_string()), &mut model);
+            for effect in update.into_effects() {
+                let request = effect.expect_time();
+                match request.operation {
+                    TimeRequest::NotifyAfter { id, .. } => timers.push((id, request)),
+                    TimeRequest::Clear { id } => {
+                        // the previous timer is superseded
+                        assert_eq!(Some(id), timers.last().map(|(id, _)| *id));
+                    }
+                    _ => panic!("unexpected time request"),
+                }
+            }
+            assert!(model.search.is_pending());
+        }
+        assert_eq!(timers.len(), 3);
+
+        // the superseded timers resolve without an event
+        for (id, request) in &mut timers[..2] {
+            app.resolve(request, TimeResponse::DurationElapsed { id: *id })
+                .unwrap()
+                .assert_empty();
+        }
+        assert!(model.searches.is_empty());
+
+        let (id, request) = &mut timers[2];
+        let ev = app
+            .resolve(request, TimeResponse::DurationElapsed { id: *id })
+            .unwrap()
+            .expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+
+        assert_eq!(model.searches, vec!["cat"]);
+        assert!(!model.search.is_pending());
+    }
+
+    #[test]
+    pub fn test_debounce_after_timer_fired_does_not_clear_it() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::QueryChanged("cat".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        let TimeRequest::NotifyAfter { id, .. } = request.operation else {
+            panic!("expected a timer request");
+        };
+        let ev = app
+            .resolve(&mut request, TimeResponse::DurationElapsed { id })
+            .unwrap()
+            .expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+        assert!(!model.search.is_pending());
+
+        // only a new timer, no clear for the one which already fired
+        let request = app
+            .update(Event::QueryChanged("cats".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert!(matches!(request.operation, TimeRequest::NotifyAfter { .. }));
+        assert!(model.search.is_pending());
+    }
+
+    #[test]
+    pub fn test_debounce_clones_share_the_pending_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let search = model.search.clone();
+
+        let mut request = app
+            .update(Event::QueryChanged("cat".to_string()), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert!(search.is_pending());
+
+        let TimeRequest::NotifyAfter { id, .. } = request.operation else {
+            panic!("expected a timer request");
+        };
+        let _ev = app
+            .resolve(&mut request, TimeResponse::DurationElapsed { id })
+            .unwrap()
+            .expect_one_event();
+        assert!(!search.is_pending());
+    }
+
+    fn elapse(
+        app: &AppTester<App, Effect>,
+        request: &mut Request<TimeRequest>,
+    ) -> Update<Effect, Event> {
+        let TimeRequest::NotifyAfter { id, .. } = request.operation else {
+            panic!("expected a timer request");
+        };
+        app.resolve(request, TimeResponse::DurationElapsed { id })
+            .unwrap()
+    }
+
+    #[test]
+    pub fn test_throttle_drops_events_during_interval() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut update = app.update(Event::Scrolled(1, ThrottlePolicy::Leading), &mut model);
+        let mut timer = update.effects.pop().unwrap().expect_time();
+        for ev in update.events {
+            app.update(ev, &mut model).assert_empty();
+        }
+        assert_eq!(model.recomputed, vec![1]);
+        assert!(model.scroll.is_throttled());
+
+        for offset in [2, 3] {
+            app.update(Event::Scrolled(offset, ThrottlePolicy::Leading), &mut model)
+                .assert_empty();
+        }
+
+        elapse(&app, &mut timer).assert_empty();
+        assert!(!model.scroll.is_throttled());
+
+        // the next event opens a new interval
+        let update = app.update(Event::Scrolled(4, ThrottlePolicy::Leading), &mut model);
+        assert_eq!(update.effects.len(), 1);
+        for ev in update.events {
+            app.update(ev, &mut model).assert_empty();
+        }
+        assert_eq!(model.recomputed, vec![1, 4]);
+    }
+
+    #[test]
+    pub fn test_throttle_clones_share_the_interval() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let scroll = model.scroll.clone();
+
+        let mut update = app.update(Event::Scrolled(1, ThrottlePolicy::Leading), &mut model);
+        let mut timer = update.effects.pop().unwrap().expect_time();
+        assert!(scroll.is_throttled());
+
+        elapse(&app, &mut timer).assert_empty();
+        assert!(!scroll.is_throttled());
+    }
+
+    #[test]
+    pub fn test_throttle_fires_latest_trailing_event() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let policy = ThrottlePolicy::LeadingAndTrailing;
+
+        let mut update = app.update(Event::Scrolled(1, policy), &mut model);
+        let mut timer = update.effects.pop().unwrap().expect_time();
+        for ev in update.events {
+            app.update(ev, &mut model).assert_empty();
+        }
+
+        for offset in [2, 3] {
+            app.update(Event::Scrolled(offset, policy), &mut model)
+                .assert_empty();
+        }
+
+        // the latest event is dispatched at the end of the interval, opening another
+        let mut update = elapse(&app, &mut timer);
+        let mut timer = update.effects.pop().unwrap().expect_time();
+        for ev in update.events {
+            app.update(ev, &mut model).assert_empty();
+        }
+        assert_eq!(model.recomputed, vec![1, 3]);
+        assert!(model.scroll.is_throttled());
+
+        elapse(&app, &mut timer).assert_empty();
+        assert!(!model.scroll.is_throttled());
+        assert_eq!(model.recomputed, vec![1, 3]);
+    }
+
+    fn fail_then_back_off(app: &AppTester<App, Effect>, request: &mut Request<TimeRequest>) {
+        let timer = &mut app
+            .resolve(request, TimeResponse::Now { instant: epoch(0) })
+            .unwrap()
+            .expect_one_effect()
+            .expect_time();
+        let TimeRequest::NotifyAfter { duration, .. } = timer.operation else {
+            panic!("expected a backoff timer");
+        };
+        assert_eq!(duration, crux_time::Duration::from_millis(100).unwrap());
+
+        *request = elapse(app, timer).expect_one_effect().expect_time();
+        assert_eq!(request.operation, TimeRequest::Now);
+    }
+
+    #[test]
+    pub fn test_retry_until_success() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithRetry, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        fail_then_back_off(&app, request);
+
+        let now = TimeResponse::Now { instant: epoch(2) };
+        let ev = app
+            .resolve(request, now.clone())
+            .unwrap()
+            .expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+
+        assert_eq!(model.retried, Some(Ok(now)));
+    }
+
+    #[test]
+    pub fn test_retry_exhausted() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithRetry, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        fail_then_back_off(&app, request);
+        fail_then_back_off(&app, request);
+
+        let ev = app
+            .resolve(request, TimeResponse::Now { instant: epoch(0) })
+            .unwrap()
+            .expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+
+        assert_eq!(
+            model.retried,
+            Some(Err(RetryError {
+                attempts: 3,
+                error: "unavailable".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    pub fn test_retry_stops_on_error_which_is_not_retryable() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::GetWithRetry, &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        let ev = app
+            .resolve(request, TimeResponse::Now { instant: epoch(1) })
+            .unwrap()
+            .expect_one_event();
+        app.update(ev, &mut model).assert_empty();
+
+        assert_eq!(
+            model.retried,
+            Some(Err(RetryError {
+                attempts: 1,
+                error: "fatal".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    pub fn test_notify_at_in_the_past_fires_immediately() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let a_day_ago = SystemTime::now() - std::time::Duration::from_secs(24 * 60 * 60);
+        let mut request = app
+            .update(Event::NotifyAt(a_day_ago.try_into().unwrap()), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(request.operation, TimeRequest::Now);
+
+        let response = TimeResponse::Now {
+            instant: SystemTime::now().try_into().unwrap(),
+        };
+        let event = app
+            .resolve(&mut request, response)
+            .expect("should resolve")
+            .expect_one_event();
+        let Event::Arrived(TimeResponse::InstantArrived { .. }) = event else {
+            panic!("expected the instant to have arrived");
+        };
+    }
+
+    #[test]
+    pub fn test_notify_at_in_the_future_sets_a_timer() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::NotifyAt(epoch(2_000)), &mut model)
+            .expect_one_effect()
+            .expect_time();
+
+        let mut timer = app
+            .resolve(
+                &mut request,
+                TimeResponse::Now {
+                    instant: epoch(1_000),
+                },
+            )
+            .expect("should resolve")
+            .expect_one_effect()
+            .expect_time();
+        let TimeRequest::NotifyAt { id, instant } = timer.operation else {
+            panic!("expected a timer to be set");
+        };
+        assert_eq!(instant, epoch(2_000));
+
+        app.resolve_to_event_then_update(
+            &mut timer,
+            TimeResponse::InstantArrived { id },
+            &mut model,
+        )
+        .assert_empty();
+        assert_eq!(model.arrived, Some(TimeResponse::InstantArrived { id }));
+    }
+
+    fn millis(millis: u64) -> crux_time::Duration {
+        crux_time::Duration::from_millis(millis).expect("valid duration")
+    }
+
+    #[test]
+    pub fn test_virtual_clock_debounce() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        for query in ["c", "ca", "cat"] {
+            let update = app.update(Event::QueryChanged(query.to_string()), &mut model);
+            clock.settle(&app, &mut model, update).expect_no_effects();
+        }
+        assert_eq!(clock.pending(), 1);
+
+        clock
+            .advance(&app, &mut model, millis(299))
+            .expect_no_effects();
+        assert!(model.searches.is_empty());
+
+        clock
+            .advance(&app, &mut model, millis(1))
+            .expect_no_effects();
+        assert_eq!(model.searches, vec!["cat"]);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    pub fn test_sleep_then_continue() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        let update = app.update(Event::Flash("Saved".to_string()), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(model.flash.as_deref(), Some("Saved"));
+
+        clock
+            .advance(&app, &mut model, millis(1999))
+            .expect_no_effects();
+        assert_eq!(model.flash.as_deref(), Some("Saved"));
+
+        clock
+            .advance(&app, &mut model, millis(1))
+            .expect_no_effects();
+        assert_eq!(model.flash, None);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    pub fn test_cleared_sleep_never_continues() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        let update = app.update(Event::Flash("Saved".to_string()), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+
+        clock
+            .advance(&app, &mut model, millis(1000))
+            .expect_no_effects();
+        let update = app.update(Event::Flash("Synced".to_string()), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(clock.pending(), 1);
+
+        // the first sleep would have ended here
+        clock
+            .advance(&app, &mut model, millis(1000))
+            .expect_no_effects();
+        assert_eq!(model.flash.as_deref(), Some("Synced"));
+
+        clock
+            .advance(&app, &mut model, millis(1000))
+            .expect_no_effects();
+        assert_eq!(model.flash, None);
+    }
+
+    fn secs(seconds: u64) -> crux_time::Duration {
+        crux_time::Duration::from_secs(seconds).expect("valid duration")
+    }
+
+    #[test]
+    pub fn test_sleep_until_fires_at_the_deadline() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        let update = app.update(Event::SetReminder(epoch(60)), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+
+        clock
+            .advance(&app, &mut model, secs(59))
+            .expect_no_effects();
+        assert_eq!(model.reminded, 0);
+
+        clock.advance(&app, &mut model, secs(1)).expect_no_effects();
+        assert_eq!(model.reminded, 1);
+        assert_eq!(model.reminder, None);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    pub fn test_deadline_passed_while_suspended_fires_when_checked() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        let update = app.update(Event::SetReminder(epoch(60)), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+
+        clock
+            .advance(&app, &mut model, secs(30))
+            .expect_no_effects();
+        clock.suspend(secs(3600));
+        assert_eq!(model.reminded, 0);
+
+        let update = app.update(Event::Foregrounded, &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(model.reminded, 1);
+        assert_eq!(clock.pending(), 0);
+
+        // the shell's timer was cleared, so the reminder doesn't go off twice
+        clock
+            .advance(&app, &mut model, secs(60))
+            .expect_no_effects();
+        assert_eq!(model.reminded, 1);
+    }
+
+    #[test]
+    pub fn test_deadline_ahead_after_suspension_is_set_again() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        let update = app.update(Event::SetReminder(epoch(60)), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+
+        clock
+            .advance(&app, &mut model, secs(10))
+            .expect_no_effects();
+        clock.suspend(secs(20));
+
+        let update = app.update(Event::Foregrounded, &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(model.reminded, 0);
+        assert_eq!(clock.pending(), 1);
+
+        // without checking, the suspended timer would only fire 20 seconds late
+        clock
+            .advance(&app, &mut model, secs(29))
+            .expect_no_effects();
+        assert_eq!(model.reminded, 0);
+
+        clock.advance(&app, &mut model, secs(1)).expect_no_effects();
+        assert_eq!(model.reminded, 1);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    pub fn test_cleared_sleep_until_does_not_fire_when_checked() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+
+        let update = app.update(Event::SetReminder(epoch(60)), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        let id = model.reminder.expect("reminder set");
+
+        let update = app.update(Event::Cancel(id), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        clock.suspend(secs(3600));
+
+        let update = app.update(Event::Foregrounded, &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(model.reminded, 0);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    pub fn test_reset_timer_ids_numbers_timers_from_one() {
+        // each app numbers its timers the same way, whichever runs first
+        for _ in 0..2 {
+            let app = AppTester::<App, _>::default();
+            reset_timer_ids(&app.as_ref().time);
+            let mut model = Model::default();
+
+            for id in [1, 2] {
+                let request = app
+                    .update(Event::StartDebounce, &mut model)
+                    .expect_one_effect()
+                    .expect_time();
+
+                assert_eq!(
+                    request.operation,
+                    TimeRequest::NotifyAfter {
+                        id: TimerId(id),
+                        duration: millis(300)
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_duration_since() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let request = &mut app
+            .update(Event::ShowPost(epoch(1_000)), &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(request.operation, TimeRequest::Now);
+
+        let response = TimeResponse::Now {
+            instant: epoch(1_000 + 3 * 60 + 20),
+        };
+        let _update = app.resolve_to_event_then_update(request, response, &mut model);
+
+        assert_eq!(model.posted, "3 minutes ago");
+    }
+
+    #[test]
+    pub fn test_notify_at_every_minute_boundary() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+
+        let mut request = app
+            .update(Event::StartClock, &mut model)
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(request.operation, TimeRequest::Now);
+
+        // 12:34:56.789, the first tick lands on 12:35:00 rather than a minute from now
+        let now = Instant::new(1_704_112_496, 789_000_000).unwrap();
+        let mut timer = app
+            .resolve(&mut request, TimeResponse::Now { instant: now })
+            .expect("should resolve")
+            .expect_one_effect()
+            .expect_time();
+        let TimeRequest::NotifyAt { id, instant } = timer.operation else {
+            panic!("expected a timer to be set");
+        };
+        assert_eq!(instant, Instant::new(1_704_112_500, 0).unwrap());
+
+        // the tick arrives late, the next one is still on the next minute
+        let mut update = app
+            .resolve(&mut timer, TimeResponse::InstantArrived { id })
+            .expect("should resolve");
+        let mut request = update.expect_effect::<TimeRequest>();
+        for event in update.events {
+            app.update(event, &mut model).assert_empty();
+        }
+        assert_eq!(model.clock_ticks, 1);
+        assert_eq!(request.operation, TimeRequest::Now);
+
+        let now = Instant::new(1_704_112_500, 300_000_000).unwrap();
+        let timer = app
+            .resolve(&mut request, TimeResponse::Now { instant: now })
+            .expect("should resolve")
+            .expect_one_effect()
+            .expect_time();
+        assert_eq!(
+            timer.operation,
+            TimeRequest::NotifyAt {
+                id,
+                instant: Instant::new(1_704_112_560, 0).unwrap()
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_virtual_clock_throttle() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::new();
+        let policy = ThrottlePolicy::LeadingAndTrailing;
+
+        for offset in [1, 2, 3] {
+            let update = app.update(Event::Scrolled(offset, policy), &mut model);
+            clock.settle(&app, &mut model, update).expect_no_effects();
+        }
+        assert_eq!(model.recomputed, vec![1]);
+
+        // the trailing event opens a second interval, which also ends within the advance
+        clock
+            .advance(&app, &mut model, millis(250))
+            .expect_no_effects();
+        assert_eq!(model.recomputed, vec![1, 3]);
+        assert!(!model.scroll.is_throttled());
+    }
+
+    #[test]
+    pub fn test_virtual_clock_fires_timers_in_deadline_order() {
+        let app = AppTester::<App, _>::default();
+        let mut model = Model::default();
+        let mut clock = VirtualClock::starting_at(epoch(1_000));
+
+        let update = app.update(Event::StartInterval, &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        let update = app.update(Event::StartDebounce, &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+
+        // the debounce fires after 300ms, before the first tick
+        clock
+            .advance(&app, &mut model, millis(500))
+            .expect_no_effects();
+        assert!(model.debounce_complete);
+        assert_eq!(model.ticks, 0);
+
+        clock
+            .advance(&app, &mut model, millis(3_000))
+            .expect_no_effects();
+        assert_eq!(model.ticks, 3);
+
+        let interval_id = model.interval.as_ref().unwrap().id();
+        let update = app.update(Event::QueryRemaining(interval_id), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(model.remaining, Some(millis(500)));
+
+        let update = app.update(Event::Get, &mut model);
+        let mut update = clock.settle(&app, &mut model, update);
+        update.expect_render();
+        assert_eq!(model.time, "1970-01-01T00:16:43.500+00:00");
+
+        let update = app.update(Event::Cancel(interval_id), &mut model);
+        clock.settle(&app, &mut model, update).expect_no_effects();
+        assert_eq!(clock.pending(), 0);
+    }
 }
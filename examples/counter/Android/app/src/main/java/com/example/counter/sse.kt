@@ -3,21 +3,33 @@ package com.example.counter
 import com.example.counter.shared_types.SseRequest
 import com.example.counter.shared_types.SseResponse
 import io.ktor.client.HttpClient
+import io.ktor.client.request.header
 import io.ktor.client.request.prepareGet
 import io.ktor.client.statement.bodyAsChannel
 import io.ktor.utils.io.core.toByteArray
 import io.ktor.utils.io.readUTF8Line
+import java.io.IOException
+import kotlinx.coroutines.delay
 
 suspend fun requestSse(
     client: HttpClient, request: SseRequest, callback: suspend (SseResponse) -> Unit
 ) {
-    client.prepareGet(request.url).execute { response ->
-        val channel = response.bodyAsChannel()
-        while (!channel.isClosedForRead) {
-            var chunk = channel.readUTF8Line() ?: break
-            chunk += "\n\n"
-            callback(SseResponse.Chunk(chunk.toByteArray().toList()))
+    delay(request.delay_ms)
+    try {
+        client.prepareGet(request.url) {
+            request.last_event_id.ifPresent { header("Last-Event-ID", it) }
+        }.execute { response ->
+            val channel = response.bodyAsChannel()
+            while (!channel.isClosedForRead) {
+                // the core puts events back together from their lines
+                var chunk = channel.readUTF8Line() ?: break
+                chunk += "\n"
+                callback(SseResponse.Chunk(chunk.toByteArray().toList()))
+            }
         }
-        callback(SseResponse.Done())
+    } catch (e: IOException) {
+        // the connection failed, reported as the end of the stream below
     }
+    // the stream ended or couldn't connect, the core decides whether to reconnect
+    callback(SseResponse.Done())
 }
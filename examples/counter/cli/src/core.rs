@@ -5,7 +5,7 @@ use std::sync::Arc;
 use tokio::spawn;
 use tracing::debug;
 
-use shared::{App, Effect, Event};
+use shared::{sse::SseResponse, App, Effect, Event};
 
 use crate::{http, sse};
 
@@ -54,13 +54,17 @@ pub fn process_effect(core: &Core, effect: Effect, tx: &Arc<Sender<Effect>>) ->
                 let tx = tx.clone();
 
                 async move {
-                    let mut stream = sse::request(&request.operation).await?;
-
-                    while let Ok(Some(response)) = stream.try_next().await {
-                        for effect in core.resolve(&mut request, response) {
-                            process_effect(&core, effect, &tx)?;
+                    if let Ok(mut stream) = sse::request(&request.operation).await {
+                        while let Ok(Some(response)) = stream.try_next().await {
+                            for effect in core.resolve(&mut request, response) {
+                                process_effect(&core, effect, &tx)?;
+                            }
                         }
                     }
+                    // the stream ended or couldn't connect, the core decides whether to reconnect
+                    for effect in core.resolve(&mut request, SseResponse::Done) {
+                        process_effect(&core, effect, &tx)?;
+                    }
                     Result::<()>::Ok(())
                 }
             });
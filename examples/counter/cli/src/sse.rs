@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use futures::{stream, StreamExt};
 
 use reqwest::{Client, Method};
@@ -7,15 +9,22 @@ use shared::{
 };
 
 pub async fn request(
-    SseRequest { url }: &SseRequest,
+    SseRequest {
+        url,
+        last_event_id,
+        delay_ms,
+    }: &SseRequest,
 ) -> Result<impl futures::TryStream<Ok = SseResponse>> {
+    tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
+
     let client = Client::new();
     let method = Method::from_bytes(b"GET").unwrap();
 
-    let request = client
-        .request(method, url)
-        .build()
-        .map_err(|e| HttpError::Url(e.to_string()))?;
+    let mut request = client.request(method, url);
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+    let request = request.build().map_err(|e| HttpError::Url(e.to_string()))?;
 
     let response = client
         .execute(request)
@@ -39,8 +39,10 @@ class Core: ObservableObject {
         case let .serverSentEvents(req):
             Task {
                 for await result in await requestSse(req) {
-                    let response = try result.get()
-                    
+                    guard case let .success(response) = result else {
+                        continue
+                    }
+
                     let effects = [UInt8](handleResponse(request.id, Data(try! response.bincodeSerialize())))
                     
                     let requests: [Request] = try! .bincodeDeserialize(input: effects)
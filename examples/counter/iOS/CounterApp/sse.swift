@@ -9,7 +9,12 @@ enum SseError: Error {
 func requestSse(_ request: SseRequest) async -> AsyncStream<Result<SseResponse, SseError>> {
     return AsyncStream { continuation in
         Task {
-            let req = URLRequest(url: URL(string: request.url)!)
+            try? await Task.sleep(nanoseconds: request.delay_ms * 1_000_000)
+
+            var req = URLRequest(url: URL(string: request.url)!)
+            if let lastEventId = request.last_event_id {
+                req.setValue(lastEventId, forHTTPHeaderField: "Last-Event-ID")
+            }
             do {
                 let (asyncBytes, response) = try await URLSession.shared.bytes(for: req)
                 if let httpResponse = response as? HTTPURLResponse {
@@ -17,19 +22,31 @@ func requestSse(_ request: SseRequest) async -> AsyncStream<Result<SseResponse,
                         continuation.yield(.failure(
                             .message("error, status code: \(httpResponse.statusCode)")
                         ))
+                        continuation.yield(.success(.done))
                         continuation.finish()
                         return
                     }
                 }
 
-                for try await line in asyncBytes.lines {
-                    let line = line + "\n\n"
-                    continuation.yield(.success(.chunk([UInt8](line.utf8))))
+                // pass on whole lines, including the empty ones which end each event,
+                // which `asyncBytes.lines` would skip
+                var line = [UInt8]()
+                for try await byte in asyncBytes {
+                    line.append(byte)
+                    if byte == UInt8(ascii: "\n") {
+                        continuation.yield(.success(.chunk(line)))
+                        line.removeAll()
+                    }
+                }
+                if !line.isEmpty {
+                    continuation.yield(.success(.chunk(line)))
                 }
                 continuation.yield(.success(.done))
                 continuation.finish()
             } catch {
                 continuation.yield(.failure(.generic(error)))
+                // the core decides whether to reconnect
+                continuation.yield(.success(.done))
                 continuation.finish()
             }
         }
@@ -1,4 +1,4 @@
-use crate::capabilities::sse::ServerSentEvents;
+use crate::capabilities::sse::{ReconnectPolicy, ServerSentEvents};
 use chrono::{serde::ts_milliseconds_option::deserialize as ts_milliseconds_option, DateTime, Utc};
 use crux_core::render::Render;
 use crux_http::Http;
@@ -104,7 +104,8 @@ impl crux_core::App for App {
             Event::StartWatch => {
                 let base = Url::parse(API_URL).unwrap();
                 let url = base.join("/sse").unwrap();
-                caps.sse.get_json(url, Event::Update);
+                caps.sse
+                    .get_with_reconnect(url, ReconnectPolicy::default(), Event::Update);
             }
         }
     }
@@ -339,6 +340,8 @@ mod tests {
             request.operation,
             SseRequest {
                 url: "https://crux-counter.fly.dev/sse".to_string(),
+                last_event_id: None,
+                delay_ms: 0,
             }
         );
     }
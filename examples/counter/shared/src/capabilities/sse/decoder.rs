@@ -0,0 +1,240 @@
+//! An incremental decoder for the `text/event-stream` format, following
+//! <https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation>
+//!
+//! Unlike decoding each chunk on its own, the decoder keeps its state between chunks, so
+//! events split across chunks are put back together and the last event id is remembered
+//! for the whole stream.
+
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+/// An event dispatched by the server
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The id of the event, or of the last event before it which had one
+    pub id: Option<String>,
+    /// The name of the event, `"message"` unless the server set it with `event:`
+    pub event: String,
+    /// The data lines of the event, joined with newlines
+    pub data: String,
+}
+
+impl SseEvent {
+    /// Deserialize the data of the event as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.data)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Item {
+    Event(SseEvent),
+    /// The server asked for a different reconnection delay with `retry:`
+    Retry(Duration),
+}
+
+#[derive(Default)]
+pub(crate) struct Decoder {
+    buffer: Vec<u8>,
+    started: bool,
+    data: String,
+    event: String,
+    id: Option<String>,
+    // only updated when an event is complete, so a reconnection never skips a partly
+    // received event
+    last_event_id: Option<String>,
+}
+
+impl Decoder {
+    /// The id of the last event seen, to send with a reconnection
+    pub(crate) fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Throw away any partly received event, e.g. because the connection dropped.
+    /// The last event id is kept.
+    pub(crate) fn reset(&mut self) {
+        self.buffer.clear();
+        self.started = false;
+        self.data.clear();
+        self.event.clear();
+        self.id.clone_from(&self.last_event_id);
+    }
+
+    /// Decode the complete lines in `chunk`, along with anything left over from
+    /// previous chunks.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Vec<Item> {
+        self.buffer.extend_from_slice(chunk);
+
+        if !self.started {
+            // wait until we can tell whether the stream starts with a byte order mark
+            if self.buffer.len() < 3 && b"\xEF\xBB\xBF".starts_with(&self.buffer) {
+                return Vec::new();
+            }
+            if self.buffer.starts_with(b"\xEF\xBB\xBF") {
+                self.buffer.drain(..3);
+            }
+            self.started = true;
+        }
+
+        let mut items = Vec::new();
+        let mut start = 0;
+
+        while let Some(offset) = self.buffer[start..]
+            .iter()
+            .position(|b| *b == b'\n' || *b == b'\r')
+        {
+            let end = start + offset;
+            let next = match self.buffer[end] {
+                b'\r' if end + 1 == self.buffer.len() => break, // might be followed by \n
+                b'\r' if self.buffer[end + 1] == b'\n' => end + 2,
+                _ => end + 1,
+            };
+
+            let line = String::from_utf8_lossy(&self.buffer[start..end]).into_owned();
+            items.extend(self.line(&line));
+            start = next;
+        }
+        self.buffer.drain(..start);
+
+        items
+    }
+
+    fn line(&mut self, line: &str) -> Option<Item> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => {
+                self.id = (!value.is_empty()).then(|| value.to_string());
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                return value
+                    .parse()
+                    .ok()
+                    .map(|millis| Item::Retry(Duration::from_millis(millis)));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Item> {
+        self.last_event_id.clone_from(&self.id);
+        let event = std::mem::take(&mut self.event);
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = std::mem::take(&mut self.data);
+        data.pop(); // the newline after the last data line
+
+        Some(Item::Event(SseEvent {
+            id: self.last_event_id.clone(),
+            event: if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            },
+            data,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: Option<&str>, event: &str, data: &str) -> Item {
+        Item::Event(SseEvent {
+            id: id.map(ToString::to_string),
+            event: event.to_string(),
+            data: data.to_string(),
+        })
+    }
+
+    #[test]
+    fn decodes_events_split_across_chunks() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(decoder.feed(b"id: 1\nda"), vec![]);
+        assert_eq!(decoder.last_event_id(), None);
+        assert_eq!(
+            decoder.feed(b"ta: {\"value\":1}\n\n"),
+            vec![message(Some("1"), "message", "{\"value\":1}")]
+        );
+        assert_eq!(decoder.last_event_id(), Some("1"));
+    }
+
+    #[test]
+    fn decodes_named_multiline_events_and_comments() {
+        let mut decoder = Decoder::default();
+
+        let items = decoder.feed(b": keep alive\r\nevent: update\r\ndata: a\r\ndata: b\r\n\r\n");
+        assert_eq!(items, vec![message(None, "update", "a\nb")]);
+    }
+
+    #[test]
+    fn waits_for_a_line_feed_after_a_carriage_return() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(decoder.feed(b"data: x\r"), vec![]);
+        assert_eq!(decoder.feed(b"\n\r\n"), vec![message(None, "message", "x")]);
+    }
+
+    #[test]
+    fn keeps_the_last_event_id_for_later_events() {
+        let mut decoder = Decoder::default();
+
+        let items = decoder.feed(b"id: 7\ndata: one\n\ndata: two\n\nid\ndata: three\n\n");
+        assert_eq!(
+            items,
+            vec![
+                message(Some("7"), "message", "one"),
+                message(Some("7"), "message", "two"),
+                message(None, "message", "three"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_retry_and_ignores_invalid_values() {
+        let mut decoder = Decoder::default();
+
+        let items = decoder.feed(b"retry: 5000\nretry: soon\n\n");
+        assert_eq!(items, vec![Item::Retry(Duration::from_millis(5000))]);
+    }
+
+    #[test]
+    fn skips_byte_order_mark_and_drops_partial_events_on_reset() {
+        let mut decoder = Decoder::default();
+
+        assert_eq!(decoder.feed(b"\xEF\xBB"), vec![]);
+        assert_eq!(
+            decoder.feed(b"\xBFid: 3\ndata: whole\n\nid: 4\ndata: partial\n"),
+            vec![message(Some("3"), "message", "whole")]
+        );
+
+        decoder.reset();
+        assert_eq!(decoder.last_event_id(), Some("3"));
+        assert_eq!(
+            decoder.feed(b"data: fresh\n\n"),
+            vec![message(Some("3"), "message", "fresh")]
+        );
+    }
+}
@@ -0,0 +1,297 @@
+mod decoder;
+
+use std::{collections::HashMap, time::Duration};
+
+use futures::{future, stream, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crux_core::capability::{CapabilityContext, Operation};
+
+use decoder::{Decoder, Item};
+
+pub use decoder::SseEvent;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SseRequest {
+    pub url: String,
+    /// The id of the last event received before reconnecting, which the shell
+    /// should send in the `Last-Event-ID` header
+    pub last_event_id: Option<String>,
+    /// How long the shell should wait before connecting, in milliseconds
+    pub delay_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum SseResponse {
+    Chunk(Vec<u8>),
+    Done,
+}
+
+impl Operation for SseRequest {
+    type Output = SseResponse;
+}
+
+/// How [`ServerSentEvents::get_with_reconnect`] reconnects when the stream ends.
+///
+/// The delay before each reconnection starts at `initial_delay`, or at the delay the
+/// server asked for with a `retry:` field, and doubles with every attempt which fails
+/// to receive an event, up to `max_delay`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Give up after this many reconnections in a row without receiving an event,
+    /// or never if `None`
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    fn delay(&self, base: Duration, attempt: u32) -> Duration {
+        base.saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+/// The handlers [`ServerSentEvents::get_typed`] dispatches events to, by event name
+pub struct SseHandlers<Ev> {
+    handlers: HashMap<String, Box<dyn Fn(SseEvent) -> Ev + Send>>,
+    fallback: Box<dyn Fn(SseEvent) -> Ev + Send>,
+}
+
+impl<Ev> SseHandlers<Ev> {
+    /// Create handlers which send every event to `fallback`, until more specific
+    /// handlers are added with [`SseHandlers::on`].
+    pub fn new<F>(fallback: F) -> Self
+    where
+        F: Fn(SseEvent) -> Ev + Send + 'static,
+    {
+        Self {
+            handlers: HashMap::new(),
+            fallback: Box::new(fallback),
+        }
+    }
+
+    /// Send events named `event` to `handler`. Events without an `event:` field are
+    /// named `"message"`.
+    #[must_use]
+    pub fn on<F>(mut self, event: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(SseEvent) -> Ev + Send + 'static,
+    {
+        self.handlers.insert(event.into(), Box::new(handler));
+        self
+    }
+
+    fn dispatch(&self, event: SseEvent) -> Ev {
+        match self.handlers.get(&event.event) {
+            Some(handler) => handler(event),
+            None => (self.fallback)(event),
+        }
+    }
+}
+
+#[derive(crux_core::macros::Capability)]
+pub struct ServerSentEvents<Ev> {
+    context: CapabilityContext<SseRequest, Ev>,
+}
+
+impl<Ev> Clone for ServerSentEvents<Ev> {
+    fn clone(&self) -> Self {
+        Self {
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<Ev> ServerSentEvents<Ev>
+where
+    Ev: 'static,
+{
+    pub fn new(context: CapabilityContext<SseRequest, Ev>) -> Self {
+        Self { context }
+    }
+
+    /// Stream the events from `url`, deserializing the data of each as JSON and
+    /// passing it to the app wrapped in the event produced by `make_event`.
+    ///
+    /// Messages whose data can't be deserialized as `T` are skipped.
+    pub fn get_json<F, T>(&self, url: impl AsRef<str>, make_event: F)
+    where
+        F: Fn(T) -> Ev + Send + 'static,
+        T: DeserializeOwned,
+    {
+        self.stream(url, None, move |event| event.json().ok().map(&make_event));
+    }
+
+    /// Like [`ServerSentEvents::get_json`], but when the shell reports the stream has
+    /// ended, with [`SseResponse::Done`], a new [`SseRequest`] is sent after a delay
+    /// chosen by the `policy`, carrying the id of the last event received so the server
+    /// can resume from it.
+    pub fn get_with_reconnect<F, T>(
+        &self,
+        url: impl AsRef<str>,
+        policy: ReconnectPolicy,
+        make_event: F,
+    ) where
+        F: Fn(T) -> Ev + Send + 'static,
+        T: DeserializeOwned,
+    {
+        self.stream(url, Some(policy), move |event| {
+            event.json().ok().map(&make_event)
+        });
+    }
+
+    /// Stream the events from `url`, sending each to the handler registered for its
+    /// name in `handlers`, or to the fallback handler if there is none.
+    ///
+    /// ```rust,ignore
+    /// let handlers = SseHandlers::new(Event::Unexpected)
+    ///     .on("update", |event| Event::Update(event.json().unwrap_or_default()))
+    ///     .on("ping", |_| Event::Ping);
+    ///
+    /// caps.sse.get_typed(url, handlers);
+    /// ```
+    pub fn get_typed(&self, url: impl AsRef<str>, handlers: SseHandlers<Ev>) {
+        self.stream(url, None, move |event| Some(handlers.dispatch(event)));
+    }
+
+    /// Stream the events from `url`, until the shell reports the stream has ended.
+    /// This is an async call to use with [`crux_core::compose::Compose`], so the events
+    /// can be combined with other effects, e.g. to wait for the first update but give
+    /// up after a while with `Time::timeout_async` from `crux_time`.
+    ///
+    /// ```rust,ignore
+    /// caps.compose.spawn(|context| {
+    ///     let sse = caps.sse.clone();
+    ///
+    ///     async move {
+    ///         let mut events = pin!(sse.get_stream(url));
+    ///         if let Some(event) = events.next().await {
+    ///             context.update_app(Event::FirstUpdate(event.data));
+    ///         }
+    ///         // dropping the stream stops listening
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// The stream doesn't reconnect, and isn't requested from the shell until it is
+    /// first polled.
+    pub fn get_stream(&self, url: impl AsRef<str>) -> impl Stream<Item = SseEvent> {
+        self.context
+            .stream_from_shell(SseRequest {
+                url: url.as_ref().to_string(),
+                last_event_id: None,
+                delay_ms: 0,
+            })
+            .scan(Decoder::default(), |decoder, response| {
+                future::ready(match response {
+                    SseResponse::Chunk(data) => Some(stream::iter(decoder.feed(&data))),
+                    SseResponse::Done => None,
+                })
+            })
+            .flatten()
+            .filter_map(|item| {
+                future::ready(match item {
+                    Item::Event(event) => Some(event),
+                    Item::Retry(_) => None,
+                })
+            })
+    }
+
+    // reconnects when the stream ends if there is a `policy`
+    fn stream<F>(&self, url: impl AsRef<str>, policy: Option<ReconnectPolicy>, dispatch: F)
+    where
+        F: Fn(SseEvent) -> Option<Ev> + Send + 'static,
+    {
+        self.context.spawn({
+            let context = self.context.clone();
+            let url = url.as_ref().to_string();
+
+            async move {
+                let mut decoder = Decoder::default();
+                let mut retry = None;
+                let mut attempt = 0;
+                let mut delay = Duration::ZERO;
+
+                loop {
+                    let mut stream = context.stream_from_shell(SseRequest {
+                        url: url.clone(),
+                        last_event_id: decoder.last_event_id().map(ToString::to_string),
+                        delay_ms: u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                    });
+
+                    while let Some(SseResponse::Chunk(data)) = stream.next().await {
+                        for item in decoder.feed(&data) {
+                            match item {
+                                Item::Retry(delay) => retry = Some(delay),
+                                Item::Event(event) => {
+                                    attempt = 0;
+                                    if let Some(event) = dispatch(event) {
+                                        context.update_app(event);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    decoder.reset();
+
+                    let Some(policy) = policy else {
+                        break;
+                    };
+                    if matches!(policy.max_attempts, Some(max) if attempt >= max) {
+                        break;
+                    }
+                    delay = policy.delay(retry.unwrap_or(policy.initial_delay), attempt);
+                    attempt += 1;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Update(String),
+        Ping,
+        Other(String),
+    }
+
+    fn event(name: &str, data: &str) -> SseEvent {
+        SseEvent {
+            id: None,
+            event: name.to_string(),
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn dispatches_by_event_name() {
+        let handlers = SseHandlers::new(|event: SseEvent| Event::Other(event.event))
+            .on("update", |event| Event::Update(event.data))
+            .on("ping", |_| Event::Ping);
+
+        assert_eq!(
+            handlers.dispatch(event("update", "{}")),
+            Event::Update("{}".to_string())
+        );
+        assert_eq!(handlers.dispatch(event("ping", "")), Event::Ping);
+        assert_eq!(
+            handlers.dispatch(event("message", "hello")),
+            Event::Other("message".to_string())
+        );
+    }
+}
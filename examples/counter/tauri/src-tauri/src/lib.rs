@@ -4,7 +4,7 @@ mod sse;
 use anyhow::anyhow;
 use futures::TryStreamExt;
 use lazy_static::lazy_static;
-use shared::{App, Core, Effect, Event};
+use shared::{sse::SseResponse, App, Core, Effect, Event};
 use std::sync::Arc;
 use tauri::Emitter;
 
@@ -53,15 +53,17 @@ fn process_effect(
                 let core = core.clone();
 
                 async move {
-                    let mut stream = sse::request(&request.operation)
-                        .await
-                        .expect("error processing SSE effect");
-
-                    while let Ok(Some(response)) = stream.try_next().await {
-                        for effect in core.resolve(&mut request, response) {
-                            let _ = process_effect(effect, &core, tauri_app.clone());
+                    if let Ok(mut stream) = sse::request(&request.operation).await {
+                        while let Ok(Some(response)) = stream.try_next().await {
+                            for effect in core.resolve(&mut request, response) {
+                                let _ = process_effect(effect, &core, tauri_app.clone());
+                            }
                         }
                     }
+                    // the stream ended or couldn't connect, the core decides whether to reconnect
+                    for effect in core.resolve(&mut request, SseResponse::Done) {
+                        let _ = process_effect(effect, &core, tauri_app.clone());
+                    }
                 }
             });
 
@@ -5,7 +5,7 @@ use dioxus::{
     signals::Writable,
 };
 use futures_util::{StreamExt, TryStreamExt};
-use shared::{App, Effect, Event, ViewModel};
+use shared::{sse::SseResponse, App, Effect, Event, ViewModel};
 use tracing::debug;
 use wasm_bindgen_futures::spawn_local;
 
@@ -82,13 +82,17 @@ fn process_effect(core: &Core, effect: Effect, view: &mut Signal<ViewModel>) {
                 let core = core.clone();
 
                 async move {
-                    let mut stream = sse::request(&request.operation).await.unwrap();
-
-                    while let Ok(Some(response)) = stream.try_next().await {
-                        for effect in core.resolve(&mut request, response) {
-                            process_effect(&core, effect, &mut view);
+                    if let Ok(mut stream) = sse::request(&request.operation).await {
+                        while let Ok(Some(response)) = stream.try_next().await {
+                            for effect in core.resolve(&mut request, response) {
+                                process_effect(&core, effect, &mut view);
+                            }
                         }
                     }
+                    // the stream ended or couldn't connect, the core decides whether to reconnect
+                    for effect in core.resolve(&mut request, SseResponse::Done) {
+                        process_effect(&core, effect, &mut view);
+                    }
                 }
             });
         }
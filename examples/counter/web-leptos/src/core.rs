@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use futures_util::TryStreamExt;
 use leptos::{spawn_local, SignalUpdate, WriteSignal};
-use shared::{App, Effect, Event, ViewModel};
+use shared::{sse::SseResponse, App, Effect, Event, ViewModel};
 
 use crate::{http, sse};
 
@@ -47,13 +47,17 @@ pub fn process_effect(core: &Core, effect: Effect, render: WriteSignal<ViewModel
                 let core = core.clone();
 
                 async move {
-                    let mut stream = sse::request(&request.operation).await.unwrap();
-
-                    while let Ok(Some(response)) = stream.try_next().await {
-                        for effect in core.resolve(&mut request, response) {
-                            process_effect(&core, effect, render);
+                    if let Ok(mut stream) = sse::request(&request.operation).await {
+                        while let Ok(Some(response)) = stream.try_next().await {
+                            for effect in core.resolve(&mut request, response) {
+                                process_effect(&core, effect, render);
+                            }
                         }
                     }
+                    // the stream ended or couldn't connect, the core decides whether to reconnect
+                    for effect in core.resolve(&mut request, SseResponse::Done) {
+                        process_effect(&core, effect, render);
+                    }
                 }
             });
         }
@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use futures_util::{stream, StreamExt};
 use gloo_net::http;
+use gloo_timers::future::sleep;
 use js_sys::Uint8Array;
 use wasm_bindgen::{prelude::*, JsValue};
 use wasm_streams::ReadableStream;
@@ -8,9 +11,19 @@ use wasm_streams::ReadableStream;
 use shared::sse::{SseRequest, SseResponse};
 
 pub async fn request(
-    SseRequest { url }: &SseRequest,
+    SseRequest {
+        url,
+        last_event_id,
+        delay_ms,
+    }: &SseRequest,
 ) -> Result<impl stream::TryStream<Ok = SseResponse, Error = JsValue>> {
-    let response = http::Request::get(url).send().await?;
+    sleep(Duration::from_millis(*delay_ms)).await;
+
+    let mut request = http::Request::get(url);
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+    let response = request.send().await?;
 
     let raw_body = response.body().unwrap_throw();
     let body = ReadableStream::from_raw(raw_body.dyn_into().unwrap_throw());
@@ -4,26 +4,36 @@ import {
   SseResponseVariantChunk,
 } from "shared_types/types/shared_types";
 
-export async function* request({ url }: SseRequest) {
-  const request = new Request(url);
+export async function* request({ url, last_event_id, delay_ms }: SseRequest) {
+  await new Promise((resolve) => setTimeout(resolve, Number(delay_ms)));
 
-  const response = await fetch(request);
-  if (!response.body) {
-    throw new Error("SSE response has no body");
+  const headers: Record<string, string> = {};
+  if (last_event_id != null) {
+    headers["Last-Event-ID"] = last_event_id;
   }
 
-  const reader = response.body.getReader();
   try {
-    while (true) {
-      const { done, value } = await reader.read();
-      yield done
-        ? new SseResponseVariantDone()
-        : new SseResponseVariantChunk(Array.from(value));
-      if (done) {
-        break;
+    const response = await fetch(new Request(url, { headers }));
+    if (!response.body) {
+      throw new Error("SSE response has no body");
+    }
+
+    const reader = response.body.getReader();
+    try {
+      while (true) {
+        const { done, value } = await reader.read();
+        if (done) {
+          break;
+        }
+        yield new SseResponseVariantChunk(Array.from(value));
       }
+    } finally {
+      reader.releaseLock();
     }
-  } finally {
-    reader.releaseLock();
+  } catch (e) {
+    console.error("SSE connection failed", e);
   }
+
+  // the stream ended or couldn't connect, the core decides whether to reconnect
+  yield new SseResponseVariantDone();
 }
@@ -1,6 +1,6 @@
 use futures_util::TryStreamExt;
 use gloo_console::log;
-use shared::{App, Effect, Event};
+use shared::{sse::SseResponse, App, Effect, Event};
 use std::rc::Rc;
 use yew::{platform::spawn_local, Callback};
 
@@ -52,13 +52,17 @@ pub fn process_effect(core: &Core, effect: Effect, callback: &Callback<Message>)
                 let callback = callback.clone();
 
                 async move {
-                    let mut stream = sse::request(&request.operation).await.unwrap();
-
-                    while let Ok(Some(response)) = stream.try_next().await {
-                        for effect in core.resolve(&mut request, response) {
-                            process_effect(&core, effect, &callback);
+                    if let Ok(mut stream) = sse::request(&request.operation).await {
+                        while let Ok(Some(response)) = stream.try_next().await {
+                            for effect in core.resolve(&mut request, response) {
+                                process_effect(&core, effect, &callback);
+                            }
                         }
                     }
+                    // the stream ended or couldn't connect, the core decides whether to reconnect
+                    for effect in core.resolve(&mut request, SseResponse::Done) {
+                        process_effect(&core, effect, &callback);
+                    }
                 }
             });
         }
@@ -2,7 +2,7 @@
 
 git checkout master
 
-for dir in crux_macros crux_core crux_http crux_kv crux_platform crux_time
+for dir in crux_macros crux_core crux_clipboard crux_http crux_kv crux_notify crux_platform crux_time
     pushd $dir
     git tag {$dir}-v(cargo pkgid | cut -d "#" -f2)
     popd